@@ -1,7 +1,7 @@
 
-use winit::VirtualKeyCode;
+use winit::MouseButton;
 
-use crate::input::EventController;
+use crate::input::{EventController, KeyAction};
 use crate::{Vec3F, Mat4F};
 
 
@@ -24,8 +24,14 @@ pub struct FlightCamera {
     yaw  : f32,
     pitch: f32,
 
+    /// The position and orientation that the camera eases toward when damping is enabled.
+    target_pos  : Vec3F,
+    target_yaw  : f32,
+    target_pitch: f32,
+
     // camera options
     move_speed: f32,
+    damping: f32,
     _mouse_sensitivity: f32,
     _wheel_sensitivity: f32,
 
@@ -52,6 +58,14 @@ impl FlightCamera {
         self.move_speed = speed;
     }
 
+    /// Smooth the movement and rotation of camera.
+    ///
+    /// `factor` is the time in seconds for the camera to cover about 63% of the remaining distance to its target.
+    /// Zero damping(the default value) applies the input to camera directly.
+    pub fn set_damping(&mut self, factor: f32) {
+        self.damping = factor.max(0.0);
+    }
+
     pub fn current_position(&self) -> Vec3F {
         self.pos.clone()
     }
@@ -72,6 +86,20 @@ impl FlightCamera {
         self.screen_aspect = (width as f32) / (height as f32);
     }
 
+    /// Set the vertical field of view in degrees.
+    pub fn set_fov(&mut self, degrees: f32) {
+        self.zoom = degrees.to_radians();
+    }
+
+    pub fn set_near_far(&mut self, near: f32, far: f32) {
+        self.near = near;
+        self.far  = far;
+    }
+
+    pub fn set_aspect_ratio(&mut self, ratio: f32) {
+        self.screen_aspect = ratio;
+    }
+
     pub fn flip_vertically(&mut self) {
         self.flip_vertically = !self.flip_vertically;
     }
@@ -81,16 +109,16 @@ impl FlightCamera {
         // keyboard
         let velocity = self.move_speed * delta_time;
 
-        if inputer.key.is_key_pressed(VirtualKeyCode::Up) {
-            self.pos += self.front * velocity;
-        } else if inputer.key.is_key_pressed(VirtualKeyCode::Down) {
-            self.pos -= self.front * velocity;
+        if inputer.is_action_pressed(KeyAction::MoveForward) {
+            self.target_pos += self.front * velocity;
+        } else if inputer.is_action_pressed(KeyAction::MoveBack) {
+            self.target_pos -= self.front * velocity;
         }
 
-        if inputer.key.is_key_pressed(VirtualKeyCode::Left) {
-            self.pos -= self.right * velocity;
-        } else if inputer.key.is_key_pressed(VirtualKeyCode::Right) {
-            self.pos += self.right * velocity;
+        if inputer.is_action_pressed(KeyAction::MoveLeft) {
+            self.target_pos -= self.right * velocity;
+        } else if inputer.is_action_pressed(KeyAction::MoveRight) {
+            self.target_pos += self.right * velocity;
         }
 
         // mouse motion
@@ -98,10 +126,33 @@ impl FlightCamera {
 
             let mouse_motion = inputer.cursor.get_cursor_motion();
 
-            self.yaw += mouse_motion.0;
-            self.pitch = num::clamp(self.pitch - mouse_motion.1, -89.0, 89.0);
+            self.target_yaw += mouse_motion.0;
+            self.target_pitch = num::clamp(self.target_pitch - mouse_motion.1, -89.0, 89.0);
+        }
+
+        self.approach_target(delta_time);
+    }
+
+    /// Move the camera toward its target position and orientation.
+    fn approach_target(&mut self, delta_time: f32) {
+
+        let is_rotated = self.yaw != self.target_yaw || self.pitch != self.target_pitch;
+
+        if self.damping > 0.0 {
+            // exponential decay keeps the easing independent of frame rate.
+            let t = 1.0 - (-delta_time / self.damping).exp();
 
-            // recalculate front, right or up vector only when mouse move.
+            self.pos   += (self.target_pos - self.pos) * t;
+            self.yaw   += (self.target_yaw - self.yaw) * t;
+            self.pitch += (self.target_pitch - self.pitch) * t;
+        } else {
+            self.pos   = self.target_pos;
+            self.yaw   = self.target_yaw;
+            self.pitch = self.target_pitch;
+        }
+
+        // recalculate front, right or up vector only when camera rotates.
+        if is_rotated {
             self.update_vectors();
         }
     }
@@ -166,11 +217,15 @@ impl FlightCameraBuilder {
             world_up : self.world_up,
             yaw      : self.yaw,
             pitch    : self.pitch,
+            target_pos  : self.pos,
+            target_yaw  : self.yaw,
+            target_pitch: self.pitch,
             near     : self.near,
             far      : self.far,
             screen_aspect: self.screen_aspect,
 
             move_speed: 2.5,
+            damping: 0.0,
             _mouse_sensitivity: 1.0,
             _wheel_sensitivity: 1.0,
             zoom: 45.0_f32.to_radians(),
@@ -208,3 +263,192 @@ impl FlightCameraBuilder {
     }
 }
 
+
+
+/// A camera orbiting around a target point, which is convenient to inspect a model.
+///
+/// Drag with the left mouse button to rotate the camera around the target, and scroll the mouse wheel to zoom.
+pub struct OrbitCamera {
+
+    /// The point that the camera looks at.
+    target: Vec3F,
+    /// The distance between camera and target.
+    distance: f32,
+    /// The horizontal angle around target in degrees.
+    azimuth: f32,
+    /// The vertical angle around target in degrees.
+    elevation: f32,
+
+    world_up: Vec3F,
+
+    // camera options
+    rotate_sensitivity: f32,
+    wheel_sensitivity : f32,
+    min_distance: f32,
+    max_distance: f32,
+
+    zoom: f32,
+    near: f32,
+    far : f32,
+    screen_aspect: f32,
+
+    /// See `FlightCamera::flip_vertically` for detail(default is true).
+    ///
+    /// Like `FlightCamera`, the flip is applied to the view matrix rather than the projection matrix.
+    flip_vertically: bool,
+}
+
+impl OrbitCamera {
+
+    pub fn new() -> OrbitCameraBuilder {
+        OrbitCameraBuilder::default()
+    }
+
+    pub fn set_target(&mut self, target: Vec3F) {
+        self.target = target;
+    }
+
+    pub fn set_distance(&mut self, distance: f32) {
+        self.distance = num::clamp(distance, self.min_distance, self.max_distance);
+    }
+
+    pub fn current_position(&self) -> Vec3F {
+
+        let offset_x = self.azimuth.to_radians().cos() * self.elevation.to_radians().cos();
+        let offset_y = self.elevation.to_radians().sin();
+        let offset_z = self.azimuth.to_radians().sin() * self.elevation.to_radians().cos();
+
+        self.target + Vec3F::new(offset_x, offset_y, offset_z) * self.distance
+    }
+
+    /// Generate a new view matrix based on camera status.
+    pub fn view_matrix(&self) -> Mat4F {
+
+        // flip the up vector in the same way as `FlightCamera::update_vectors`, so that both cameras share the same winding order.
+        let up = if self.flip_vertically {
+            Vec3F::new(self.world_up.x, -self.world_up.y, self.world_up.z)
+        } else {
+            self.world_up
+        };
+
+        Mat4F::look_at_rh(self.current_position(), self.target, up)
+    }
+
+    /// Generate a new projection matrix based on camera status.
+    pub fn proj_matrix(&self) -> Mat4F {
+
+        Mat4F::perspective_rh_zo(self.zoom, self.screen_aspect, self.near, self.far)
+    }
+
+    pub fn reset_screen_dimension(&mut self, width: u32, height: u32) {
+        self.screen_aspect = (width as f32) / (height as f32);
+    }
+
+    pub fn flip_vertically(&mut self) {
+        self.flip_vertically = !self.flip_vertically;
+    }
+
+    pub fn receive_input(&mut self, inputer: &EventController, _delta_time: f32) {
+
+        // mouse drag
+        if inputer.is_cursor_active() && inputer.cursor.is_button_pressed(MouseButton::Left) {
+
+            let mouse_motion = inputer.cursor.get_cursor_motion();
+
+            self.azimuth += mouse_motion.0 * self.rotate_sensitivity;
+            self.elevation = num::clamp(self.elevation + mouse_motion.1 * self.rotate_sensitivity, -89.0, 89.0);
+        }
+
+        // mouse wheel
+        let wheel_motion = inputer.cursor.get_wheel_motion();
+        if wheel_motion != 0.0 {
+            // zoom in proportion to current distance, so that the zoom speed feels the same at any distance.
+            let distance = self.distance * (1.0 - wheel_motion * self.wheel_sensitivity);
+            self.set_distance(distance);
+        }
+    }
+}
+
+pub struct OrbitCameraBuilder {
+
+    target  : Vec3F,
+    distance: f32,
+    world_up: Vec3F,
+
+    azimuth  : f32,
+    elevation: f32,
+
+    near: f32,
+    far : f32,
+    screen_aspect: f32,
+}
+
+impl Default for OrbitCameraBuilder {
+
+    fn default() -> OrbitCameraBuilder {
+        OrbitCameraBuilder {
+            target   : Vec3F::new(0.0, 0.0, 0.0),
+            distance : 5.0,
+            world_up : Vec3F::new(0.0, 1.0, 0.0),
+            azimuth  : 90.0,
+            elevation: 0.0,
+            near     : 0.1,
+            far      : 100.0,
+            screen_aspect: 1.0,
+        }
+    }
+}
+
+impl OrbitCameraBuilder {
+
+    pub fn build(self) -> OrbitCamera {
+
+        OrbitCamera {
+            target   : self.target,
+            distance : self.distance,
+            azimuth  : self.azimuth,
+            elevation: self.elevation,
+            world_up : self.world_up,
+            near     : self.near,
+            far      : self.far,
+            screen_aspect: self.screen_aspect,
+
+            rotate_sensitivity: 0.25,
+            wheel_sensitivity : 0.1,
+            min_distance: self.near,
+            max_distance: self.far,
+            zoom: 45.0_f32.to_radians(),
+
+            flip_vertically: true,
+        }
+    }
+
+    pub fn target(mut self, target: Vec3F) -> OrbitCameraBuilder {
+        self.target = target; self
+    }
+
+    pub fn distance(mut self, distance: f32) -> OrbitCameraBuilder {
+        self.distance = distance; self
+    }
+
+    pub fn world_up(mut self, up: Vec3F) -> OrbitCameraBuilder {
+        self.world_up = up; self
+    }
+
+    pub fn azimuth(mut self, azimuth: f32) -> OrbitCameraBuilder {
+        self.azimuth = azimuth; self
+    }
+
+    pub fn elevation(mut self, elevation: f32) -> OrbitCameraBuilder {
+        self.elevation = elevation; self
+    }
+
+    pub fn view_distance(mut self, near: f32, far: f32) -> OrbitCameraBuilder {
+        self.near = near;
+        self.far = far; self
+    }
+
+    pub fn screen_aspect_ratio(mut self, ratio: f32) -> OrbitCameraBuilder {
+        self.screen_aspect = ratio; self
+    }
+}
@@ -9,6 +9,7 @@ pub mod descriptor;
 pub mod memory;
 pub mod command;
 pub mod sync;
+pub mod query;
 
 
 use crate::context::VkDevice;
@@ -22,5 +23,8 @@ pub(crate) trait VulkanCI<CI>: Sized + AsRef<CI> {
 pub trait VkObjectBuildableCI {
     type ObjectType;
 
+    /// Create the object, which must be discarded before the device is destroyed.
+    ///
+    /// Implementations should be marked `#[track_caller]`, so that the leak report points to the caller.
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType>;
 }
@@ -33,6 +33,8 @@ use std::ptr;
 pub struct BufferCI {
     inner: vk::BufferCreateInfo,
     queue_families: Option<Vec<vkuint>>,
+    /// the debug name of the buffer.
+    name: Option<String>,
 }
 
 impl VulkanCI<vk::BufferCreateInfo> for BufferCI {
@@ -63,19 +65,25 @@ impl VkObjectBuildableCI for BufferCI {
     type ObjectType = (vk::Buffer, vk::MemoryRequirements);
 
     /// Create `vk::Buffer` object, and return its handle and memory requirement.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         debug_assert_ne!(self.inner.usage, vk::BufferUsageFlags::empty(), "the usage member of vk::BufferCreateInfo must not be 0!");
 
         let buffer = unsafe {
             device.logic.handle.create_buffer(self.as_ref(), None)
-                .map_err(|_| VkError::create("Buffer"))?
+                .map_err(|e| VkError::vk(e, "Create Buffer"))?
         };
 
         let requirement = unsafe {
             device.logic.handle.get_buffer_memory_requirements(buffer)
         };
 
+        if let Some(ref name) = self.name {
+            device.set_object_name(buffer, name)?;
+        }
+
+        device.tracker.track(buffer);
         Ok((buffer, requirement))
     }
 }
@@ -95,6 +103,7 @@ impl BufferCI {
                 ..BufferCI::default_ci()
             },
             queue_families: None,
+            name: None,
         }
     }
 
@@ -128,11 +137,45 @@ impl BufferCI {
         self.queue_families = Some(families_indices);
         self.inner.sharing_mode = vk::SharingMode::CONCURRENT; self
     }
+
+    /// Create `vk::Buffer` object, and bind it to a dedicated memory that supports `properties`.
+    ///
+    /// This is the allocation path without VMA. The buffer and memory must be discarded separately.
+    #[track_caller]
+    pub fn build_with_memory(&self, device: &VkDevice, properties: vk::MemoryPropertyFlags) -> VkResult<(vk::Buffer, vk::DeviceMemory)> {
+
+        let (buffer, requirement) = self.build(device)?;
+
+        let memory = match crate::ci::memory::allocate_dedicated_memory(device, &requirement, properties) {
+            | Ok(memory) => memory,
+            | Err(e) => {
+                device.discard(buffer);
+                return Err(e)
+            },
+        };
+
+        if let Err(e) = device.bind_memory(buffer, memory, 0) {
+            device.discard(buffer);
+            device.discard(memory);
+            return Err(e)
+        }
+
+        Ok((buffer, memory))
+    }
+
+    /// Set the name of buffer displayed in debugging tools. It is ignored if validation is disabled.
+    ///
+    /// The name only applies to the buffer created by `build` method.
+    #[inline(always)]
+    pub fn name(mut self, name: impl Into<String>) -> BufferCI {
+        self.name = Some(name.into()); self
+    }
 }
 
 impl VkObjectDiscardable for vk::Buffer {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_buffer(self, None)
         }
@@ -145,7 +188,7 @@ impl VkObjectBindable for vk::Buffer {
     fn bind(self, device: &VkDevice, memory: vk::DeviceMemory, offset: vkbytes) -> VkResult<()> {
         unsafe {
             device.logic.handle.bind_buffer_memory(self, memory, offset)
-                .map_err(|_| VkError::device("Binding Buffer Memory"))
+                .map_err(|e| VkError::vk(e, "Binding Buffer Memory"))
         }
     }
 }
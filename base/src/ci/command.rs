@@ -57,11 +57,12 @@ impl VkObjectBuildableCI for CommandBufferAI {
     type ObjectType = Vec<vk::CommandBuffer>;
 
     /// Create `vk::CommandBuffer` objects, and return their handles.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         let commands = unsafe {
             device.logic.handle.allocate_command_buffers(self.as_ref())
-                .map_err(|_| VkError::create("Command Buffers"))?
+                .map_err(|e| VkError::vk(e, "Allocate Command Buffers"))?
         };
         Ok(commands)
     }
@@ -161,12 +162,14 @@ impl VkObjectBuildableCI for CommandPoolCI {
     type ObjectType = vk::CommandPool;
 
     /// Create `vk::CommandPool` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         let pool = unsafe {
             device.logic.handle.create_command_pool(self.as_ref(), None)
-                .map_err(|_| VkError::create("Command Pool"))?
+                .map_err(|e| VkError::vk(e, "Create Command Pool"))?
         };
+        device.tracker.track(pool);
         Ok(pool)
     }
 }
@@ -198,6 +201,7 @@ impl CommandPoolCI {
 impl VkObjectDiscardable for vk::CommandPool {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_command_pool(self, None);
         }
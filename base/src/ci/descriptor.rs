@@ -61,14 +61,16 @@ impl VkObjectBuildableCI for DescriptorPoolCI {
     type ObjectType = vk::DescriptorPool;
 
     /// Create `vk::DescriptorPool` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         debug_assert!(!self.pool_sizes.is_empty(), "The count of pool sizes must be greater than 0!");
 
         let descriptor_pool = unsafe {
             device.logic.handle.create_descriptor_pool(self.as_ref(), None)
-                .map_err(|_| VkError::create("Descriptor Pool"))?
+                .map_err(|e| VkError::vk(e, "Create Descriptor Pool"))?
         };
+        device.tracker.track(descriptor_pool);
         Ok(descriptor_pool)
     }
 }
@@ -99,6 +101,23 @@ impl DescriptorPoolCI {
         self.inner.flags = flags; self
     }
 
+    /// Add `vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET` to the `flags` member of `vk::DescriptorPoolCreateInfo`.
+    ///
+    /// Freeing individual descriptor sets(by `VkDevice::free`) requires this flag, otherwise it triggers a validation error.
+    /// Use `VkDevice::reset_descriptor_pool` instead if all the sets of the pool are freed at once.
+    #[inline(always)]
+    pub fn allow_free_sets(mut self) -> DescriptorPoolCI {
+        self.inner.flags |= vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET; self
+    }
+
+    /// Add `vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND_EXT` to the `flags` member of `vk::DescriptorPoolCreateInfo`.
+    ///
+    /// The sets of layouts with `vk::DescriptorBindingFlagsEXT::UPDATE_AFTER_BIND` must be allocated from a pool with this flag.
+    #[inline(always)]
+    pub fn allow_update_after_bind(mut self) -> DescriptorPoolCI {
+        self.inner.flags |= vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND_EXT; self
+    }
+
     /// Add a new descriptor type that can be allocated by this pool.
     ///
     /// `type_` is the type of descriptor.
@@ -121,6 +140,7 @@ impl DescriptorPoolCI {
 impl VkObjectDiscardable for vk::DescriptorPool {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_descriptor_pool(self, None);
         }
@@ -149,6 +169,8 @@ impl VkObjectDiscardable for vk::DescriptorPool {
 pub struct DescriptorSetLayoutCI {
     inner: vk::DescriptorSetLayoutCreateInfo,
     bindings: Vec<vk::DescriptorSetLayoutBinding>,
+    /// the flags of each binding, which requires `VK_EXT_descriptor_indexing`.
+    binding_flags: Option<Vec<vk::DescriptorBindingFlagsEXT>>,
 }
 
 impl VulkanCI<vk::DescriptorSetLayoutCreateInfo> for DescriptorSetLayoutCI {
@@ -176,12 +198,46 @@ impl VkObjectBuildableCI for DescriptorSetLayoutCI {
     type ObjectType = vk::DescriptorSetLayout;
 
     /// Create `vk::DescriptorSetLayout` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
-        let descriptor_set_layout = unsafe {
-            device.logic.handle.create_descriptor_set_layout(self.as_ref(), None)
-                .map_err(|_| VkError::create("Descriptor Set Layout"))?
+        let descriptor_set_layout = if let Some(ref binding_flags) = self.binding_flags {
+
+            device.require_descriptor_indexing()?;
+            debug_assert_eq!(binding_flags.len(), self.bindings.len(), "The count of binding flags must match the count of bindings!");
+
+            let flags_ci = vk::DescriptorSetLayoutBindingFlagsCreateInfoEXT {
+                binding_count: binding_flags.len() as _,
+                p_binding_flags: binding_flags.as_ptr(),
+                ..Default::default()
+            };
+
+            // the layout with update-after-bind bindings must be created with the pool flag.
+            let is_update_after_bind = binding_flags.iter()
+                .any(|flags| flags.contains(vk::DescriptorBindingFlagsEXT::UPDATE_AFTER_BIND));
+            let layout_flags = if is_update_after_bind {
+                self.inner.flags | vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL_EXT
+            } else {
+                self.inner.flags
+            };
+
+            let layout_ci = vk::DescriptorSetLayoutCreateInfo {
+                p_next: &flags_ci as *const vk::DescriptorSetLayoutBindingFlagsCreateInfoEXT as *const _,
+                flags : layout_flags,
+                ..self.inner
+            };
+
+            unsafe {
+                device.logic.handle.create_descriptor_set_layout(&layout_ci, None)
+                    .map_err(|e| VkError::vk(e, "Create Descriptor Set Layout"))?
+            }
+        } else {
+            unsafe {
+                device.logic.handle.create_descriptor_set_layout(self.as_ref(), None)
+                    .map_err(|e| VkError::vk(e, "Create Descriptor Set Layout"))?
+            }
         };
+        device.tracker.track(descriptor_set_layout);
         Ok(descriptor_set_layout)
     }
 }
@@ -195,6 +251,7 @@ impl DescriptorSetLayoutCI {
         DescriptorSetLayoutCI {
             inner: DescriptorSetLayoutCI::default_ci(),
             bindings: Vec::new(),
+            binding_flags: None,
         }
     }
 
@@ -214,11 +271,31 @@ impl DescriptorSetLayoutCI {
     pub fn flags(mut self, flags: vk::DescriptorSetLayoutCreateFlags) -> DescriptorSetLayoutCI {
         self.inner.flags = flags; self
     }
+
+    /// Add `vk::DescriptorSetLayoutCreateFlags::PUSH_DESCRIPTOR_KHR` to the `flags` member of `vk::DescriptorSetLayoutCreateInfo`.
+    ///
+    /// The descriptors of this layout are updated by `VkCmdRecorder::push_descriptor_set` instead of allocating sets from pool.
+    #[inline(always)]
+    pub fn push_descriptor(mut self) -> DescriptorSetLayoutCI {
+        self.inner.flags |= vk::DescriptorSetLayoutCreateFlags::PUSH_DESCRIPTOR_KHR; self
+    }
+
+    /// Set the flags of each binding(etc. `UPDATE_AFTER_BIND | PARTIALLY_BOUND | VARIABLE_DESCRIPTOR_COUNT` for a bindless texture array).
+    ///
+    /// `flags` is in the same order as the bindings added by `add_binding`, and its length must match the count of bindings.
+    /// Only the last binding may use `VARIABLE_DESCRIPTOR_COUNT`, whose count is set by `DescriptorSetAI::variable_descriptor_counts`.
+    ///
+    /// Building the layout requires `DeviceExtensionType::DescriptorIndexing`, and the set must be allocated from a pool with `DescriptorPoolCI::allow_update_after_bind` if `UPDATE_AFTER_BIND` is used.
+    #[inline(always)]
+    pub fn binding_flags(mut self, flags: &[vk::DescriptorBindingFlagsEXT]) -> DescriptorSetLayoutCI {
+        self.binding_flags = Some(flags.to_vec()); self
+    }
 }
 
 impl VkObjectDiscardable for vk::DescriptorSetLayout {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_descriptor_set_layout(self, None);
         }
@@ -246,6 +323,8 @@ impl VkObjectDiscardable for vk::DescriptorSetLayout {
 pub struct DescriptorSetAI {
     inner: vk::DescriptorSetAllocateInfo,
     set_layouts: Vec<vk::DescriptorSetLayout>,
+    /// the descriptor count of the variable-sized binding of each set, which requires `VK_EXT_descriptor_indexing`.
+    variable_counts: Option<Vec<vkuint>>,
 }
 
 impl VulkanCI<vk::DescriptorSetAllocateInfo> for DescriptorSetAI {
@@ -273,13 +352,35 @@ impl VkObjectBuildableCI for DescriptorSetAI {
     type ObjectType = Vec<vk::DescriptorSet>;
 
     /// Create `vk::DescriptorSet` objects, and return their handles.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         debug_assert!(!self.set_layouts.is_empty(), "Descriptor sets count must be greater than 0!");
 
-        let descriptor_sets = unsafe {
-            device.logic.handle.allocate_descriptor_sets(self.as_ref())
-                .map_err(|_| VkError::create("Allocate Descriptor Set"))?
+        let descriptor_sets = if let Some(ref variable_counts) = self.variable_counts {
+
+            device.require_descriptor_indexing()?;
+            debug_assert_eq!(variable_counts.len(), self.set_layouts.len(), "The count of variable descriptor counts must match the count of set layouts!");
+
+            let counts_ai = vk::DescriptorSetVariableDescriptorCountAllocateInfoEXT {
+                descriptor_set_count: variable_counts.len() as _,
+                p_descriptor_counts : variable_counts.as_ptr(),
+                ..Default::default()
+            };
+            let sets_ai = vk::DescriptorSetAllocateInfo {
+                p_next: &counts_ai as *const vk::DescriptorSetVariableDescriptorCountAllocateInfoEXT as *const _,
+                ..self.inner
+            };
+
+            unsafe {
+                device.logic.handle.allocate_descriptor_sets(&sets_ai)
+                    .map_err(|e| VkError::vk(e, "Allocate Descriptor Set"))?
+            }
+        } else {
+            unsafe {
+                device.logic.handle.allocate_descriptor_sets(self.as_ref())
+                    .map_err(|e| VkError::vk(e, "Allocate Descriptor Set"))?
+            }
         };
         Ok(descriptor_sets)
     }
@@ -298,6 +399,7 @@ impl DescriptorSetAI {
                 ..DescriptorSetAI::default_ci()
             },
             set_layouts: Vec::new(),
+            variable_counts: None,
         }
     }
 
@@ -309,8 +411,17 @@ impl DescriptorSetAI {
         self.inner.descriptor_set_count = self.set_layouts.len() as _;
         self.inner.p_set_layouts        = self.set_layouts.as_ptr(); self
     }
+
+    /// Set the actual descriptor count of the `VARIABLE_DESCRIPTOR_COUNT` binding for each set, in the order of `add_set_layout`.
+    ///
+    /// Allocating the sets requires `DeviceExtensionType::DescriptorIndexing`.
+    #[inline(always)]
+    pub fn variable_descriptor_counts(mut self, counts: &[vkuint]) -> DescriptorSetAI {
+        self.variable_counts = Some(counts.to_vec()); self
+    }
 }
 
+/// Freeing descriptor sets requires the pool to be created with `DescriptorPoolCI::allow_free_sets`.
 impl VkObjectAllocatable for vk::DescriptorSet {
     type AllocatePool = vk::DescriptorPool;
 
@@ -542,6 +653,99 @@ impl DescriptorImageSetWI {
 }
 // ----------------------------------------------------------------------------------------------
 
+// ----------------------------------------------------------------------------------------------
+/// Wrapper class for `vk::CopyDescriptorSet`.
+///
+/// The default values are defined as follows:
+/// ``` ignore
+/// vk::CopyDescriptorSet {
+///     s_type: vk::StructureType::COPY_DESCRIPTOR_SET,
+///     p_next: ptr::null(),
+///     src_set: vk::DescriptorSet::null(),
+///     src_binding: 0,
+///     src_array_element: 0,
+///     dst_set: vk::DescriptorSet::null(),
+///     dst_binding: 0,
+///     dst_array_element: 0,
+///     descriptor_count : 1,
+/// }
+/// ```
+///
+/// See [VkCopyDescriptorSet](https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkCopyDescriptorSet.html) for more detail.
+///
+#[derive(Debug, Clone)]
+pub struct DescriptorSetCopyCI {
+    inner: vk::CopyDescriptorSet,
+}
+
+impl VulkanCI<vk::CopyDescriptorSet> for DescriptorSetCopyCI {
+
+    fn default_ci() -> vk::CopyDescriptorSet {
+
+        vk::CopyDescriptorSet {
+            s_type: vk::StructureType::COPY_DESCRIPTOR_SET,
+            p_next: ptr::null(),
+            src_set: vk::DescriptorSet::null(),
+            src_binding: 0,
+            src_array_element: 0,
+            dst_set: vk::DescriptorSet::null(),
+            dst_binding: 0,
+            dst_array_element: 0,
+            descriptor_count : 1,
+        }
+    }
+}
+
+impl AsRef<vk::CopyDescriptorSet> for DescriptorSetCopyCI {
+
+    fn as_ref(&self) -> &vk::CopyDescriptorSet {
+        &self.inner
+    }
+}
+
+impl DescriptorSetCopyCI {
+
+    /// Initialize `vk::CopyDescriptorSet` with default value.
+    ///
+    /// `src_set` and `src_binding` specify the descriptor binding to copy from.
+    ///
+    /// `dst_set` and `dst_binding` specify the descriptor binding to copy to.
+    pub fn new(src_set: vk::DescriptorSet, src_binding: vkuint, dst_set: vk::DescriptorSet, dst_binding: vkuint) -> DescriptorSetCopyCI {
+
+        DescriptorSetCopyCI {
+            inner: vk::CopyDescriptorSet {
+                src_set, src_binding, dst_set, dst_binding,
+                ..DescriptorSetCopyCI::default_ci()
+            },
+        }
+    }
+
+    /// Set the `descriptor_count` member for `vk::CopyDescriptorSet`.
+    ///
+    /// It is the number of descriptors to copy from source to destination.
+    #[inline(always)]
+    pub fn count(mut self, count: vkuint) -> DescriptorSetCopyCI {
+        self.inner.descriptor_count = count; self
+    }
+
+    /// Set the `src_array_element` and `dst_array_element` members for `vk::CopyDescriptorSet`.
+    ///
+    /// `src` and `dst` are the starting element index in the source and destination descriptor array.
+    #[inline(always)]
+    pub fn array_element(mut self, src: vkuint, dst: vkuint) -> DescriptorSetCopyCI {
+        self.inner.src_array_element = src;
+        self.inner.dst_array_element = dst; self
+    }
+}
+
+impl From<DescriptorSetCopyCI> for vk::CopyDescriptorSet {
+
+    fn from(v: DescriptorSetCopyCI) -> vk::CopyDescriptorSet {
+        v.inner
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
 // ----------------------------------------------------------------------------------------------
 /// Utility type to update descriptor set.
 #[derive(Default)]
@@ -577,6 +781,12 @@ impl<'a, 'b: 'a> DescriptorSetsUpdateCI<'a> {
         self.copies.push(value.clone()); self
     }
 
+    /// Add a `DescriptorSetCopyCI` to the descriptor update sequences.
+    #[inline(always)]
+    pub fn add_copy_ci(mut self, value: &'b DescriptorSetCopyCI) -> DescriptorSetsUpdateCI<'a> {
+        self.copies.push(value.as_ref().clone()); self
+    }
+
     /// Execute the descriptor sets update operations.
     #[inline(always)]
     pub fn update(self, device: &VkDevice) {
@@ -587,3 +797,63 @@ impl<'a, 'b: 'a> DescriptorSetsUpdateCI<'a> {
     }
 }
 // ----------------------------------------------------------------------------------------------
+
+// ----------------------------------------------------------------------------------------------
+/// Utility type that holds a descriptor set for each frame in flight.
+///
+/// A descriptor set must not be updated while a command buffer using it is still executing.
+/// Keep one set per frame in flight, and only bind and update the set of current frame.
+pub struct PerFrameDescriptorSet {
+
+    sets: Vec<vk::DescriptorSet>,
+    current_frame: usize,
+}
+
+impl PerFrameDescriptorSet {
+
+    /// Allocate `frames_in_flight` descriptor sets with the same `layout` from `pool`.
+    pub fn new(device: &VkDevice, pool: vk::DescriptorPool, layout: vk::DescriptorSetLayout, frames_in_flight: usize) -> VkResult<PerFrameDescriptorSet> {
+
+        debug_assert!(frames_in_flight > 0, "Frames in flight must be greater than 0!");
+
+        let layouts = vec![layout; frames_in_flight];
+        let sets = device.allocate_descriptor_sets(pool, &layouts)?;
+
+        let result = PerFrameDescriptorSet { sets, current_frame: 0 };
+        Ok(result)
+    }
+
+    /// Set the frame index that is currently being recorded.
+    ///
+    /// It should be the frame-in-flight index(etc. `FrameInFlight::frame_index`), not the image index returned by swapchain,
+    /// since only the fence of the frame in flight guarantees that its descriptor set is no longer used by GPU.
+    #[inline]
+    pub fn set_frame(&mut self, frame_index: usize) {
+        self.current_frame = frame_index % self.sets.len();
+    }
+
+    /// Return the descriptor set of current frame, which is safe to bind and update.
+    #[inline]
+    pub fn current(&self) -> vk::DescriptorSet {
+        self.sets[self.current_frame]
+    }
+
+    /// Return the descriptor sets of all frames.
+    #[inline]
+    pub fn sets(&self) -> &[vk::DescriptorSet] {
+        &self.sets
+    }
+
+    /// Update the buffer descriptor at `binding` of current frame's set.
+    #[inline]
+    pub fn update_buffer(&self, device: &VkDevice, binding: vkuint, type_: vk::DescriptorType, info: vk::DescriptorBufferInfo) {
+        device.update_descriptor_buffer(self.current(), binding, type_, info);
+    }
+
+    /// Update the image descriptor at `binding` of current frame's set.
+    #[inline]
+    pub fn update_image(&self, device: &VkDevice, binding: vkuint, type_: vk::DescriptorType, info: vk::DescriptorImageInfo) {
+        device.update_descriptor_image(self.current(), binding, type_, info);
+    }
+}
+// ----------------------------------------------------------------------------------------------
@@ -4,8 +4,9 @@ use ash::vk;
 use ash::version::DeviceV1_0;
 
 use crate::ci::VulkanCI;
-use crate::context::{VkSubmitCI, VkDevice};
+use crate::context::{VkSubmitCI, VkDevice, TimelineSemaphoreSubmitInfo};
 use crate::error::{VkResult, VkError};
+use crate::vklint;
 
 use std::ptr;
 
@@ -37,6 +38,9 @@ pub struct SubmitCI {
     wait_semaphores   : Option<Vec<vk::Semaphore>>,
     signal_semaphores : Option<Vec<vk::Semaphore>>,
     commands          : Vec<vk::CommandBuffer>,
+
+    /// the values of timeline semaphores, which are chained to `p_next`.
+    timeline_values: Option<Box<(Vec<vklint>, Vec<vklint>, TimelineSemaphoreSubmitInfo)>>,
 }
 
 impl VulkanCI<vk::SubmitInfo> for SubmitCI {
@@ -75,6 +79,7 @@ impl SubmitCI {
             wait_semaphores   : None,
             signal_semaphores : None,
             commands          : Vec::new(),
+            timeline_values   : None,
         }
     }
 
@@ -87,6 +92,15 @@ impl SubmitCI {
         self.inner.p_command_buffers    = self.commands.as_ptr(); self
     }
 
+    /// Add multiple command buffers to this submission, which are executed in the order of `commands`.
+    #[inline]
+    pub fn add_commands(mut self, commands: &[vk::CommandBuffer]) -> SubmitCI {
+
+        self.commands.extend_from_slice(commands);
+        self.inner.command_buffer_count = self.commands.len() as _;
+        self.inner.p_command_buffers    = self.commands.as_ptr(); self
+    }
+
     /// Add semaphore to wait before executing the command buffers.
     ///
     /// `semaphore` is the semaphore to wait.
@@ -118,6 +132,33 @@ impl SubmitCI {
         self.inner.signal_semaphore_count = signals.len() as _;
         self.inner.p_signal_semaphores    = signals.as_ptr() as _; self
     }
+
+    /// Set the values of timeline semaphores to wait and signal, in the same order as `add_wait` and `add_signal`.
+    ///
+    /// The length of `wait_values` and `signal_values` must be equal to the count of wait and signal semaphores,
+    /// and the values of binary semaphores are ignored. Call this method after all the semaphores have been added.
+    #[inline]
+    pub fn timeline_values(mut self, wait_values: Vec<vklint>, signal_values: Vec<vklint>) -> SubmitCI {
+
+        debug_assert_eq!(wait_values.len(), self.inner.wait_semaphore_count as usize, "The count of wait values must be equal to the count of wait semaphores.");
+        debug_assert_eq!(signal_values.len(), self.inner.signal_semaphore_count as usize, "The count of signal values must be equal to the count of signal semaphores.");
+
+        let submit_info = TimelineSemaphoreSubmitInfo::new(&wait_values, &signal_values);
+        // the heap memory of vectors does not move with the box.
+        let values = Box::new((wait_values, signal_values, submit_info));
+
+        self.inner.p_next = &values.2 as *const TimelineSemaphoreSubmitInfo as *const _;
+        self.timeline_values = Some(values); self
+    }
+
+    /// Check that each wait semaphore has its corresponding pipeline stage in `p_wait_dst_stage_mask`.
+    fn is_wait_aligned(&self) -> bool {
+
+        let stage_count = self.wait_stage.as_ref().map_or(0, |stages| stages.len());
+        let semaphore_count = self.wait_semaphores.as_ref().map_or(0, |semaphores| semaphores.len());
+
+        stage_count == semaphore_count && semaphore_count == self.inner.wait_semaphore_count as usize
+    }
 }
 
 impl VkSubmitCI for vk::SubmitInfo {
@@ -126,7 +167,7 @@ impl VkSubmitCI for vk::SubmitInfo {
 
         unsafe {
             device.logic.handle.queue_submit(queue, &[self], wait_fence.unwrap_or(vk::Fence::null()))
-                .map_err(|_| VkError::device("Queue Submit"))
+                .map_err(|e| VkError::vk(e, "Queue Submit"))
         }
     }
 }
@@ -140,7 +181,54 @@ impl VkSubmitCI for SubmitCI {
     /// `wait_fence` is an optional fence to be signaled after the executions of command buffers.
     fn submit(self, device: &VkDevice, queue: vk::Queue, wait_fence: Option<vk::Fence>) -> VkResult<()> {
 
+        debug_assert!(self.is_wait_aligned(), "The count of wait stages must be equal to the count of wait semaphores.");
         (self.as_ref()).submit(device, queue, wait_fence)
     }
 }
 // ----------------------------------------------------------------------------------------------
+
+// ----------------------------------------------------------------------------------------------
+/// A collection of `SubmitCI`, which are submitted as multiple batches in a single `vkQueueSubmit` call.
+///
+/// The batches begin execution in the order they are added(etc. a compute pass and then a graphics pass),
+/// but they may overlap unless they are synchronized by semaphores.
+#[derive(Debug, Default)]
+pub struct SubmitBatchesCI {
+
+    batches: Vec<SubmitCI>,
+}
+
+impl SubmitBatchesCI {
+
+    pub fn new() -> SubmitBatchesCI {
+        SubmitBatchesCI::default()
+    }
+
+    /// Add a batch of command buffers with its wait and signal semaphores.
+    #[inline]
+    pub fn add_batch(mut self, batch: SubmitCI) -> SubmitBatchesCI {
+        self.batches.push(batch); self
+    }
+}
+
+impl VkSubmitCI for SubmitBatchesCI {
+
+    /// Submit all batches to `queue` in one call.
+    ///
+    /// `wait_fence` is an optional fence to be signaled after the executions of all batches.
+    fn submit(self, device: &VkDevice, queue: vk::Queue, wait_fence: Option<vk::Fence>) -> VkResult<()> {
+
+        debug_assert!(self.batches.iter().all(SubmitCI::is_wait_aligned), "The count of wait stages must be equal to the count of wait semaphores.");
+
+        // the pointers in each vk::SubmitInfo refer to the vectors of `self.batches`, which live until the end of this function.
+        let submit_infos: Vec<vk::SubmitInfo> = self.batches.iter()
+            .map(|batch| *batch.as_ref())
+            .collect();
+
+        unsafe {
+            device.logic.handle.queue_submit(queue, &submit_infos, wait_fence.unwrap_or(vk::Fence::null()))
+                .map_err(|e| VkError::vk(e, "Queue Submit"))
+        }
+    }
+}
+// ----------------------------------------------------------------------------------------------
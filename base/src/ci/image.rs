@@ -5,6 +5,7 @@ use ash::version::DeviceV1_0;
 
 use crate::context::{VkDevice, VkObjectDiscardable, VkObjectBindable};
 use crate::ci::{VulkanCI, VkObjectBuildableCI};
+use crate::ci::vma::VkImageHandle;
 use crate::error::{VkResult, VkError};
 use crate::{vkbytes, vkuint, vkfloat};
 
@@ -78,19 +79,25 @@ impl VkObjectBuildableCI for ImageCI {
     type ObjectType = (vk::Image, vk::MemoryRequirements);
 
     /// Create `vk::Image` object, and return its handle and memory requirement.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         debug_assert_ne!(self.inner.usage, vk::ImageUsageFlags::empty(), "the usage member of vk::ImageCreateInfo must not be 0!");
 
+        if self.inner.image_type == vk::ImageType::TYPE_3D && self.inner.array_layers != 1 {
+            return Err(VkError::custom("The array_layers of 3D image must be 1."))
+        }
+
         let image = unsafe {
             device.logic.handle.create_image(self.as_ref(), None)
-                .map_err(|_| VkError::create("Image"))?
+                .map_err(|e| VkError::vk(e, "Create Image"))?
         };
 
         let requirement = unsafe {
             device.logic.handle.get_image_memory_requirements(image)
         };
 
+        device.tracker.track(image);
         Ok((image, requirement))
     }
 }
@@ -137,6 +144,17 @@ impl ImageCI {
         ImageCI::new(vk::ImageType::TYPE_2D, format, extent)
     }
 
+    /// Convenient method to create a 3D `ImageCI`(etc. a volume texture).
+    ///
+    /// `format` specifies the texel format of this image.
+    ///
+    /// `dimension` specifies dimension of the base level.
+    ///
+    /// The `array_layers` of a 3D image must be 1.
+    pub fn new_3d(format: vk::Format, dimension: vk::Extent3D) -> ImageCI {
+        ImageCI::new(vk::ImageType::TYPE_3D, format, dimension)
+    }
+
     /// Set the `flags` member for `vk::ImageCreateInfo`.
     ///
     /// It describes additional parameters of the image.
@@ -184,6 +202,7 @@ impl ImageCI {
     #[inline(always)]
     pub fn array_layers(mut self, layers: vkuint) -> ImageCI {
         debug_assert!(layers > 0, "The array_layers of image must be greater than 0!");
+        debug_assert!(self.inner.image_type != vk::ImageType::TYPE_3D || layers == 1, "The array_layers of 3D image must be 1!");
         self.inner.array_layers = layers; self
     }
 
@@ -209,11 +228,37 @@ impl ImageCI {
         self.queue_families = Some(families_indices);
         self.inner.sharing_mode = vk::SharingMode::CONCURRENT; self
     }
+
+    /// Create `vk::Image` object, and bind it to a dedicated memory that supports `properties`.
+    ///
+    /// This is the allocation path without VMA. The image and memory must be discarded separately.
+    #[track_caller]
+    pub fn build_with_memory(&self, device: &VkDevice, properties: vk::MemoryPropertyFlags) -> VkResult<(vk::Image, vk::DeviceMemory)> {
+
+        let (image, requirement) = self.build(device)?;
+
+        let memory = match crate::ci::memory::allocate_dedicated_memory(device, &requirement, properties) {
+            | Ok(memory) => memory,
+            | Err(e) => {
+                device.discard(image);
+                return Err(e)
+            },
+        };
+
+        if let Err(e) = device.bind_memory(image, memory, 0) {
+            device.discard(image);
+            device.discard(memory);
+            return Err(e)
+        }
+
+        Ok((image, memory))
+    }
 }
 
 impl VkObjectDiscardable for vk::Image {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_image(self, None);
         }
@@ -226,7 +271,7 @@ impl VkObjectBindable for vk::Image {
     fn bind(self, device: &VkDevice, memory: vk::DeviceMemory, offset: vkbytes) -> VkResult<()> {
         unsafe {
             device.logic.handle.bind_image_memory(self, memory, offset)
-                .map_err(|_| VkError::device("Binding Image Memory"))
+                .map_err(|e| VkError::vk(e, "Binding Image Memory"))
         }
     }
 }
@@ -306,12 +351,14 @@ impl VkObjectBuildableCI for ImageViewCI {
     type ObjectType = vk::ImageView;
 
     /// Create `vk::ImageView` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         let view = unsafe {
             device.logic.handle.create_image_view(self.as_ref(), None)
-                .map_err(|_| VkError::create("Image View"))?
+                .map_err(|e| VkError::vk(e, "Create Image View"))?
         };
+        device.tracker.track(view);
         Ok(view)
     }
 }
@@ -325,17 +372,25 @@ impl ImageViewCI {
     /// `type_` specifies the type of this image view.
     ///
     /// `format` specifies what texel format would this image view interpret to.
-    pub fn new(image: vk::Image, type_: vk::ImageViewType, format: vk::Format) -> ImageViewCI {
+    pub fn new(image: impl VkImageHandle, type_: vk::ImageViewType, format: vk::Format) -> ImageViewCI {
 
         ImageViewCI {
             inner: vk::ImageViewCreateInfo {
-                image, format,
+                image: image.image_handle(),
+                format,
                 view_type: type_,
                 ..ImageViewCI::default_ci()
             },
         }
     }
 
+    /// Convenient method to create a view of the whole 3D image, whose `view_type` is `vk::ImageViewType::TYPE_3D`.
+    ///
+    /// `image` must be created with `vk::ImageType::TYPE_3D`(see `ImageCI::new_3d`).
+    pub fn new_3d(image: impl VkImageHandle, format: vk::Format) -> ImageViewCI {
+        ImageViewCI::new(image, vk::ImageViewType::TYPE_3D, format)
+    }
+
     /// Set the `flags` member for `vk::ImageViewCreateInfo`.
     ///
     /// It describes additional parameters of the image view.
@@ -357,6 +412,7 @@ impl ImageViewCI {
     /// It specifies the levels and layers that this image view would access.
     #[inline(always)]
     pub fn sub_range(mut self, range: vk::ImageSubresourceRange) -> ImageViewCI {
+        debug_assert!(self.inner.view_type != vk::ImageViewType::TYPE_3D || range.layer_count == 1, "The layer_count of 3D image view must be 1!");
         self.inner.subresource_range = range; self
     }
 }
@@ -364,6 +420,7 @@ impl ImageViewCI {
 impl VkObjectDiscardable for vk::ImageView {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_image_view(self, None)
         }
@@ -430,11 +487,11 @@ impl ImageBarrierCI {
     /// `image` is the image affected by this barrier.
     ///
     /// `subrange` specifies the subresource range affected by this barrier.
-    pub fn new(image: vk::Image, subrange: vk::ImageSubresourceRange) -> ImageBarrierCI {
+    pub fn new(image: impl VkImageHandle, subrange: vk::ImageSubresourceRange) -> ImageBarrierCI {
 
         ImageBarrierCI {
             inner: vk::ImageMemoryBarrier {
-                image,
+                image: image.image_handle(),
                 subresource_range: subrange,
                 ..ImageBarrierCI::default_ci()
             },
@@ -539,12 +596,14 @@ impl VkObjectBuildableCI for SamplerCI {
     type ObjectType = vk::Sampler;
 
     /// Create `vk::Sampler` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         let sampler = unsafe {
             device.logic.handle.create_sampler(self.as_ref(), None)
-                .map_err(|_| VkError::create("Sampler"))?
+                .map_err(|e| VkError::vk(e, "Create Sampler"))?
         };
+        device.tracker.track(sampler);
         Ok(sampler)
     }
 }
@@ -675,6 +734,7 @@ impl SamplerCI {
 impl VkObjectDiscardable for vk::Sampler {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_sampler(self, None);
         }
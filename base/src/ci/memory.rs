@@ -54,12 +54,14 @@ impl VkObjectBuildableCI for MemoryAI {
     type ObjectType = vk::DeviceMemory;
 
     /// Allocate `vk::DeviceMemory` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         let memory = unsafe {
             device.logic.handle.allocate_memory(self.as_ref(), None)
-                .map_err(|_| VkError::create("Memory Allocate"))?
+                .map_err(|e| VkError::vk(e, "Allocate Memory"))?
         };
+        device.tracker.track(memory);
         Ok(memory)
     }
 }
@@ -82,9 +84,23 @@ impl MemoryAI {
     }
 }
 
+/// Allocate a dedicated `vk::DeviceMemory` for `requirement` from the first memory type supporting `properties`, without VMA.
+#[track_caller]
+pub(crate) fn allocate_dedicated_memory(device: &VkDevice, requirement: &vk::MemoryRequirements, properties: vk::MemoryPropertyFlags) -> VkResult<vk::DeviceMemory> {
+
+    use crate::utils::memory::find_memory_type_index;
+
+    let memory_type = find_memory_type_index(device, requirement.memory_type_bits, properties)
+        .ok_or(VkError::unsupported("Memory type of requested properties"))?;
+
+    MemoryAI::new(requirement.size, memory_type)
+        .build(device)
+}
+
 impl crate::context::VkObjectDiscardable for vk::DeviceMemory {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.free_memory(self, None);
         }
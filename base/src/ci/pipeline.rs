@@ -1,6 +1,7 @@
 
 pub use self::renderpass::{RenderPassCI, RenderPassBI};
 pub use self::renderpass::{AttachmentDescCI, SubpassDescCI, SubpassDependencyCI};
+pub use self::rendering::DynamicRenderingBI;
 
 pub use self::state::VertexInputSCI;
 pub use self::state::InputAssemblySCI;
@@ -13,6 +14,7 @@ pub use self::state::DynamicSCI;
 
 mod state;
 mod renderpass;
+mod rendering;
 
 
 
@@ -22,11 +24,14 @@ use ash::version::DeviceV1_0;
 
 use crate::context::VkDevice;
 use crate::context::VkObjectDiscardable;
+use crate::context::PipelineRenderingCreateInfo;
 use crate::ci::shader::ShaderStageCI;
 use crate::ci::{VulkanCI, VkObjectBuildableCI};
 use crate::error::{VkResult, VkError};
 use crate::vkuint;
 
+use std::collections::HashMap;
+use std::os::raw::c_void;
 use std::ptr;
 
 // ----------------------------------------------------------------------------------------------
@@ -65,12 +70,16 @@ impl AsRef<vk::PipelineLayoutCreateInfo> for PipelineLayoutCI {
 impl VkObjectBuildableCI for PipelineLayoutCI {
     type ObjectType = vk::PipelineLayout;
 
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
+        self.validate_push_constants(device.phy.limits.max_push_constants_size)?;
+
         let pipeline_layout = unsafe {
             device.logic.handle.create_pipeline_layout(self.as_ref(), None)
-                .map_err(|_| VkError::create("Pipeline Layout"))?
+                .map_err(|e| VkError::vk(e, "Create Pipeline Layout"))?
         };
+        device.tracker.track(pipeline_layout);
         Ok(pipeline_layout)
     }
 }
@@ -96,6 +105,10 @@ impl PipelineLayoutCI {
         self.inner.p_set_layouts    = set_layouts.as_ptr(); self
     }
 
+    /// Add a push constant range, which can be called multiple times for the ranges of different stages(etc. a vertex block and a fragment block).
+    ///
+    /// The stages of different ranges must not intersect, and each range must be within `maxPushConstantsSize` of the device.
+    /// These requirements are checked when building the pipeline layout.
     #[inline(always)]
     pub fn add_push_constants(mut self, range: vk::PushConstantRange) -> PipelineLayoutCI {
 
@@ -110,11 +123,45 @@ impl PipelineLayoutCI {
     pub fn flags(mut self, flags: vk::PipelineLayoutCreateFlags) -> PipelineLayoutCI {
         self.inner.flags = flags; self
     }
+
+    /// Check the push constant ranges against the valid usage of `vk::PipelineLayoutCreateInfo`.
+    fn validate_push_constants(&self, max_size: vkuint) -> VkResult<()> {
+
+        let ranges = match self.push_constants {
+            | Some(ref ranges) => ranges,
+            | None => return Ok(()),
+        };
+
+        for (i, range) in ranges.iter().enumerate() {
+
+            if range.size == 0 || range.offset % 4 != 0 || range.size % 4 != 0 {
+                return Err(VkError::custom(format!(
+                    "Push constant range {}(offset: {}, size: {}) must have non-zero size, and both offset and size must be multiples of 4.",
+                    i, range.offset, range.size)))
+            }
+
+            if range.offset + range.size > max_size {
+                return Err(VkError::custom(format!(
+                    "Push constant range {}(offset: {}, size: {}) exceeds the maxPushConstantsSize({} bytes) of device.",
+                    i, range.offset, range.size, max_size)))
+            }
+
+            // the ranges of the same stage would overlap, so any two ranges must not share a stage.
+            if let Some((j, other)) = ranges.iter().enumerate().skip(i + 1).find(|(_, other)| other.stage_flags.intersects(range.stage_flags)) {
+                return Err(VkError::custom(format!(
+                    "Push constant range {} and {} are both used by {:?}, merge them into one range instead.",
+                    i, j, range.stage_flags & other.stage_flags)))
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl VkObjectDiscardable for vk::PipelineLayout {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_pipeline_layout(self, None);
         }
@@ -159,12 +206,14 @@ impl AsRef<vk::FramebufferCreateInfo> for FramebufferCI {
 impl VkObjectBuildableCI for FramebufferCI {
     type ObjectType = vk::Framebuffer;
 
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         let framebuffer = unsafe {
             device.logic.handle.create_framebuffer(self.as_ref(), None)
-                .map_err(|_| VkError::create("Framebuffer"))?
+                .map_err(|e| VkError::vk(e, "Create Framebuffer"))?
         };
+        device.tracker.track(framebuffer);
         Ok(framebuffer)
     }
 }
@@ -212,21 +261,12 @@ impl FramebufferCI {
 impl VkObjectDiscardable for vk::Framebuffer {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_framebuffer(self, None);
         }
     }
 }
-
-impl VkObjectDiscardable for &Vec<vk::Framebuffer> {
-
-    fn discard_by(self, device: &VkDevice) {
-
-        for framebuffer in self {
-            device.discard(*framebuffer);
-        }
-    }
-}
 // ---------------------------------------------------------------------------------------------
 
 // ----------------------------------------------------------------------------------------------
@@ -247,6 +287,11 @@ pub struct GraphicsPipelineCI<'a> {
 
     cache: Option<vk::PipelineCache>,
     shader_stages: Vec<vk::PipelineShaderStageCreateInfo>,
+    /// the debug name of the pipeline.
+    name: Option<String>,
+    /// the attachment formats of dynamic rendering, or `None` if the pipeline is used in a render pass.
+    rendering: Option<PipelineRenderingCreateInfo>,
+    rendering_color_formats: Vec<vk::Format>,
 
     phantom_type: ::std::marker::PhantomData<&'a ()>,
 }
@@ -254,27 +299,24 @@ pub struct GraphicsPipelineCI<'a> {
 impl<'a> VkObjectBuildableCI for GraphicsPipelineCI<'a> {
     type ObjectType = vk::Pipeline;
 
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
-        let pipeline_ci = vk::GraphicsPipelineCreateInfo {
-            stage_count            : self.shader_stages.len() as _,
-            p_stages               : self.shader_stages.as_ptr(),
-            p_vertex_input_state   : self.vertex_input.as_ref(),
-            p_input_assembly_state : self.input_assembly.as_ref(),
-            p_tessellation_state   : ptr::null(), // this field is not cover yet.
-            p_viewport_state       : self.viewport.as_ref(),
-            p_rasterization_state  : self.rasterization.as_ref(),
-            p_multisample_state    : self.multisample.as_ref(),
-            p_depth_stencil_state  : self.depth_stencil.as_ref(),
-            p_color_blend_state    : self.color_blend.as_ref(),
-            p_dynamic_state        : self.dynamics.as_ref(),
-            ..self.inner
-        };
+        if self.rendering.is_some() {
+            device.dynamic_rendering_fn()?;
+        }
+
+        let pipeline_ci = self.pipeline_ci();
 
         let pipeline = unsafe {
             device.logic.handle.create_graphics_pipelines(self.cache.unwrap_or(device.pipeline_cache), &[pipeline_ci], None)
-                .map_err(|_| VkError::create("Graphics Pipeline"))?
+                .map_err(|(_, e)| VkError::vk(e, "Create Graphics Pipeline"))?
         }.remove(0);
+        device.tracker.track(pipeline);
+
+        if let Some(ref name) = self.name {
+            device.set_object_name(pipeline, name)?;
+        }
 
         Ok(pipeline)
     }
@@ -301,10 +343,34 @@ impl<'b, 'a: 'b> GraphicsPipelineCI<'a> {
             multisample    : MultisampleSCI::new(),
             dynamics       : DynamicSCI::new(),
             cache: None,
+            name : None,
+            rendering: None,
+            rendering_color_formats: Vec::new(),
             phantom_type: ::std::marker::PhantomData,
         }
     }
 
+    /// Generate vk::GraphicsPipelineCreateInfo whose pointers refer to the states of `self`.
+    fn pipeline_ci(&self) -> vk::GraphicsPipelineCreateInfo {
+
+        vk::GraphicsPipelineCreateInfo {
+            stage_count            : self.shader_stages.len() as _,
+            p_stages               : self.shader_stages.as_ptr(),
+            p_vertex_input_state   : self.vertex_input.as_ref(),
+            p_input_assembly_state : self.input_assembly.as_ref(),
+            p_tessellation_state   : ptr::null(), // this field is not cover yet.
+            p_viewport_state       : self.viewport.as_ref(),
+            p_rasterization_state  : self.rasterization.as_ref(),
+            p_multisample_state    : self.multisample.as_ref(),
+            p_depth_stencil_state  : self.depth_stencil.as_ref(),
+            p_color_blend_state    : self.color_blend.as_ref(),
+            p_dynamic_state        : self.dynamics.as_ref(),
+            p_next: self.rendering.as_ref()
+                .map_or(ptr::null(), |rendering| rendering as *const PipelineRenderingCreateInfo as *const c_void),
+            ..self.inner
+        }
+    }
+
     #[inline(always)]
     pub fn set_use_subpass(&mut self, subpass: vkuint) {
         self.inner.subpass = subpass
@@ -315,6 +381,12 @@ impl<'b, 'a: 'b> GraphicsPipelineCI<'a> {
         self.inner.base_pipeline_handle = pipeline;
     }
 
+    /// Use the pipeline at `index` in the same `VkDevice::build_pipelines` call as the base pipeline.
+    #[inline(always)]
+    pub fn set_base_pipeline_index(&mut self, index: i32) {
+        self.inner.base_pipeline_index = index;
+    }
+
     #[inline(always)]
     pub fn set_flags(&mut self, flags: vk::PipelineCreateFlags) {
         self.inner.flags = flags;
@@ -372,11 +444,31 @@ impl<'b, 'a: 'b> GraphicsPipelineCI<'a> {
     pub fn set_pipeline_cache(&mut self, cache: vk::PipelineCache) {
         self.cache = Some(cache);
     }
+
+    /// Set the name of pipeline displayed in debugging tools(etc. "phong pipeline"). It is ignored if validation is disabled.
+    #[inline(always)]
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = Some(name.into());
+    }
+
+    /// Use this pipeline in dynamic rendering(see `DynamicRenderingBI`) instead of a render pass.
+    ///
+    /// The formats must match the attachments of `DynamicRenderingBI`, and use `vk::Format::UNDEFINED` for unused depth or stencil attachment.
+    /// The render pass of this pipeline is reset to null, and building it requires `DeviceExtensionType::DynamicRendering`.
+    pub fn set_rendering_formats(&mut self, color_formats: &[vk::Format], depth_format: vk::Format, stencil_format: vk::Format) {
+
+        self.rendering_color_formats = color_formats.to_vec();
+        self.rendering = Some(PipelineRenderingCreateInfo::new(&self.rendering_color_formats, depth_format, stencil_format));
+
+        self.inner.render_pass = vk::RenderPass::null();
+        self.inner.subpass = 0;
+    }
 }
 
 impl VkObjectDiscardable for vk::Pipeline {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_pipeline(self, None);
         }
@@ -384,6 +476,273 @@ impl VkObjectDiscardable for vk::Pipeline {
 }
 // ----------------------------------------------------------------------------------------------
 
+// ----------------------------------------------------------------------------------------------
+/// Wrapper class for vk::ComputePipelineCreateInfo.
+#[derive(Debug)]
+pub struct ComputePipelineCI<'a> {
+
+    inner: vk::ComputePipelineCreateInfo,
+    cache: Option<vk::PipelineCache>,
+
+    phantom_type: ::std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> VulkanCI<vk::ComputePipelineCreateInfo> for ComputePipelineCI<'a> {
+
+    fn default_ci() -> vk::ComputePipelineCreateInfo {
+
+        vk::ComputePipelineCreateInfo {
+            s_type: vk::StructureType::COMPUTE_PIPELINE_CREATE_INFO,
+            p_next: ptr::null(),
+            flags : vk::PipelineCreateFlags::empty(),
+            stage : vk::PipelineShaderStageCreateInfo::default(),
+            layout: vk::PipelineLayout::null(),
+            base_pipeline_handle: vk::Pipeline::null(),
+            base_pipeline_index : -1,
+        }
+    }
+}
+
+impl<'a> AsRef<vk::ComputePipelineCreateInfo> for ComputePipelineCI<'a> {
+
+    fn as_ref(&self) -> &vk::ComputePipelineCreateInfo {
+        &self.inner
+    }
+}
+
+impl<'a> VkObjectBuildableCI for ComputePipelineCI<'a> {
+    type ObjectType = vk::Pipeline;
+
+    #[track_caller]
+    fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
+
+        let pipeline = unsafe {
+            device.logic.handle.create_compute_pipelines(self.cache.unwrap_or(device.pipeline_cache), &[self.inner], None)
+                .map_err(|(_, e)| VkError::vk(e, "Create Compute Pipeline"))?
+        }.remove(0);
+        device.tracker.track(pipeline);
+
+        Ok(pipeline)
+    }
+}
+
+impl<'a> ComputePipelineCI<'a> {
+
+    /// `shader` must be a `COMPUTE` stage, and it must outlive this create info.
+    pub fn new(shader: &'a ShaderStageCI, pipeline_layout: vk::PipelineLayout) -> ComputePipelineCI<'a> {
+
+        ComputePipelineCI {
+            inner: vk::ComputePipelineCreateInfo {
+                stage: shader.as_ref().clone(),
+                layout: pipeline_layout,
+                ..ComputePipelineCI::default_ci()
+            },
+            cache: None,
+            phantom_type: ::std::marker::PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub fn flags(mut self, flags: vk::PipelineCreateFlags) -> ComputePipelineCI<'a> {
+        self.inner.flags = flags; self
+    }
+
+    #[inline(always)]
+    pub fn base_pipeline(mut self, pipeline: vk::Pipeline) -> ComputePipelineCI<'a> {
+        self.inner.base_pipeline_handle = pipeline; self
+    }
+
+    #[inline(always)]
+    pub fn pipeline_cache(mut self, cache: vk::PipelineCache) -> ComputePipelineCI<'a> {
+        self.cache = Some(cache); self
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+/// Create the pipelines of `cis` in one `vkCreateGraphicsPipelines` call.
+///
+/// The pipeline cache of the first `GraphicsPipelineCI` is used for the whole batch.
+#[track_caller]
+pub(crate) fn build_graphics_pipelines(device: &VkDevice, cis: &[&GraphicsPipelineCI]) -> VkResult<Vec<vk::Pipeline>> {
+
+    if cis.is_empty() {
+        return Ok(Vec::new())
+    }
+
+    if cis.iter().any(|ci| ci.rendering.is_some()) {
+        device.dynamic_rendering_fn()?;
+    }
+
+    let pipeline_cis: Vec<vk::GraphicsPipelineCreateInfo> = cis.iter()
+        .map(|ci| ci.pipeline_ci())
+        .collect();
+    let cache = cis[0].cache.unwrap_or(device.pipeline_cache);
+
+    let pipelines = unsafe {
+        device.logic.handle.create_graphics_pipelines(cache, &pipeline_cis, None)
+            .map_err(|(pipelines, e)| {
+                destroy_partial_pipelines(device, pipelines);
+                VkError::vk(e, "Create Graphics Pipelines")
+            })?
+    };
+
+    for (ci, &pipeline) in cis.iter().zip(pipelines.iter()) {
+        device.tracker.track(pipeline);
+        if let Some(ref name) = ci.name {
+            device.set_object_name(pipeline, name)?;
+        }
+    }
+
+    Ok(pipelines)
+}
+// ----------------------------------------------------------------------------------------------
+
+// ----------------------------------------------------------------------------------------------
+/// The key to identify a pipeline created by `PipelineVariants`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PipelineVariant {
+    pub cull_mode: vk::CullModeFlags,
+    pub polygon_mode: vk::PolygonMode,
+    /// the index of color blend state in the order of `PipelineVariants::add_color_blend`.
+    pub color_blend: usize,
+}
+
+/// A helper to create all the combinations of cull mode, polygon mode and color blend states based on a `GraphicsPipelineCI`.
+///
+/// All the pipelines are created in one `vkCreateGraphicsPipelines` call.
+/// If no override is added for a state, the state of the base pipeline is used(and the `color_blend` index of the key is 0).
+pub struct PipelineVariants<'a, 'b> {
+
+    base: &'b GraphicsPipelineCI<'a>,
+
+    cull_modes   : Vec<vk::CullModeFlags>,
+    polygon_modes: Vec<vk::PolygonMode>,
+    color_blends : Vec<ColorBlendSCI>,
+}
+
+impl<'a, 'b> VkObjectBuildableCI for PipelineVariants<'a, 'b> {
+    type ObjectType = HashMap<PipelineVariant, vk::Pipeline>;
+
+    #[track_caller]
+    fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
+
+        // duplicated overrides would produce pipelines with the same key, and the replaced ones would be leaked.
+        if has_duplicate(&self.cull_modes) {
+            return Err(VkError::custom("Duplicated cull mode is added to PipelineVariants."))
+        }
+        if has_duplicate(&self.polygon_modes) {
+            return Err(VkError::custom("Duplicated polygon mode is added to PipelineVariants."))
+        }
+
+        let base_rasterization: &vk::PipelineRasterizationStateCreateInfo = self.base.rasterization.as_ref();
+
+        let cull_modes = if self.cull_modes.is_empty() {
+            vec![base_rasterization.cull_mode]
+        } else {
+            self.cull_modes.clone()
+        };
+        let polygon_modes = if self.polygon_modes.is_empty() {
+            vec![base_rasterization.polygon_mode]
+        } else {
+            self.polygon_modes.clone()
+        };
+        let color_blends: Vec<&vk::PipelineColorBlendStateCreateInfo> = if self.color_blends.is_empty() {
+            vec![self.base.color_blend.as_ref()]
+        } else {
+            self.color_blends.iter().map(|blend| blend.as_ref()).collect()
+        };
+
+        let mut variants = Vec::with_capacity(cull_modes.len() * polygon_modes.len() * color_blends.len());
+        let mut rasterizations = Vec::with_capacity(variants.capacity());
+
+        for &cull_mode in cull_modes.iter() {
+            for &polygon_mode in polygon_modes.iter() {
+                for color_blend in 0..color_blends.len() {
+
+                    variants.push(PipelineVariant { cull_mode, polygon_mode, color_blend });
+                    rasterizations.push(vk::PipelineRasterizationStateCreateInfo {
+                        cull_mode, polygon_mode,
+                        ..*base_rasterization
+                    });
+                }
+            }
+        }
+
+        // `rasterizations` will not be reallocated from now on, so it is safe to refer to its elements.
+        let pipeline_cis: Vec<vk::GraphicsPipelineCreateInfo> = variants.iter().zip(rasterizations.iter())
+            .map(|(variant, rasterization)| {
+                vk::GraphicsPipelineCreateInfo {
+                    p_rasterization_state: rasterization,
+                    p_color_blend_state  : color_blends[variant.color_blend],
+                    ..self.base.pipeline_ci()
+                }
+            }).collect();
+
+        let pipelines = unsafe {
+            device.logic.handle.create_graphics_pipelines(self.base.cache.unwrap_or(device.pipeline_cache), &pipeline_cis, None)
+                .map_err(|(pipelines, e)| {
+                    destroy_partial_pipelines(device, pipelines);
+                    VkError::vk(e, "Create Graphics Pipeline Variants")
+                })?
+        };
+        for &pipeline in pipelines.iter() {
+            device.tracker.track(pipeline);
+        }
+
+        let result = variants.into_iter().zip(pipelines.into_iter()).collect();
+        Ok(result)
+    }
+}
+
+impl<'a, 'b> PipelineVariants<'a, 'b> {
+
+    pub fn new(base: &'b GraphicsPipelineCI<'a>) -> PipelineVariants<'a, 'b> {
+
+        PipelineVariants {
+            base,
+            cull_modes   : Vec::new(),
+            polygon_modes: Vec::new(),
+            color_blends : Vec::new(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn add_cull_mode(mut self, mode: vk::CullModeFlags) -> PipelineVariants<'a, 'b> {
+        self.cull_modes.push(mode); self
+    }
+
+    #[inline(always)]
+    pub fn add_polygon_mode(mut self, mode: vk::PolygonMode) -> PipelineVariants<'a, 'b> {
+        self.polygon_modes.push(mode); self
+    }
+
+    #[inline(always)]
+    pub fn add_color_blend(mut self, sci: ColorBlendSCI) -> PipelineVariants<'a, 'b> {
+        self.color_blends.push(sci); self
+    }
+
+    /// The number of pipelines that will be created.
+    pub fn variant_count(&self) -> usize {
+        self.cull_modes.len().max(1) * self.polygon_modes.len().max(1) * self.color_blends.len().max(1)
+    }
+}
+
+fn has_duplicate<T: PartialEq>(values: &[T]) -> bool {
+    values.iter().enumerate()
+        .any(|(i, value)| values[(i + 1)..].contains(value))
+}
+
+/// Destroy the pipelines which have been created successfully before a batched creation fails.
+fn destroy_partial_pipelines(device: &VkDevice, pipelines: Vec<vk::Pipeline>) {
+
+    for pipeline in pipelines.into_iter().filter(|&p| p != vk::Pipeline::null()) {
+        unsafe {
+            device.logic.handle.destroy_pipeline(pipeline, None);
+        }
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
 // ----------------------------------------------------------------------------------------------
 // Wrapper class for vk::PipelineCacheCreateInfo.
 #[derive(Debug, Clone)]
@@ -426,17 +785,22 @@ impl PipelineCacheCI {
         self.inner.flags = flags; self
     }
 
+    #[track_caller]
     pub fn build(&self, device: &VkDevice) -> VkResult<vk::PipelineCache> {
-        unsafe {
+
+        let cache = unsafe {
             device.logic.handle.create_pipeline_cache(self.as_ref(), None)
-                .map_err(|_| VkError::create("Graphics Cache"))
-        }
+                .map_err(|e| VkError::vk(e, "Create Graphics Cache"))?
+        };
+        device.tracker.track(cache);
+        Ok(cache)
     }
 }
 
 impl VkObjectDiscardable for vk::PipelineCache {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_pipeline_cache(self, None);
         }
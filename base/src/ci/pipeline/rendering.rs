@@ -0,0 +1,137 @@
+
+use ash::vk;
+
+use crate::context::{RenderingInfo, RenderingAttachmentInfo};
+use crate::vkuint;
+
+// ----------------------------------------------------------------------------------------------
+/// Wrapper class for `VkRenderingInfo`, which begins a dynamic rendering without `vk::RenderPass` and `vk::Framebuffer`.
+///
+/// Request `DeviceExtensionType::DynamicRendering` in `PhysicalDevConfig` to use it.
+///
+/// The default values are defined as follows:
+/// ``` ignore
+/// VkRenderingInfo {
+///     s_type: vk::StructureType::RENDERING_INFO,
+///     p_next: ptr::null(),
+///     flags : 0,
+///     render_area: vk::Rect2D {
+///         extent: vk::Extent2D { width : 0, height: 0 },
+///         offset: vk::Offset2D { x: 0, y: 0 },
+///     },
+///     layer_count: 1,
+///     view_mask  : 0,
+///     color_attachment_count: 0,
+///     p_color_attachments   : ptr::null(),
+///     p_depth_attachment    : ptr::null(),
+///     p_stencil_attachment  : ptr::null(),
+/// }
+/// ```
+///
+/// See [VkRenderingInfo](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkRenderingInfo.html) for more detail.
+///
+pub struct DynamicRenderingBI {
+
+    inner: RenderingInfo,
+
+    colors : Vec<RenderingAttachmentInfo>,
+    depth  : Option<Box<RenderingAttachmentInfo>>,
+    stencil: Option<Box<RenderingAttachmentInfo>>,
+}
+
+impl DynamicRenderingBI {
+
+    /// Initialize `VkRenderingInfo` with default value.
+    ///
+    /// `extent` is the dimension of render area, which is usually the dimension of swapchain.
+    pub fn new(extent: vk::Extent2D) -> DynamicRenderingBI {
+
+        let mut inner = RenderingInfo::default();
+        inner.render_area.extent = extent;
+
+        DynamicRenderingBI {
+            inner,
+            colors : Vec::new(),
+            depth  : None,
+            stencil: None,
+        }
+    }
+
+    /// Set the offset of render area. Default is 0 for both x, y coordinates.
+    #[inline(always)]
+    pub fn render_area_offset(mut self, offset: vk::Offset2D) -> DynamicRenderingBI {
+        self.inner.render_area.offset = offset; self
+    }
+
+    /// Set the number of layers rendered to in each attachment. Default is 1.
+    #[inline(always)]
+    pub fn layer_count(mut self, count: vkuint) -> DynamicRenderingBI {
+        debug_assert!(count > 0, "The layer_count of dynamic rendering must be greater than 0!");
+        self.inner.layer_count = count; self
+    }
+
+    /// Add a color attachment, whose location in fragment shader is the order of adding.
+    ///
+    /// `clear` is only used if `load_op` is `vk::AttachmentLoadOp::CLEAR`.
+    #[inline]
+    pub fn add_color_attachment(mut self, view: vk::ImageView, layout: vk::ImageLayout, load_op: vk::AttachmentLoadOp, store_op: vk::AttachmentStoreOp, clear: vk::ClearValue) -> DynamicRenderingBI {
+
+        self.colors.push(RenderingAttachmentInfo {
+            load_op, store_op,
+            clear_value: clear,
+            ..RenderingAttachmentInfo::new(view, layout)
+        });
+
+        self.inner.color_attachment_count = self.colors.len() as _;
+        self.inner.p_color_attachments    = self.colors.as_ptr(); self
+    }
+
+    /// Resolve the multisampled color attachment at `index` to `view` at the end of rendering, by averaging the samples.
+    #[inline]
+    pub fn resolve_color_attachment(mut self, index: usize, view: vk::ImageView, layout: vk::ImageLayout) -> DynamicRenderingBI {
+
+        // `VK_RESOLVE_MODE_AVERAGE_BIT`.
+        const RESOLVE_MODE_AVERAGE: vkuint = 0x0000_0002;
+
+        let color = &mut self.colors[index];
+        color.resolve_mode = RESOLVE_MODE_AVERAGE;
+        color.resolve_image_view   = view;
+        color.resolve_image_layout = layout; self
+    }
+
+    /// Set the depth attachment.
+    ///
+    /// `clear` is only used if `load_op` is `vk::AttachmentLoadOp::CLEAR`.
+    pub fn depth_attachment(mut self, view: vk::ImageView, layout: vk::ImageLayout, load_op: vk::AttachmentLoadOp, store_op: vk::AttachmentStoreOp, clear: vk::ClearValue) -> DynamicRenderingBI {
+
+        let depth = Box::new(RenderingAttachmentInfo {
+            load_op, store_op,
+            clear_value: clear,
+            ..RenderingAttachmentInfo::new(view, layout)
+        });
+
+        self.inner.p_depth_attachment = depth.as_ref();
+        self.depth = Some(depth); self
+    }
+
+    /// Set the stencil attachment, which is usually the same view as depth attachment for the packed depth stencil formats.
+    ///
+    /// `clear` is only used if `load_op` is `vk::AttachmentLoadOp::CLEAR`.
+    pub fn stencil_attachment(mut self, view: vk::ImageView, layout: vk::ImageLayout, load_op: vk::AttachmentLoadOp, store_op: vk::AttachmentStoreOp, clear: vk::ClearValue) -> DynamicRenderingBI {
+
+        let stencil = Box::new(RenderingAttachmentInfo {
+            load_op, store_op,
+            clear_value: clear,
+            ..RenderingAttachmentInfo::new(view, layout)
+        });
+
+        self.inner.p_stencil_attachment = stencil.as_ref();
+        self.stencil = Some(stencil); self
+    }
+
+    #[inline]
+    pub(crate) fn rendering_info(&self) -> &RenderingInfo {
+        &self.inner
+    }
+}
+// ----------------------------------------------------------------------------------------------
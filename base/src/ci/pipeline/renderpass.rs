@@ -33,13 +33,29 @@ use std::ptr;
 ///
 /// See [VkRenderPassBeginInfo](https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkRenderPassBeginInfo.html) for more detail.
 ///
-#[derive(Clone)]
 pub struct RenderPassBI {
 
     inner: vk::RenderPassBeginInfo,
+    /// the storage of clear values, which `inner.p_clear_values` always points to.
     clears: Option<Vec<vk::ClearValue>>,
 }
 
+impl Clone for RenderPassBI {
+
+    fn clone(&self) -> RenderPassBI {
+
+        // the derived clone would copy `p_clear_values` that points to the clear values of `self`,
+        // which dangles once `self` is dropped, so point it to the cloned clear values instead.
+        let clears = self.clears.clone();
+        let inner = vk::RenderPassBeginInfo {
+            p_clear_values: clears.as_ref().map_or(ptr::null(), |values| values.as_ptr()),
+            ..self.inner
+        };
+
+        RenderPassBI { inner, clears }
+    }
+}
+
 impl VulkanCI<vk::RenderPassBeginInfo> for RenderPassBI {
 
     fn default_ci() -> vk::RenderPassBeginInfo {
@@ -118,6 +134,14 @@ impl RenderPassBI {
 
         self.clears.replace(values); self
     }
+
+    /// Set all the clear values for attachments used in this render pass.
+    ///
+    /// `values` is copied, so it does not need to outlive this `RenderPassBI`(etc. a temporary array is acceptable).
+    #[inline]
+    pub fn clear_values(self, values: &[vk::ClearValue]) -> RenderPassBI {
+        self.set_clear_values(values.to_vec())
+    }
 }
 // ----------------------------------------------------------------------------------------------
 
@@ -151,6 +175,8 @@ pub struct RenderPassCI {
     dependencies: Option<Vec<vk::SubpassDependency>>,
 
     subpass_cis: Vec<SubpassDescCI>,
+    /// the debug name of the render pass.
+    name: Option<String>,
 }
 
 impl VulkanCI<vk::RenderPassCreateInfo> for RenderPassCI {
@@ -182,12 +208,21 @@ impl VkObjectBuildableCI for RenderPassCI {
     type ObjectType = vk::RenderPass;
 
     /// Create `vk::RenderPass` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
+        self.validate()?;
+
         let render_pass = unsafe {
             device.logic.handle.create_render_pass(self.as_ref(), None)
-                .map_err(|_| VkError::create("Render Pass"))?
+                .map_err(|e| VkError::vk(e, "Create Render Pass"))?
         };
+
+        if let Some(ref name) = self.name {
+            device.set_object_name(render_pass, name)?;
+        }
+
+        device.tracker.track(render_pass);
         Ok(render_pass)
     }
 }
@@ -203,6 +238,7 @@ impl RenderPassCI {
             subpasses   : Vec::new(),
             dependencies: None,
             subpass_cis : Vec::new(),
+            name        : None,
         }
     }
 
@@ -230,6 +266,61 @@ impl RenderPassCI {
         self.inner.p_subpasses   = self.subpasses.as_ptr(); self
     }
 
+    /// Add a graphics subpass that reads `inputs` as input attachments, and writes to `colors` and `depth_stencil`.
+    ///
+    /// All the values are the indices of attachments added by `add_attachment`.
+    /// The dependencies from the previous subpasses that write to `inputs` are added automatically,
+    /// so that the input attachments are read after they have been written.
+    ///
+    /// For example, a deferred renderer with a G-buffer subpass followed by a lighting subpass:
+    /// ``` ignore
+    /// // attachment 0: swapchain image, 1: position, 2: normal, 3: albedo, 4: depth.
+    /// let render_pass = RenderPassCI::new()
+    ///     .add_attachment(swapchain_attachment)
+    ///     .add_attachment(position_attachment)
+    ///     .add_attachment(normal_attachment)
+    ///     .add_attachment(albedo_attachment)
+    ///     .add_attachment(depth_attachment)
+    ///     // subpass 0 fills the G-buffer.
+    ///     .add_subpass_with_inputs(&[], &[1, 2, 3], Some(4))
+    ///     // subpass 1 composes the G-buffer to the swapchain image.
+    ///     .add_subpass_with_inputs(&[1, 2, 3], &[0], None)
+    ///     .build(device)?;
+    /// ```
+    pub fn add_subpass_with_inputs(self, inputs: &[vkuint], colors: &[vkuint], depth_stencil: Option<vkuint>) -> RenderPassCI {
+
+        let subpass_index = self.subpass_cis.len() as vkuint;
+
+        // find the previous subpasses that write to any input attachment of this subpass.
+        let src_subpasses: Vec<vkuint> = self.subpass_cis.iter().enumerate()
+            .filter(|(_, subpass)| subpass.written_attachments().any(|attachment| inputs.contains(&attachment)))
+            .map(|(i, _)| i as vkuint)
+            .collect();
+
+        let mut subpass = SubpassDescCI::new(vk::PipelineBindPoint::GRAPHICS);
+        for &input in inputs {
+            subpass = subpass.add_input_attachment(input, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+        }
+        for &color in colors {
+            subpass = subpass.add_color_attachment(color, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+        }
+        if let Some(depth) = depth_stencil {
+            subpass = subpass.set_depth_stencil_attachment(depth, vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+        }
+
+        src_subpasses.into_iter().fold(self.add_subpass(subpass), |render_pass, src| {
+            let dependency = SubpassDependencyCI::new(src, subpass_index)
+                .stage_mask(
+                    vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS,
+                    vk::PipelineStageFlags::FRAGMENT_SHADER)
+                .access_mask(
+                    vk::AccessFlags::COLOR_ATTACHMENT_WRITE | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                    vk::AccessFlags::INPUT_ATTACHMENT_READ)
+                .flags(vk::DependencyFlags::BY_REGION);
+            render_pass.add_dependency(dependency)
+        })
+    }
+
     /// Add a subpass dependency between two subpass.
     #[inline]
     pub fn add_dependency(mut self, dependency: SubpassDependencyCI) -> RenderPassCI {
@@ -246,11 +337,39 @@ impl RenderPassCI {
     pub fn flags(mut self, flags: vk::RenderPassCreateFlags) -> RenderPassCI {
         self.inner.flags = flags; self
     }
+
+    /// Set the name of render pass displayed in debugging tools. It is ignored if validation is disabled.
+    #[inline(always)]
+    pub fn name(mut self, name: impl Into<String>) -> RenderPassCI {
+        self.name = Some(name.into()); self
+    }
+
+    /// Check that the attachments referenced by subpasses and the subpasses referenced by dependencies exist.
+    fn validate(&self) -> VkResult<()> {
+
+        let attachment_count = self.attachments.len() as vkuint;
+        for (i, subpass) in self.subpass_cis.iter().enumerate() {
+            if let Some(attachment) = subpass.referenced_attachments().find(|&attachment| attachment >= attachment_count) {
+                return Err(VkError::custom(format!("Subpass {} references attachment {}, but the render pass only has {} attachments.", i, attachment, attachment_count)))
+            }
+        }
+
+        let subpass_count = self.subpasses.len() as vkuint;
+        let is_invalid_subpass = |subpass: vkuint| subpass != vk::SUBPASS_EXTERNAL && subpass >= subpass_count;
+        for dependency in self.dependencies.iter().flatten() {
+            if is_invalid_subpass(dependency.src_subpass) || is_invalid_subpass(dependency.dst_subpass) {
+                return Err(VkError::custom(format!("Subpass dependency({} -> {}) references a subpass that does not exist.", dependency.src_subpass, dependency.dst_subpass)))
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl VkObjectDiscardable for vk::RenderPass {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_render_pass(self, None);
         }
@@ -516,8 +635,7 @@ impl SubpassDescCI {
             layout: image_layout,
         });
 
-        self.inner.preserve_attachment_count = resolves.len() as _;
-        self.inner.p_resolve_attachments     = resolves.as_ptr(); self
+        self.inner.p_resolve_attachments = resolves.as_ptr(); self
     }
 
     /// Add preserve attachment to this subpass.
@@ -529,7 +647,8 @@ impl SubpassDescCI {
         let preserves = self.preserves.get_or_insert(Vec::new());
         preserves.push(attachment_index);
 
-        self.inner.p_preserve_attachments = preserves.as_ptr(); self
+        self.inner.preserve_attachment_count = preserves.len() as _;
+        self.inner.p_preserve_attachments    = preserves.as_ptr(); self
     }
 
     /// Set depth stencil attachment of this subpass.
@@ -555,6 +674,26 @@ impl SubpassDescCI {
     pub fn flags(mut self, flags: vk::SubpassDescriptionFlags) -> SubpassDescCI {
         self.inner.flags = flags; self
     }
+
+    /// The indices of attachments that this subpass writes to(color, resolve and depth stencil attachments).
+    fn written_attachments<'a>(&'a self) -> impl Iterator<Item = vkuint> + 'a {
+
+        self.colors.iter().flatten()
+            .chain(self.resolves.iter().flatten())
+            .chain(self.depth_stencil.iter().flatten())
+            .map(|reference| reference.attachment)
+            .filter(|&attachment| attachment != vk::ATTACHMENT_UNUSED)
+    }
+
+    /// The indices of all attachments referenced by this subpass.
+    fn referenced_attachments<'a>(&'a self) -> impl Iterator<Item = vkuint> + 'a {
+
+        self.inputs.iter().flatten()
+            .map(|reference| reference.attachment)
+            .filter(|&attachment| attachment != vk::ATTACHMENT_UNUSED)
+            .chain(self.written_attachments())
+            .chain(self.preserves.iter().flatten().cloned())
+    }
 }
 // ----------------------------------------------------------------------------------------------
 
@@ -652,6 +791,17 @@ impl SubpassDependencyCI {
     pub fn flags(mut self, flags: vk::DependencyFlags) -> SubpassDependencyCI {
         self.inner.dependency_flags = flags; self
     }
+
+    /// Initialize a self-dependency of `subpass`, which is required by a pipeline barrier recorded inside the subpass.
+    ///
+    /// A self-dependency must only contain framebuffer-space stages, so `vk::DependencyFlags::BY_REGION` is set.
+    pub fn self_dependency(subpass: vkuint) -> SubpassDependencyCI {
+
+        debug_assert_ne!(subpass, vk::SUBPASS_EXTERNAL, "A self-dependency must not reference vk::SUBPASS_EXTERNAL!");
+
+        SubpassDependencyCI::new(subpass, subpass)
+            .flags(vk::DependencyFlags::BY_REGION)
+    }
 }
 
 impl From<SubpassDependencyCI> for vk::SubpassDependency {
@@ -668,3 +818,48 @@ impl From<vk::SubpassDependency> for SubpassDependencyCI {
     }
 }
 // ----------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+
+    use ash::vk;
+    use super::RenderPassBI;
+
+    fn color_clear(value: f32) -> vk::ClearValue {
+        vk::ClearValue { color: vk::ClearColorValue { float32: [value, 0.0, 0.0, 1.0] } }
+    }
+
+    /// Read back the clear values through the raw pointer in `vk::RenderPassBeginInfo`, as `vkCmdBeginRenderPass` does.
+    fn read_clear_reds(begin_bi: &RenderPassBI) -> Vec<f32> {
+
+        let begin_info = begin_bi.as_ref();
+        unsafe {
+            ::std::slice::from_raw_parts(begin_info.p_clear_values, begin_info.clear_value_count as usize)
+                .iter().map(|clear| clear.color.float32[0]).collect()
+        }
+    }
+
+    #[test]
+    fn clear_values_from_temporary_outlive_it() {
+
+        let begin_bi = RenderPassBI::new(vk::RenderPass::null(), vk::Framebuffer::null())
+            .clear_values(&[color_clear(0.25), color_clear(0.5)]);
+
+        // the temporary array has been dropped here.
+        assert_eq!(read_clear_reds(&begin_bi), vec![0.25, 0.5]);
+    }
+
+    #[test]
+    fn clone_points_to_its_own_clear_values() {
+
+        let begin_bi = RenderPassBI::new(vk::RenderPass::null(), vk::Framebuffer::null())
+            .add_clear_value(color_clear(0.75));
+        let cloned_bi = begin_bi.clone();
+
+        assert_eq!(cloned_bi.as_ref().p_clear_values, cloned_bi.clears.as_ref().unwrap().as_ptr());
+        assert_ne!(cloned_bi.as_ref().p_clear_values, begin_bi.as_ref().p_clear_values);
+
+        drop(begin_bi);
+        assert_eq!(read_clear_reds(&cloned_bi), vec![0.75]);
+    }
+}
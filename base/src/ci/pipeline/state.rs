@@ -24,7 +24,7 @@ use std::ptr;
 ///
 /// See [VkPipelineVertexInputStateCreateInfo](https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkPipelineVertexInputStateCreateInfo.html) for more detail.
 ///
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct VertexInputSCI {
 
     inner: vk::PipelineVertexInputStateCreateInfo,
@@ -32,6 +32,22 @@ pub struct VertexInputSCI {
     attributes: Vec<vk::VertexInputAttributeDescription>,
 }
 
+impl Clone for VertexInputSCI {
+
+    fn clone(&self) -> VertexInputSCI {
+
+        let bindings   = self.bindings.clone();
+        let attributes = self.attributes.clone();
+
+        // re-point the descriptions to the cloned vectors, or they would dangle after the original is dropped.
+        let mut inner = self.inner.clone();
+        inner.p_vertex_binding_descriptions   = if bindings.is_empty()   { ptr::null() } else { bindings.as_ptr() };
+        inner.p_vertex_attribute_descriptions = if attributes.is_empty() { ptr::null() } else { attributes.as_ptr() };
+
+        VertexInputSCI { inner, bindings, attributes }
+    }
+}
+
 impl VulkanCI<vk::PipelineVertexInputStateCreateInfo> for VertexInputSCI {
 
     fn default_ci() -> vk::PipelineVertexInputStateCreateInfo {
@@ -85,6 +101,32 @@ impl VertexInputSCI {
         self.inner.p_vertex_binding_descriptions    = self.bindings.as_ptr(); self
     }
 
+    /// Add a binding whose data is advanced per instance instead of per vertex(etc. the transform of each instance).
+    ///
+    /// `binding` is the binding number, and `stride` is the byte size of the data of each instance.
+    ///
+    /// The vertex buffer of this binding is bound by `bind_vertex_buffers` as usual,
+    /// and the count of instances is the `instance_count` parameter of `draw`/`draw_indexed`,
+    /// which reads the data of instance `first_instance + i` from this binding:
+    /// ``` ignore
+    /// let vertex_input = VertexInputSCI::new()
+    ///     .add_binding(vertex_binding) // binding 0, per vertex.
+    ///     .add_instance_binding(1, ::std::mem::size_of::<InstanceData>() as _)
+    ///     .add_attribute(..) // the attributes of binding 0 and binding 1.
+    ///     .auto_attribute_locations();
+    ///
+    /// recorder.bind_vertex_buffers(0, &[vertex_buffer, instance_buffer], &[0, 0]);
+    /// recorder.draw_indexed(index_count, instance_count, 0, 0, 0);
+    /// ```
+    #[inline(always)]
+    pub fn add_instance_binding(self, binding: vkuint, stride: vkuint) -> VertexInputSCI {
+
+        self.add_binding(vk::VertexInputBindingDescription {
+            binding, stride,
+            input_rate: vk::VertexInputRate::INSTANCE,
+        })
+    }
+
     /// Add a vertex attribute to `vk::PipelineVertexInputStateCreateInfo`.
     ///
     /// `attribute` is the vertex attribute used in a specific vertex binding.
@@ -102,14 +144,51 @@ impl VertexInputSCI {
         self.inner.flags = flags; self
     }
 
+    /// Reassign the `location` of all attributes, so that the shader locations are consecutive in the order of adding.
+    ///
+    /// The attributes are grouped by their bindings, in the order that bindings were added(etc. the per-vertex attributes before the per-instance attributes).
+    /// The 64-bit formats with 3 or 4 components occupy 2 locations.
+    #[inline(always)]
+    pub fn auto_attribute_locations(mut self) -> VertexInputSCI {
+        self.inner_set_attribute_locations(); self
+    }
+
     // For crate inner use.
     #[doc(hidden)]
     pub(crate) fn inner_set_attribute_locations(&mut self) {
-        for (i, attribute) in self.attributes.iter_mut().enumerate() {
-            attribute.location = i as _;
+
+        let mut next_location = 0;
+
+        let binding_order: Vec<vkuint> = self.bindings.iter().map(|binding| binding.binding).collect();
+        let mut attribute_order: Vec<usize> = (0..self.attributes.len()).collect();
+        // the attributes referring to unknown bindings are placed at the end. The sort is stable, so attributes of the same binding keep their order.
+        attribute_order.sort_by_key(|&i| {
+            binding_order.iter()
+                .position(|&binding| binding == self.attributes[i].binding)
+                .unwrap_or(binding_order.len())
+        });
+
+        for i in attribute_order {
+            let attribute = &mut self.attributes[i];
+            attribute.location = next_location;
+            next_location += format_location_count(attribute.format);
         }
     }
 }
+
+/// Return the count of shader locations consumed by a vertex attribute of `format`.
+fn format_location_count(format: vk::Format) -> vkuint {
+
+    match format {
+        | vk::Format::R64G64B64_UINT
+        | vk::Format::R64G64B64_SINT
+        | vk::Format::R64G64B64_SFLOAT
+        | vk::Format::R64G64B64A64_UINT
+        | vk::Format::R64G64B64A64_SINT
+        | vk::Format::R64G64B64A64_SFLOAT => 2,
+        | _ => 1,
+    }
+}
 // ----------------------------------------------------------------------------------------------
 
 // ----------------------------------------------------------------------------------------------
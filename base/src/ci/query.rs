@@ -0,0 +1,118 @@
+//! Types which simplify the creation of Vulkan query objects.
+
+use ash::vk;
+use ash::version::DeviceV1_0;
+
+use std::ptr;
+
+use crate::context::{VkDevice, VkObjectDiscardable};
+use crate::ci::{VulkanCI, VkObjectBuildableCI};
+use crate::error::{VkResult, VkError};
+use crate::vkuint;
+
+
+// ----------------------------------------------------------------------------------------------
+/// Wrapper class for `vk::QueryPoolCreateInfo`.
+///
+/// The default values are defined as follows:
+/// ``` ignore
+/// vk::QueryPoolCreateInfo {
+///     s_type: vk::StructureType::QUERY_POOL_CREATE_INFO,
+///     p_next: ptr::null(),
+///     flags : vk::QueryPoolCreateFlags::empty(),
+///     query_type : vk::QueryType::TIMESTAMP,
+///     query_count: 0,
+///     pipeline_statistics: vk::QueryPipelineStatisticFlags::empty(),
+/// }
+/// ```
+///
+/// See [VkQueryPoolCreateInfo](https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkQueryPoolCreateInfo.html) for more detail.
+///
+#[derive(Debug, Clone)]
+pub struct QueryPoolCI {
+    inner: vk::QueryPoolCreateInfo,
+}
+
+impl VulkanCI<vk::QueryPoolCreateInfo> for QueryPoolCI {
+
+    fn default_ci() -> vk::QueryPoolCreateInfo {
+
+        vk::QueryPoolCreateInfo {
+            s_type: vk::StructureType::QUERY_POOL_CREATE_INFO,
+            p_next: ptr::null(),
+            flags : vk::QueryPoolCreateFlags::empty(),
+            query_type : vk::QueryType::TIMESTAMP,
+            query_count: 0,
+            pipeline_statistics: vk::QueryPipelineStatisticFlags::empty(),
+        }
+    }
+}
+
+impl AsRef<vk::QueryPoolCreateInfo> for QueryPoolCI {
+
+    fn as_ref(&self) -> &vk::QueryPoolCreateInfo {
+        &self.inner
+    }
+}
+
+impl VkObjectBuildableCI for QueryPoolCI {
+    type ObjectType = vk::QueryPool;
+
+    /// Create `vk::QueryPool` object, and return its handle.
+    #[track_caller]
+    fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
+
+        let pool = unsafe {
+            device.logic.handle.create_query_pool(self.as_ref(), None)
+                .map_err(|e| VkError::vk(e, "Create Query Pool"))?
+        };
+        device.tracker.track(pool);
+        Ok(pool)
+    }
+}
+
+impl QueryPoolCI {
+
+    /// Initialize `vk::QueryPoolCreateInfo` with default value.
+    ///
+    /// `type_` specifies the type of queries managed by the pool.
+    ///
+    /// `count` is the number of queries managed by the pool.
+    pub fn new(type_: vk::QueryType, count: vkuint) -> QueryPoolCI {
+
+        QueryPoolCI {
+            inner: vk::QueryPoolCreateInfo {
+                query_type : type_,
+                query_count: count,
+                ..QueryPoolCI::default_ci()
+            },
+        }
+    }
+
+    /// Set the `pipeline_statistics` member for `vk::QueryPoolCreateInfo`.
+    ///
+    /// It specifies which counters will be returned in queries of `PIPELINE_STATISTICS` type.
+    #[inline(always)]
+    pub fn pipeline_statistics(mut self, flags: vk::QueryPipelineStatisticFlags) -> QueryPoolCI {
+        self.inner.pipeline_statistics = flags; self
+    }
+
+    /// Set the `flags` member for `vk::QueryPoolCreateInfo`.
+    ///
+    /// It is still reserved for future use.
+    #[inline(always)]
+    pub fn flags(mut self, flags: vk::QueryPoolCreateFlags) -> QueryPoolCI {
+        self.inner.flags = flags; self
+    }
+}
+
+impl VkObjectDiscardable for vk::QueryPool {
+
+    fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
+        unsafe {
+            device.logic.handle.destroy_query_pool(self, None);
+        }
+    }
+}
+// ----------------------------------------------------------------------------------------------
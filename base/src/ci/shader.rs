@@ -5,9 +5,12 @@ use ash::version::DeviceV1_0;
 
 use crate::context::VkDevice;
 use crate::ci::{VulkanCI, VkObjectBuildableCI};
+use crate::utils::shaderc::{VkShaderCompiler, ShadercOptions};
 use crate::error::{VkResult, VkError};
+use crate::vkuint;
 
 use std::ffi::CString;
+use std::path::Path;
 use std::ptr;
 
 // ---------------------------------------------------------------------------------------------------
@@ -58,13 +61,15 @@ impl VkObjectBuildableCI for ShaderModuleCI {
     type ObjectType = vk::ShaderModule;
 
     /// Create `vk::ShaderModule` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         let module = unsafe {
             device.logic.handle.create_shader_module(self.as_ref(), None)
-                .or(Err(VkError::create("Shader Module")))?
+                .map_err(|e| VkError::vk(e, "Create Shader Module"))?
         };
 
+        device.tracker.track(module);
         Ok(module)
     }
 }
@@ -86,6 +91,73 @@ impl ShaderModuleCI {
         }
     }
 
+    /// Compile the GLSL file at `path` with `options`(etc. the optimization level and debug information), and initialize `vk::ShaderModuleCreateInfo` with the SPIR-V.
+    ///
+    /// `stage` is the shader kind of this file, and the entry point of shader must be `main`.
+    ///
+    /// Use `VkShaderCompiler` directly to compile multiple shaders with the same compiler.
+    pub fn from_glsl(path: impl AsRef<Path>, stage: shaderc::ShaderKind, options: ShadercOptions) -> VkResult<ShaderModuleCI> {
+
+        let mut compiler = VkShaderCompiler::new()?;
+        compiler.reset_compile_options(options);
+
+        let input_name = path.as_ref().display().to_string();
+        let codes = compiler.compile_from_path(path.as_ref(), stage, &input_name, "main")?;
+
+        Ok(ShaderModuleCI::new(codes))
+    }
+
+    /// Compile the HLSL file at `path`, and initialize `vk::ShaderModuleCreateInfo` with the SPIR-V.
+    ///
+    /// `stage` is the shader kind of this file, and `entry` is the name of its entry function(etc. `VSMain` or `PSMain`).
+    ///
+    /// Return error if the compiled SPIR-V does not contain an entry point of `stage`.
+    pub fn from_hlsl(stage: shaderc::ShaderKind, path: impl AsRef<Path>, entry: &str) -> VkResult<ShaderModuleCI> {
+
+        let mut compiler = VkShaderCompiler::new()?;
+        compiler.set_source_language(shaderc::SourceLanguage::HLSL);
+
+        let input_name = path.as_ref().display().to_string();
+        let codes = compiler.compile_from_path(path.as_ref(), stage, &input_name, entry)?;
+        crate::utils::shaderc::validate_spirv_stage(&codes, stage, &input_name)?;
+
+        Ok(ShaderModuleCI::new(codes))
+    }
+
+    /// Load the precompiled SPIR-V file at `path`(etc. compiled by `glslangValidator` or `glslc` ahead of time), without the shader compiler.
+    ///
+    /// `stage` is the shader stage that the module must contain an entry point for.
+    pub fn from_spv_file(stage: vk::ShaderStageFlags, path: impl AsRef<Path>) -> VkResult<ShaderModuleCI> {
+
+        let codes = ::std::fs::read(path.as_ref())
+            .map_err(|_| VkError::path(path.as_ref()))?;
+
+        let input_name = path.as_ref().display().to_string();
+        if codes.len() % 4 != 0 {
+            return Err(VkError::custom(format!("The size of SPIR-V file {} is not a multiple of 4 bytes.", input_name)))
+        }
+
+        let words: Vec<vkuint> = codes.chunks(4)
+            .map(|chunk| vkuint::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+        validate_spirv(&words, stage, &input_name)?;
+
+        Ok(ShaderModuleCI::new(codes))
+    }
+
+    /// Initialize `vk::ShaderModuleCreateInfo` with the precompiled SPIR-V `words`(etc. embedded by `include_bytes!`), without the shader compiler.
+    ///
+    /// `stage` is the shader stage that the module must contain an entry point for.
+    pub fn from_spv_bytes(stage: vk::ShaderStageFlags, words: &[vkuint]) -> VkResult<ShaderModuleCI> {
+
+        validate_spirv(words, stage, "[SPIR-V bytes]")?;
+
+        let codes = words.iter()
+            .flat_map(|word| word.to_ne_bytes().to_vec())
+            .collect();
+        Ok(ShaderModuleCI::new(codes))
+    }
+
     /// Set the `flags` member for `vk::ShaderModuleCreateInfo`.
     #[inline(always)]
     pub fn flags(mut self, flags: vk::ShaderModuleCreateFlags) -> ShaderModuleCI {
@@ -93,9 +165,67 @@ impl ShaderModuleCI {
     }
 }
 
+/// The magic number in the first word of SPIR-V module.
+const SPIRV_MAGIC_NUMBER: vkuint = 0x0723_0203;
+/// The opcode of `OpEntryPoint` in SPIR-V.
+const SPIRV_OP_ENTRY_POINT: vkuint = 15;
+
+/// Return the execution models of all entry points declared in the SPIR-V `words`, or error if it is not a SPIR-V module.
+///
+/// See `ExecutionModel` in the SPIR-V specification for the meaning of each value.
+pub(crate) fn spirv_execution_models(words: &[vkuint], input_name: &str) -> VkResult<Vec<vkuint>> {
+
+    if words.len() < 5 || words[0] != SPIRV_MAGIC_NUMBER {
+        return Err(VkError::custom(format!("{} is not a valid SPIR-V module(missing magic number).", input_name)))
+    }
+
+    // skip the 5 words of header, and walk through the instructions.
+    let mut models = Vec::new();
+    let mut cursor = 5;
+    while cursor < words.len() {
+
+        let word_count = (words[cursor] >> 16) as usize;
+        let opcode = words[cursor] & 0xFFFF;
+
+        if word_count == 0 {
+            break
+        }
+        if opcode == SPIRV_OP_ENTRY_POINT && cursor + 1 < words.len() {
+            models.push(words[cursor + 1]);
+        }
+
+        cursor += word_count;
+    }
+
+    Ok(models)
+}
+
+/// Check that `words` is a SPIR-V module containing an entry point for `stage`.
+fn validate_spirv(words: &[vkuint], stage: vk::ShaderStageFlags, input_name: &str) -> VkResult<()> {
+
+    let models = spirv_execution_models(words, input_name)?;
+
+    let expect_model = match stage {
+        | vk::ShaderStageFlags::VERTEX                  => 0,
+        | vk::ShaderStageFlags::TESSELLATION_CONTROL    => 1,
+        | vk::ShaderStageFlags::TESSELLATION_EVALUATION => 2,
+        | vk::ShaderStageFlags::GEOMETRY                => 3,
+        | vk::ShaderStageFlags::FRAGMENT                => 4,
+        | vk::ShaderStageFlags::COMPUTE                 => 5,
+        | _ => return Ok(()),
+    };
+
+    if models.contains(&expect_model) {
+        Ok(())
+    } else {
+        Err(VkError::custom(format!("The SPIR-V of {} has no entry point for {:?} stage(found execution models: {:?}).", input_name, stage, models)))
+    }
+}
+
 impl crate::context::VkObjectDiscardable for vk::ShaderModule {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_shader_module(self, None);
         }
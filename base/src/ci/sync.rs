@@ -8,8 +8,10 @@ use std::ptr;
 use crate::context::VkDevice;
 use crate::context::{VkObjectDiscardable, VkObjectWaitable};
 use crate::ci::{VulkanCI, VkObjectBuildableCI};
+use crate::context::SemaphoreTypeCreateInfo;
 use crate::error::{VkResult, VkError};
 use crate::utils::time::VkTimeDuration;
+use crate::vklint;
 
 
 // ----------------------------------------------------------------------------------------------
@@ -29,6 +31,8 @@ use crate::utils::time::VkTimeDuration;
 #[derive(Debug, Clone)]
 pub struct SemaphoreCI {
     inner: vk::SemaphoreCreateInfo,
+    /// the initial value of timeline semaphore, or `None` for binary semaphore.
+    timeline_value: Option<vklint>,
 }
 
 impl VulkanCI<vk::SemaphoreCreateInfo> for SemaphoreCI {
@@ -54,12 +58,30 @@ impl VkObjectBuildableCI for SemaphoreCI {
     type ObjectType = vk::Semaphore;
 
     /// Create `vk::Semaphore` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
-        let semaphore = unsafe {
-            device.logic.handle.create_semaphore(self.as_ref(), None)
-                .map_err(|_| VkError::create("Semaphore"))?
+        let semaphore = if let Some(initial_value) = self.timeline_value {
+
+            device.timeline_semaphore_fn()?;
+
+            let type_ci = SemaphoreTypeCreateInfo::timeline(initial_value);
+            let semaphore_ci = vk::SemaphoreCreateInfo {
+                p_next: &type_ci as *const SemaphoreTypeCreateInfo as *const _,
+                ..self.inner
+            };
+
+            unsafe {
+                device.logic.handle.create_semaphore(&semaphore_ci, None)
+                    .map_err(|e| VkError::vk(e, "Create Timeline Semaphore"))?
+            }
+        } else {
+            unsafe {
+                device.logic.handle.create_semaphore(self.as_ref(), None)
+                    .map_err(|e| VkError::vk(e, "Create Semaphore"))?
+            }
         };
+        device.tracker.track(semaphore);
         Ok(semaphore)
     }
 }
@@ -72,6 +94,19 @@ impl SemaphoreCI {
 
         SemaphoreCI {
             inner: SemaphoreCI::default_ci(),
+            timeline_value: None,
+        }
+    }
+
+    /// Initialize the create info of a timeline semaphore, whose counter starts at `initial_value`.
+    ///
+    /// It requires `DeviceExtensionType::TimelineSemaphore`, and an error is returned on building if it is not enabled.
+    #[inline(always)]
+    pub fn timeline(initial_value: vklint) -> SemaphoreCI {
+
+        SemaphoreCI {
+            inner: SemaphoreCI::default_ci(),
+            timeline_value: Some(initial_value),
         }
     }
 
@@ -87,6 +122,7 @@ impl SemaphoreCI {
 impl VkObjectDiscardable for vk::Semaphore {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_semaphore(self, None);
         }
@@ -136,12 +172,14 @@ impl VkObjectBuildableCI for FenceCI {
     type ObjectType = vk::Fence;
 
     /// Create `vk::Fence` object, and return its handle.
+    #[track_caller]
     fn build(&self, device: &VkDevice) -> VkResult<Self::ObjectType> {
 
         let fence = unsafe {
             device.logic.handle.create_fence(self.as_ref(), None)
-                .or(Err(VkError::create("Fence")))?
+                .map_err(|e| VkError::vk(e, "Create Fence"))?
         };
+        device.tracker.track(fence);
         Ok(fence)
     }
 }
@@ -172,28 +210,19 @@ impl FenceCI {
 impl VkObjectDiscardable for vk::Fence {
 
     fn discard_by(self, device: &VkDevice) {
+        device.tracker.untrack(self);
         unsafe {
             device.logic.handle.destroy_fence(self, None);
         }
     }
 }
 
-impl VkObjectDiscardable for &Vec<vk::Fence> {
-
-    fn discard_by(self, device: &VkDevice) {
-
-        for fence in self {
-            device.discard(*fence);
-        }
-    }
-}
-
 impl VkObjectWaitable for vk::Fence {
 
     fn wait(self, device: &VkDevice, time: VkTimeDuration) -> VkResult<()> {
         unsafe {
             device.logic.handle.wait_for_fences(&[self], true, time.into())
-                .map_err(|_| VkError::device("Wait for fences"))
+                .map_err(|e| VkError::vk(e, "Wait for fences"))
         }
     }
 }
@@ -75,6 +75,74 @@ impl VmaResourceDiscardable for VmaImage {
 // ----------------------------------------------------------------------------------------------
 
 
+// ----------------------------------------------------------------------------------------------
+/// A type that owns or refers to a `vk::Buffer`.
+///
+/// The API accepting this trait can take both the raw `vk::Buffer` and `&VmaBuffer`,
+/// so that passing the wrong kind of resource is a compile error.
+pub trait VkBufferHandle {
+
+    fn buffer_handle(&self) -> vk::Buffer;
+}
+
+/// A type that owns or refers to a `vk::Image`.
+///
+/// The API accepting this trait can take both the raw `vk::Image` and `&VmaImage`.
+pub trait VkImageHandle {
+
+    fn image_handle(&self) -> vk::Image;
+}
+
+impl VkBufferHandle for vk::Buffer {
+
+    #[inline(always)]
+    fn buffer_handle(&self) -> vk::Buffer {
+        *self
+    }
+}
+
+impl VkBufferHandle for VmaBuffer {
+
+    #[inline(always)]
+    fn buffer_handle(&self) -> vk::Buffer {
+        self.handle
+    }
+}
+
+impl<T: VkBufferHandle> VkBufferHandle for &T {
+
+    #[inline(always)]
+    fn buffer_handle(&self) -> vk::Buffer {
+        (*self).buffer_handle()
+    }
+}
+
+impl VkImageHandle for vk::Image {
+
+    #[inline(always)]
+    fn image_handle(&self) -> vk::Image {
+        *self
+    }
+}
+
+impl VkImageHandle for VmaImage {
+
+    #[inline(always)]
+    fn image_handle(&self) -> vk::Image {
+        self.handle
+    }
+}
+
+impl<T: VkImageHandle> VkImageHandle for &T {
+
+    #[inline(always)]
+    fn image_handle(&self) -> vk::Image {
+        (*self).image_handle()
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+
 // ----------------------------------------------------------------------------------------------
 /// Wrapper class for `vma::AllocationCreateInfo`.
 ///
@@ -1,8 +1,10 @@
 
 use ash::vk;
+use ash::version::DeviceV1_0;
 
 use crate::command::VkCommandType;
 use crate::command::recorder::VkCmdRecorder;
+use crate::vkuint;
 
 pub struct ICompute;
 
@@ -11,9 +13,49 @@ impl VkCommandType for ICompute {
 }
 
 impl<'a> CmdComputeApi for VkCmdRecorder<'a, ICompute> {
-    // Not implement yet...
+
+    fn push_constants(&self, layout: vk::PipelineLayout, stage: vk::ShaderStageFlags, offset: vkuint, data: &[u8]) -> &VkCmdRecorder<'a, ICompute> {
+        unsafe {
+            self.device.handle.cmd_push_constants(self.command, layout, stage, offset, data);
+        } self
+    }
+
+    fn bind_pipeline(&self, pipeline: vk::Pipeline) -> &VkCmdRecorder<'a, ICompute> {
+        unsafe {
+            self.device.handle.cmd_bind_pipeline(self.command, ICompute::BIND_POINT, pipeline);
+        } self
+    }
+
+    fn bind_descriptor_sets(&self, layout: vk::PipelineLayout, first_set: vkuint, descriptor_sets: &[vk::DescriptorSet], dynamic_offsets: &[vkuint]) -> &VkCmdRecorder<'a, ICompute> {
+        unsafe {
+            self.device.handle.cmd_bind_descriptor_sets(self.command, ICompute::BIND_POINT, layout, first_set, descriptor_sets, dynamic_offsets);
+        } self
+    }
+
+    fn dispatch(&self, group_count_x: vkuint, group_count_y: vkuint, group_count_z: vkuint) -> &VkCmdRecorder<'a, ICompute> {
+        unsafe {
+            self.device.handle.cmd_dispatch(self.command, group_count_x, group_count_y, group_count_z);
+        } self
+    }
+
+    fn memory_pipeline_barrier(&self, src_stage: vk::PipelineStageFlags, dst_stage: vk::PipelineStageFlags, dependencies: vk::DependencyFlags, memory_barriers: &[vk::MemoryBarrier], buffer_barriers: &[vk::BufferMemoryBarrier]) -> &VkCmdRecorder<'a, ICompute> {
+        unsafe {
+            self.device.handle.cmd_pipeline_barrier(self.command, src_stage, dst_stage, dependencies, memory_barriers, buffer_barriers, &[]);
+        } self
+    }
 }
 
 pub trait CmdComputeApi {
-    // Not implement yet...
+
+    fn push_constants(&self, layout: vk::PipelineLayout, stage: vk::ShaderStageFlags, offset: vkuint, data: &[u8]) -> &Self;
+
+    fn bind_pipeline(&self, pipeline: vk::Pipeline) -> &Self;
+
+    fn bind_descriptor_sets(&self, layout: vk::PipelineLayout, first_set: vkuint, descriptor_sets: &[vk::DescriptorSet], dynamic_offsets: &[vkuint]) -> &Self;
+
+    /// Dispatch the work groups of bound compute pipeline.
+    fn dispatch(&self, group_count_x: vkuint, group_count_y: vkuint, group_count_z: vkuint) -> &Self;
+
+    /// Insert the global memory barriers and buffer barriers(etc. between two dispatches writing and reading a storage buffer).
+    fn memory_pipeline_barrier(&self, src_stage: vk::PipelineStageFlags, dst_stage: vk::PipelineStageFlags, dependencies: vk::DependencyFlags, memory_barriers: &[vk::MemoryBarrier], buffer_barriers: &[vk::BufferMemoryBarrier]) -> &Self;
 }
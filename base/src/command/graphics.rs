@@ -6,7 +6,9 @@ use crate::command::VkCommandType;
 use crate::command::recorder::VkCmdRecorder;
 use crate::{vkuint, vkfloat, vksint, vkbytes};
 
-use crate::ci::pipeline::RenderPassBI;
+use crate::ci::pipeline::{RenderPassBI, DynamicRenderingBI};
+use crate::ci::vma::VkBufferHandle;
+use crate::context::{ExtendedDynamicStateFn, DynamicRenderingFn};
 
 
 pub struct IGraphics;
@@ -15,6 +17,72 @@ impl VkCommandType for IGraphics {
     const BIND_POINT: vk::PipelineBindPoint = vk::PipelineBindPoint::GRAPHICS;
 }
 
+/// Commands of `VK_EXT_extended_dynamic_state`.
+///
+/// Request `DeviceExtensionType::ExtendedDynamicState` in `PhysicalDevConfig`,
+/// and add the corresponding `DynamicStateEXT` to pipeline before using these methods.
+impl<'a> VkCmdRecorder<'a, IGraphics> {
+
+    #[inline]
+    fn ext_dynamic_state(&self) -> &ExtendedDynamicStateFn {
+        self.device.ext_dynamic_state.as_ref()
+            .expect("VK_EXT_extended_dynamic_state is not enabled.")
+    }
+
+    /// Set the cull mode dynamically.
+    pub fn set_cull_mode(&self, cull_mode: vk::CullModeFlags) -> &VkCmdRecorder<'a, IGraphics> {
+        (self.ext_dynamic_state().cmd_set_cull_mode)(self.command, cull_mode); self
+    }
+
+    /// Set the front face orientation dynamically.
+    pub fn set_front_face(&self, front_face: vk::FrontFace) -> &VkCmdRecorder<'a, IGraphics> {
+        (self.ext_dynamic_state().cmd_set_front_face)(self.command, front_face); self
+    }
+
+    /// Set the primitive topology dynamically. It must be in the same topology class as the one specified in pipeline.
+    pub fn set_primitive_topology(&self, topology: vk::PrimitiveTopology) -> &VkCmdRecorder<'a, IGraphics> {
+        (self.ext_dynamic_state().cmd_set_primitive_topology)(self.command, topology); self
+    }
+
+    /// Enable or disable depth test dynamically.
+    pub fn set_depth_test_enable(&self, is_enable: bool) -> &VkCmdRecorder<'a, IGraphics> {
+        (self.ext_dynamic_state().cmd_set_depth_test_enable)(self.command, if is_enable { vk::TRUE } else { vk::FALSE }); self
+    }
+
+    /// Enable or disable depth write dynamically.
+    pub fn set_depth_write_enable(&self, is_enable: bool) -> &VkCmdRecorder<'a, IGraphics> {
+        (self.ext_dynamic_state().cmd_set_depth_write_enable)(self.command, if is_enable { vk::TRUE } else { vk::FALSE }); self
+    }
+
+    /// Set the depth compare operator dynamically.
+    pub fn set_depth_compare_op(&self, compare_op: vk::CompareOp) -> &VkCmdRecorder<'a, IGraphics> {
+        (self.ext_dynamic_state().cmd_set_depth_compare_op)(self.command, compare_op); self
+    }
+}
+
+/// Commands of dynamic rendering, which replace `begin_render_pass` and `end_render_pass` without render pass and framebuffer objects.
+///
+/// Request `DeviceExtensionType::DynamicRendering` in `PhysicalDevConfig`,
+/// and create the pipelines by `GraphicsPipelineCI::set_rendering_formats` before using these methods.
+impl<'a> VkCmdRecorder<'a, IGraphics> {
+
+    #[inline]
+    fn dynamic_rendering(&self) -> &DynamicRenderingFn {
+        self.device.dynamic_rendering.as_ref()
+            .expect("VK_KHR_dynamic_rendering is not enabled(request `DeviceExtensionType::DynamicRendering` in `PhysicalDevConfig`).")
+    }
+
+    /// Begin a dynamic rendering to the attachments of `bi`.
+    pub fn begin_rendering(&self, bi: DynamicRenderingBI) -> &VkCmdRecorder<'a, IGraphics> {
+        (self.dynamic_rendering().cmd_begin_rendering)(self.command, bi.rendering_info()); self
+    }
+
+    /// End current dynamic rendering.
+    pub fn end_rendering(&self) -> &VkCmdRecorder<'a, IGraphics> {
+        (self.dynamic_rendering().cmd_end_rendering)(self.command); self
+    }
+}
+
 impl<'a> CmdGraphicsApi for VkCmdRecorder<'a, IGraphics> {
 
     fn begin_render_pass(&self, bi: RenderPassBI) -> &VkCmdRecorder<'a, IGraphics> {
@@ -107,9 +175,9 @@ impl<'a> CmdGraphicsApi for VkCmdRecorder<'a, IGraphics> {
         } self
     }
 
-    fn bind_index_buffer(&self, buffer: vk::Buffer, index_type: vk::IndexType, offset: vkbytes) -> &Self {
+    fn bind_index_buffer(&self, buffer: impl VkBufferHandle, index_type: vk::IndexType, offset: vkbytes) -> &Self {
         unsafe {
-            self.device.handle.cmd_bind_index_buffer(self.command, buffer, offset, index_type);
+            self.device.handle.cmd_bind_index_buffer(self.command, buffer.buffer_handle(), offset, index_type);
         } self
     }
 
@@ -132,6 +200,23 @@ impl<'a> CmdGraphicsApi for VkCmdRecorder<'a, IGraphics> {
         } self
     }
 
+    fn draw_indexed_indirect(&self, buffer: impl VkBufferHandle, offset: vkbytes, draw_count: vkuint, stride: vkuint) -> &VkCmdRecorder<'a, IGraphics> {
+
+        let buffer = buffer.buffer_handle();
+
+        unsafe {
+            if draw_count <= 1 || self.device.is_multi_draw_indirect_enabled {
+                self.device.handle.cmd_draw_indexed_indirect(self.command, buffer, offset, draw_count, stride);
+            } else {
+                // `multiDrawIndirect` is not enabled, so issue the draws one by one.
+                for i in 0..draw_count {
+                    let draw_offset = offset + (i as vkbytes) * (stride as vkbytes);
+                    self.device.handle.cmd_draw_indexed_indirect(self.command, buffer, draw_offset, 1, stride);
+                }
+            }
+        } self
+    }
+
     fn end_render_pass(&self) -> &VkCmdRecorder<'a, IGraphics> {
         // Ending the render pass will add an implicit barrier transitioning the frame buffer color attachment vk::IMAGE_LAYOUT_PRESENT_SRC_KHR for presenting it to the windowing system.
         unsafe {
@@ -168,7 +253,7 @@ pub trait CmdGraphicsApi {
 
     fn bind_vertex_buffers(&self, first_binding: vkuint, buffers: &[vk::Buffer], offsets: &[vkbytes]) -> &Self;
 
-    fn bind_index_buffer(&self, buffer: vk::Buffer, index_type: vk::IndexType, offset: vkbytes) -> &Self;
+    fn bind_index_buffer(&self, buffer: impl VkBufferHandle, index_type: vk::IndexType, offset: vkbytes) -> &Self;
 
     fn bind_descriptor_sets(&self, layout: vk::PipelineLayout, first_set: vkuint, descriptor_sets: &[vk::DescriptorSet], dynamic_offsets: &[vkuint]) -> &Self;
 
@@ -176,5 +261,10 @@ pub trait CmdGraphicsApi {
 
     fn draw_indexed(&self, index_count: vkuint, instance_count: vkuint, first_index: vkuint, vertex_offset: vksint, first_instance: vkuint) -> &Self;
 
+    /// Issue `draw_count` indexed draws whose parameters are read from `buffer` at `offset`, as an array of `vk::DrawIndexedIndirectCommand` spaced by `stride` bytes.
+    ///
+    /// If the `multiDrawIndirect` feature is not enabled and `draw_count` is greater than 1, the draws are recorded one by one.
+    fn draw_indexed_indirect(&self, buffer: impl VkBufferHandle, offset: vkbytes, draw_count: vkuint, stride: vkuint) -> &Self;
+
     fn end_render_pass(&self) -> &Self;
 }
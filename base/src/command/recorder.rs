@@ -2,9 +2,13 @@
 use ash::vk;
 use ash::version::DeviceV1_0;
 
+use crate::command::VkCommandType;
 use crate::context::VkLogicalDevice;
+use crate::utils::color::VkColor;
 use crate::error::{VkResult, VkError};
+use crate::vkuint;
 
+use std::ffi::CString;
 use std::marker::PhantomData;
 use std::ptr;
 
@@ -43,7 +47,7 @@ impl<'a, 'd: 'a, T> VkCmdRecorder<'a, T> {
 
         unsafe {
             self.device.handle.begin_command_buffer(self.command, &begin_ci)
-                .or(Err(VkError::device("Begin Command Buffer.")))?;
+                .map_err(|e| VkError::vk(e, "Begin Command Buffer"))?;
         }
         Ok(self)
     }
@@ -52,18 +56,115 @@ impl<'a, 'd: 'a, T> VkCmdRecorder<'a, T> {
 
         unsafe {
             self.device.handle.end_command_buffer(self.command)
-                .or(Err(VkError::device("End Command Buffer.")))?;
+                .map_err(|e| VkError::vk(e, "End Command Buffer"))?;
         }
 
         Ok(())
     }
 
+    /// Reset `count` queries of `pool` starting from `first_query`, which must be done before the queries are written again.
+    pub fn reset_query_pool(&self, pool: vk::QueryPool, first_query: vkuint, count: vkuint) -> &VkCmdRecorder<T> {
+        unsafe {
+            self.device.handle.cmd_reset_query_pool(self.command, pool, first_query, count);
+        } self
+    }
+
+    /// Write the timestamp to `query` of `pool` when all previous commands have completed `stage`.
+    pub fn write_timestamp(&self, stage: vk::PipelineStageFlags, pool: vk::QueryPool, query: vkuint) -> &VkCmdRecorder<T> {
+        unsafe {
+            self.device.handle.cmd_write_timestamp(self.command, stage, pool, query);
+        } self
+    }
+
+    /// Begin `query` of `pool`, whose results are collected until `end_query` is recorded.
+    pub fn begin_query(&self, pool: vk::QueryPool, query: vkuint, flags: vk::QueryControlFlags) -> &VkCmdRecorder<T> {
+        unsafe {
+            self.device.handle.cmd_begin_query(self.command, pool, query, flags);
+        } self
+    }
+
+    pub fn end_query(&self, pool: vk::QueryPool, query: vkuint) -> &VkCmdRecorder<T> {
+        unsafe {
+            self.device.handle.cmd_end_query(self.command, pool, query);
+        } self
+    }
+
+    /// Begin a labeled region of commands, which is displayed in debugging tools(etc. RenderDoc).
+    ///
+    /// Each `begin_label` must be matched by an `end_label` in the same command buffer.
+    /// This is a no-op if validation is disabled.
+    pub fn begin_label(&self, name: &str, color: VkColor) -> &VkCmdRecorder<T> {
+
+        if let Some(ref debug_utils) = self.device.debug_utils {
+            let label_name = debug_label_name(name);
+            let label = debug_label(&label_name, color);
+            unsafe {
+                debug_utils.cmd_begin_debug_utils_label(self.command, &label);
+            }
+        } self
+    }
+
+    /// End the labeled region opened by last `begin_label`.
+    pub fn end_label(&self) -> &VkCmdRecorder<T> {
+
+        if let Some(ref debug_utils) = self.device.debug_utils {
+            unsafe {
+                debug_utils.cmd_end_debug_utils_label(self.command);
+            }
+        } self
+    }
+
+    /// Insert a single label between commands.
+    pub fn insert_label(&self, name: &str) -> &VkCmdRecorder<T> {
+
+        if let Some(ref debug_utils) = self.device.debug_utils {
+            let label_name = debug_label_name(name);
+            let label = debug_label(&label_name, VkColor::WHITE);
+            unsafe {
+                debug_utils.cmd_insert_debug_utils_label(self.command, &label);
+            }
+        } self
+    }
+
     pub fn reset_command(&self, flags: vk::CommandBufferResetFlags) -> VkResult<()> {
 
         unsafe {
             self.device.handle.reset_command_buffer(self.command, flags)
-                .or(Err(VkError::device("End Command Buffer.")))?;
+                .map_err(|e| VkError::vk(e, "Reset Command Buffer"))?;
         }
         Ok(())
     }
 }
+
+/// Commands of `VK_KHR_push_descriptor`.
+///
+/// Request `DeviceExtensionType::PushDescriptor` in `PhysicalDevConfig` before using these methods.
+impl<'a, T: VkCommandType> VkCmdRecorder<'a, T> {
+
+    /// Push the descriptors of `writes` to the set at `set` of `layout` directly, without allocating a descriptor set from pool.
+    ///
+    /// The descriptor set layout at `set` must be created with `DescriptorSetLayoutCI::push_descriptor`,
+    /// and the `dst_set` member of each write is ignored.
+    pub fn push_descriptor_set(&self, layout: vk::PipelineLayout, set: vkuint, writes: &[vk::WriteDescriptorSet]) -> &VkCmdRecorder<'a, T> {
+
+        let push_descriptor = self.device.push_descriptor.as_ref()
+            .expect("VK_KHR_push_descriptor is not enabled(request `DeviceExtensionType::PushDescriptor` in `PhysicalDevConfig`).");
+
+        (push_descriptor.cmd_push_descriptor_set)(self.command, T::BIND_POINT, layout, set, writes.len() as _, writes.as_ptr()); self
+    }
+}
+
+/// Convert `name` to CString, the interior nul byte is dropped instead of failing the recording.
+fn debug_label_name(name: &str) -> CString {
+    CString::new(name.replace('\0', "")).unwrap_or_default()
+}
+
+fn debug_label(name: &CString, color: VkColor) -> vk::DebugUtilsLabelEXT {
+
+    vk::DebugUtilsLabelEXT {
+        s_type: vk::StructureType::DEBUG_UTILS_LABEL_EXT,
+        p_next: ptr::null(),
+        p_label_name: name.as_ptr(),
+        color: color.into(),
+    }
+}
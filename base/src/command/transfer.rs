@@ -4,6 +4,7 @@ use ash::version::DeviceV1_0;
 
 use crate::ci::sync::FenceCI;
 use crate::ci::device::SubmitCI;
+use crate::ci::vma::{VkBufferHandle, VkImageHandle};
 
 use crate::command::VkCommandType;
 use crate::command::recorder::VkCmdRecorder;
@@ -27,11 +28,11 @@ impl<'a> VkCmdRecorder<'a, ITransfer> {
                 .add_command(self.command);
 
             let wait_fence = self.device.handle.create_fence(FenceCI::new(false).as_ref(), None)
-                .or(Err(VkError::create("Fence")))?;
+                .map_err(|e| VkError::vk(e, "Create Fence"))?;
             self.device.handle.queue_submit(queue, &[*submit_ci.as_ref()], wait_fence)
-                .map_err(|_| VkError::device("Queue Submit"))?;
+                .map_err(|e| VkError::vk(e, "Queue Submit"))?;
             self.device.handle.wait_for_fences(&[wait_fence], true, VkTimeDuration::Infinite.into())
-                .map_err(|_| VkError::device("Wait for fences"))?;
+                .map_err(|e| VkError::vk(e, "Wait for fences"))?;
             self.device.handle.destroy_fence(wait_fence, None);
         }
 
@@ -41,27 +42,27 @@ impl<'a> VkCmdRecorder<'a, ITransfer> {
 
 impl<'a> CmdTransferApi for VkCmdRecorder<'a, ITransfer> {
 
-    fn copy_buf2buf(&self, src: vk::Buffer, dst: vk::Buffer, regions: &[vk::BufferCopy]) -> &Self {
+    fn copy_buf2buf(&self, src: impl VkBufferHandle, dst: impl VkBufferHandle, regions: &[vk::BufferCopy]) -> &Self {
         unsafe {
-            self.device.handle.cmd_copy_buffer(self.command, src, dst, regions);
+            self.device.handle.cmd_copy_buffer(self.command, src.buffer_handle(), dst.buffer_handle(), regions);
         } self
     }
 
-    fn copy_buf2img(&self, src: vk::Buffer, dst: vk::Image, dst_layout: vk::ImageLayout, regions: &[vk::BufferImageCopy]) -> &Self {
+    fn copy_buf2img(&self, src: impl VkBufferHandle, dst: impl VkImageHandle, dst_layout: vk::ImageLayout, regions: &[vk::BufferImageCopy]) -> &Self {
         unsafe {
-            self.device.handle.cmd_copy_buffer_to_image(self.command, src, dst, dst_layout, regions);
+            self.device.handle.cmd_copy_buffer_to_image(self.command, src.buffer_handle(), dst.image_handle(), dst_layout, regions);
         } self
     }
 
-    fn copy_img2buf(&self, src: vk::Image, src_layout: vk::ImageLayout, dst: vk::Buffer, regions: &[vk::BufferImageCopy]) -> &Self {
+    fn copy_img2buf(&self, src: impl VkImageHandle, src_layout: vk::ImageLayout, dst: impl VkBufferHandle, regions: &[vk::BufferImageCopy]) -> &Self {
         unsafe {
-            self.device.handle.cmd_copy_image_to_buffer(self.command, src, src_layout, dst, regions);
+            self.device.handle.cmd_copy_image_to_buffer(self.command, src.image_handle(), src_layout, dst.buffer_handle(), regions);
         } self
     }
 
-    fn copy_img2img(&self,src: vk::Image, src_layout: vk::ImageLayout, dst: vk::Image, dst_layout: vk::ImageLayout, regions: &[vk::ImageCopy]) -> &Self {
+    fn copy_img2img(&self, src: impl VkImageHandle, src_layout: vk::ImageLayout, dst: impl VkImageHandle, dst_layout: vk::ImageLayout, regions: &[vk::ImageCopy]) -> &Self {
         unsafe {
-            self.device.handle.cmd_copy_image(self.command, src, src_layout, dst, dst_layout, regions);
+            self.device.handle.cmd_copy_image(self.command, src.image_handle(), src_layout, dst.image_handle(), dst_layout, regions);
         } self
     }
 
@@ -72,24 +73,28 @@ impl<'a> CmdTransferApi for VkCmdRecorder<'a, ITransfer> {
         } self
     }
 
-    fn blit_image(&self, src_handle: vk::Image, src_layout: vk::ImageLayout, dst_handle: vk::Image, dst_layout: vk::ImageLayout, regions: &[vk::ImageBlit], filter: vk::Filter) -> &Self {
+    fn blit_image(&self, src: impl VkImageHandle, src_layout: vk::ImageLayout, dst: impl VkImageHandle, dst_layout: vk::ImageLayout, regions: &[vk::ImageBlit], filter: vk::Filter) -> &Self {
         unsafe {
-            self.device.handle.cmd_blit_image(self.command, src_handle, src_layout, dst_handle, dst_layout, regions, filter);
+            self.device.handle.cmd_blit_image(self.command, src.image_handle(), src_layout, dst.image_handle(), dst_layout, regions, filter);
         } self
     }
 }
 
 pub trait CmdTransferApi {
 
-    fn copy_buf2buf(&self, src_buffer_handle: vk::Buffer, dst_buffer_handle: vk::Buffer, regions: &[vk::BufferCopy]) -> &Self;
+    fn copy_buf2buf(&self, src: impl VkBufferHandle, dst: impl VkBufferHandle, regions: &[vk::BufferCopy]) -> &Self;
 
-    fn copy_buf2img(&self, src_handle: vk::Buffer, dst_handle: vk::Image, dst_layout: vk::ImageLayout, regions: &[vk::BufferImageCopy]) -> &Self;
+    /// Copy data from buffer to image.
+    ///
+    /// Each region may specify any mip level and range of array layers, so all faces or layers of an image can be uploaded in one call.
+    /// See `texture::layered_copy_regions` to build the regions for a KTX texture.
+    fn copy_buf2img(&self, src: impl VkBufferHandle, dst: impl VkImageHandle, dst_layout: vk::ImageLayout, regions: &[vk::BufferImageCopy]) -> &Self;
 
-    fn copy_img2buf(&self, src_handle: vk::Image, src_layout: vk::ImageLayout, dst_buffer: vk::Buffer, regions: &[vk::BufferImageCopy]) -> &Self;
+    fn copy_img2buf(&self, src: impl VkImageHandle, src_layout: vk::ImageLayout, dst: impl VkBufferHandle, regions: &[vk::BufferImageCopy]) -> &Self;
 
-    fn copy_img2img(&self,src_handle: vk::Image, src_layout: vk::ImageLayout, dst_handle: vk::Image, dst_layout: vk::ImageLayout, regions: &[vk::ImageCopy]) -> &Self;
+    fn copy_img2img(&self, src: impl VkImageHandle, src_layout: vk::ImageLayout, dst: impl VkImageHandle, dst_layout: vk::ImageLayout, regions: &[vk::ImageCopy]) -> &Self;
 
     fn image_pipeline_barrier(&self, src_stage: vk::PipelineStageFlags, dst_stage: vk::PipelineStageFlags, dependencies: vk::DependencyFlags, image_barriers: &[vk::ImageMemoryBarrier]) -> &Self;
 
-    fn blit_image(&self, src_handle: vk::Image, src_layout: vk::ImageLayout, dst_handle: vk::Image, dst_layout: vk::ImageLayout, regions: &[vk::ImageBlit], filter: vk::Filter) -> &Self;
+    fn blit_image(&self, src: impl VkImageHandle, src_layout: vk::ImageLayout, dst: impl VkImageHandle, dst_layout: vk::ImageLayout, regions: &[vk::ImageBlit], filter: vk::Filter) -> &Self;
 }
@@ -1,13 +1,18 @@
 
-pub use self::device::{VkDevice, VkLogicalDevice, VkPhysicalDevice};
+pub use self::device::{VkDevice, VkLogicalDevice, VkPhysicalDevice, AllocationStats, MemoryBudget, HeapBudget};
 pub use self::device::{VkObjectDiscardable, VkObjectAllocatable, VkObjectBindable};
 pub use self::device::VmaResourceDiscardable;
 pub use self::device::{VkObjectWaitable, VkSubmitCI};
 pub use self::swapchain::{VkSwapchain, SwapchainSyncError};
+pub use self::sync::{FrameSync, FrameInFlight};
+pub use self::owned::{Owned, OwnedDiscardable, VkDeletionQueue};
 
 pub use self::instance::InstanceConfig;
 pub use self::debug::ValidationConfig;
-pub use self::device::{LogicDevConfig, PhysicalDevConfig};
+pub use self::device::{LogicDevConfig, PhysicalDevConfig, DeviceExtensionType, DeviceSelector};
+pub use self::device::{DynamicStateEXT, ExtendedDynamicStateFn, TimelineSemaphoreFn, DynamicRenderingFn, PushDescriptorFn};
+pub(crate) use self::device::{SemaphoreTypeCreateInfo, TimelineSemaphoreSubmitInfo};
+pub(crate) use self::device::{RenderingInfo, RenderingAttachmentInfo, PipelineRenderingCreateInfo};
 pub use self::swapchain::SwapchainConfig;
 
 mod instance;
@@ -15,8 +20,11 @@ mod debug;
 mod surface;
 mod device;
 mod swapchain;
+mod sync;
+mod owned;
 
 
+use ash::vk;
 use ash::version::DeviceV1_0;
 use crate::workflow::WindowContext;
 use crate::error::{VkResult, VkError, VkErrorKind};
@@ -35,7 +43,8 @@ pub struct VulkanContext {
 
     instance  : instance::VkInstance,
     debugger  : debug::VkDebugger,
-    surface   : surface::VkSurface,
+    /// the surface of window, which is `None` in headless mode.
+    surface   : Option<surface::VkSurface>,
 
     pub swapchain: swapchain::VkSwapchain,
     pub device: device::VkDevice,
@@ -46,32 +55,63 @@ impl VulkanContext {
     pub fn new(window: &WindowContext) -> VulkanContextBuilder {
 
         VulkanContextBuilder {
-            window,
+            window: Some(window),
+            headless_dimension: vk::Extent2D { width: 0, height: 0 },
             config: VulkanConfig::default(),
         }
     }
 
-    pub(super) fn recreate_swapchain(&mut self, window: &WindowContext) -> VkResult<()> {
+    /// Create the context without window, whose swapchain images of `dimension` are rendered offscreen(etc. for tests or screenshot generation).
+    ///
+    /// Use `ProcPipeline::headless` to run it, and `VkSwapchain::read_back` to get the rendered pixels.
+    pub fn headless(dimension: vk::Extent2D) -> VulkanContextBuilder<'static> {
 
-        let dimension = window.dimension()?;
-        self.swapchain.rebuild(&self.instance, &self.device, &self.surface, dimension)?;
+        VulkanContextBuilder {
+            window: None,
+            headless_dimension: dimension,
+            config: VulkanConfig::default(),
+        }
+    }
+
+    pub(super) fn recreate_swapchain(&mut self, window: Option<&WindowContext>) -> VkResult<()> {
+
+        match window {
+            | Some(window) => {
+                let dimension = window.dimension()?;
+                self.swapchain.rebuild(&self.instance, &mut self.device, self.surface.as_ref(), dimension)?;
+                self.swapchain.set_render_scale(window.render_scale());
+                self.swapchain.set_hidpi_factor(window.hidpi_factor());
+            },
+            | None => {
+                let dimension = self.swapchain.dimension;
+                self.swapchain.rebuild(&self.instance, &mut self.device, None, dimension)?;
+            },
+        }
 
         Ok(())
     }
 
+    /// Return the ERROR messages reported by validation layer since last call as `VkError`.
+    ///
+    /// This function always returns `Ok` unless `ValidationConfig::promote_errors` is enabled.
+    pub fn check_validation(&self) -> VkResult<()> {
+        self.debugger.check_errors()
+    }
+
     pub(super) fn wait_idle(&self) -> VkResult<()> {
         unsafe {
             self.device.logic.handle.device_wait_idle()
-                .map_err(|_| VkError::device("Device Waiting Idle"))?;
+                .map_err(|e| VkError::vk(e, "Device Waiting Idle"))?;
         }
 
         Ok(())
     }
 
-    pub(super) fn discard(self) {
+    pub(super) fn discard(mut self) {
 
-        self.swapchain.discard(&self.device);
-        drop(self.swapchain);
+        if let Err(e) = self.swapchain.discard(&mut self.device) {
+            println!("[Warning] Failed to discard swapchain: {}", e);
+        }
 
         self.device.drop_self();
 
@@ -83,7 +123,10 @@ impl VulkanContext {
 
 pub struct VulkanContextBuilder<'a> {
 
-    window: &'a WindowContext,
+    /// the window to present images, or `None` in headless mode.
+    window: Option<&'a WindowContext>,
+    /// the dimension of offscreen images in headless mode.
+    headless_dimension: vk::Extent2D,
     config: VulkanConfig,
 }
 
@@ -97,6 +140,29 @@ impl<'a> VulkanContextBuilder<'a> {
         self.config.debugger = config; self
     }
 
+    /// Enable or disable validation layer and the debug messenger, whose messages are routed to `log` crate.
+    ///
+    /// Enabling validation is a no-op if the validation layer is not installed.
+    pub fn with_validation(mut self, is_enable: bool) -> VulkanContextBuilder<'a> {
+
+        let is_validation_layer = |layer: &String| debug::VALIDATION_LAYER_NAMES.iter().any(|name| name == layer);
+        let require_layers = &mut self.config.instance.require_layer_names;
+
+        if is_enable {
+            if require_layers.iter().any(is_validation_layer) == false {
+                require_layers.push(String::from(debug::VALIDATION_LAYER_NAMES[0]));
+            }
+            self.config.instance.debug = debug::DebugType::DebugUtils;
+            self.config.debugger.debug_type = debug::DebugType::DebugUtils;
+        } else {
+            require_layers.retain(|layer| is_validation_layer(layer) == false);
+            self.config.instance.debug = debug::DebugType::None;
+            self.config.debugger.debug_type = debug::DebugType::None;
+        }
+
+        self
+    }
+
     pub fn with_logic_device_config(mut self, config: LogicDevConfig) -> VulkanContextBuilder<'a> {
         self.config.dev_logic = config; self
     }
@@ -109,19 +175,37 @@ impl<'a> VulkanContextBuilder<'a> {
         self.config.swapchain = config; self
     }
 
-    pub fn build(self) -> VkResult<VulkanContext> {
+    pub fn build(mut self) -> VkResult<VulkanContext> {
 
-        let instance = instance::VkInstance::new(self.config.instance, &self.config.debugger)?;
+        let instance = instance::VkInstance::new(self.config.instance, &mut self.config.debugger)?;
+        let debug_type = self.config.debugger.debug_type;
         let debugger = debug::VkDebugger::new(&instance, self.config.debugger)?;
-        let surface = surface::VkSurface::new(&instance, &self.window.handle)?;
+        let surface = match self.window {
+            | Some(window) => Some(surface::VkSurface::new(&instance, &window.handle)?),
+            | None => None,
+        };
 
         let phy_device = device::VkPhysicalDevice::new(&instance, self.config.dev_phy)?;
-        let logic_device = device::VkLogicalDevice::new(&instance, &phy_device, self.config.dev_logic)?;
+        let mut logic_device = device::VkLogicalDevice::new(&instance, &phy_device, self.config.dev_logic)?;
         let vma = VulkanContextBuilder::build_vma(&instance, &phy_device, &logic_device)?;
-        let device = device::VkDevice::new(logic_device, phy_device, vma)?;
-
-        let dimension = self.window.dimension()?;
-        let swapchain = swapchain::VkSwapchain::new(&instance, &device, &surface, self.config.swapchain, dimension)?;
+        // object naming and command labels share the same toggle with validation.
+        if debug_type == debug::DebugType::DebugUtils {
+            logic_device.debug_utils = Some(ash::extensions::ext::DebugUtils::new(&instance.entry, &instance.handle));
+        }
+        let mut device = device::VkDevice::new(logic_device, phy_device, vma)?;
+
+        let swapchain = match (self.window, &surface) {
+            | (Some(window), Some(surface)) => {
+                let dimension = window.dimension()?;
+                let mut swapchain = swapchain::VkSwapchain::new(&instance, &device, surface, self.config.swapchain, dimension)?;
+                swapchain.set_render_scale(window.render_scale());
+                swapchain.set_hidpi_factor(window.hidpi_factor());
+                swapchain
+            },
+            | _ => {
+                swapchain::VkSwapchain::new_offscreen(&mut device, self.config.swapchain, self.headless_dimension)?
+            },
+        };
 
         let context = VulkanContext { instance, debugger, surface, device, swapchain };
         Ok(context)
@@ -6,8 +6,17 @@ use crate::{vklint, vksint, vkchar, vkptr, vkbool};
 use crate::error::{VkResult, VkError};
 
 use std::ffi::CStr;
+use std::sync::Mutex;
 use std::ptr;
 
+/// The names of validation layer, in the order of preference.
+///
+/// `VK_LAYER_LUNARG_standard_validation` is deprecated by `VK_LAYER_KHRONOS_validation` in newer Vulkan SDK.
+pub(super) const VALIDATION_LAYER_NAMES: [&str; 2] = [
+    "VK_LAYER_KHRONOS_validation",
+    "VK_LAYER_LUNARG_standard_validation",
+];
+
 #[derive(Debug, Default)]
 pub struct ValidationConfig {
 
@@ -17,6 +26,11 @@ pub struct ValidationConfig {
     pub report_config: DebugReportConfig,
     /// `utils_config` specifies the configuration parameters used in Debug Utils.
     pub  utils_config: DebugUtilsConfig,
+    /// `promote_errors` specifies whether the messages of ERROR severity should be turned into `VkError`.
+    ///
+    /// The errors are collected by the message callback, and returned by `VulkanContext::check_validation`.
+    /// It is useful during development to stop the program at the first validation error.
+    pub promote_errors: bool,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
@@ -50,19 +64,27 @@ impl DebugCreateInfo for vk::DebugReportCallbackCreateInfoEXT {}
 pub struct VkDebugger {
 
     target: Option<Box<dyn DebugInstance>>,
+    /// the destination of debug messages, whose address is passed to the callback as user data.
+    sink: Box<DebugMessageSink>,
 }
 
 impl VkDebugger {
 
     pub fn new(instance: &VkInstance, config: ValidationConfig) -> VkResult<VkDebugger> {
 
+        let sink = Box::new(DebugMessageSink {
+            promote_errors: config.promote_errors,
+            errors: Mutex::new(Vec::new()),
+        });
+        let user_data = (&*sink) as *const DebugMessageSink as vkptr;
+
         let debugger = match config.debug_type {
             | DebugType::DebugReport => {
-                let report = VkDebugReport::new(instance, &config.report_config)?;
+                let report = VkDebugReport::new(instance, &config.report_config, user_data)?;
                 Some(Box::new(report) as Box<dyn DebugInstance>)
             },
             | DebugType::DebugUtils => {
-                let utils = VkDebugUtils::new(instance, &config.utils_config)?;
+                let utils = VkDebugUtils::new(instance, &config.utils_config, user_data)?;
                 Some(Box::new(utils) as Box<dyn DebugInstance>)
             },
             | DebugType::None => {
@@ -70,19 +92,20 @@ impl VkDebugger {
             },
         };
 
-        let result = VkDebugger { target: debugger };
+        let result = VkDebugger { target: debugger, sink };
         Ok(result)
     }
 
     pub fn instance_debug_info(debug: DebugType, config: &ValidationConfig) -> Option<Box<dyn DebugCreateInfo>> {
 
+        // messages during instance creation and destruction are only logged.
         match debug {
             | DebugType::DebugReport => {
-                let report = VkDebugReport::create_info(&config.report_config);
+                let report = VkDebugReport::create_info(&config.report_config, ptr::null_mut());
                 Some(Box::new(report) as Box<dyn DebugCreateInfo>)
             },
             | DebugType::DebugUtils => {
-                let utils = VkDebugUtils::create_info(&config.utils_config);
+                let utils = VkDebugUtils::create_info(&config.utils_config, ptr::null_mut());
                 Some(Box::new(utils) as Box<dyn DebugCreateInfo>)
             },
             | DebugType::None => {
@@ -90,6 +113,20 @@ impl VkDebugger {
             },
         }
     }
+
+    /// Return the ERROR messages reported since last call as `VkError`, if `ValidationConfig::promote_errors` is enabled.
+    pub fn check_errors(&self) -> VkResult<()> {
+
+        let mut errors = self.sink.errors.lock()
+            .map_err(|_| VkError::custom("Failed to access validation messages."))?;
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            let messages: Vec<String> = errors.drain(..).collect();
+            Err(VkError::validation(messages.join("\n")))
+        }
+    }
 }
 
 impl Drop for VkDebugger {
@@ -108,21 +145,67 @@ impl Drop for VkDebugger {
 
 
 
+// Debug Message ----------------------------------------------------------------------------------
+
+/// The receiver of debug messages, which is shared with the callback function through user data pointer.
+struct DebugMessageSink {
+    /// whether ERROR messages should be collected.
+    promote_errors: bool,
+    /// the ERROR messages that have not been checked.
+    errors: Mutex<Vec<String>>,
+}
+
+/// Route the debug message to `log` crate, or print it to console if no logger has been installed.
+unsafe fn dispatch_debug_message(level: log::Level, category: &str, message: &CStr, user_data: vkptr) {
+
+    let message = message.to_string_lossy();
+
+    if log::max_level() == log::LevelFilter::Off {
+        println!("[Debug][{}]{} {}", level, category, message);
+    } else {
+        log::log!(target: "vulkan", level, "{} {}", category, message);
+    }
+
+    if level == log::Level::Error && user_data.is_null() == false {
+        let sink = &*(user_data as *const DebugMessageSink);
+        if sink.promote_errors {
+            if let Ok(mut errors) = sink.errors.lock() {
+                errors.push(format!("{} {}", category, message));
+            }
+        }
+    }
+}
+// ------------------------------------------------------------------------------------------------
+
+
+
 // Debug Report -----------------------------------------------------------------------------------
 
 /// the callback function used in Debug Report.
 unsafe extern "system" fn vulkan_debug_report_callback(
-    _flags       : vk::DebugReportFlagsEXT,
+    flags        : vk::DebugReportFlagsEXT,
     _obj_type    : vk::DebugReportObjectTypeEXT,
     _obj         : vklint,
     _location    : usize,
     _code        : vksint,
     _layer_prefix: *const vkchar,
     p_message    : *const vkchar,
-    _user_data   : vkptr
+    p_user_data  : vkptr
 ) -> u32 {
 
-    println!("[Debug] {:?}", CStr::from_ptr(p_message));
+    let (level, category) = if flags.contains(vk::DebugReportFlagsEXT::ERROR) {
+        (log::Level::Error, "[Validation]")
+    } else if flags.contains(vk::DebugReportFlagsEXT::PERFORMANCE_WARNING) {
+        (log::Level::Warn, "[Performance]")
+    } else if flags.contains(vk::DebugReportFlagsEXT::WARNING) {
+        (log::Level::Warn, "[Validation]")
+    } else if flags.contains(vk::DebugReportFlagsEXT::INFORMATION) {
+        (log::Level::Info, "[General]")
+    } else {
+        (log::Level::Debug, "[General]")
+    };
+
+    dispatch_debug_message(level, category, CStr::from_ptr(p_message), p_user_data);
     vk::FALSE
 }
 
@@ -157,24 +240,24 @@ struct VkDebugReport {
 impl VkDebugReport {
 
     /// Initialize debug extension loader and `vk::DebugReport` object.
-    pub fn new(instance: &VkInstance, config: &DebugReportConfig) -> VkResult<VkDebugReport> {
+    pub fn new(instance: &VkInstance, config: &DebugReportConfig, user_data: vkptr) -> VkResult<VkDebugReport> {
 
         // load the debug extension.
         let loader = ash::extensions::ext::DebugReport::new(&instance.entry, &instance.handle);
 
         // configure debug callback.
-        let debug_callback_ci = VkDebugReport::create_info(config);
+        let debug_callback_ci = VkDebugReport::create_info(config, user_data);
 
         let callback = unsafe {
             loader.create_debug_report_callback(&debug_callback_ci, None)
-                .or(Err(VkError::create("Debug Report Callback")))?
+                .map_err(|e| VkError::vk(e, "Create Debug Report Callback"))?
         };
 
         let report = VkDebugReport { loader, callback };
         Ok(report)
     }
 
-    fn create_info(config: &DebugReportConfig) -> vk::DebugReportCallbackCreateInfoEXT {
+    fn create_info(config: &DebugReportConfig, user_data: vkptr) -> vk::DebugReportCallbackCreateInfoEXT {
 
         vk::DebugReportCallbackCreateInfoEXT {
             s_type      : vk::StructureType::DEBUG_REPORT_CALLBACK_CREATE_INFO_EXT,
@@ -182,7 +265,7 @@ impl VkDebugReport {
             // Enum DebugReportFlags enumerate all available flags.
             flags       : config.flags,
             pfn_callback: Some(vulkan_debug_report_callback),
-            p_user_data : ptr::null_mut(),
+            p_user_data : user_data,
         }
     }
 }
@@ -208,25 +291,24 @@ unsafe extern "system" fn vulkan_debug_utils_callback(
     message_severity : vk::DebugUtilsMessageSeverityFlagsEXT,
     message_type     : vk::DebugUtilsMessageTypeFlagsEXT,
     p_callback_data  : *const vk::DebugUtilsMessengerCallbackDataEXT,
-    _p_user_data     : vkptr
+    p_user_data      : vkptr
 ) -> vkbool {
 
-    let severity = match message_severity {
-        | vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE => "[Verbose]",
-        | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => "[Warning]",
-        | vk::DebugUtilsMessageSeverityFlagsEXT::ERROR   => "[Error]",
-        | vk::DebugUtilsMessageSeverityFlagsEXT::INFO    => "[Info]",
-        | _ => "[Unknown]",
+    let level = match message_severity {
+        | vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE => log::Level::Trace,
+        | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => log::Level::Warn,
+        | vk::DebugUtilsMessageSeverityFlagsEXT::ERROR   => log::Level::Error,
+        | vk::DebugUtilsMessageSeverityFlagsEXT::INFO    => log::Level::Info,
+        | _ => log::Level::Debug,
     };
-    let types = match message_type {
+    let category = match message_type {
         | vk::DebugUtilsMessageTypeFlagsEXT::GENERAL     => "[General]",
         | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE => "[Performance]",
         | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION  => "[Validation]",
         | _ => "[Unknown]",
     };
-    let message = CStr::from_ptr((*p_callback_data).p_message);
-    println!("[Debug]{}{}{:?}", severity, types, message);
 
+    dispatch_debug_message(level, category, CStr::from_ptr((*p_callback_data).p_message), p_user_data);
     vk::FALSE
 }
 
@@ -268,22 +350,22 @@ struct VkDebugUtils {
 impl VkDebugUtils {
 
     /// Initialize debug report extension loader and `vk::DebugUtilsMessengerExt` object.
-    pub fn new(instance: &VkInstance, config: &DebugUtilsConfig) -> VkResult<VkDebugUtils> {
+    pub fn new(instance: &VkInstance, config: &DebugUtilsConfig, user_data: vkptr) -> VkResult<VkDebugUtils> {
 
         let loader = ash::extensions::ext::DebugUtils::new(&instance.entry, &instance.handle);
 
-        let messenger_ci = VkDebugUtils::create_info(config);
+        let messenger_ci = VkDebugUtils::create_info(config, user_data);
 
         let utils_messenger = unsafe {
             loader.create_debug_utils_messenger(&messenger_ci, None)
-                .or(Err(VkError::create("Debug Utils Callback")))?
+                .map_err(|e| VkError::vk(e, "Create Debug Utils Callback"))?
         };
 
         let utils = VkDebugUtils { loader, utils_messenger };
         Ok(utils)
     }
 
-    fn create_info(config: &DebugUtilsConfig) -> vk::DebugUtilsMessengerCreateInfoEXT {
+    fn create_info(config: &DebugUtilsConfig, user_data: vkptr) -> vk::DebugUtilsMessengerCreateInfoEXT {
 
         vk::DebugUtilsMessengerCreateInfoEXT {
             s_type: vk::StructureType::DEBUG_UTILS_MESSENGER_CREATE_INFO_EXT,
@@ -292,7 +374,7 @@ impl VkDebugUtils {
             message_severity : config.severity,
             message_type     : config.types,
             pfn_user_callback: Some(vulkan_debug_utils_callback),
-            p_user_data      : ptr::null_mut(),
+            p_user_data      : user_data,
         }
     }
 }
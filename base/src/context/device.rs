@@ -2,21 +2,32 @@
 mod physical;
 mod logical;
 mod queue;
+mod extension;
+mod tracker;
 
 pub use self::logical::{VkLogicalDevice, VkQueue, LogicDevConfig};
-pub use self::physical::{VkPhysicalDevice, PhysicalDevConfig};
+pub use self::physical::{VkPhysicalDevice, PhysicalDevConfig, DeviceExtensionType, DeviceSelector};
+pub(crate) use self::physical::DEPTH_FORMAT_CANDIDATES;
+pub use self::extension::{DynamicStateEXT, ExtendedDynamicStateFn, TimelineSemaphoreFn, DynamicRenderingFn, PushDescriptorFn};
+pub(crate) use self::extension::{SemaphoreTypeCreateInfo, TimelineSemaphoreSubmitInfo};
+pub(crate) use self::extension::PhysicalDeviceFeatures2Fn;
+pub(crate) use self::extension::{RenderingInfo, RenderingAttachmentInfo, PipelineRenderingCreateInfo};
+
+use self::tracker::VkHandleTracker;
 
 use ash::vk;
 use ash::version::DeviceV1_0;
 
 use crate::ci::command::{CommandPoolCI, CommandBufferAI};
-use crate::ci::pipeline::PipelineCacheCI;
+use crate::ci::pipeline::{PipelineCacheCI, GraphicsPipelineCI};
 use crate::ci::VkObjectBuildableCI;
 
 use crate::utils::time::VkTimeDuration;
 use crate::command::{VkCmdRecorder, ITransfer};
-use crate::{VkResult, VkError};
-use crate::{vkbytes, vkuint, vkptr};
+use crate::context::owned::{Owned, OwnedDiscardable, VkDeletionQueue, PendingDeletion};
+use crate::context::swapchain::{VkSwapchain, copy_image_to_host};
+use crate::{VkResult, VkError, VkErrorKind};
+use crate::{vkbytes, vkuint, vklint, vkptr};
 
 pub struct VkDevice {
 
@@ -29,6 +40,16 @@ pub struct VkDevice {
     /// An internal command pool that used to allocate command buffers for data transfer operations.
     transfer_cmd_pool: vk::CommandPool,
     transfer_command : vk::CommandBuffer,
+
+    /// An internal command pool that used to allocate command buffers for transfer operations requiring graphics queue(such as image blitting).
+    blit_cmd_pool: vk::CommandPool,
+    blit_command : vk::CommandBuffer,
+
+    /// The objects dropped by `Owned`, waiting to be destroyed.
+    deletion_queue: VkDeletionQueue,
+
+    /// The objects that have been created but not discarded yet, which are reported as leaks when the device is destroyed(debug builds only).
+    pub(crate) tracker: VkHandleTracker,
 }
 
 impl VkDevice {
@@ -40,6 +61,10 @@ impl VkDevice {
             pipeline_cache   : vk::PipelineCache::null(),
             transfer_cmd_pool: vk::CommandPool::null(),
             transfer_command : vk::CommandBuffer::null(),
+            blit_cmd_pool    : vk::CommandPool::null(),
+            blit_command     : vk::CommandBuffer::null(),
+            deletion_queue   : VkDeletionQueue::default(),
+            tracker          : VkHandleTracker::default(),
         };
 
         // Create an empty pipeline cache.
@@ -53,6 +78,13 @@ impl VkDevice {
         device.transfer_command = CommandBufferAI::new(device.transfer_cmd_pool, 1)
             .build(&device)?.remove(0);
 
+        // Create command pool for blit operations, which must be submitted to a queue with graphics capability.
+        device.blit_cmd_pool = CommandPoolCI::new(device.logic.queues.graphics.family_index)
+            .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER | vk::CommandPoolCreateFlags::TRANSIENT)
+            .build(&device)?;
+        device.blit_command = CommandBufferAI::new(device.blit_cmd_pool, 1)
+            .build(&device)?.remove(0);
+
         Ok(device)
     }
 
@@ -71,14 +103,79 @@ impl VkDevice {
         // reset the command buffer after transfer operation has been done.
         unsafe {
             self.logic.handle.reset_command_buffer(self.transfer_command, vk::CommandBufferResetFlags::RELEASE_RESOURCES)
-                .map_err(|_| VkError::device("Reset Command Buffer"))
+                .map_err(|e| VkError::vk(e, "Reset Command Buffer"))
+        }
+    }
+
+    /// Get a recorder for transfer operations that must be executed on graphics queue(`vkCmdBlitImage` for example).
+    pub fn get_blit_recorder(&self) -> VkCmdRecorder<ITransfer> {
+
+        let mut recorder = VkCmdRecorder::new(&self.logic, self.blit_command);
+        recorder.set_usage(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+
+        recorder
+    }
+
+    pub fn flush_blit(&self, recorder: VkCmdRecorder<ITransfer>) -> VkResult<()> {
+
+        recorder.flush_copy_command(self.logic.queues.graphics.handle)?;
+
+        unsafe {
+            self.logic.handle.reset_command_buffer(self.blit_command, vk::CommandBufferResetFlags::RELEASE_RESOURCES)
+                .map_err(|e| VkError::vk(e, "Reset Command Buffer"))
         }
     }
 
-    pub(super) fn drop_self(self) {
+    /// Wrap `object` in an `Owned`, which pushes the object to the deletion queue of this device on drop.
+    #[inline]
+    pub fn owned<T: OwnedDiscardable>(&self, object: T) -> Owned<T> {
+        Owned::new(object, self.deletion_queue.downgrade())
+    }
+
+    /// Destroy all objects dropped by `Owned` so far.
+    ///
+    /// Call this method only when the GPU no longer uses these objects(etc. after waiting device idle).
+    pub fn flush_deletion_queue(&mut self) -> VkResult<()> {
+
+        let deletions = self.deletion_queue.take_all();
+        self.destroy_deletions(deletions)
+    }
+
+    /// Destroy the objects dropped by `Owned` during the last use of the frame-in-flight `frame_index`,
+    /// and collect the objects dropped from now on to this frame.
+    ///
+    /// Call this method after waiting the fence of this frame-in-flight(`ProcPipeline` calls it after acquiring each frame).
+    pub fn flush_frame_deletions(&mut self, frame_index: usize) -> VkResult<()> {
+
+        let deletions = self.deletion_queue.begin_frame(frame_index);
+        self.destroy_deletions(deletions)
+    }
+
+    fn destroy_deletions(&mut self, deletions: Vec<PendingDeletion>) -> VkResult<()> {
 
+        for deletion in deletions {
+            match deletion {
+                | PendingDeletion::Object(discard) => discard(self),
+                | PendingDeletion::Vma(discard) => discard(&mut self.vma)?,
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn drop_self(mut self) {
+
+        // destroy the remaining objects dropped by `Owned`.
+        if let Err(e) = self.flush_deletion_queue() {
+            println!("[Warning] Failed to flush deletion queue: {}", e);
+        }
+
+        self.discard(self.blit_cmd_pool);
         self.discard(self.transfer_cmd_pool);
         self.discard(self.pipeline_cache);
+
+        // all objects should have been discarded at this point.
+        self.tracker.report_leaks();
+
         // destroy vma manually, so that vma will be destroyed before logic device.
         drop(self.vma);
     }
@@ -87,12 +184,22 @@ impl VkDevice {
 impl VkDevice {
 
     #[inline]
+    #[track_caller]
     pub fn build<T>(&self, ci: &T) -> VkResult<T::ObjectType>
         where
             T: crate::ci::VkObjectBuildableCI {
         ci.build(self)
     }
 
+    /// Create multiple graphics pipelines in one driver call.
+    ///
+    /// The base pipeline of a derivative can be referenced by its index in `cis`(see `GraphicsPipelineCI::set_base_pipeline_index`).
+    #[inline]
+    #[track_caller]
+    pub fn build_pipelines(&self, cis: &[&GraphicsPipelineCI]) -> VkResult<Vec<vk::Pipeline>> {
+        crate::ci::pipeline::build_graphics_pipelines(self, cis)
+    }
+
     #[inline]
     pub fn bind_memory(&self, object: impl VkObjectBindable, memory: vk::DeviceMemory, offset: vkbytes) -> VkResult<()> {
         object.bind(self, memory, offset)
@@ -102,7 +209,7 @@ impl VkDevice {
     pub fn map_memory<T>(&self, memory: vk::DeviceMemory, offset: vkbytes, size: vkbytes) -> VkResult<vkptr<T>> {
         let ptr = unsafe {
             self.logic.handle.map_memory(memory, offset, size, vk::MemoryMapFlags::empty())
-                .map_err(|_| VkError::device("Map Memory"))?
+                .map_err(|e| VkError::vk(e, "Map Memory"))?
         };
         Ok(ptr as vkptr<T>)
     }
@@ -142,12 +249,336 @@ impl VkDevice {
         object.free(self, pool);
     }
 
+    /// Free all the descriptor sets allocated from `pool` and return them to the pool, without recreating the pool.
+    ///
+    /// The pool does not require `vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET`, and the sets from it must not be used by GPU anymore.
+    pub fn reset_descriptor_pool(&self, pool: vk::DescriptorPool) -> VkResult<()> {
+        unsafe {
+            self.logic.handle.reset_descriptor_pool(pool, vk::DescriptorPoolResetFlags::empty())
+                .map_err(|e| VkError::vk(e, "Reset Descriptor Pool"))
+        }
+    }
+
+    /// Give `handle` a readable name, which is displayed in validation messages and debugging tools(etc. RenderDoc).
+    ///
+    /// This is a no-op if validation is disabled.
+    pub fn set_object_name<T: vk::Handle>(&self, handle: T, name: &str) -> VkResult<()> {
+
+        if let Some(ref debug_utils) = self.logic.debug_utils {
+
+            let object_name = ::std::ffi::CString::new(name)
+                .map_err(|_| VkError::custom("Failed to cast object name to CString."))?;
+
+            let name_info = vk::DebugUtilsObjectNameInfoEXT {
+                s_type: vk::StructureType::DEBUG_UTILS_OBJECT_NAME_INFO_EXT,
+                p_next: ::std::ptr::null(),
+                object_type  : T::TYPE,
+                object_handle: handle.as_raw(),
+                p_object_name: object_name.as_ptr(),
+            };
+
+            unsafe {
+                debug_utils.debug_utils_set_object_name(self.logic.handle.handle(), &name_info)
+                    .map_err(|e| VkError::vk(e, "Set Debug Object Name"))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the functions of timeline semaphores, or return an error if `DeviceExtensionType::TimelineSemaphore` is not requested.
+    pub(crate) fn timeline_semaphore_fn(&self) -> VkResult<&TimelineSemaphoreFn> {
+        self.logic.timeline_semaphore.as_ref()
+            .ok_or(VkError::unsupported("Timeline semaphore(request `DeviceExtensionType::TimelineSemaphore` in `PhysicalDevConfig` to enable it)"))
+    }
+
+    /// Get the functions of dynamic rendering, or return an error if `DeviceExtensionType::DynamicRendering` is not requested.
+    pub(crate) fn dynamic_rendering_fn(&self) -> VkResult<&DynamicRenderingFn> {
+        self.logic.dynamic_rendering.as_ref()
+            .ok_or(VkError::unsupported("Dynamic rendering(request `DeviceExtensionType::DynamicRendering` in `PhysicalDevConfig` to enable it)"))
+    }
+
+    /// Return an error if `DeviceExtensionType::DescriptorIndexing` is not requested.
+    pub(crate) fn require_descriptor_indexing(&self) -> VkResult<()> {
+        if self.logic.is_descriptor_indexing_enabled {
+            Ok(())
+        } else {
+            Err(VkError::unsupported("Descriptor indexing(request `DeviceExtensionType::DescriptorIndexing` in `PhysicalDevConfig` to enable it)"))
+        }
+    }
+
+    /// Block the host until each timeline semaphore in `semaphores` reaches its paired value, or `timeout` is exceeded.
+    ///
+    /// If `timeout` is exceeded, the error carries `vk::Result::TIMEOUT`(see `VkError::vk_result`).
+    pub fn wait_semaphores(&self, semaphores: &[(vk::Semaphore, vklint)], timeout: VkTimeDuration) -> VkResult<()> {
+
+        use self::extension::SemaphoreWaitInfo;
+
+        let functions = self.timeline_semaphore_fn()?;
+
+        let (handles, values): (Vec<vk::Semaphore>, Vec<vklint>) = semaphores.iter().cloned().unzip();
+        let wait_info = SemaphoreWaitInfo::new(&handles, &values);
+
+        match (functions.wait_semaphores)(self.logic.handle.handle(), &wait_info, timeout.into()) {
+            | vk::Result::SUCCESS => Ok(()),
+            | e => Err(VkError::vk(e, "Wait for timeline semaphores")),
+        }
+    }
+
+    /// Set the value of timeline `semaphore` to `value` from the host.
+    ///
+    /// `value` must be greater than the current value of the semaphore.
+    pub fn signal_semaphore(&self, semaphore: vk::Semaphore, value: vklint) -> VkResult<()> {
+
+        use self::extension::SemaphoreSignalInfo;
+
+        let functions = self.timeline_semaphore_fn()?;
+        let signal_info = SemaphoreSignalInfo::new(semaphore, value);
+
+        match (functions.signal_semaphore)(self.logic.handle.handle(), &signal_info) {
+            | vk::Result::SUCCESS => Ok(()),
+            | e => Err(VkError::vk(e, "Signal timeline semaphore")),
+        }
+    }
+
+    /// Query the current value of timeline `semaphore`.
+    pub fn semaphore_counter_value(&self, semaphore: vk::Semaphore) -> VkResult<vklint> {
+
+        let functions = self.timeline_semaphore_fn()?;
+
+        let mut value: vklint = 0;
+        match (functions.get_semaphore_counter_value)(self.logic.handle.handle(), semaphore, &mut value) {
+            | vk::Result::SUCCESS => Ok(value),
+            | e => Err(VkError::vk(e, "Get Semaphore Counter Value")),
+        }
+    }
+
+    /// Allocate a single descriptor set with `layout` from `pool`.
+    pub fn allocate_descriptor_set(&self, pool: vk::DescriptorPool, layout: vk::DescriptorSetLayout) -> VkResult<vk::DescriptorSet> {
+
+        let mut descriptor_sets = self.allocate_descriptor_sets(pool, &[layout])?;
+        Ok(descriptor_sets.remove(0))
+    }
+
+    /// Allocate a descriptor set from `pool` for each layout in `layouts`.
+    ///
+    /// The returned sets keep the same order as `layouts`.
+    pub fn allocate_descriptor_sets(&self, pool: vk::DescriptorPool, layouts: &[vk::DescriptorSetLayout]) -> VkResult<Vec<vk::DescriptorSet>> {
+
+        use crate::ci::descriptor::DescriptorSetAI;
+
+        let descriptor_sets = layouts.iter()
+            .fold(DescriptorSetAI::new(pool), |allocate_ci, &layout| allocate_ci.add_set_layout(layout))
+            .build(self)?;
+
+        if descriptor_sets.len() == layouts.len() {
+            Ok(descriptor_sets)
+        } else {
+            Err(VkError::create("Allocate Descriptor Set"))
+        }
+    }
+
+    /// Point the descriptor at `binding` of `set` to `info`, without updating other bindings.
+    ///
+    /// `type_` must match the descriptor type declared in the set layout.
+    pub fn update_descriptor_buffer(&self, set: vk::DescriptorSet, binding: vkuint, type_: vk::DescriptorType, info: vk::DescriptorBufferInfo) {
+
+        use crate::ci::descriptor::{DescriptorBufferSetWI, DescriptorSetsUpdateCI};
+
+        let write_info = DescriptorBufferSetWI::new(set, binding, type_)
+            .add_buffer(info);
+
+        DescriptorSetsUpdateCI::new()
+            .add_write(&write_info)
+            .update(self);
+    }
+
+    /// Point the descriptor at `binding` of `set` to `info`, without updating other bindings.
+    ///
+    /// `type_` must match the descriptor type declared in the set layout.
+    pub fn update_descriptor_image(&self, set: vk::DescriptorSet, binding: vkuint, type_: vk::DescriptorType, info: vk::DescriptorImageInfo) {
+
+        use crate::ci::descriptor::{DescriptorImageSetWI, DescriptorSetsUpdateCI};
+
+        let write_info = DescriptorImageSetWI::new(set, binding, type_)
+            .add_image(info);
+
+        DescriptorSetsUpdateCI::new()
+            .add_write(&write_info)
+            .update(self);
+    }
+
+    /// Copy the swapchain image of `image_index` to host memory, and convert it to RGBA order.
+    ///
+    /// The image must have been presented(etc. the image of the last frame), and the device must be idle.
+    /// Only the swapchain of 8-bit RGBA or BGRA format is supported.
+    pub fn capture_swapchain_image(&mut self, swapchain: &VkSwapchain, image_index: usize) -> VkResult<image::RgbaImage> {
+
+        let is_bgra = match swapchain.backend_format {
+            | vk::Format::B8G8R8A8_UNORM
+            | vk::Format::B8G8R8A8_SRGB => true,
+            | vk::Format::R8G8B8A8_UNORM
+            | vk::Format::R8G8B8A8_SRGB => false,
+            | _ => return Err(VkError::custom(format!("Capturing the swapchain image in {:?} format is not supported.", swapchain.backend_format))),
+        };
+
+        if swapchain.image_usage().contains(vk::ImageUsageFlags::TRANSFER_SRC) == false {
+            return Err(VkError::custom("The swapchain images can not be captured, since the surface does not support transfer source usage."))
+        }
+
+        let swapchain_image = swapchain.images.get(image_index)
+            .ok_or(VkError::custom(format!("Invalid swapchain image index: {}", image_index)))?;
+        let mut pixels = copy_image_to_host(self, swapchain_image.image, swapchain.dimension)?;
+
+        if is_bgra {
+            for pixel in pixels.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        image::RgbaImage::from_raw(swapchain.dimension.width, swapchain.dimension.height, pixels)
+            .ok_or(VkError::custom("The size of captured pixels does not match the swapchain dimension."))
+    }
+
+    /// Collect the count and size of all the allocations made by `vma`.
+    ///
+    /// The resources created from `vk::DeviceMemory` directly(without `vma`) are not counted.
+    pub fn allocation_stats(&self) -> VkResult<AllocationStats> {
+
+        let stats = self.vma.calculate_stats()
+            .map_err(VkErrorKind::Vma)?;
+
+        let result = AllocationStats {
+            allocation_count: stats.total.allocationCount as usize,
+            block_count: stats.total.blockCount as usize,
+            used_bytes: stats.total.usedBytes as vkbytes,
+        };
+        Ok(result)
+    }
+
+    /// Collect the usage and budget of each memory heap.
+    ///
+    /// `used_bytes` and `allocated_bytes` only count the allocations made by `vma`.
+    /// The budget and the usage of the whole process are reported by `VK_EXT_memory_budget`, and they are `None` if this extension is not supported.
+    pub fn memory_stats(&self) -> VkResult<MemoryBudget> {
+
+        let stats = self.vma.calculate_stats()
+            .map_err(VkErrorKind::Vma)?;
+        let budget = self.phy.memory_budget();
+
+        let heap_count = self.phy.memories.memory_heap_count as usize;
+        let heaps = self.phy.memories.memory_heaps[..heap_count].iter()
+            .zip(stats.memoryHeap.iter()).enumerate()
+            .map(|(i, (heap, heap_stats))| HeapBudget {
+                flags: heap.flags,
+                heap_size: heap.size,
+                used_bytes: heap_stats.usedBytes as vkbytes,
+                allocated_bytes: (heap_stats.usedBytes + heap_stats.unusedBytes) as vkbytes,
+                budget_bytes : budget.as_ref().map(|budget| budget.heap_budget[i]),
+                process_bytes: budget.as_ref().map(|budget| budget.heap_usage[i]),
+            }).collect();
+
+        Ok(MemoryBudget { heaps })
+    }
+
+    /// Check if `format` supports all the `features` with `tiling`.
+    ///
+    /// Call it before creating an image whose format may be optional(etc. `vk::Format::BC7_UNORM_BLOCK` or a depth format).
+    pub fn is_format_supported(&self, format: vk::Format, tiling: vk::ImageTiling, features: vk::FormatFeatureFlags) -> bool {
+
+        let properties = self.phy.format_properties(format);
+
+        match tiling {
+            | vk::ImageTiling::LINEAR  => properties.linear_tiling_features.contains(features),
+            | vk::ImageTiling::OPTIMAL => properties.optimal_tiling_features.contains(features),
+            | _ => false,
+        }
+    }
+
+    /// Return the first depth format(in the order of `D32_SFLOAT_S8_UINT`, `D32_SFLOAT`, `D24_UNORM_S8_UINT`, `D16_UNORM_S8_UINT`, `D16_UNORM`)
+    /// that supports depth stencil attachment with optimal tiling.
+    ///
+    /// `D16_UNORM` is returned if no candidate is supported, since it is always supported as required by specification.
+    /// The result is the same as `VkPhysicalDevice::depth_format`.
+    pub fn supported_depth_format(&self) -> vk::Format {
+
+        DEPTH_FORMAT_CANDIDATES.iter().cloned()
+            .find(|&format| self.is_format_supported(format, vk::ImageTiling::OPTIMAL, vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT))
+            .unwrap_or(vk::Format::D16_UNORM)
+    }
+
     /// Return the first memory type index that is support `request_flags`.
     #[inline]
     pub fn get_memory_type(&self, type_bits: vkuint, request_flags: vk::MemoryPropertyFlags) -> vkuint {
         use crate::utils::memory::get_memory_type_index;
         get_memory_type_index(self, type_bits, request_flags)
     }
+
+    /// Return the first memory type index that supports `preferred` flags, or the one that supports `required` flags if none supports `preferred`.
+    ///
+    /// Use it when the memory layout differs between devices(etc. prefer `DEVICE_LOCAL | HOST_VISIBLE` memory for resizable BAR, but accept `HOST_VISIBLE` only on other devices).
+    /// `None` is returned if no memory type supports `required` flags.
+    pub fn get_memory_type_with_fallback(&self, type_bits: vkuint, preferred: vk::MemoryPropertyFlags, required: vk::MemoryPropertyFlags) -> Option<vkuint> {
+
+        use crate::utils::memory::find_memory_type_index;
+
+        debug_assert!(preferred.contains(required), "`preferred` flags should include all the `required` flags.");
+
+        find_memory_type_index(self, type_bits, preferred)
+            .or_else(|| find_memory_type_index(self, type_bits, required))
+    }
+}
+
+/// A snapshot of the allocations of `vma`, which is returned by `VkDevice::allocation_stats`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AllocationStats {
+    /// the count of alive allocations(etc. a `VmaBuffer` or `VmaImage`).
+    pub allocation_count: usize,
+    /// the count of `vk::DeviceMemory` blocks that the allocations are sub-allocated from.
+    pub block_count: usize,
+    /// the total size of alive allocations in bytes.
+    pub used_bytes: vkbytes,
+}
+
+/// The memory usage of each memory heap, which is returned by `VkDevice::memory_stats`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryBudget {
+    /// the budget of each memory heap, indexed by the heap index of `vk::PhysicalDeviceMemoryProperties`.
+    pub heaps: Vec<HeapBudget>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct HeapBudget {
+    /// the flags of the heap(etc. `vk::MemoryHeapFlags::DEVICE_LOCAL`).
+    pub flags: vk::MemoryHeapFlags,
+    /// the total size of the heap in bytes.
+    pub heap_size: vkbytes,
+    /// the size of alive allocations in this heap.
+    pub used_bytes: vkbytes,
+    /// the size of `vk::DeviceMemory` blocks allocated from this heap, which is not less than `used_bytes`.
+    pub allocated_bytes: vkbytes,
+    /// the size that the process can allocate from this heap without performance penalty(`heapBudget` of `VK_EXT_memory_budget`),
+    /// or `None` if this extension is not supported.
+    pub budget_bytes: Option<vkbytes>,
+    /// the size of this heap used by the whole process, including the memory not allocated by `vma`(`heapUsage` of `VK_EXT_memory_budget`),
+    /// or `None` if this extension is not supported.
+    pub process_bytes: Option<vkbytes>,
+}
+
+impl MemoryBudget {
+
+    /// The total usage of all `DEVICE_LOCAL` heaps and their total budget in bytes.
+    ///
+    /// The budget is `None` if `VK_EXT_memory_budget` is not supported.
+    pub fn device_local(&self) -> (vkbytes, Option<vkbytes>) {
+
+        self.heaps.iter()
+            .filter(|heap| heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL))
+            .fold((0, Some(0)), |(used, budget), heap| {
+                let budget = budget.and_then(|budget| heap.budget_bytes.map(|heap_budget| budget + heap_budget));
+                (used + heap.used_bytes, budget)
+            })
+    }
 }
 
 pub trait VkObjectDiscardable: Copy {
@@ -155,6 +586,33 @@ pub trait VkObjectDiscardable: Copy {
     fn discard_by(self, device: &VkDevice);
 }
 
+/// Discard the objects in order, from the first to the last.
+impl<'a, T: VkObjectDiscardable> VkObjectDiscardable for &'a [T] {
+
+    fn discard_by(self, device: &VkDevice) {
+
+        for &object in self {
+            object.discard_by(device);
+        }
+    }
+}
+
+impl<'a, T: VkObjectDiscardable> VkObjectDiscardable for &'a Vec<T> {
+
+    #[inline]
+    fn discard_by(self, device: &VkDevice) {
+        self.as_slice().discard_by(device);
+    }
+}
+
+impl<'a, T: VkObjectDiscardable, const N: usize> VkObjectDiscardable for &'a [T; N] {
+
+    #[inline]
+    fn discard_by(self, device: &VkDevice) {
+        (&self[..]).discard_by(device);
+    }
+}
+
 pub trait VmaResourceDiscardable {
 
     fn discard_by(self, vma: &mut vma::Allocator) -> VkResult<()>;
@@ -0,0 +1,526 @@
+//! Device extensions that are not covered by `ash` yet.
+
+use ash::vk;
+use ash::version::{InstanceV1_0, EntryV1_0};
+
+use crate::{vkbool, vkuint, vklint, vkbytes};
+
+use std::ffi::CStr;
+use std::os::raw::c_void;
+use std::ptr;
+
+
+// ----------------------------------------------------------------------------------------------
+/// Load a function pointer by the first available name of `$name`s, or return `None` from the enclosing function if none of them is available.
+///
+/// `$proc_addr` maps a null-terminated function name to its address, which is 0 if the function is missing.
+macro_rules! load_fn {
+    ($proc_addr:expr; $($name:expr),+) => {{
+        let mut raw: usize = 0;
+        $(
+            if raw == 0 {
+                raw = $proc_addr($name);
+            }
+        )+
+        if raw == 0 {
+            return None
+        }
+        unsafe { ::std::mem::transmute(raw) }
+    }};
+}
+
+/// Get the address of device level function `name`, or 0 if it is not available.
+fn device_proc_addr(instance: &ash::Instance, device: &ash::Device, name: &'static [u8]) -> usize {
+
+    let name = CStr::from_bytes_with_nul(name).unwrap();
+    unsafe {
+        ::std::mem::transmute(instance.fp_v1_0().get_device_proc_addr(device.handle(), name.as_ptr()))
+    }
+}
+
+/// Get the address of instance level function `name`, or 0 if it is not available.
+fn instance_proc_addr(entry: &ash::Entry, instance: &ash::Instance, name: &'static [u8]) -> usize {
+
+    let name = CStr::from_bytes_with_nul(name).unwrap();
+    unsafe {
+        ::std::mem::transmute(entry.static_fn().get_instance_proc_addr(instance.handle(), name.as_ptr()))
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+// ----------------------------------------------------------------------------------------------
+/// `VkPhysicalDeviceFeatures2`, whose `p_next` chain is filled by `vkGetPhysicalDeviceFeatures2` with the supported features of extensions.
+#[repr(C)]
+struct PhysicalDeviceFeatures2 {
+    s_type: vk::StructureType,
+    p_next: *mut c_void,
+    features: vk::PhysicalDeviceFeatures,
+}
+
+/// `VkPhysicalDeviceMemoryProperties2`, whose `p_next` chain is filled by `vkGetPhysicalDeviceMemoryProperties2`.
+#[repr(C)]
+struct PhysicalDeviceMemoryProperties2 {
+    s_type: vk::StructureType,
+    p_next: *mut c_void,
+    memory_properties: vk::PhysicalDeviceMemoryProperties,
+}
+
+/// `VkPhysicalDeviceMemoryBudgetPropertiesEXT`, which reports the budget and usage of each memory heap by `VK_EXT_memory_budget`.
+#[repr(C)]
+pub(crate) struct PhysicalDeviceMemoryBudgetPropertiesEXT {
+    pub s_type: vk::StructureType,
+    pub p_next: *mut c_void,
+    pub heap_budget: [vkbytes; vk::MAX_MEMORY_HEAPS],
+    pub heap_usage : [vkbytes; vk::MAX_MEMORY_HEAPS],
+}
+
+/// The functions to query the features of extensions and the memory properties, from `VK_KHR_get_physical_device_properties2` or Vulkan 1.1.
+#[derive(Clone)]
+pub(crate) struct PhysicalDeviceFeatures2Fn {
+    get_physical_device_features2: extern "system" fn(vk::PhysicalDevice, *mut PhysicalDeviceFeatures2),
+    get_physical_device_memory_properties2: extern "system" fn(vk::PhysicalDevice, *mut PhysicalDeviceMemoryProperties2),
+}
+
+impl PhysicalDeviceFeatures2Fn {
+
+    pub const NAME: &'static str = "VK_KHR_get_physical_device_properties2";
+
+    /// Load the function pointer from `instance`, or return `None` if it is missing.
+    ///
+    /// The function of the extension is preferred, and the core function of Vulkan 1.1 is used as fallback.
+    pub fn load(entry: &ash::Entry, instance: &ash::Instance) -> Option<PhysicalDeviceFeatures2Fn> {
+
+        let proc_addr = |name: &'static [u8]| instance_proc_addr(entry, instance, name);
+
+        let result = PhysicalDeviceFeatures2Fn {
+            get_physical_device_features2: load_fn!(proc_addr; b"vkGetPhysicalDeviceFeatures2KHR\0", b"vkGetPhysicalDeviceFeatures2\0"),
+            get_physical_device_memory_properties2: load_fn!(proc_addr; b"vkGetPhysicalDeviceMemoryProperties2KHR\0", b"vkGetPhysicalDeviceMemoryProperties2\0"),
+        };
+        Some(result)
+    }
+
+    /// Fill the feature structure pointed by `features`(etc. `PhysicalDeviceTimelineSemaphoreFeatures`) with the support of `physical_device`.
+    ///
+    /// The `p_next` of the structure should be null, so that only this structure is queried.
+    pub fn query(&self, physical_device: vk::PhysicalDevice, features: *mut c_void) {
+
+        let mut features2 = PhysicalDeviceFeatures2 {
+            s_type: vk::StructureType::from_raw(1_000_059_000),
+            p_next: features,
+            features: vk::PhysicalDeviceFeatures::default(),
+        };
+        (self.get_physical_device_features2)(physical_device, &mut features2);
+    }
+
+    /// Query the budget and usage of each memory heap of `physical_device`.
+    ///
+    /// The result is only meaningful if `VK_EXT_memory_budget` is enabled for the device.
+    pub fn query_memory_budget(&self, physical_device: vk::PhysicalDevice) -> PhysicalDeviceMemoryBudgetPropertiesEXT {
+
+        let mut budget = PhysicalDeviceMemoryBudgetPropertiesEXT {
+            s_type: vk::StructureType::from_raw(1_000_237_000),
+            p_next: ptr::null_mut(),
+            heap_budget: [0; vk::MAX_MEMORY_HEAPS],
+            heap_usage : [0; vk::MAX_MEMORY_HEAPS],
+        };
+        let mut properties2 = PhysicalDeviceMemoryProperties2 {
+            s_type: vk::StructureType::from_raw(1_000_059_006),
+            p_next: &mut budget as *mut PhysicalDeviceMemoryBudgetPropertiesEXT as *mut c_void,
+            memory_properties: vk::PhysicalDeviceMemoryProperties::default(),
+        };
+        (self.get_physical_device_memory_properties2)(physical_device, &mut properties2);
+
+        budget
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+
+// ----------------------------------------------------------------------------------------------
+/// The dynamic states introduced by `VK_EXT_extended_dynamic_state`.
+///
+/// Add them to the dynamic states of pipeline, and set them by `VkCmdRecorder::set_cull_mode` and so on.
+pub struct DynamicStateEXT;
+
+impl DynamicStateEXT {
+
+    #[inline]
+    pub fn cull_mode() -> vk::DynamicState { vk::DynamicState::from_raw(1_000_267_000) }
+    #[inline]
+    pub fn front_face() -> vk::DynamicState { vk::DynamicState::from_raw(1_000_267_001) }
+    #[inline]
+    pub fn primitive_topology() -> vk::DynamicState { vk::DynamicState::from_raw(1_000_267_002) }
+    #[inline]
+    pub fn depth_test_enable() -> vk::DynamicState { vk::DynamicState::from_raw(1_000_267_006) }
+    #[inline]
+    pub fn depth_write_enable() -> vk::DynamicState { vk::DynamicState::from_raw(1_000_267_007) }
+    #[inline]
+    pub fn depth_compare_op() -> vk::DynamicState { vk::DynamicState::from_raw(1_000_267_008) }
+}
+
+/// `VkPhysicalDeviceExtendedDynamicStateFeaturesEXT`, which is queried by `PhysicalDeviceFeatures2Fn` and chained to `vk::DeviceCreateInfo` to enable this extension.
+#[repr(C)]
+pub(crate) struct PhysicalDeviceExtendedDynamicStateFeaturesEXT {
+    pub s_type: vk::StructureType,
+    pub p_next: *mut c_void,
+    pub extended_dynamic_state: vkbool,
+}
+
+impl Default for PhysicalDeviceExtendedDynamicStateFeaturesEXT {
+
+    fn default() -> PhysicalDeviceExtendedDynamicStateFeaturesEXT {
+        PhysicalDeviceExtendedDynamicStateFeaturesEXT {
+            s_type: vk::StructureType::from_raw(1_000_267_000),
+            p_next: ptr::null_mut(),
+            extended_dynamic_state: vk::FALSE,
+        }
+    }
+}
+
+/// The command functions of `VK_EXT_extended_dynamic_state`.
+#[derive(Clone)]
+pub struct ExtendedDynamicStateFn {
+    pub cmd_set_cull_mode         : extern "system" fn(vk::CommandBuffer, vk::CullModeFlags),
+    pub cmd_set_front_face        : extern "system" fn(vk::CommandBuffer, vk::FrontFace),
+    pub cmd_set_primitive_topology: extern "system" fn(vk::CommandBuffer, vk::PrimitiveTopology),
+    pub cmd_set_depth_test_enable : extern "system" fn(vk::CommandBuffer, vkbool),
+    pub cmd_set_depth_write_enable: extern "system" fn(vk::CommandBuffer, vkbool),
+    pub cmd_set_depth_compare_op  : extern "system" fn(vk::CommandBuffer, vk::CompareOp),
+}
+
+impl ExtendedDynamicStateFn {
+
+    pub const NAME: &'static str = "VK_EXT_extended_dynamic_state";
+
+    /// Load the function pointers from `device`, or return `None` if any of them is missing.
+    pub(crate) fn load(instance: &ash::Instance, device: &ash::Device) -> Option<ExtendedDynamicStateFn> {
+
+        let proc_addr = |name: &'static [u8]| device_proc_addr(instance, device, name);
+
+        let result = ExtendedDynamicStateFn {
+            cmd_set_cull_mode         : load_fn!(proc_addr; b"vkCmdSetCullModeEXT\0"),
+            cmd_set_front_face        : load_fn!(proc_addr; b"vkCmdSetFrontFaceEXT\0"),
+            cmd_set_primitive_topology: load_fn!(proc_addr; b"vkCmdSetPrimitiveTopologyEXT\0"),
+            cmd_set_depth_test_enable : load_fn!(proc_addr; b"vkCmdSetDepthTestEnableEXT\0"),
+            cmd_set_depth_write_enable: load_fn!(proc_addr; b"vkCmdSetDepthWriteEnableEXT\0"),
+            cmd_set_depth_compare_op  : load_fn!(proc_addr; b"vkCmdSetDepthCompareOpEXT\0"),
+        };
+        Some(result)
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+// ----------------------------------------------------------------------------------------------
+/// `VK_SEMAPHORE_TYPE_TIMELINE`.
+const SEMAPHORE_TYPE_TIMELINE: i32 = 1;
+
+/// `VkPhysicalDeviceTimelineSemaphoreFeatures`, which is queried by `PhysicalDeviceFeatures2Fn` and chained to `vk::DeviceCreateInfo` to enable timeline semaphores.
+#[repr(C)]
+pub(crate) struct PhysicalDeviceTimelineSemaphoreFeatures {
+    pub s_type: vk::StructureType,
+    pub p_next: *mut c_void,
+    pub timeline_semaphore: vkbool,
+}
+
+impl Default for PhysicalDeviceTimelineSemaphoreFeatures {
+
+    fn default() -> PhysicalDeviceTimelineSemaphoreFeatures {
+        PhysicalDeviceTimelineSemaphoreFeatures {
+            s_type: vk::StructureType::from_raw(1_000_207_000),
+            p_next: ptr::null_mut(),
+            timeline_semaphore: vk::FALSE,
+        }
+    }
+}
+
+/// `VkSemaphoreTypeCreateInfo`, which is chained to `vk::SemaphoreCreateInfo` to create a timeline semaphore.
+#[repr(C)]
+pub(crate) struct SemaphoreTypeCreateInfo {
+    pub s_type: vk::StructureType,
+    pub p_next: *const c_void,
+    pub semaphore_type: i32,
+    pub initial_value: vklint,
+}
+
+impl SemaphoreTypeCreateInfo {
+
+    pub fn timeline(initial_value: vklint) -> SemaphoreTypeCreateInfo {
+        SemaphoreTypeCreateInfo {
+            s_type: vk::StructureType::from_raw(1_000_207_002),
+            p_next: ptr::null(),
+            semaphore_type: SEMAPHORE_TYPE_TIMELINE,
+            initial_value,
+        }
+    }
+}
+
+/// `VkTimelineSemaphoreSubmitInfo`, which is chained to `vk::SubmitInfo` to specify the values of timeline semaphores.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub(crate) struct TimelineSemaphoreSubmitInfo {
+    pub s_type: vk::StructureType,
+    pub p_next: *const c_void,
+    pub wait_semaphore_value_count  : vkuint,
+    pub p_wait_semaphore_values     : *const vklint,
+    pub signal_semaphore_value_count: vkuint,
+    pub p_signal_semaphore_values   : *const vklint,
+}
+
+impl TimelineSemaphoreSubmitInfo {
+
+    pub fn new(wait_values: &[vklint], signal_values: &[vklint]) -> TimelineSemaphoreSubmitInfo {
+        TimelineSemaphoreSubmitInfo {
+            s_type: vk::StructureType::from_raw(1_000_207_003),
+            p_next: ptr::null(),
+            wait_semaphore_value_count  : wait_values.len() as _,
+            p_wait_semaphore_values     : wait_values.as_ptr(),
+            signal_semaphore_value_count: signal_values.len() as _,
+            p_signal_semaphore_values   : signal_values.as_ptr(),
+        }
+    }
+}
+
+/// `VkSemaphoreWaitInfo`.
+#[repr(C)]
+pub(crate) struct SemaphoreWaitInfo {
+    pub s_type: vk::StructureType,
+    pub p_next: *const c_void,
+    pub flags : vkuint,
+    pub semaphore_count: vkuint,
+    pub p_semaphores   : *const vk::Semaphore,
+    pub p_values       : *const vklint,
+}
+
+impl SemaphoreWaitInfo {
+
+    pub fn new(semaphores: &[vk::Semaphore], values: &[vklint]) -> SemaphoreWaitInfo {
+
+        debug_assert_eq!(semaphores.len(), values.len());
+
+        SemaphoreWaitInfo {
+            s_type: vk::StructureType::from_raw(1_000_207_004),
+            p_next: ptr::null(),
+            // wait for all the semaphores.
+            flags : 0,
+            semaphore_count: semaphores.len() as _,
+            p_semaphores   : semaphores.as_ptr(),
+            p_values       : values.as_ptr(),
+        }
+    }
+}
+
+/// `VkSemaphoreSignalInfo`.
+#[repr(C)]
+pub(crate) struct SemaphoreSignalInfo {
+    pub s_type: vk::StructureType,
+    pub p_next: *const c_void,
+    pub semaphore: vk::Semaphore,
+    pub value: vklint,
+}
+
+impl SemaphoreSignalInfo {
+
+    pub fn new(semaphore: vk::Semaphore, value: vklint) -> SemaphoreSignalInfo {
+        SemaphoreSignalInfo {
+            s_type: vk::StructureType::from_raw(1_000_207_005),
+            p_next: ptr::null(),
+            semaphore, value,
+        }
+    }
+}
+
+/// The host functions of timeline semaphores, from Vulkan 1.2 or `VK_KHR_timeline_semaphore`.
+#[derive(Clone)]
+pub struct TimelineSemaphoreFn {
+    pub(crate) get_semaphore_counter_value: extern "system" fn(vk::Device, vk::Semaphore, *mut vklint) -> vk::Result,
+    pub(crate) wait_semaphores : extern "system" fn(vk::Device, *const SemaphoreWaitInfo, vklint) -> vk::Result,
+    pub(crate) signal_semaphore: extern "system" fn(vk::Device, *const SemaphoreSignalInfo) -> vk::Result,
+}
+
+impl TimelineSemaphoreFn {
+
+    pub const NAME: &'static str = "VK_KHR_timeline_semaphore";
+
+    /// Load the function pointers from `device`, or return `None` if any of them is missing.
+    ///
+    /// The functions of the extension are preferred, and the core functions of Vulkan 1.2 are used as fallback.
+    pub(crate) fn load(instance: &ash::Instance, device: &ash::Device) -> Option<TimelineSemaphoreFn> {
+
+        let proc_addr = |name: &'static [u8]| device_proc_addr(instance, device, name);
+
+        let result = TimelineSemaphoreFn {
+            get_semaphore_counter_value: load_fn!(proc_addr; b"vkGetSemaphoreCounterValueKHR\0", b"vkGetSemaphoreCounterValue\0"),
+            wait_semaphores : load_fn!(proc_addr; b"vkWaitSemaphoresKHR\0", b"vkWaitSemaphores\0"),
+            signal_semaphore: load_fn!(proc_addr; b"vkSignalSemaphoreKHR\0", b"vkSignalSemaphore\0"),
+        };
+        Some(result)
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+// ----------------------------------------------------------------------------------------------
+/// `VkPhysicalDeviceDynamicRenderingFeatures`, which is queried by `PhysicalDeviceFeatures2Fn` and chained to `vk::DeviceCreateInfo` to enable dynamic rendering.
+#[repr(C)]
+pub(crate) struct PhysicalDeviceDynamicRenderingFeatures {
+    pub s_type: vk::StructureType,
+    pub p_next: *mut c_void,
+    pub dynamic_rendering: vkbool,
+}
+
+impl Default for PhysicalDeviceDynamicRenderingFeatures {
+
+    fn default() -> PhysicalDeviceDynamicRenderingFeatures {
+        PhysicalDeviceDynamicRenderingFeatures {
+            s_type: vk::StructureType::from_raw(1_000_044_003),
+            p_next: ptr::null_mut(),
+            dynamic_rendering: vk::FALSE,
+        }
+    }
+}
+
+/// `VkRenderingAttachmentInfo`, which describes an attachment used in dynamic rendering.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct RenderingAttachmentInfo {
+    pub s_type: vk::StructureType,
+    pub p_next: *const c_void,
+    pub image_view  : vk::ImageView,
+    pub image_layout: vk::ImageLayout,
+    /// `VkResolveModeFlagBits`, 0 means no resolve.
+    pub resolve_mode: vkuint,
+    pub resolve_image_view  : vk::ImageView,
+    pub resolve_image_layout: vk::ImageLayout,
+    pub load_op : vk::AttachmentLoadOp,
+    pub store_op: vk::AttachmentStoreOp,
+    pub clear_value: vk::ClearValue,
+}
+
+impl RenderingAttachmentInfo {
+
+    pub fn new(image_view: vk::ImageView, image_layout: vk::ImageLayout) -> RenderingAttachmentInfo {
+        RenderingAttachmentInfo {
+            s_type: vk::StructureType::from_raw(1_000_044_001),
+            p_next: ptr::null(),
+            image_view, image_layout,
+            resolve_mode: 0,
+            resolve_image_view  : vk::ImageView::null(),
+            resolve_image_layout: vk::ImageLayout::UNDEFINED,
+            load_op : vk::AttachmentLoadOp::DONT_CARE,
+            store_op: vk::AttachmentStoreOp::DONT_CARE,
+            clear_value: vk::ClearValue { color: vk::ClearColorValue { float32: [0.0; 4] } },
+        }
+    }
+}
+
+/// `VkRenderingInfo`, which is passed to `vkCmdBeginRendering`.
+#[repr(C)]
+#[derive(Clone)]
+pub struct RenderingInfo {
+    pub s_type: vk::StructureType,
+    pub p_next: *const c_void,
+    pub flags : vkuint,
+    pub render_area: vk::Rect2D,
+    pub layer_count: vkuint,
+    pub view_mask  : vkuint,
+    pub color_attachment_count: vkuint,
+    pub p_color_attachments   : *const RenderingAttachmentInfo,
+    pub p_depth_attachment    : *const RenderingAttachmentInfo,
+    pub p_stencil_attachment  : *const RenderingAttachmentInfo,
+}
+
+impl Default for RenderingInfo {
+
+    fn default() -> RenderingInfo {
+        RenderingInfo {
+            s_type: vk::StructureType::from_raw(1_000_044_000),
+            p_next: ptr::null(),
+            flags : 0,
+            render_area: vk::Rect2D::default(),
+            layer_count: 1,
+            view_mask  : 0,
+            color_attachment_count: 0,
+            p_color_attachments   : ptr::null(),
+            p_depth_attachment    : ptr::null(),
+            p_stencil_attachment  : ptr::null(),
+        }
+    }
+}
+
+/// `VkPipelineRenderingCreateInfo`, which is chained to `vk::GraphicsPipelineCreateInfo` to create a pipeline without render pass.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct PipelineRenderingCreateInfo {
+    pub s_type: vk::StructureType,
+    pub p_next: *const c_void,
+    pub view_mask: vkuint,
+    pub color_attachment_count    : vkuint,
+    pub p_color_attachment_formats: *const vk::Format,
+    pub depth_attachment_format   : vk::Format,
+    pub stencil_attachment_format : vk::Format,
+}
+
+impl PipelineRenderingCreateInfo {
+
+    pub fn new(color_formats: &[vk::Format], depth_format: vk::Format, stencil_format: vk::Format) -> PipelineRenderingCreateInfo {
+        PipelineRenderingCreateInfo {
+            s_type: vk::StructureType::from_raw(1_000_044_002),
+            p_next: ptr::null(),
+            view_mask: 0,
+            color_attachment_count    : color_formats.len() as _,
+            p_color_attachment_formats: color_formats.as_ptr(),
+            depth_attachment_format   : depth_format,
+            stencil_attachment_format : stencil_format,
+        }
+    }
+}
+
+/// The command functions of dynamic rendering, from Vulkan 1.3 or `VK_KHR_dynamic_rendering`.
+#[derive(Clone)]
+pub struct DynamicRenderingFn {
+    pub(crate) cmd_begin_rendering: extern "system" fn(vk::CommandBuffer, *const RenderingInfo),
+    pub(crate) cmd_end_rendering  : extern "system" fn(vk::CommandBuffer),
+}
+
+impl DynamicRenderingFn {
+
+    pub const NAME: &'static str = "VK_KHR_dynamic_rendering";
+
+    /// Load the function pointers from `device`, or return `None` if any of them is missing.
+    ///
+    /// The functions of the extension are preferred, and the core functions of Vulkan 1.3 are used as fallback.
+    pub(crate) fn load(instance: &ash::Instance, device: &ash::Device) -> Option<DynamicRenderingFn> {
+
+        let proc_addr = |name: &'static [u8]| device_proc_addr(instance, device, name);
+
+        let result = DynamicRenderingFn {
+            cmd_begin_rendering: load_fn!(proc_addr; b"vkCmdBeginRenderingKHR\0", b"vkCmdBeginRendering\0"),
+            cmd_end_rendering  : load_fn!(proc_addr; b"vkCmdEndRenderingKHR\0", b"vkCmdEndRendering\0"),
+        };
+        Some(result)
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+// ----------------------------------------------------------------------------------------------
+/// The command functions of `VK_KHR_push_descriptor`.
+#[derive(Clone)]
+pub struct PushDescriptorFn {
+    pub(crate) cmd_push_descriptor_set: extern "system" fn(vk::CommandBuffer, vk::PipelineBindPoint, vk::PipelineLayout, vkuint, vkuint, *const vk::WriteDescriptorSet),
+}
+
+impl PushDescriptorFn {
+
+    pub const NAME: &'static str = "VK_KHR_push_descriptor";
+
+    /// Load the function pointers from `device`, or return `None` if any of them is missing.
+    pub(crate) fn load(instance: &ash::Instance, device: &ash::Device) -> Option<PushDescriptorFn> {
+
+        let proc_addr = |name: &'static [u8]| device_proc_addr(instance, device, name);
+
+        let result = PushDescriptorFn {
+            cmd_push_descriptor_set: load_fn!(proc_addr; b"vkCmdPushDescriptorSetKHR\0"),
+        };
+        Some(result)
+    }
+}
+// ----------------------------------------------------------------------------------------------
@@ -3,11 +3,16 @@ use ash::vk;
 use ash::version::{DeviceV1_0, InstanceV1_0};
 
 use crate::context::instance::VkInstance;
-use crate::context::device::physical::VkPhysicalDevice;
+use crate::context::device::physical::{VkPhysicalDevice, DESCRIPTOR_INDEXING_NAME};
 use crate::context::device::queue::{QueueRequester, QueueRequestStrategy};
+use crate::context::device::extension::{ExtendedDynamicStateFn, PhysicalDeviceExtendedDynamicStateFeaturesEXT};
+use crate::context::device::extension::{TimelineSemaphoreFn, PhysicalDeviceTimelineSemaphoreFeatures};
+use crate::context::device::extension::{DynamicRenderingFn, PhysicalDeviceDynamicRenderingFeatures};
+use crate::context::device::extension::{PushDescriptorFn, PhysicalDeviceFeatures2Fn};
 use crate::error::{VkResult, VkError};
 use crate::vkuint;
 
+use std::os::raw::c_void;
 use std::ptr;
 
 
@@ -33,6 +38,21 @@ pub struct VkLogicalDevice {
 
     pub handle: ash::Device,
     pub queues: QueryFamilies,
+
+    /// the functions of `VK_EXT_extended_dynamic_state`, or `None` if this extension is not requested.
+    pub(crate) ext_dynamic_state: Option<ExtendedDynamicStateFn>,
+    /// the loader of `VK_EXT_debug_utils`, or `None` if validation is disabled.
+    pub(crate) debug_utils: Option<ash::extensions::ext::DebugUtils>,
+    /// the functions of timeline semaphores, or `None` if `VK_KHR_timeline_semaphore` is not requested.
+    pub(crate) timeline_semaphore: Option<TimelineSemaphoreFn>,
+    /// the functions of dynamic rendering, or `None` if `VK_KHR_dynamic_rendering` is not requested.
+    pub(crate) dynamic_rendering: Option<DynamicRenderingFn>,
+    /// whether the features of `VK_EXT_descriptor_indexing` are enabled.
+    pub(crate) is_descriptor_indexing_enabled: bool,
+    /// the functions of `VK_KHR_push_descriptor`, or `None` if this extension is not requested.
+    pub(crate) push_descriptor: Option<PushDescriptorFn>,
+    /// whether the `multiDrawIndirect` feature is enabled, which allows indirect draws with draw count greater than 1.
+    pub(crate) is_multi_draw_indirect_enabled: bool,
 }
 
 pub struct QueryFamilies {
@@ -45,6 +65,8 @@ pub struct QueryFamilies {
 pub struct VkQueue {
     pub handle: vk::Queue,
     pub family_index: vkuint,
+    /// the number of meaningful bits in the timestamps written on this queue, or 0 if timestamps are not supported.
+    pub timestamp_valid_bits: vkuint,
 }
 
 impl VkLogicalDevice {
@@ -73,10 +95,61 @@ impl VkLogicalDevice {
         let enable_layer_names = cstrings2ptrs(&instance.enable_layer_names);
         let enable_extension_names = cstrings2ptrs(phy.enable_extensions());
 
+        // the feature of extended dynamic state must be enabled explicitly if its extension is requested.
+        let is_dynamic_state_requested = phy.enable_extensions().iter()
+            .any(|extension| extension.to_bytes() == ExtendedDynamicStateFn::NAME.as_bytes());
+        // so does the feature of timeline semaphore.
+        let is_timeline_requested = phy.enable_extensions().iter()
+            .any(|extension| extension.to_bytes() == TimelineSemaphoreFn::NAME.as_bytes());
+        // and the feature of dynamic rendering.
+        let is_dynamic_rendering_requested = phy.enable_extensions().iter()
+            .any(|extension| extension.to_bytes() == DynamicRenderingFn::NAME.as_bytes());
+        // and the features of descriptor indexing.
+        let is_descriptor_indexing_requested = phy.enable_extensions().iter()
+            .any(|extension| extension.to_bytes() == DESCRIPTOR_INDEXING_NAME.as_bytes());
+
+        // chain the feature structures of requested extensions, after checking that the device reports them.
+        let mut features_next: *const c_void = ptr::null();
+        let mut timeline_features = PhysicalDeviceTimelineSemaphoreFeatures::default();
+        if is_timeline_requested {
+            query_features(instance, phy, &mut timeline_features as *mut PhysicalDeviceTimelineSemaphoreFeatures as *mut c_void)?;
+            if timeline_features.timeline_semaphore != vk::TRUE {
+                return Err(VkError::unsupported("timelineSemaphore of VK_KHR_timeline_semaphore"))
+            }
+            timeline_features.p_next = features_next as *mut c_void;
+            features_next = &mut timeline_features as *mut PhysicalDeviceTimelineSemaphoreFeatures as *const c_void;
+        }
+        let mut dynamic_rendering_features = PhysicalDeviceDynamicRenderingFeatures::default();
+        if is_dynamic_rendering_requested {
+            query_features(instance, phy, &mut dynamic_rendering_features as *mut PhysicalDeviceDynamicRenderingFeatures as *mut c_void)?;
+            if dynamic_rendering_features.dynamic_rendering != vk::TRUE {
+                return Err(VkError::unsupported("dynamicRendering of VK_KHR_dynamic_rendering"))
+            }
+            dynamic_rendering_features.p_next = features_next as *mut c_void;
+            features_next = &mut dynamic_rendering_features as *mut PhysicalDeviceDynamicRenderingFeatures as *const c_void;
+        }
+        let mut descriptor_indexing_features = vk::PhysicalDeviceDescriptorIndexingFeaturesEXT::default();
+        if is_descriptor_indexing_requested {
+            let mut supported_features = vk::PhysicalDeviceDescriptorIndexingFeaturesEXT::default();
+            query_features(instance, phy, &mut supported_features as *mut vk::PhysicalDeviceDescriptorIndexingFeaturesEXT as *mut c_void)?;
+            descriptor_indexing_features = descriptor_indexing_enabled(&supported_features, &phy.device_name);
+            descriptor_indexing_features.p_next = features_next as *mut c_void;
+            features_next = &mut descriptor_indexing_features as *mut vk::PhysicalDeviceDescriptorIndexingFeaturesEXT as *const c_void;
+        }
+        let mut dynamic_state_features = PhysicalDeviceExtendedDynamicStateFeaturesEXT::default();
+        if is_dynamic_state_requested {
+            query_features(instance, phy, &mut dynamic_state_features as *mut PhysicalDeviceExtendedDynamicStateFeaturesEXT as *mut c_void)?;
+            if dynamic_state_features.extended_dynamic_state != vk::TRUE {
+                return Err(VkError::unsupported("extendedDynamicState of VK_EXT_extended_dynamic_state"))
+            }
+            dynamic_state_features.p_next = features_next as *mut c_void;
+            features_next = &mut dynamic_state_features as *mut PhysicalDeviceExtendedDynamicStateFeaturesEXT as *const c_void;
+        }
+
         // Create the logical device.
         let device_ci = vk::DeviceCreateInfo {
             s_type                     : vk::StructureType::DEVICE_CREATE_INFO,
-            p_next                     : ptr::null(),
+            p_next                     : features_next,
             // flags is reserved for future use in API version 1.1.82.
             flags                      : vk::DeviceCreateFlags::empty(),
             queue_create_info_count    : queue_cis.len() as _,
@@ -90,7 +163,7 @@ impl VkLogicalDevice {
 
         let handle = unsafe {
             instance.handle.create_device(phy.handle, &device_ci, None)
-                .or(Err(VkError::create("Logical Device")))?
+                .map_err(|e| VkError::vk(e, "Create Logical Device"))?
         };
 
         let queues = queue_requests.dispatch_queues(&handle, &queue_requester);
@@ -105,11 +178,90 @@ impl VkLogicalDevice {
             debug_assert_ne!(queues.transfer.handle, vk::Queue::null())
         }
 
-        let device = VkLogicalDevice { handle, queues };
+        let ext_dynamic_state = if is_dynamic_state_requested {
+            let functions = ExtendedDynamicStateFn::load(&instance.handle, &handle)
+                .ok_or(VkError::unsupported("VK_EXT_extended_dynamic_state"))?;
+            Some(functions)
+        } else {
+            None
+        };
+
+        let timeline_semaphore = if is_timeline_requested {
+            let functions = TimelineSemaphoreFn::load(&instance.handle, &handle)
+                .ok_or(VkError::unsupported("VK_KHR_timeline_semaphore"))?;
+            Some(functions)
+        } else {
+            None
+        };
+
+        let dynamic_rendering = if is_dynamic_rendering_requested {
+            let functions = DynamicRenderingFn::load(&instance.handle, &handle)
+                .ok_or(VkError::unsupported("VK_KHR_dynamic_rendering"))?;
+            Some(functions)
+        } else {
+            None
+        };
+
+        let is_push_descriptor_requested = phy.enable_extensions().iter()
+            .any(|extension| extension.to_bytes() == PushDescriptorFn::NAME.as_bytes());
+        let push_descriptor = if is_push_descriptor_requested {
+            let functions = PushDescriptorFn::load(&instance.handle, &handle)
+                .ok_or(VkError::unsupported("VK_KHR_push_descriptor"))?;
+            Some(functions)
+        } else {
+            None
+        };
+
+        let device = VkLogicalDevice {
+            handle, queues, ext_dynamic_state, timeline_semaphore, dynamic_rendering, push_descriptor,
+            is_descriptor_indexing_enabled: is_descriptor_indexing_requested,
+            is_multi_draw_indirect_enabled: phy.features_enabled().multi_draw_indirect == vk::TRUE,
+            debug_utils: None,
+        };
         Ok(device)
     }
 }
 
+/// Fill the feature structure pointed by `features` with the support of `phy`.
+///
+/// The features of device extensions can only be queried by `VK_KHR_get_physical_device_properties2`, so return an error if it is unavailable.
+fn query_features(instance: &VkInstance, phy: &VkPhysicalDevice, features: *mut c_void) -> VkResult<()> {
+
+    let features2 = instance.features2.as_ref()
+        .ok_or(VkError::unsupported(PhysicalDeviceFeatures2Fn::NAME))?;
+    features2.query(phy.handle, features);
+    Ok(())
+}
+
+/// Only enable the descriptor indexing features that are required by bindless sampled images and reported by the device.
+fn descriptor_indexing_enabled(supported: &vk::PhysicalDeviceDescriptorIndexingFeaturesEXT, device_name: &str) -> vk::PhysicalDeviceDescriptorIndexingFeaturesEXT {
+
+    let mut enabled = vk::PhysicalDeviceDescriptorIndexingFeaturesEXT::default();
+
+    macro_rules! enable_if_support {
+        ($($feature:ident),+) => {
+            $(
+                if supported.$feature == vk::TRUE {
+                    enabled.$feature = vk::TRUE;
+                } else {
+                    println!("[Warning] Vulkan feature '{}' is not support on {}.", stringify!($feature), device_name);
+                }
+            )+
+        };
+    }
+
+    enable_if_support!(
+        shader_sampled_image_array_non_uniform_indexing,
+        descriptor_binding_sampled_image_update_after_bind,
+        descriptor_binding_update_unused_while_pending,
+        descriptor_binding_partially_bound,
+        descriptor_binding_variable_descriptor_count,
+        runtime_descriptor_array
+    );
+
+    enabled
+}
+
 impl Drop for VkLogicalDevice {
 
     fn drop(&mut self) {
@@ -157,6 +309,7 @@ impl Default for VkQueue {
         VkQueue {
             handle: vk::Queue::null(),
             family_index: 0,
+            timestamp_valid_bits: 0,
         }
     }
 }
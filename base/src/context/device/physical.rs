@@ -3,10 +3,12 @@ use ash::vk;
 use ash::version::InstanceV1_0;
 
 use crate::context::instance::VkInstance;
+use crate::context::device::extension::{ExtendedDynamicStateFn, TimelineSemaphoreFn, DynamicRenderingFn, PushDescriptorFn};
+use crate::context::device::extension::{PhysicalDeviceFeatures2Fn, PhysicalDeviceMemoryBudgetPropertiesEXT};
 use crate::utils::cast::{chars2string, chars2cstring};
 use crate::error::{VkResult, VkError};
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 
 
 #[derive(Debug, Clone)]
@@ -14,6 +16,8 @@ pub struct PhysicalDevConfig {
 
     pub print_device_properties: bool,
     pub device_type_preference: vk::PhysicalDeviceType,
+    /// `device_selector` forces the selection of a specific physical device, overriding `device_type_preference`.
+    pub device_selector: Option<DeviceSelector>,
 
     pub print_available_extensions: bool,
     pub request_extensions: Vec<CString>,
@@ -29,6 +33,7 @@ impl Default for PhysicalDevConfig {
         PhysicalDevConfig {
             print_device_properties: false,
             device_type_preference: vk::PhysicalDeviceType::DISCRETE_GPU,
+            device_selector: None,
 
             print_available_extensions: false,
             request_extensions: vec![
@@ -52,11 +57,66 @@ pub struct VkPhysicalDevice {
 
     features_enable: vk::PhysicalDeviceFeatures,
     config: PhysicalDevConfig,
+
+    /// the instance handle that is kept to query physical device properties in runtime.
+    instance: ash::Instance,
+    /// the function to query memory budget, or `None` if `VK_EXT_memory_budget` is not supported.
+    memory_budget: Option<PhysicalDeviceFeatures2Fn>,
+}
+
+impl PhysicalDevConfig {
+
+    /// Select the physical device by `selector`(etc. `0_usize` for the first enumerated device, or `"GeForce"` to match the device name),
+    /// instead of the first device of `device_type_preference`.
+    #[inline(always)]
+    pub fn prefer_device(mut self, selector: impl Into<DeviceSelector>) -> PhysicalDevConfig {
+        self.device_selector = Some(selector.into()); self
+    }
+}
+
+/// Specify the physical device to use on the machine with multiple GPUs.
+#[derive(Debug, Clone)]
+pub enum DeviceSelector {
+    /// The index of the device in the order of `vkEnumeratePhysicalDevices`.
+    Index(usize),
+    /// A case-insensitive substring of the device name(etc. "NVIDIA" or "Intel").
+    Name(String),
+}
+
+impl DeviceSelector {
+
+    fn is_match(&self, index: usize, device_name: &str) -> bool {
+        match self {
+            | DeviceSelector::Index(selected_index) => *selected_index == index,
+            | DeviceSelector::Name(name) => device_name.to_lowercase().contains(&name.to_lowercase()),
+        }
+    }
+}
+
+impl From<usize> for DeviceSelector {
+    fn from(index: usize) -> DeviceSelector {
+        DeviceSelector::Index(index)
+    }
+}
+
+impl From<&str> for DeviceSelector {
+    fn from(name: &str) -> DeviceSelector {
+        DeviceSelector::Name(name.to_string())
+    }
+}
+
+impl From<String> for DeviceSelector {
+    fn from(name: String) -> DeviceSelector {
+        DeviceSelector::Name(name)
+    }
 }
 
 impl VkPhysicalDevice {
 
-    pub(crate) fn new(instance: &VkInstance, config: PhysicalDevConfig) -> VkResult<VkPhysicalDevice> {
+    pub(crate) fn new(instance: &VkInstance, mut config: PhysicalDevConfig) -> VkResult<VkPhysicalDevice> {
+
+        // the extensions required by the requested extensions must be enabled too.
+        append_extension_dependencies(&mut config.request_extensions);
 
         let alternative_devices = VkPhysicalDevice::query_phy_devices(instance, &config)?;
 
@@ -86,12 +146,26 @@ impl VkPhysicalDevice {
 
             let depth_format = query_depth_format(instance, &phy_device);
 
+            // enable `VK_EXT_memory_budget` if it is available, so that `VkDevice::memory_stats` can report the budget of each heap.
+            let is_memory_budget_available = instance.features2.is_some()
+                && query_available_extensions(instance, &phy_device)?.iter().any(|extension| extension.to_bytes() == MEMORY_BUDGET_NAME.as_bytes());
+            let memory_budget = if is_memory_budget_available {
+                let extension = CString::new(MEMORY_BUDGET_NAME).unwrap();
+                if config.request_extensions.contains(&extension) == false {
+                    config.request_extensions.push(extension);
+                }
+                instance.features2.clone()
+            } else {
+                None
+            };
+
             let dst_device = VkPhysicalDevice {
                 device_name: chars2string(&phy_device.property.device_name),
                 handle: phy_device.handle,
                 limits: phy_device.property.limits,
                 features_enable: enable_feature_if_support(&phy_device, &config),
-                config, memories, depth_format,
+                instance: instance.handle.clone(),
+                config, memories, depth_format, memory_budget,
             };
 
             Ok(dst_device)
@@ -105,13 +179,30 @@ impl VkPhysicalDevice {
 
         let alternative_devices = unsafe {
             instance.handle.enumerate_physical_devices()
-                .or(Err(VkError::query("Physical Device")))?
+                .map_err(|e| VkError::vk(e, "Query Physical Device"))?
         };
 
         let mut alternative_devices: Vec<PhyDeviceTmp> = alternative_devices.into_iter().map(|phy_device| {
             query_device_property(instance, phy_device)
         }).collect();
 
+        // the selected device is the only candidate if a selector is specified.
+        if let Some(ref selector) = config.device_selector {
+
+            let selected_index = alternative_devices.iter().enumerate()
+                .position(|(i, phy_device)| selector.is_match(i, &chars2string(&phy_device.property.device_name)));
+
+            return match selected_index {
+                | Some(index) => Ok(vec![alternative_devices.swap_remove(index)]),
+                | None => {
+                    let available_names: Vec<String> = alternative_devices.iter().enumerate()
+                        .map(|(i, phy_device)| format!("[{}] {}", i, chars2string(&phy_device.property.device_name)))
+                        .collect();
+                    Err(VkError::custom(format!("No physical device matches {:?}, the available devices are: {}.", selector, available_names.join(", "))))
+                },
+            }
+        }
+
         // sort available device by their device type.
         alternative_devices.sort_by(|dev1, dev2| {
             use std::cmp::Ordering;
@@ -137,6 +228,20 @@ impl VkPhysicalDevice {
     pub fn enable_extensions(&self) -> &Vec<CString> {
         &self.config.request_extensions
     }
+
+    /// Query the budget and usage of each memory heap, or return `None` if `VK_EXT_memory_budget` is not supported.
+    pub(crate) fn memory_budget(&self) -> Option<PhysicalDeviceMemoryBudgetPropertiesEXT> {
+        self.memory_budget.as_ref()
+            .map(|budget_fn| budget_fn.query_memory_budget(self.handle))
+    }
+
+    /// Query the features supported by this physical device for the specific `format`.
+    #[inline]
+    pub fn format_properties(&self, format: vk::Format) -> vk::FormatProperties {
+        unsafe {
+            self.instance.get_physical_device_format_properties(self.handle, format)
+        }
+    }
 }
 
 struct PhyDeviceTmp {
@@ -154,29 +259,91 @@ struct PhyDeviceTmp {
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum DeviceExtensionType {
     Swapchain,
+    /// `VK_EXT_extended_dynamic_state`, see `VkCmdRecorder::set_cull_mode` and so on.
+    ExtendedDynamicState,
+    /// `VK_KHR_timeline_semaphore`, see `SemaphoreCI::timeline` and `VkDevice::wait_semaphores`.
+    TimelineSemaphore,
+    /// `VK_KHR_dynamic_rendering`, see `DynamicRenderingBI` and `GraphicsPipelineCI::set_rendering_formats`.
+    DynamicRendering,
+    /// `VK_EXT_descriptor_indexing`, see `DescriptorSetLayoutCI::binding_flags` and `DescriptorSetAI::variable_descriptor_counts`.
+    DescriptorIndexing,
+    /// `VK_KHR_push_descriptor`, see `VkCmdRecorder::push_descriptor_set`.
+    PushDescriptor,
 }
 
 impl DeviceExtensionType {
 
-    fn name(&self) -> CString {
+    pub fn name(&self) -> CString {
         match self {
             | DeviceExtensionType::Swapchain => {
                 CString::new("VK_KHR_swapchain").unwrap()
             },
+            | DeviceExtensionType::ExtendedDynamicState => {
+                CString::new(ExtendedDynamicStateFn::NAME).unwrap()
+            },
+            | DeviceExtensionType::TimelineSemaphore => {
+                CString::new(TimelineSemaphoreFn::NAME).unwrap()
+            },
+            | DeviceExtensionType::DynamicRendering => {
+                CString::new(DynamicRenderingFn::NAME).unwrap()
+            },
+            | DeviceExtensionType::DescriptorIndexing => {
+                CString::new(DESCRIPTOR_INDEXING_NAME).unwrap()
+            },
+            | DeviceExtensionType::PushDescriptor => {
+                CString::new(PushDescriptorFn::NAME).unwrap()
+            },
         }
     }
 }
 
-fn is_all_extension_support(instance: &VkInstance, phy_device: &PhyDeviceTmp, config: &PhysicalDevConfig) -> VkResult<bool> {
+/// Return the names of device extensions that `extension` depends on.
+fn extension_dependencies(extension: &CStr) -> &'static [&'static str] {
+
+    let extension = extension.to_bytes();
+
+    if extension == DESCRIPTOR_INDEXING_NAME.as_bytes() {
+        &["VK_KHR_maintenance3"]
+    } else if extension == DynamicRenderingFn::NAME.as_bytes() {
+        &["VK_KHR_depth_stencil_resolve", "VK_KHR_create_renderpass2", "VK_KHR_multiview", "VK_KHR_maintenance2"]
+    } else {
+        &[]
+    }
+}
+
+/// Append the dependencies of `request_extensions` to itself, skipping the extensions already requested.
+fn append_extension_dependencies(request_extensions: &mut Vec<CString>) {
+
+    let mut dependencies: Vec<CString> = Vec::new();
+
+    for extension in request_extensions.iter() {
+        for dependency in extension_dependencies(extension) {
+            let dependency = CString::new(*dependency).unwrap();
+            if !request_extensions.contains(&dependency) && !dependencies.contains(&dependency) {
+                dependencies.push(dependency);
+            }
+        }
+    }
+
+    request_extensions.extend(dependencies);
+}
+
+fn query_available_extensions(instance: &VkInstance, phy_device: &PhyDeviceTmp) -> VkResult<Vec<CString>> {
 
     let query_extensions = unsafe {
         instance.handle.enumerate_device_extension_properties(phy_device.handle)
-            .or(Err(VkError::query("Device Extensions")))?
+            .map_err(|e| VkError::vk(e, "Query Device Extensions"))?
     };
 
-    let available_extensions: Vec<CString> = query_extensions.into_iter().map(|extension| {
+    let available_extensions = query_extensions.into_iter().map(|extension| {
         chars2cstring(&extension.extension_name)
     }).collect();
+    Ok(available_extensions)
+}
+
+fn is_all_extension_support(instance: &VkInstance, phy_device: &PhyDeviceTmp, config: &PhysicalDevConfig) -> VkResult<bool> {
+
+    let available_extensions = query_available_extensions(instance, phy_device)?;
 
     // print available extensions to console if need.
     if config.print_available_extensions {
@@ -335,19 +502,26 @@ fn enable_feature_if_support(phy_device: &PhyDeviceTmp, config: &PhysicalDevConf
 
 
 // ----------------------------------------------------------------------------------
+/// The name of `VK_EXT_descriptor_indexing`, which has no command functions to load.
+pub(crate) const DESCRIPTOR_INDEXING_NAME: &'static str = "VK_EXT_descriptor_indexing";
+
+/// The name of `VK_EXT_memory_budget`, which is enabled automatically if it is available.
+const MEMORY_BUDGET_NAME: &'static str = "VK_EXT_memory_budget";
+
+/// The depth formats ordered by preference, starting with the highest precision packed format.
+///
+/// Since all depth formats may be optional, these formats are probed in order to find a suitable one.
+pub(crate) const DEPTH_FORMAT_CANDIDATES: [vk::Format; 5] = [
+    vk::Format::D32_SFLOAT_S8_UINT,
+    vk::Format::D32_SFLOAT,
+    vk::Format::D24_UNORM_S8_UINT,
+    vk::Format::D16_UNORM_S8_UINT,
+    vk::Format::D16_UNORM,
+];
+
 fn query_depth_format(instance: &VkInstance, phy_device: &PhyDeviceTmp) -> vk::Format {
 
-    // since all depth formats may be optional, we need to find a suitable depth format to use.
-    // start with the highest precision packed format.
-    let candidates = [
-        vk::Format::D32_SFLOAT_S8_UINT,
-        vk::Format::D32_SFLOAT,
-        vk::Format::D24_UNORM_S8_UINT,
-        vk::Format::D16_UNORM_S8_UINT,
-        vk::Format::D16_UNORM,
-    ];
-
-    for &format in candidates.iter() {
+    for &format in DEPTH_FORMAT_CANDIDATES.iter() {
         let format_properties = unsafe {
             instance.handle.get_physical_device_format_properties(phy_device.handle, format)
         };
@@ -358,6 +532,7 @@ fn query_depth_format(instance: &VkInstance, phy_device: &PhyDeviceTmp) -> vk::F
         }
     }
 
-    panic!("Failed to find a supported depth format.")
+    // the specification requires `D16_UNORM` to be supported as depth stencil attachment.
+    vk::Format::D16_UNORM
 }
 // ----------------------------------------------------------------------------------
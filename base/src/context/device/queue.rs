@@ -155,6 +155,7 @@ impl QueueRequester {
         VkQueue {
             handle,
             family_index: family_index as _,
+            timestamp_valid_bits: self.family_properties[family_index].timestamp_valid_bits,
         }
     }
 }
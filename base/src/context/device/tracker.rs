@@ -0,0 +1,73 @@
+//! A debug-only tracker of the Vulkan objects created and discarded through `VkDevice`.
+//!
+//! Each object is recorded with the source location that created it, and removed when it is discarded.
+//! The objects still alive on device teardown are reported as leaks.
+//! In release builds, the tracker is an empty struct and all its methods are no-ops.
+
+use ash::vk;
+
+#[cfg(debug_assertions)]
+use std::cell::RefCell;
+#[cfg(debug_assertions)]
+use std::collections::HashMap;
+#[cfg(debug_assertions)]
+use std::panic::Location;
+
+#[derive(Default)]
+pub(crate) struct VkHandleTracker {
+    /// the creation site of each alive object, keyed by its object type and raw handle.
+    #[cfg(debug_assertions)]
+    alive: RefCell<HashMap<(vk::ObjectType, u64), &'static Location<'static>>>,
+}
+
+#[cfg(debug_assertions)]
+impl VkHandleTracker {
+
+    /// Record `handle` as created by the caller.
+    #[track_caller]
+    pub fn track<T: vk::Handle>(&self, handle: T) {
+        self.alive.borrow_mut().insert((T::TYPE, handle.as_raw()), Location::caller());
+    }
+
+    /// Remove `handle` from the alive objects. The objects that are not tracked are ignored.
+    pub fn untrack<T: vk::Handle>(&self, handle: T) {
+        self.alive.borrow_mut().remove(&(T::TYPE, handle.as_raw()));
+    }
+
+    /// Print the count and creation sites of the objects that have not been discarded, grouped by object type.
+    pub fn report_leaks(&self) {
+
+        let alive = self.alive.borrow();
+        if alive.is_empty() {
+            return
+        }
+
+        let mut leaks_by_type: HashMap<vk::ObjectType, Vec<&'static Location<'static>>> = HashMap::new();
+        for (&(object_type, _), &location) in alive.iter() {
+            leaks_by_type.entry(object_type).or_default().push(location);
+        }
+
+        println!("[Warning] {} Vulkan objects were not discarded before the device was destroyed:", alive.len());
+        for (object_type, mut locations) in leaks_by_type {
+
+            locations.sort_by_key(|location| (location.file(), location.line()));
+            println!("[Warning]     {:?} x {}:", object_type, locations.len());
+            for location in locations {
+                println!("[Warning]         created at {}:{}", location.file(), location.line());
+            }
+        }
+    }
+}
+
+#[cfg(not(debug_assertions))]
+impl VkHandleTracker {
+
+    #[inline(always)]
+    pub fn track<T: vk::Handle>(&self, _handle: T) {}
+
+    #[inline(always)]
+    pub fn untrack<T: vk::Handle>(&self, _handle: T) {}
+
+    #[inline(always)]
+    pub fn report_leaks(&self) {}
+}
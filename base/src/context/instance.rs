@@ -3,11 +3,12 @@ use ash::vk;
 use ash::vk_make_version;
 use ash::version::{InstanceV1_0, EntryV1_0};
 
-use crate::context::debug::{DebugType, VkDebugger, ValidationConfig};
+use crate::context::debug::{DebugType, VkDebugger, ValidationConfig, VALIDATION_LAYER_NAMES};
+use crate::context::device::PhysicalDeviceFeatures2Fn;
 use crate::error::{VkResult, VkError};
 use crate::vkuint;
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::os::raw::c_void;
 use std::ptr;
 
@@ -64,12 +65,16 @@ pub struct VkInstance {
     pub(crate) entry: ash::Entry,
     /// an array to store the names of vulkan layers enabled in instance creation.
     pub(crate) enable_layer_names: Vec<CString>,
+    /// the function to query the features of device extensions, or `None` if neither `VK_KHR_get_physical_device_properties2` nor Vulkan 1.1 is available.
+    pub(crate) features2: Option<PhysicalDeviceFeatures2Fn>,
 }
 
 impl VkInstance {
 
     /// Initialize `vk::Instance` object.
-    pub fn new(config: InstanceConfig, validation_config: &ValidationConfig) -> VkResult<VkInstance> {
+    ///
+    /// If the requested validation layer is not installed, the validation is disabled instead of failing.
+    pub fn new(mut config: InstanceConfig, validation_config: &mut ValidationConfig) -> VkResult<VkInstance> {
 
         let entry = ash::Entry::new()
             .or(Err(VkError::unlink("Entry")))?;
@@ -89,6 +94,12 @@ impl VkInstance {
             api_version         : config.api_version,
         };
 
+        // replace the validation layer with available one, or disable all debug tools if there is no validation layer.
+        if resolve_validation_layers(&entry, &mut config.require_layer_names)? == false {
+            config.debug = DebugType::None;
+            validation_config.debug_type = DebugType::None;
+        }
+
         // check if all instance layer is support.
         if is_all_instance_layer_support(&entry, config.print_available_layers, &config.require_layer_names)? == false {
             return Err(VkError::unsupported("Some of Vulkan instance layer"))
@@ -106,7 +117,7 @@ impl VkInstance {
         let enable_layer_names = layer_names_to_cstring(&config.require_layer_names)?;
         let enable_layer_names_ptr = crate::utils::cast::cstrings2ptrs(&enable_layer_names);
         // get the names of required vulkan extensions.
-        let enable_extension_names = VkInstance::require_extensions(validation_config.debug_type, config.debug);
+        let enable_extension_names = VkInstance::require_extensions(&entry, validation_config.debug_type, config.debug)?;
 
         let instance_ci = vk::InstanceCreateInfo {
             s_type : vk::StructureType::INSTANCE_CREATE_INFO,
@@ -126,12 +137,14 @@ impl VkInstance {
         };
 
 
-        let instance = VkInstance { entry, handle, enable_layer_names };
+        let features2 = PhysicalDeviceFeatures2Fn::load(&entry, &handle);
+
+        let instance = VkInstance { entry, handle, enable_layer_names, features2 };
         Ok(instance)
     }
 
     /// Specify the necessary extensions.
-    fn require_extensions(validation_debug: DebugType, instance_debug: DebugType) -> Vec<*const i8>  {
+    fn require_extensions(entry: &ash::Entry, validation_debug: DebugType, instance_debug: DebugType) -> VkResult<Vec<*const i8>> {
 
         // request extension about platform specific surface and debug tools.
         let mut instance_extensions = vec![
@@ -139,6 +152,14 @@ impl VkInstance {
             crate::platforms::platform_surface_names(),
         ];
 
+        // request the extension to query the features of device extensions, if it is available.
+        let properties2_name = CStr::from_bytes_with_nul(b"VK_KHR_get_physical_device_properties2\0").unwrap();
+        let available_extensions = entry.enumerate_instance_extension_properties()
+            .map_err(|e| VkError::vk(e, "Query Instance Extensions"))?;
+        if available_extensions.iter().any(|extension| crate::utils::cast::chars2cstring(&extension.extension_name).as_c_str() == properties2_name) {
+            instance_extensions.push(properties2_name);
+        }
+
 
         let mut add_debug_extension = |debug: DebugType| {
             match debug {
@@ -154,9 +175,10 @@ impl VkInstance {
             add_debug_extension(instance_debug)
         }
 
-        instance_extensions.into_iter().map(|extension| {
+        let extension_names = instance_extensions.into_iter().map(|extension| {
             extension.as_ptr()
-        }).collect()
+        }).collect();
+        Ok(extension_names)
     }
 }
 
@@ -180,7 +202,7 @@ fn is_all_instance_layer_support(entry: &ash::Entry, print_available_layers: boo
     use crate::utils::cast::chars2string;
 
     let layer_properties = entry.enumerate_instance_layer_properties()
-        .or(Err(VkError::query("Layer Properties")))?;
+        .map_err(|e| VkError::vk(e, "Query Layer Properties"))?;
 
     let available_layer_names: Vec<String> = layer_properties.into_iter().map(|available_layer| {
         chars2string(&available_layer.layer_name)
@@ -204,6 +226,43 @@ fn is_all_instance_layer_support(entry: &ash::Entry, print_available_layers: boo
     Ok(result)
 }
 
+/// Make sure the requested validation layer is available in `required_layers`.
+///
+/// Return false if validation layer is requested but none of them is installed, and the validation layer is removed from `required_layers`.
+fn resolve_validation_layers(entry: &ash::Entry, required_layers: &mut Vec<String>) -> VkResult<bool> {
+
+    use crate::utils::cast::chars2string;
+
+    let is_validation_layer = |layer: &String| VALIDATION_LAYER_NAMES.iter().any(|name| name == layer);
+
+    if required_layers.iter().any(is_validation_layer) == false {
+        // validation layer is not requested.
+        return Ok(true)
+    }
+
+    let layer_properties = entry.enumerate_instance_layer_properties()
+        .map_err(|e| VkError::vk(e, "Query Layer Properties"))?;
+    let available_layer_names: Vec<String> = layer_properties.into_iter().map(|available_layer| {
+        chars2string(&available_layer.layer_name)
+    }).collect();
+
+    let available_validation_layer = VALIDATION_LAYER_NAMES.iter()
+        .find(|name| available_layer_names.iter().any(|layer| layer == *name));
+
+    required_layers.retain(|layer| is_validation_layer(layer) == false);
+
+    match available_validation_layer {
+        | Some(layer_name) => {
+            required_layers.push(layer_name.to_string());
+            Ok(true)
+        },
+        | None => {
+            println!("[Warning] Validation layer is not installed, so validation is disabled.");
+            Ok(false)
+        },
+    }
+}
+
 fn layer_names_to_cstring(layers: &[String]) -> VkResult<Vec<CString>> {
 
     let mut layer_names = Vec::with_capacity(layers.len());
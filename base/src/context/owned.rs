@@ -0,0 +1,185 @@
+//! An optional scope-based cleanup for Vulkan objects.
+//!
+//! `Owned<T>` discards its object when dropped, as an alternative to calling `VkDevice::discard` or `VkDevice::vma_discard` manually.
+//! Since the GPU may still use the object when it is dropped, the object is not destroyed immediately,
+//! but pushed to the deletion queue of `VkDevice`, and destroyed at a safe point(etc. by `VkDevice::flush_frame_deletions` after waiting the fence of the frame, or by `VkDevice::flush_deletion_queue` after waiting device idle).
+
+use ash::vk;
+
+use crate::context::{VkDevice, VkObjectDiscardable, VmaResourceDiscardable};
+use crate::ci::vma::{VmaBuffer, VmaImage};
+use crate::error::VkResult;
+
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+use std::rc::{Rc, Weak};
+
+/// The deferred destruction of an object.
+pub enum PendingDeletion {
+    /// object destroyed by `VkDevice::discard`.
+    Object(Box<dyn FnOnce(&VkDevice)>),
+    /// object destroyed by `VkDevice::vma_discard`.
+    Vma(Box<dyn FnOnce(&mut vma::Allocator) -> VkResult<()>>),
+}
+
+/// The objects that can be wrapped by `Owned`.
+pub trait OwnedDiscardable: 'static {
+
+    fn into_deletion(self) -> PendingDeletion;
+}
+
+macro_rules! impl_owned_object {
+    ($($object:ty),*) => {
+        $(
+            impl OwnedDiscardable for $object {
+
+                fn into_deletion(self) -> PendingDeletion {
+                    PendingDeletion::Object(Box::new(move |device: &VkDevice| self.discard_by(device)))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_owned_vma_resource {
+    ($($resource:ty),*) => {
+        $(
+            impl OwnedDiscardable for $resource {
+
+                fn into_deletion(self) -> PendingDeletion {
+                    PendingDeletion::Vma(Box::new(move |vma: &mut vma::Allocator| self.discard_by(vma)))
+                }
+            }
+        )*
+    };
+}
+
+impl_owned_object!(vk::Buffer, vk::Image, vk::ImageView, vk::Sampler, vk::Pipeline, vk::PipelineLayout);
+impl_owned_vma_resource!(VmaBuffer, VmaImage);
+
+
+// ----------------------------------------------------------------------------------------------
+/// A queue collecting the objects dropped by `Owned`, owned by `VkDevice`.
+///
+/// The dropped objects are grouped by the frame-in-flight that was being recorded when they were dropped,
+/// so that each group can be destroyed after the fence of its frame has been waited.
+#[derive(Default)]
+pub struct VkDeletionQueue {
+    pending: Rc<RefCell<DeletionFrames>>,
+}
+
+pub(crate) struct DeletionFrames {
+    /// the dropped objects of each frame-in-flight.
+    frames: Vec<Vec<PendingDeletion>>,
+    /// the index of the frame-in-flight that collects the objects dropped now.
+    current: usize,
+}
+
+impl Default for DeletionFrames {
+
+    fn default() -> DeletionFrames {
+        DeletionFrames { frames: vec![Vec::new()], current: 0 }
+    }
+}
+
+impl VkDeletionQueue {
+
+    /// The number of objects waiting to be destroyed.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pending.borrow().frames.iter().map(|frame| frame.len()).sum()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get a weak reference to this queue, which does not keep the queue alive after the device is destroyed.
+    #[inline]
+    pub(crate) fn downgrade(&self) -> Weak<RefCell<DeletionFrames>> {
+        Rc::downgrade(&self.pending)
+    }
+
+    /// Take the objects of all frames.
+    pub(crate) fn take_all(&self) -> Vec<PendingDeletion> {
+        let mut pending = self.pending.borrow_mut();
+        pending.frames.iter_mut().flat_map(|frame| frame.drain(..)).collect()
+    }
+
+    /// Take the objects dropped during the last use of `frame_index`, and collect the objects dropped from now on to it.
+    pub(crate) fn begin_frame(&self, frame_index: usize) -> Vec<PendingDeletion> {
+
+        let mut pending = self.pending.borrow_mut();
+        if pending.frames.len() <= frame_index {
+            pending.frames.resize_with(frame_index + 1, Vec::new);
+        }
+        pending.current = frame_index;
+        ::std::mem::replace(&mut pending.frames[frame_index], Vec::new())
+    }
+}
+
+impl DeletionFrames {
+
+    #[inline]
+    fn push(&mut self, deletion: PendingDeletion) {
+        let current = self.current;
+        self.frames[current].push(deletion);
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+
+// ----------------------------------------------------------------------------------------------
+/// A RAII wrapper that destroys its object on drop(through the deletion queue of `VkDevice`).
+///
+/// Create it by `VkDevice::owned`.
+pub struct Owned<T: OwnedDiscardable> {
+
+    object: Option<T>,
+    /// the deletion queue of the device, which is gone if the device has been destroyed.
+    queue: Weak<RefCell<DeletionFrames>>,
+}
+
+impl<T: OwnedDiscardable> Owned<T> {
+
+    pub(crate) fn new(object: T, queue: Weak<RefCell<DeletionFrames>>) -> Owned<T> {
+        Owned { object: Some(object), queue }
+    }
+
+    /// Take the object out of this wrapper, so that it will not be destroyed on drop.
+    pub fn into_inner(mut self) -> T {
+        self.object.take().unwrap()
+    }
+}
+
+impl<T: OwnedDiscardable> Deref for Owned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.object.as_ref().unwrap()
+    }
+}
+
+impl<T: OwnedDiscardable> DerefMut for Owned<T> {
+
+    fn deref_mut(&mut self) -> &mut T {
+        self.object.as_mut().unwrap()
+    }
+}
+
+impl<T: OwnedDiscardable> Drop for Owned<T> {
+
+    fn drop(&mut self) {
+        if let Some(object) = self.object.take() {
+            match self.queue.upgrade() {
+                | Some(queue) => queue.borrow_mut().push(object.into_deletion()),
+                | None => {
+                    // the object can not be destroyed without its device.
+                    println!("[Warning] Owned<{}> is dropped after its VkDevice has been destroyed, and the object is leaked.", ::std::any::type_name::<T>());
+                },
+            }
+        }
+    }
+}
+// ----------------------------------------------------------------------------------------------
@@ -49,7 +49,7 @@ impl VkSurface {
 
         unsafe {
             self.loader.get_physical_device_surface_capabilities(physical_device, self.handle)
-                .or(Err(VkError::query("Surface Capabilities")))
+                .map_err(|e| VkError::vk(e, "Query Surface Capabilities"))
         }
     }
 
@@ -58,7 +58,7 @@ impl VkSurface {
 
         unsafe {
             self.loader.get_physical_device_surface_formats(physical_device, self.handle)
-                .or(Err(VkError::query("Surface Formats")))
+                .map_err(|e| VkError::vk(e, "Query Surface Formats"))
         }
     }
 
@@ -67,7 +67,7 @@ impl VkSurface {
 
         unsafe {
             self.loader.get_physical_device_surface_present_modes(physical_device, self.handle)
-                .or(Err(VkError::query("Surface Present Modes")))
+                .map_err(|e| VkError::vk(e, "Query Surface Present Modes"))
         }
     }
 }
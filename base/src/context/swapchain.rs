@@ -6,18 +6,44 @@ use failure_derive::Fail;
 use crate::context::instance::VkInstance;
 use crate::context::device::{VkDevice, VkQueue};
 use crate::context::surface::VkSurface;
-use crate::ci::image::ImageViewCI;
+use crate::ci::image::{ImageCI, ImageViewCI, ImageBarrierCI};
+use crate::ci::buffer::BufferCI;
+use crate::ci::device::SubmitCI;
+use crate::ci::vma::{VmaImage, VmaBuffer, VmaAllocationCI};
 use crate::ci::VkObjectBuildableCI;
-use crate::error::{VkResult, VkError};
+use crate::command::CmdTransferApi;
+use crate::error::{VkResult, VkError, VkErrorKind};
 use crate::utils::time::VkTimeDuration;
-use crate::{vkuint, vklint};
+use crate::{vkuint, vklint, vkbytes, vkptr};
 
+use ash::version::DeviceV1_0;
+
+use std::cell::Cell;
 use std::ptr;
 
+/// the number of images used in headless mode.
+const OFFSCREEN_IMAGE_COUNT: usize = 2;
+/// the format of images in headless mode, so that the pixels read back are in RGBA order.
+const OFFSCREEN_FORMAT: vk::Format = vk::Format::R8G8B8A8_UNORM;
+
+/// the usage of images in headless mode, which can be read back, and written by blitting(etc. by `RenderTarget::record_blit`).
+fn offscreen_usage() -> vk::ImageUsageFlags {
+    vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC | vk::ImageUsageFlags::TRANSFER_DST
+}
+
 #[derive(Debug, Clone)]
 pub struct SwapchainConfig {
 
+    /// `present_vsync` indicates whether to wait for the vertical blank when presenting images.
+    ///
+    /// It only takes effect if `present_mode` is `None`. If it is false, `vk::PresentModeKHR::MAILBOX` is preferred,
+    /// then `vk::PresentModeKHR::IMMEDIATE`.
     pub present_vsync: bool,
+    /// `present_mode` is the preferred present mode(etc. `vk::PresentModeKHR::MAILBOX` for low latency,
+    /// or `vk::PresentModeKHR::IMMEDIATE` to uncap the frame rate).
+    ///
+    /// `vk::PresentModeKHR::FIFO` is used if the preferred mode is not supported by the surface.
+    pub present_mode: Option<vk::PresentModeKHR>,
     pub image_acquire_time: VkTimeDuration,
 }
 
@@ -26,7 +52,8 @@ impl Default for SwapchainConfig {
     fn default() -> SwapchainConfig {
 
         SwapchainConfig {
-            present_vsync: false,
+            present_vsync: true,
+            present_mode : None,
             image_acquire_time: VkTimeDuration::Infinite,
         }
     }
@@ -34,10 +61,8 @@ impl Default for SwapchainConfig {
 
 pub struct VkSwapchain {
 
-    /// handle of `vk::SwapchainKHR`.
-    handle: vk::SwapchainKHR,
-    /// the extension loader provides functions for creation and destruction of `vk::SwapchainKHR` object.
-    loader: ash::extensions::khr::Swapchain,
+    /// the `vk::SwapchainKHR` object, or the offscreen images in headless mode.
+    backing: SwapchainBacking,
 
     /// the queue used to present image.
     present_queue: VkQueue,
@@ -47,14 +72,42 @@ pub struct VkSwapchain {
     pub backend_format: vk::Format,
     /// the dimension of presentable images.
     pub dimension: vk::Extent2D,
+    /// the dimension that scene renders at, which is `dimension` scaled by `WindowConfig::render_scale`.
+    pub render_dimension: vk::Extent2D,
+    /// the ratio of physical pixels to logical pixels of the window, which is 1.0 in headless mode.
+    hidpi_factor: f32,
 
     pub frame_in_flight: usize,
 
+    /// the present mode actually selected for the swapchain.
+    present_mode: vk::PresentModeKHR,
+    /// the usage that presentable images are created with.
+    image_usage: vk::ImageUsageFlags,
     image_acquire_time: vklint,
 
     config: SwapchainConfig,
 }
 
+enum SwapchainBacking {
+    /// the swapchain presenting images to the surface of window.
+    Surface {
+        /// handle of `vk::SwapchainKHR`.
+        handle: vk::SwapchainKHR,
+        /// the extension loader provides functions for creation and destruction of `vk::SwapchainKHR` object.
+        loader: ash::extensions::khr::Swapchain,
+    },
+    /// the images that are rendered but never presented, see `VulkanContext::headless`.
+    Offscreen {
+        images: Vec<VmaImage>,
+        /// the index of the image returned by next `next_image` call.
+        next_index: Cell<vkuint>,
+        /// the index of the image queued by last `present` call.
+        last_presented: Cell<Option<vkuint>>,
+        /// the device handle to submit the semaphore operations, which replace the acquirement and presentation.
+        device: ash::Device,
+    },
+}
+
 pub struct SwapchainImage {
 
     /// the presentable image objects associated with the swapchain.
@@ -67,16 +120,32 @@ pub struct SwapchainImage {
 
 #[derive(Debug, Fail)]
 pub enum SwapchainSyncError {
-    #[fail(display = "No image became available within the time allowed.")]
+    #[fail(display = "No image became available or the previous frame did not finish within the time allowed. The GPU may hang(etc. by an infinite loop in shader).")]
     TimeOut,
     #[fail(display = "Swapchain does not match the surface properties exactly.")]
     SubOptimal,
     #[fail(display = "Surface has changed and is not compatible with the swapchain.")]
     SurfaceOutDate,
+    #[fail(display = "The device was lost.")]
+    DeviceLost,
     #[fail(display = "Get unknown error when acquiring image.")]
     Unknown,
 }
 
+impl From<vk::Result> for SwapchainSyncError {
+
+    /// Translate the error result of acquiring or presentation.
+    fn from(result: vk::Result) -> SwapchainSyncError {
+
+        match result {
+            | vk::Result::ERROR_OUT_OF_DATE_KHR => SwapchainSyncError::SurfaceOutDate,
+            | vk::Result::SUBOPTIMAL_KHR        => SwapchainSyncError::SubOptimal,
+            | vk::Result::ERROR_DEVICE_LOST     => SwapchainSyncError::DeviceLost,
+            | _ => SwapchainSyncError::Unknown,
+        }
+    }
+}
+
 impl VkSwapchain {
 
     pub(crate) fn new(instance: &VkInstance, device: &VkDevice, surface: &VkSurface, config: SwapchainConfig, dimension: vk::Extent2D) -> VkResult<VkSwapchain> {
@@ -84,13 +153,84 @@ impl VkSwapchain {
         VkSwapchain::build(instance, device, surface, config, dimension, None)
     }
 
-    pub(crate) fn rebuild(&mut self, instance: &VkInstance, device: &VkDevice, surface: &VkSurface, dimension: vk::Extent2D) -> VkResult<()> {
+    /// Create the swapchain whose images are rendered offscreen, without window or surface.
+    pub(crate) fn new_offscreen(device: &mut VkDevice, config: SwapchainConfig, dimension: vk::Extent2D) -> VkResult<VkSwapchain> {
 
-        let new_chain = VkSwapchain::build(instance, device, surface, self.config.clone(), dimension, Some(self.handle))?;
-        self.discard(device);
-        *self = new_chain;
+        let mut images = Vec::with_capacity(OFFSCREEN_IMAGE_COUNT);
+        let mut image_resources = Vec::with_capacity(OFFSCREEN_IMAGE_COUNT);
 
-        Ok(())
+        for _ in 0..OFFSCREEN_IMAGE_COUNT {
+
+            let image = {
+                let image_ci = ImageCI::new_2d(OFFSCREEN_FORMAT, dimension)
+                    .usages(offscreen_usage());
+                let allocation_ci = VmaAllocationCI::new(vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
+                let image_allocation = device.vma.create_image(image_ci.as_ref(), allocation_ci.as_ref())
+                    .map_err(VkErrorKind::Vma)?;
+                VmaImage::from(image_allocation)
+            };
+
+            let view = ImageViewCI::new(image.handle, vk::ImageViewType::TYPE_2D, OFFSCREEN_FORMAT)
+                .sub_range(color_subrange())
+                .build(device)?;
+
+            image_resources.push(SwapchainImage { image: image.handle, view });
+            images.push(image);
+        }
+
+        let backing = SwapchainBacking::Offscreen {
+            images,
+            next_index: Cell::new(0),
+            last_presented: Cell::new(None),
+            device: device.logic.handle.clone(),
+        };
+
+        let result = VkSwapchain {
+            backing, config,
+            present_queue: device.logic.queues.graphics.clone(),
+            frame_in_flight: OFFSCREEN_IMAGE_COUNT,
+            image_acquire_time: VkTimeDuration::Infinite.into(),
+            present_mode: vk::PresentModeKHR::FIFO,
+            hidpi_factor: 1.0,
+            image_usage: offscreen_usage(),
+            images: image_resources,
+            backend_format: OFFSCREEN_FORMAT,
+            dimension,
+            render_dimension: dimension,
+        };
+        Ok(result)
+    }
+
+    /// Recreate the swapchain in `dimension`. `surface` is ignored in headless mode, and the dimension keeps unchanged.
+    pub(crate) fn rebuild(&mut self, instance: &VkInstance, device: &mut VkDevice, surface: Option<&VkSurface>, dimension: vk::Extent2D) -> VkResult<()> {
+
+        let new_chain = match (&self.backing, surface) {
+            | (SwapchainBacking::Surface { handle, .. }, Some(surface)) => {
+                VkSwapchain::build(instance, device, surface, self.config.clone(), dimension, Some(*handle))?
+            },
+            | (SwapchainBacking::Surface { .. }, None) => {
+                return Err(VkError::custom("The surface is required to recreate the swapchain."))
+            },
+            | (SwapchainBacking::Offscreen { .. }, _) => {
+                VkSwapchain::new_offscreen(device, self.config.clone(), self.dimension)?
+            },
+        };
+
+        let old_chain = ::std::mem::replace(self, new_chain);
+        old_chain.discard(device)
+    }
+
+    /// The usage of presentable images(etc. to check if they can be copied with `vk::ImageUsageFlags::TRANSFER_SRC`).
+    pub fn image_usage(&self) -> vk::ImageUsageFlags {
+        self.image_usage
+    }
+
+    /// Check if the images are rendered offscreen without window.
+    pub fn is_headless(&self) -> bool {
+        match self.backing {
+            | SwapchainBacking::Surface { .. } => false,
+            | SwapchainBacking::Offscreen { .. } => true,
+        }
     }
 
     fn build(instance: &VkInstance, device: &VkDevice, surface: &VkSurface, config: SwapchainConfig, dimension: vk::Extent2D, old_chain: Option<vk::SwapchainKHR>) -> VkResult<VkSwapchain> {
@@ -127,7 +267,7 @@ impl VkSwapchain {
 
         let handle = unsafe {
             loader.create_swapchain(&swapchain_ci, None)
-                .or(Err(VkError::create("Swapchain")))?
+                .map_err(|e| VkError::vk(e, "Create Swapchain"))?
         };
 
         let image_resources = obtain_swapchain_images(device, handle, &loader, &swapchain_format)?;
@@ -135,10 +275,15 @@ impl VkSwapchain {
         let image_acquire_time = config.image_acquire_time.into();
 
         let result = VkSwapchain {
-            handle, loader, present_queue, frame_in_flight, image_acquire_time, config,
+            present_queue, frame_in_flight, image_acquire_time, config,
+            backing: SwapchainBacking::Surface { handle, loader },
+            present_mode: swapchain_present_mode,
+            image_usage: swapchain_capability.support_usage,
             images: image_resources,
             backend_format: swapchain_format.color_format,
             dimension: swapchain_capability.swapchain_extent,
+            render_dimension: swapchain_capability.swapchain_extent,
+            hidpi_factor: 1.0,
         };
 
         Ok(result)
@@ -149,26 +294,44 @@ impl VkSwapchain {
     /// `sign_semaphore` is the semaphore to signal during this function, or None for no semaphore to signal.
     ///
     /// `sign_fence` is the fence to signal during this function, or None for no fence to signal.
+    ///
+    /// If the swapchain is suboptimal, the acquired image is still returned, since the semaphore and fence will be signaled.
+    /// The suboptimal state is reported again by `present`, where the swapchain should be recreated.
     pub(crate) fn next_image(&self, semaphore: Option<vk::Semaphore>, fence: Option<vk::Fence>) -> Result<vkuint, SwapchainSyncError> {
 
+        let (handle, loader) = match self.backing {
+            | SwapchainBacking::Surface { handle, ref loader } => (handle, loader),
+            | SwapchainBacking::Offscreen { ref images, ref next_index, ref device, .. } => {
+
+                let image_index = next_index.get();
+                next_index.set((image_index + 1) % (images.len() as vkuint));
+
+                // signal the semaphore and fence, just like an image is acquired.
+                let mut submit_ci = SubmitCI::new();
+                if let Some(semaphore) = semaphore {
+                    submit_ci = submit_ci.add_signal(semaphore);
+                }
+                unsafe {
+                    device.queue_submit(self.present_queue.handle, &[*submit_ci.as_ref()], fence.unwrap_or(vk::Fence::null()))
+                        .or(Err(SwapchainSyncError::Unknown))?;
+                }
+                return Ok(image_index)
+            },
+        };
+
         let semaphore = semaphore.unwrap_or(vk::Semaphore::null());
         let fence = fence.unwrap_or(vk::Fence::null());
 
         // execute next image acquire operation.
-        let (image_index, is_sub_optimal) = unsafe {
-            self.loader.acquire_next_image(self.handle, self.image_acquire_time, semaphore, fence)
+        let (image_index, _is_sub_optimal) = unsafe {
+            loader.acquire_next_image(handle, self.image_acquire_time, semaphore, fence)
                 .map_err(|error| match error {
-                    | vk::Result::TIMEOUT               => SwapchainSyncError::TimeOut,
-                    | vk::Result::ERROR_OUT_OF_DATE_KHR => SwapchainSyncError::SurfaceOutDate,
-                    | _ => SwapchainSyncError::Unknown,
+                    | vk::Result::TIMEOUT => SwapchainSyncError::TimeOut,
+                    | _ => SwapchainSyncError::from(error),
                 })?
         };
 
-        if is_sub_optimal {
-            Err(SwapchainSyncError::SubOptimal)
-        } else {
-            Ok(image_index)
-        }
+        Ok(image_index)
     }
 
     /// Queue an image for presentation.
@@ -181,6 +344,23 @@ impl VkSwapchain {
     /// `image_index` is the index of swapchain’s presentable images.
     pub(crate) fn present(&self, wait_semaphores: &[vk::Semaphore], image_index: vkuint) -> Result<(), SwapchainSyncError> {
 
+        let (handle, loader) = match self.backing {
+            | SwapchainBacking::Surface { ref handle, ref loader } => (handle, loader),
+            | SwapchainBacking::Offscreen { ref last_presented, ref device, .. } => {
+
+                // just wait for the semaphores, since nothing is presented.
+                let submit_ci = wait_semaphores.iter().fold(SubmitCI::new(), |submit_ci, &semaphore| {
+                    submit_ci.add_wait(vk::PipelineStageFlags::BOTTOM_OF_PIPE, semaphore)
+                });
+                unsafe {
+                    device.queue_submit(self.present_queue.handle, &[*submit_ci.as_ref()], vk::Fence::null())
+                        .or(Err(SwapchainSyncError::Unknown))?;
+                }
+                last_presented.set(Some(image_index));
+                return Ok(())
+            },
+        };
+
         // Currently only support single swapchain and single image index.
         let present_info = vk::PresentInfoKHR {
             s_type              : vk::StructureType::PRESENT_INFO_KHR,
@@ -188,14 +368,14 @@ impl VkSwapchain {
             wait_semaphore_count: wait_semaphores.len() as _,
             p_wait_semaphores   : wait_semaphores.as_ptr(),
             swapchain_count     : 1,
-            p_swapchains        : &self.handle,
+            p_swapchains        : handle,
             p_image_indices     : &image_index,
             p_results           : ptr::null_mut(),
         };
 
         let is_sub_optimal = unsafe {
-            self.loader.queue_present(self.present_queue.handle, &present_info)
-                .or(Err(SwapchainSyncError::Unknown))?
+            loader.queue_present(self.present_queue.handle, &present_info)
+                .map_err(SwapchainSyncError::from)?
         };
 
         if is_sub_optimal {
@@ -209,18 +389,65 @@ impl VkSwapchain {
         self.frame_in_flight.clone()
     }
 
-    /// Destroy the `vk::SwapchainKHR` object.
+    /// The present mode selected according to `SwapchainConfig`.
+    pub fn present_mode(&self) -> vk::PresentModeKHR {
+        self.present_mode
+    }
+
+    /// The HiDPI factor of the window when this swapchain was created(etc. 2.0 on a Retina display).
+    ///
+    /// The UI should scale its content by this factor, so that it keeps the same physical size on every display.
+    #[inline]
+    pub fn hidpi_factor(&self) -> f32 {
+        self.hidpi_factor
+    }
+
+    pub(crate) fn set_hidpi_factor(&mut self, factor: f32) {
+        self.hidpi_factor = factor;
+    }
+
+    pub(crate) fn set_render_scale(&mut self, scale: f32) {
+        self.render_dimension = crate::texture::scale_dimension(self.dimension, scale);
+    }
+
+    /// Copy the pixels of the image queued by last presentation to host memory, in `OFFSCREEN_FORMAT`(RGBA, 8 bits per channel, row by row).
+    ///
+    /// It is only available in headless mode, and the device must be idle.
+    pub fn read_back(&self, device: &mut VkDevice) -> VkResult<Vec<u8>> {
+
+        let image_index = match self.backing {
+            | SwapchainBacking::Offscreen { ref last_presented, .. } => {
+                last_presented.get()
+                    .ok_or(VkError::custom("No frame has been rendered to read back."))?
+            },
+            | SwapchainBacking::Surface { .. } => {
+                return Err(VkError::custom("Only the images rendered in headless mode can be read back."))
+            },
+        };
+        copy_image_to_host(device, self.images[image_index as usize].image, self.dimension)
+    }
+
+    /// Destroy the `vk::SwapchainKHR` object, or the offscreen images in headless mode.
     ///
     /// The application must not destroy `vk::SwapchainKHR` until after completion of all outstanding operations on images that were acquired from the `vk::SwapchainKHR`.
-    pub(crate) fn discard(&self, device: &VkDevice) {
+    pub(crate) fn discard(self, device: &mut VkDevice) -> VkResult<()> {
 
         self.images.iter().for_each(|swapchain_image| {
             device.discard(swapchain_image.view);
         });
 
-        unsafe {
-            self.loader.destroy_swapchain(self.handle, None);
+        match self.backing {
+            | SwapchainBacking::Surface { handle, loader } => unsafe {
+                loader.destroy_swapchain(handle, None);
+            },
+            | SwapchainBacking::Offscreen { images, .. } => {
+                for image in images.into_iter() {
+                    device.vma_discard(image)?;
+                }
+            },
         }
+
+        Ok(())
     }
 }
 
@@ -238,11 +465,77 @@ fn query_present_queue(device: &VkDevice, surface: &VkSurface) -> Option<VkQueue
     }
 }
 
+/// Copy the pixels of the color `image`(with 4 bytes per pixel) to host memory, and keep its layout in `vk::ImageLayout::PRESENT_SRC_KHR`.
+///
+/// The image must have been created with `vk::ImageUsageFlags::TRANSFER_SRC`, and the device must be idle.
+pub(crate) fn copy_image_to_host(device: &mut VkDevice, image: vk::Image, dimension: vk::Extent2D) -> VkResult<Vec<u8>> {
+
+    let bytes_count = (dimension.width * dimension.height * 4) as usize;
+    let readback_buffer = {
+        let buffer_ci = BufferCI::new(bytes_count as vkbytes)
+            .usage(vk::BufferUsageFlags::TRANSFER_DST);
+        let allocation_ci = VmaAllocationCI::new(vma::MemoryUsage::GpuToCpu, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT);
+        let buffer_allocation = device.vma.create_buffer(buffer_ci.as_ref(), allocation_ci.as_ref())
+            .map_err(VkErrorKind::Vma)?;
+        VmaBuffer::from(buffer_allocation)
+    };
+
+    let copy_region = vk::BufferImageCopy {
+        buffer_offset: 0,
+        buffer_row_length  : 0,
+        buffer_image_height: 0,
+        image_subresource: vk::ImageSubresourceLayers {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            base_array_layer: 0, layer_count: 1,
+        },
+        image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+        image_extent: vk::Extent3D { width: dimension.width, height: dimension.height, depth: 1 },
+    };
+
+    // the image is in the final layout of render pass.
+    let copy_src_barrier = ImageBarrierCI::new(image, color_subrange())
+        .access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE, vk::AccessFlags::TRANSFER_READ)
+        .layout(vk::ImageLayout::PRESENT_SRC_KHR, vk::ImageLayout::TRANSFER_SRC_OPTIMAL);
+    let restore_barrier = ImageBarrierCI::new(image, color_subrange())
+        .access_mask(vk::AccessFlags::TRANSFER_READ, vk::AccessFlags::empty())
+        .layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL, vk::ImageLayout::PRESENT_SRC_KHR);
+
+    let recorder = device.get_blit_recorder();
+    recorder.begin_record()?
+        .image_pipeline_barrier(vk::PipelineStageFlags::ALL_COMMANDS, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[copy_src_barrier.into()])
+        .copy_img2buf(image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, readback_buffer.handle, &[copy_region])
+        .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::BOTTOM_OF_PIPE, vk::DependencyFlags::empty(), &[restore_barrier.into()])
+        .end_record()?;
+    device.flush_blit(recorder)?;
+
+    let mut pixels = vec![0_u8; bytes_count];
+    let data_ptr = device.vma.map_memory(&readback_buffer.allocation)
+        .map_err(VkErrorKind::Vma)? as vkptr<u8>;
+    unsafe { pixels.as_mut_ptr().copy_from_nonoverlapping(data_ptr, bytes_count); }
+    device.vma.unmap_memory(&readback_buffer.allocation)
+        .map_err(VkErrorKind::Vma)?;
+
+    device.vma_discard(readback_buffer)?;
+
+    Ok(pixels)
+}
+
+fn color_subrange() -> vk::ImageSubresourceRange {
+    vk::ImageSubresourceRange {
+        aspect_mask: vk::ImageAspectFlags::COLOR,
+        base_mip_level: 0,
+        level_count: 1,
+        base_array_layer: 0,
+        layer_count: 1,
+    }
+}
+
 fn obtain_swapchain_images(device: &VkDevice, swapchain: vk::SwapchainKHR, loader: &ash::extensions::khr::Swapchain, format: &SwapchainFormat) -> VkResult<Vec<SwapchainImage>> {
 
     let image_handles = unsafe {
         loader.get_swapchain_images(swapchain)
-            .or(Err(VkError::query("Swapchain Images")))?
+            .map_err(|e| VkError::vk(e, "Query Swapchain Images"))?
     };
 
     let mut result = Vec::with_capacity(image_handles.len());
@@ -278,29 +571,24 @@ fn query_optimal_present_mode(device: &VkDevice, surface: &VkSurface, config: &S
 
     // The vk::PresentModeKHR::FIFO mode must always be present as per spec.
     // This mode waits for the vertical blank ("v-sync").
-    let result = if config.present_vsync {
-
+    let preferred_modes = match config.present_mode {
+        | Some(present_mode) => vec![present_mode],
+        | None if config.present_vsync => vec![],
         // if v-sync is not requested, try to find a mailbox mode.
         // it's the lowest latency non-tearing present mode available.
-        let present_mode_searching = || {
-
-            for present_mode in available_modes.into_iter() {
-                if present_mode == vk::PresentModeKHR::MAILBOX {
-                    return vk::PresentModeKHR::MAILBOX
-                }
-
-                if present_mode == vk::PresentModeKHR::IMMEDIATE {
-                    return vk::PresentModeKHR::IMMEDIATE
-                }
-            }
+        | None => vec![vk::PresentModeKHR::MAILBOX, vk::PresentModeKHR::IMMEDIATE],
+    };
 
-            vk::PresentModeKHR::FIFO
-        };
+    let result = preferred_modes.into_iter()
+        .find(|preferred_mode| available_modes.contains(preferred_mode))
+        .unwrap_or(vk::PresentModeKHR::FIFO);
 
-        present_mode_searching()
-    } else {
-        vk::PresentModeKHR::FIFO
-    };
+    if let Some(present_mode) = config.present_mode {
+        if present_mode != result {
+            println!("[Warning] Present mode {:?} is not supported by the surface, fallback to {:?}.", present_mode, result);
+        }
+    }
+    println!("[Info] Swapchain present mode: {:?}.", result);
 
     Ok(result)
 }
@@ -0,0 +1,181 @@
+
+use ash::vk;
+use ash::version::DeviceV1_0;
+
+use crate::context::{VkDevice, VkSwapchain, SwapchainSyncError};
+use crate::ci::device::SubmitCI;
+use crate::ci::sync::{SemaphoreCI, FenceCI};
+use crate::utils::frame::FrameCounter;
+use crate::utils::time::VkTimeDuration;
+use crate::error::VkResult;
+use crate::vkuint;
+
+/// The synchronization primitives of a frame-in-flight, returned by `FrameSync::acquire_next`.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameInFlight {
+
+    /// the index of the acquired swapchain image, which is not guaranteed to follow the frame order.
+    ///
+    /// Use it to select the per-image resources(etc. framebuffers or the command buffers recorded for each framebuffer).
+    pub image_index: usize,
+    /// the index of current frame-in-flight, which is in the range of `0..frame_in_flight`.
+    pub frame_index: usize,
+    /// the semaphore signaled when the acquired image is available for writing.
+    pub image_available: vk::Semaphore,
+    /// the semaphore that must be signaled when the rendering has finished, which is waited by presentation.
+    pub render_finished: vk::Semaphore,
+    /// the fence that must be signaled by the submission of this frame.
+    pub fence: vk::Fence,
+}
+
+impl FrameInFlight {
+
+    /// Generate a `SubmitCI` which waits `image_available` and signals `render_finished`.
+    ///
+    /// Add the command buffers to the returned `SubmitCI`, and submit it with `fence`.
+    pub fn submit_ci(&self) -> SubmitCI {
+
+        SubmitCI::new()
+            .add_wait(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, self.image_available)
+            .add_signal(self.render_finished)
+    }
+
+    /// Submit `commands` to the graphics queue with the synchronization primitives of this frame.
+    pub fn submit(&self, device: &VkDevice, commands: &[vk::CommandBuffer]) -> VkResult<()> {
+
+        let submit_ci = commands.iter()
+            .fold(self.submit_ci(), |submit_ci, &command| submit_ci.add_command(command));
+
+        device.submit(submit_ci, device.logic.queues.graphics.handle, Some(self.fence))
+    }
+}
+
+/// The semaphores and fences to synchronize the frames-in-flight with the swapchain.
+///
+/// Each frame-in-flight owns an image-available semaphore, a render-finished semaphore and a fence.
+pub struct FrameSync {
+
+    image_available: Vec<vk::Semaphore>,
+    render_finished: Vec<vk::Semaphore>,
+    in_flight_fences: Vec<vk::Fence>,
+    /// the fence of the frame that is currently using each swapchain image(not owned by this field).
+    image_fences: Vec<vk::Fence>,
+
+    frame_counter: FrameCounter,
+}
+
+impl FrameSync {
+
+    pub fn new(device: &VkDevice, frame_in_flight: usize, image_count: usize) -> VkResult<FrameSync> {
+
+        let semaphore_ci = SemaphoreCI::new();
+        // the fences are signaled initially, so that the first frames do not wait forever.
+        let fence_ci = FenceCI::new(true);
+
+        let mut image_available  = Vec::with_capacity(frame_in_flight);
+        let mut render_finished  = Vec::with_capacity(frame_in_flight);
+        let mut in_flight_fences = Vec::with_capacity(frame_in_flight);
+
+        for _ in 0..frame_in_flight {
+            image_available.push(device.build(&semaphore_ci)?);
+            render_finished.push(device.build(&semaphore_ci)?);
+            in_flight_fences.push(device.build(&fence_ci)?);
+        }
+
+        let syncs = FrameSync {
+            image_available, render_finished, in_flight_fences,
+            image_fences: vec![vk::Fence::null(); image_count],
+            frame_counter: FrameCounter::new(frame_in_flight),
+        };
+        Ok(syncs)
+    }
+
+    /// Wait for the previous work of current frame-in-flight and the acquired image, and then acquire next swapchain image.
+    ///
+    /// `SwapchainSyncError::TimeOut` is returned if the waiting exceeds `timeout`(the GPU may hang, etc. by an infinite loop in shader),
+    /// and `SwapchainSyncError::DeviceLost` is returned if the device is lost.
+    pub fn acquire_next(&mut self, device: &VkDevice, swapchain: &VkSwapchain, timeout: VkTimeDuration) -> Result<FrameInFlight, SwapchainSyncError> {
+
+        let frame_index = self.frame_counter.current_frame();
+        let fence = self.in_flight_fences[frame_index];
+        FrameSync::wait_fence(device, fence, timeout)?;
+
+        let image_available = self.image_available[frame_index];
+        let acquire_index = swapchain.next_image(Some(image_available), None)?;
+
+        // the acquired image index is not guaranteed to equal the frame index,
+        // so wait for the previous frame which is still using this image(and its per-image resources).
+        let image_index = acquire_index as usize;
+        debug_assert!(image_index < self.image_fences.len(), "The acquired image index is out of the range of swapchain images.");
+
+        let image_in_flight = self.image_fences[image_index];
+        if image_in_flight != vk::Fence::null() && image_in_flight != fence {
+            FrameSync::wait_fence(device, image_in_flight, timeout)?;
+        }
+        self.image_fences[image_index] = fence;
+
+        unsafe {
+            device.logic.handle.reset_fences(&[fence])
+                .or(Err(SwapchainSyncError::Unknown))?;
+        }
+
+        let frame = FrameInFlight {
+            image_index, frame_index, image_available, fence,
+            render_finished: self.render_finished[frame_index],
+        };
+        Ok(frame)
+    }
+
+    /// Queue the image of `frame` for presentation after its rendering has finished, and move to next frame-in-flight.
+    pub fn present(&mut self, swapchain: &VkSwapchain, frame: &FrameInFlight) -> Result<(), SwapchainSyncError> {
+
+        // the work of `frame` has been submitted, so move on even if the presentation fails.
+        self.frame_counter.tick_frame();
+        swapchain.present(&[frame.render_finished], frame.image_index as vkuint)
+    }
+
+    /// The index of the frame-in-flight that will be acquired next.
+    #[inline]
+    pub fn current_frame(&self) -> usize {
+        self.frame_counter.current_frame()
+    }
+
+    /// Recreate all the synchronization primitives for the new swapchain of `image_count` images.
+    ///
+    /// The acquire semaphore may be left signaled by an outdated swapchain, so it is safer to recreate them all.
+    /// The device must be idle when calling this method.
+    pub fn reset(&mut self, device: &VkDevice, image_count: usize) -> VkResult<()> {
+
+        let frame_in_flight = self.in_flight_fences.len();
+        self.discard(device);
+        *self = FrameSync::new(device, frame_in_flight, image_count)?;
+
+        Ok(())
+    }
+
+    pub fn discard(&mut self, device: &VkDevice) {
+
+        device.discard(&self.image_available);
+        device.discard(&self.render_finished);
+        device.discard(&self.in_flight_fences);
+
+        self.image_available.clear();
+        self.render_finished.clear();
+        self.in_flight_fences.clear();
+        self.image_fences.clear();
+    }
+
+    fn wait_fence(device: &VkDevice, fence: vk::Fence, timeout: VkTimeDuration) -> Result<(), SwapchainSyncError> {
+
+        let wait_result = unsafe {
+            device.logic.handle.wait_for_fences(&[fence], true, timeout.into())
+        };
+
+        match wait_result {
+            | Ok(_) => Ok(()),
+            | Err(vk::Result::TIMEOUT) => Err(SwapchainSyncError::TimeOut),
+            | Err(vk::Result::ERROR_DEVICE_LOST) => Err(SwapchainSyncError::DeviceLost),
+            | Err(_) => Err(SwapchainSyncError::Unknown),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 
+use ash::vk;
 use failure::{ Backtrace, Context, Fail };
 
 use std::result;
@@ -20,6 +21,14 @@ impl VkError {
         self.ctx.get_context()
     }
 
+    /// Return the `vk::Result` reported by Vulkan, if this error comes from a failed Vulkan call.
+    pub fn vk_result(&self) -> Option<vk::Result> {
+        match self.kind() {
+            | VkErrorKind::Vulkan { result, .. } => Some(*result),
+            | _ => None,
+        }
+    }
+
     pub fn unlink(target_name: &'static str) -> VkError {
         VkError::from(VkErrorKind::Unlink { target_name })
     }
@@ -40,6 +49,13 @@ impl VkError {
         VkError::from(VkErrorKind::Device { ops_description })
     }
 
+    /// Create an error for a failed Vulkan call, keeping the `vk::Result` it returned.
+    ///
+    /// `context` describes the failed operation(etc. "Create Fence", "Queue Submit").
+    pub fn vk(result: vk::Result, context: impl AsRef<str>) -> VkError {
+        VkError::from(VkErrorKind::Vulkan { result, context: context.as_ref().to_string() })
+    }
+
     pub fn shaderc(compile_message: impl AsRef<str>) -> VkError {
         VkError::from(VkErrorKind::Shaderc {
             compile_message: compile_message.as_ref().to_string()
@@ -63,6 +79,10 @@ impl VkError {
         VkError::from(VkErrorKind::Serialize(error))
     }
 
+    pub fn validation(message: impl AsRef<str>) -> VkError {
+        VkError::from(VkErrorKind::Validation { message: message.as_ref().to_string() })
+    }
+
     pub fn custom(description: impl AsRef<str>) -> VkError {
         VkError::from(VkErrorKind::Custom {
             description: description.as_ref().to_string()
@@ -109,6 +129,9 @@ pub enum VkErrorKind {
     /// An error triggered by Invalid Device operations.
     #[fail(display = "Invalid Operation: {}", ops_description)]
     Device { ops_description: &'static str },
+    /// An error returned by a Vulkan call, along with the operation it failed in.
+    #[fail(display = "{} failed with {:?}.", context, result)]
+    Vulkan { result: vk::Result, context: String },
     /// An error that occurred while trying to compile shader code in runtime.
     #[fail(display = "Error occurred during runtime shader compiling: {}.", compile_message)]
     Shaderc { compile_message: String },
@@ -123,6 +146,9 @@ pub enum VkErrorKind {
     /// An error occurred while loading texture by gli crate.
     #[fail(display = "gli error: {}.", _0)]
     Gli(#[cause] gli::Error),
+    /// An error occurred while decoding image file by image crate.
+    #[fail(display = "image error: {}.", _0)]
+    Image(#[cause] image::ImageError),
     /// An error that occurred while working with a file path.
     #[fail(display = "Failed to locate file at: {:?}", path)]
     Path { path: PathBuf },
@@ -130,6 +156,9 @@ pub enum VkErrorKind {
     Unimplemented { function: String },
     #[fail(display = "Failed to serialize data into bytes: {}", _0)]
     Serialize(#[cause] bincode::Error),
+    /// An error message reported by Vulkan Validation Layer.
+    #[fail(display = "Validation layer reported error: {}", message)]
+    Validation { message: String },
     /// Other errors.
     #[fail(display = "{}", description)]
     Custom { description: String },
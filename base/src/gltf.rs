@@ -1,15 +1,18 @@
 
-pub use self::loader::GltfModelInfo;
-pub use self::loader::load_gltf;
+pub use self::loader::{GltfModelInfo, GltfSliceInfo};
+pub use self::loader::{load_gltf, load_gltf_from_slice};
 pub use self::asset::{VkglTFModel, ModelRenderParams};
+pub use self::skins::SkinResource;
 
-pub use self::meshes::AttributeFlags;
+pub use self::meshes::{AttributeFlags, IndexPrecision};
 pub use self::nodes::NodeAttachmentFlags;
 
 mod loader;
 
 mod scene;
 mod material;
+mod skins;
+mod animation;
 
 mod asset;
 mod meshes;
@@ -0,0 +1,254 @@
+
+use crate::gltf::asset::{GltfDocument, AssetAbstract, AssetElementList};
+use crate::gltf::asset::ReferenceIndex;
+use crate::gltf::nodes::Node;
+use crate::gltf::scene::Scene;
+use crate::error::VkResult;
+use crate::{Vec3F, Vec4F};
+
+type QuaternionF = vek::Quaternion<f32>;
+
+
+// ------------------------------------------------------------------------------------
+/// The node property that an animation channel targets.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ChannelPath {
+    Translation,
+    Rotation,
+    Scale,
+}
+
+/// A wrapper class for animation channel(with its sampler) in glTF.
+struct AnimationChannel {
+
+    /// the json index of the node that this channel targets.
+    target_node: ReferenceIndex,
+    path: ChannelPath,
+    interpolation: gltf::animation::Interpolation,
+    /// the keyframe time in seconds.
+    inputs: Vec<f32>,
+    /// the keyframe values. Translation and scale are stored in xyz component, and rotation is stored as quaternion(xyzw).
+    outputs: Vec<Vec4F>,
+}
+
+impl AnimationChannel {
+
+    fn from_doc(doc_channel: gltf::animation::Channel, source: &GltfDocument) -> Option<AnimationChannel> {
+
+        use gltf::animation::util::ReadOutputs;
+
+        let reader = doc_channel.reader(|b| Some(&source.buffers[b.index()]));
+        let inputs: Vec<f32> = reader.read_inputs()?.collect();
+
+        let (path, outputs) = match reader.read_outputs()? {
+            | ReadOutputs::Translations(translations) => {
+                let outputs = translations.map(|t| Vec4F::new(t[0], t[1], t[2], 0.0)).collect();
+                (ChannelPath::Translation, outputs)
+            },
+            | ReadOutputs::Rotations(rotations) => {
+                let outputs = rotations.into_f32().map(Vec4F::from).collect();
+                (ChannelPath::Rotation, outputs)
+            },
+            | ReadOutputs::Scales(scales) => {
+                let outputs = scales.map(|s| Vec4F::new(s[0], s[1], s[2], 0.0)).collect();
+                (ChannelPath::Scale, outputs)
+            },
+            // morph target animation is not supported.
+            | ReadOutputs::MorphTargetWeights(_) => return None,
+        };
+
+        let channel = AnimationChannel {
+            target_node: doc_channel.target().node().index(),
+            interpolation: doc_channel.sampler().interpolation(),
+            path, inputs, outputs,
+        };
+        Some(channel)
+    }
+
+    /// Get the keyframe value at `frame`.
+    ///
+    /// For cubic spline interpolation, each keyframe stores (in-tangent, value, out-tangent), and this returns the value.
+    fn keyframe_value(&self, frame: usize) -> Vec4F {
+        match self.interpolation {
+            | gltf::animation::Interpolation::CubicSpline => self.outputs[frame * 3 + 1],
+            | _ => self.outputs[frame],
+        }
+    }
+
+    /// Evaluate the cubic Hermite spline between `prev_frame` and `next_frame` at `factor`.
+    ///
+    /// See the appendix of glTF 2.0 specification for the definition of the spline.
+    fn cubic_spline(&self, prev_frame: usize, next_frame: usize, factor: f32) -> Vec4F {
+
+        let delta_time = self.inputs[next_frame] - self.inputs[prev_frame];
+
+        let prev_value   = self.outputs[prev_frame * 3 + 1];
+        let prev_tangent = self.outputs[prev_frame * 3 + 2] * delta_time; // the out-tangent of previous keyframe.
+        let next_value   = self.outputs[next_frame * 3 + 1];
+        let next_tangent = self.outputs[next_frame * 3] * delta_time; // the in-tangent of next keyframe.
+
+        let t2 = factor * factor;
+        let t3 = t2 * factor;
+
+        prev_value * (2.0 * t3 - 3.0 * t2 + 1.0)
+            + prev_tangent * (t3 - 2.0 * t2 + factor)
+            + next_value * (-2.0 * t3 + 3.0 * t2)
+            + next_tangent * (t3 - t2)
+    }
+
+    /// Interpolate the channel value at `time`, and write it to the target node.
+    fn apply(&self, time: f32, nodes: &mut AssetElementList<Node>) {
+
+        if self.inputs.is_empty() {
+            return
+        }
+
+        // find the keyframes before and after `time`.
+        let next_frame = self.inputs.iter().position(|&input| input > time)
+            .unwrap_or(self.inputs.len() - 1);
+        let prev_frame = next_frame.saturating_sub(1);
+
+        let factor = if next_frame == prev_frame || time <= self.inputs[prev_frame] {
+            0.0
+        } else if time >= self.inputs[next_frame] {
+            1.0
+        } else {
+            (time - self.inputs[prev_frame]) / (self.inputs[next_frame] - self.inputs[prev_frame])
+        };
+
+        // the interpolated value, and the value of next keyframe with the blend factor if the two keyframes are blended.
+        let (value, blend) = match self.interpolation {
+            | gltf::animation::Interpolation::Step => {
+                (self.keyframe_value(prev_frame), None)
+            },
+            | gltf::animation::Interpolation::Linear => {
+                (self.keyframe_value(prev_frame), Some((self.keyframe_value(next_frame), factor)))
+            },
+            | gltf::animation::Interpolation::CubicSpline if next_frame == prev_frame => {
+                (self.keyframe_value(prev_frame), None)
+            },
+            | gltf::animation::Interpolation::CubicSpline => {
+                (self.cubic_spline(prev_frame, next_frame, factor), None)
+            },
+        };
+
+        let lerp = |value: Vec4F| {
+            blend.map_or(value, |(next_value, factor)| value + (next_value - value) * factor)
+        };
+
+        let node = nodes.get_mut(self.target_node);
+
+        match self.path {
+            | ChannelPath::Translation => {
+                let value = lerp(value);
+                node.set_translation(Vec3F::new(value.x, value.y, value.z));
+            },
+            | ChannelPath::Rotation => {
+                let rotation = QuaternionF::from_xyzw(value.x, value.y, value.z, value.w);
+                let rotation = match blend {
+                    | Some((next_value, factor)) => {
+                        let next_rotation = QuaternionF::from_xyzw(next_value.x, next_value.y, next_value.z, next_value.w);
+                        QuaternionF::slerp(rotation, next_rotation, factor)
+                    },
+                    | None => rotation,
+                };
+                // the result of cubic spline is not normalized either.
+                node.set_rotation(rotation.normalized());
+            },
+            | ChannelPath::Scale => {
+                let value = lerp(value);
+                node.set_scale(Vec3F::new(value.x, value.y, value.z));
+            },
+        }
+    }
+}
+// ------------------------------------------------------------------------------------
+
+
+// ------------------------------------------------------------------------------------
+/// A wrapper class for animation in glTF.
+pub struct Animation {
+
+    /// the name property of current animation.
+    name: Option<String>,
+    channels: Vec<AnimationChannel>,
+    /// the time of the first and the last keyframe in this animation.
+    start: f32,
+    end  : f32,
+}
+
+impl Animation {
+
+    fn from_doc(doc_animation: gltf::Animation, source: &GltfDocument) -> Animation {
+
+        let name = doc_animation.name().and_then(|n| Some(n.to_string()));
+
+        let channels: Vec<AnimationChannel> = doc_animation.channels()
+            .filter_map(|doc_channel| AnimationChannel::from_doc(doc_channel, source))
+            .collect();
+
+        let start = channels.iter()
+            .filter_map(|channel| channel.inputs.first().cloned())
+            .fold(::std::f32::MAX, f32::min);
+        let end = channels.iter()
+            .filter_map(|channel| channel.inputs.last().cloned())
+            .fold(0.0, f32::max);
+
+        Animation { name, channels, start: start.min(end), end }
+    }
+
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(String::as_str)
+    }
+
+    /// The duration of this animation in seconds.
+    #[inline]
+    pub fn duration(&self) -> f32 {
+        self.end - self.start
+    }
+
+    /// The json index of the nodes targeted by this animation.
+    pub(crate) fn target_nodes<'a>(&'a self) -> impl Iterator<Item=ReferenceIndex> + 'a {
+        self.channels.iter().map(|channel| channel.target_node)
+    }
+
+    /// Apply this animation at `time`(in seconds) to the node transforms. The animation is looped.
+    pub(crate) fn apply(&self, time: f32, nodes: &mut AssetElementList<Node>) {
+
+        let duration = self.duration();
+        let local_time = if duration > 0.0 {
+            self.start + ((time % duration) + duration) % duration
+        } else {
+            self.start
+        };
+
+        for channel in self.channels.iter() {
+            channel.apply(local_time, nodes);
+        }
+    }
+}
+// ------------------------------------------------------------------------------------
+
+
+// ------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct AnimationAsset {
+
+    pub(crate) animations: Vec<Animation>,
+}
+
+impl AssetAbstract for AnimationAsset {
+    const ASSET_NAME: &'static str = "Animations";
+
+    fn read_doc(&mut self, source: &GltfDocument, _scene: &Scene) -> VkResult<()> {
+
+        for doc_animation in source.doc.animations() {
+            let animation = Animation::from_doc(doc_animation, source);
+            self.animations.push(animation);
+        }
+
+        Ok(())
+    }
+}
+// ------------------------------------------------------------------------------------
@@ -4,14 +4,18 @@ use ash::vk;
 use crate::gltf::meshes::{MeshAsset, MeshResource, AttributeFlags};
 use crate::gltf::nodes::{NodeAsset, NodeResource, NodeAttachmentFlags};
 use crate::gltf::material::{MaterialAsset, MaterialResource};
+use crate::gltf::skins::{SkinAsset, SkinResource};
+use crate::gltf::animation::{AnimationAsset, Animation};
 use crate::gltf::scene::Scene;
 
+use crate::ci::buffer::BufferCI;
+use crate::ci::vma::{VmaBuffer, VmaAllocationCI, VkBufferHandle};
 use crate::command::{VkCmdRecorder, IGraphics};
 use crate::context::{VkDevice, VmaResourceDiscardable};
-use crate::error::VkResult;
-use crate::Mat4F;
+use crate::error::{VkResult, VkError, VkErrorKind};
+use crate::{Mat4F, Vec3F, vkbytes};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 
 pub type ReferenceIndex = usize;
@@ -25,6 +29,17 @@ pub struct GltfDocument {
     pub images : Vec<gltf::image::Data>,
 
     pub transform: Option<Mat4F>,
+    /// whether `transform` is baked into the vertex positions, instead of applied as the root of node hierarchy.
+    pub is_transform_baked: bool,
+}
+
+impl GltfDocument {
+
+    /// Return the model transform if it is baked into the vertex positions.
+    #[inline]
+    pub fn baked_transform(&self) -> Option<&Mat4F> {
+        if self.is_transform_baked { self.transform.as_ref() } else { None }
+    }
 }
 // --------------------------------------------------------------------------------------
 
@@ -68,6 +83,34 @@ impl<T> AssetElementList<T> {
         let storage_index = self.query_table.get(&ref_index).cloned().unwrap();
         &self.list[storage_index]
     }
+
+    pub fn get_mut(&mut self, ref_index: ReferenceIndex) -> &mut T {
+
+        debug_assert!(self.query_table.contains_key(&ref_index));
+
+        let storage_index = self.query_table.get(&ref_index).cloned().unwrap();
+        &mut self.list[storage_index]
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Iterate all elements with their json index.
+    pub fn iter_indexed(&self) -> impl Iterator<Item=(ReferenceIndex, &T)> {
+        self.query_table.iter()
+            .map(move |(&ref_index, &storage_index)| (ref_index, &self.list[storage_index]))
+    }
+}
+
+impl<T> IntoIterator for AssetElementList<T> {
+    type Item = T;
+    type IntoIter = ::std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
+    }
 }
 // --------------------------------------------------------------------------------------
 
@@ -76,6 +119,8 @@ pub struct AssetRepository {
     pub nodes : NodeAsset,
     pub meshes: MeshAsset,
     pub materials: MaterialAsset,
+    pub skins: SkinAsset,
+    pub animations: AnimationAsset,
 }
 
 impl AssetRepository {
@@ -86,6 +131,8 @@ impl AssetRepository {
             nodes : NodeAsset::try_from(attachment_flag)?,
             meshes: MeshAsset::try_from(attr_flag)?,
             materials: MaterialAsset::new()?,
+            skins: SkinAsset::default(),
+            animations: AnimationAsset::default(),
         };
         Ok(repository)
     }
@@ -94,12 +141,19 @@ impl AssetRepository {
 
         let nodes_allocated  = self.nodes.allocate(device, device.phy.limits.min_uniform_buffer_offset_alignment)?;
         let meshes_allocated = self.meshes.allocate(device)?;
+        let materials_allocated = self.materials.allocate(device)?;
+        // the joint matrices are written with bind pose.
+        let skins_allocated = self.skins.allocate(device, &scene.global_transforms(&nodes_allocated.list))?;
 
         let result = VkglTFModel {
             scene,
             meshes: meshes_allocated,
             nodes : nodes_allocated,
-            materials: self.materials,
+            materials: materials_allocated,
+            skins: skins_allocated,
+            animations: self.animations.animations,
+            active_animation: None,
+            bounds: (Vec3F::zero(), Vec3F::zero()),
         };
 
         Ok(result)
@@ -114,6 +168,13 @@ pub struct VkglTFModel {
     pub meshes: MeshResource,
     pub nodes : NodeResource,
     pub materials: MaterialResource,
+    pub skins: SkinResource,
+
+    animations: Vec<Animation>,
+    active_animation: Option<usize>,
+
+    /// the min and max corners of the model's bounding box, computed during loading.
+    pub(crate) bounds: (Vec3F, Vec3F),
 
     scene: Scene,
 }
@@ -128,16 +189,166 @@ pub struct ModelRenderParams {
 impl VkglTFModel {
 
     pub fn record_command(&self, recorder: &VkCmdRecorder<IGraphics>, params: &ModelRenderParams) {
+        self.record_frame_command(recorder, params, 0)
+    }
+
+    /// Record the draw commands of this model, binding the node attachments of `frame_index`.
+    ///
+    /// `frame_index` selects the copy written by `update_animation`, and it is ignored if `enable_animation` has not been called.
+    pub fn record_frame_command(&self, recorder: &VkCmdRecorder<IGraphics>, params: &ModelRenderParams, frame_index: usize) {
+
+        self.meshes.record_command(recorder);
+        self.scene.record_command(recorder, self, params, frame_index);
+    }
+
+    /// Return the indirect draw parameters of all indexed primitives in this model, in the order read by `record_indirect`.
+    ///
+    /// The primitives without indices are not included, since they are drawn directly.
+    pub fn indirect_commands(&self) -> Vec<vk::DrawIndexedIndirectCommand> {
+        self.scene.indirect_commands(&self.nodes.list, &self.meshes.list)
+    }
+
+    /// Allocate a host visible buffer filled with `indirect_commands`, which can be used by `record_indirect`.
+    ///
+    /// The buffer can be modified to cull the primitives(etc. set `instance_count` to 0), and should be discarded by `device.vma_discard`.
+    pub fn create_indirect_buffer(&self, device: &mut VkDevice) -> VkResult<VmaBuffer> {
+
+        let commands = self.indirect_commands();
+        let buffer_size = (::std::mem::size_of::<vk::DrawIndexedIndirectCommand>() * commands.len().max(1)) as vkbytes;
+
+        let indirect_ci = BufferCI::new(buffer_size)
+            .usage(vk::BufferUsageFlags::INDIRECT_BUFFER);
+        let allocate_ci = VmaAllocationCI::new(vma::MemoryUsage::CpuToGpu, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT);
+        let indirect_allocation = device.vma.create_buffer(
+            indirect_ci.as_ref(), allocate_ci.as_ref())
+            .map_err(VkErrorKind::Vma)?;
+        let indirect_buffer = VmaBuffer::from(indirect_allocation);
+
+        let data_ptr = device.vma.map_memory(&indirect_buffer.allocation)
+            .map_err(VkErrorKind::Vma)? as *mut vk::DrawIndexedIndirectCommand;
+
+        unsafe {
+            data_ptr.copy_from_nonoverlapping(commands.as_ptr(), commands.len());
+        }
+
+        device.vma.unmap_memory(&indirect_buffer.allocation)
+            .map_err(VkErrorKind::Vma)?;
+
+        Ok(indirect_buffer)
+    }
+
+    /// Record the draw commands of this model, reading the parameters of indexed primitives from `buffer`(etc. the one created by `create_indirect_buffer`).
+    ///
+    /// If `params.material_stage` is `None`, the primitives of each mesh are submitted by one multi-draw,
+    /// otherwise each primitive is submitted separately to push its material.
+    /// `frame_index` selects the node attachments as `record_frame_command` does.
+    pub fn record_indirect(&self, recorder: &VkCmdRecorder<IGraphics>, params: &ModelRenderParams, buffer: impl VkBufferHandle, frame_index: usize) {
 
         self.meshes.record_command(recorder);
-        self.scene.record_command(recorder, self, params);
+        self.scene.record_indirect(recorder, self, params, buffer.buffer_handle(), 0, frame_index);
+    }
+
+    /// Recalculate the joint matrices of all skins from current node transforms, and write them to the copy of `frame_index`.
+    ///
+    /// Call this method every frame after the node transforms have been changed manually.
+    /// `update_animation` has updated the joint matrices already. It does nothing for models without skin.
+    pub fn update_joint_matrices(&self, frame_index: usize) {
+
+        if self.skins.skin_count() == 0 {
+            return
+        }
+
+        let global_transforms = self.scene.global_transforms(&self.nodes.list);
+        self.skins.update_joints(frame_index, &global_transforms);
+    }
+
+    /// Return the min and max corners of the axis-aligned bounding box of this model(in model space, at bind pose).
+    #[inline]
+    pub fn bounds(&self) -> (Vec3F, Vec3F) {
+        self.bounds
+    }
+
+    /// Return the center of the bounding box.
+    #[inline]
+    pub fn center(&self) -> Vec3F {
+        (self.bounds.0 + self.bounds.1) * 0.5
+    }
+
+    /// Return the radius of the bounding sphere that encloses the bounding box.
+    #[inline]
+    pub fn radius(&self) -> f32 {
+        (self.bounds.1 - self.bounds.0).magnitude() * 0.5
+    }
+
+    /// Return the names of all animations in this model. The position in list is the index used by `set_active_animation`.
+    pub fn animation_names(&self) -> Vec<Option<&str>> {
+        self.animations.iter().map(Animation::name).collect()
+    }
+
+    /// Select the animation played by `update_animation`, or `None` to stop animating.
+    pub fn set_active_animation(&mut self, animation_index: Option<usize>) {
+        debug_assert!(animation_index.map_or(true, |index| index < self.animations.len()));
+        self.active_animation = animation_index;
+    }
+
+    /// Allocate a copy of node attachments and joint matrices for each frame in flight, so that `update_animation` never writes the data still read by GPU.
+    ///
+    /// Call it once before `update_animation`, and write the descriptor sets with `nodes.node_descriptor()` and `skins.joints_descriptor()` after it.
+    /// If the copies are indexed by the `image_index` of `RenderWorkflow::render_frame`, `frame_in_flight` must be the count of swapchain images.
+    pub fn enable_animation(&mut self, device: &mut VkDevice, frame_in_flight: usize) -> VkResult<()> {
+
+        // the nodes moved by any animation, including the descendants that inherit the movement.
+        let mut animated_nodes = HashSet::new();
+        for animation in self.animations.iter() {
+            for target_node in animation.target_nodes() {
+                self.nodes.list.get(target_node).collect_subtree(&self.nodes.list, &mut animated_nodes);
+            }
+        }
+
+        let global_transforms = self.scene.global_transforms(&self.nodes.list);
+        self.nodes.allocate_frames(device, frame_in_flight, &global_transforms, &animated_nodes)?;
+        self.skins.allocate_frames(device, frame_in_flight, &global_transforms)
+    }
+
+    /// Interpolate the active animation at `time`(in seconds, looped), and write the animated node transforms to the copy of `frame_index`.
+    ///
+    /// The joint matrices of skins are also updated. It does nothing if there is no active animation.
+    pub fn update_animation(&mut self, time: f32, frame_index: usize) -> VkResult<()> {
+
+        if let Some(animation_index) = self.active_animation {
+
+            self.animations[animation_index].apply(time, &mut self.nodes.list);
+
+            let global_transforms = self.scene.global_transforms(&self.nodes.list);
+            self.nodes.update_transforms(frame_index, &global_transforms)?;
+            self.skins.update_joints(frame_index, &global_transforms);
+        }
+
+        Ok(())
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        self.materials.discard_by(device)?;
+        self.skins.discard_by(&mut device.vma)?;
+        self.meshes.discard_by(&mut device.vma)?;
+        self.nodes.discard_by(&mut device.vma)
     }
 }
 
 impl VmaResourceDiscardable for VkglTFModel {
 
+    /// Discard the buffers of this model.
+    ///
+    /// The views and samplers of material textures can only be destroyed by `VkDevice`,
+    /// so an error is returned for the model with textures, and `VkglTFModel::discard_by` should be used instead.
     fn discard_by(self, vma: &mut vma::Allocator) -> VkResult<()> {
 
+        if self.materials.has_textures() {
+            return Err(VkError::custom("VkglTFModel with textures must be discarded by VkglTFModel::discard_by."))
+        }
+
+        self.skins.discard_by(vma)?;
         self.meshes.discard_by(vma)?;
         self.nodes.discard_by(vma)
     }
@@ -3,13 +3,13 @@ use std::path::Path;
 
 use crate::gltf::scene::Scene;
 use crate::gltf::nodes::NodeAttachmentFlags;
-use crate::gltf::meshes::AttributeFlags;
+use crate::gltf::meshes::{AttributeFlags, IndexPrecision};
 use crate::gltf::asset::{GltfDocument, AssetAbstract, AssetRepository};
 use crate::gltf::asset::VkglTFModel;
 
 use crate::context::VkDevice;
 use crate::error::{VkResult, VkError, VkErrorKind};
-use crate::Mat4F;
+use crate::{Mat4F, Vec3F};
 
 
 pub struct GltfModelInfo<'a> {
@@ -19,7 +19,30 @@ pub struct GltfModelInfo<'a> {
     pub attribute: AttributeFlags,
     /// Indicate what properties will be read for Node hierarchy(etc. transform matrix).
     pub node: NodeAttachmentFlags,
-    /// A matrix that will apply to position attribute of the model.
+    /// The integer type of index buffer. `IndexPrecision::U16` falls back to 32-bit if the model has too many vertices.
+    pub index_type: IndexPrecision,
+    /// A root matrix that is multiplied into the transform of each root node of the scene at load time(etc. to flip the Y axis or scale the model).
+    ///
+    /// Since the child nodes inherit it from their parents, it applies to the whole node hierarchy.
+    /// If `node` does not contain `NodeAttachmentFlags::TRANSFORM_MATRIX`, it is baked into the vertex positions instead.
+    pub transform: Option<Mat4F>,
+}
+
+/// The information to load glTF model from the bytes in memory, which is used by `load_gltf_from_slice`.
+pub struct GltfSliceInfo<'a> {
+    /// A binary `.glb` blob or the JSON of `.gltf`(etc. embedded by `include_bytes!`).
+    pub bytes: &'a [u8],
+    /// The directory to resolve the external buffer or image files referenced by the model.
+    ///
+    /// It can be `None` if all the resources are embedded in `bytes`.
+    pub base_dir: Option<&'a Path>,
+    /// See `GltfModelInfo::attribute`.
+    pub attribute: AttributeFlags,
+    /// See `GltfModelInfo::node`.
+    pub node: NodeAttachmentFlags,
+    /// See `GltfModelInfo::index_type`.
+    pub index_type: IndexPrecision,
+    /// See `GltfModelInfo::transform`.
     pub transform: Option<Mat4F>,
 }
 
@@ -30,20 +53,249 @@ pub fn load_gltf(device: &mut VkDevice, info: GltfModelInfo) -> VkResult<VkglTFM
     let document = GltfDocument {
         doc, buffers, images,
         transform: info.transform,
+        is_transform_baked: false,
     };
 
+    load_document(device, document, info.attribute, info.node, info.index_type)
+}
+
+/// Load glTF model from the bytes in memory(etc. embedded by `include_bytes!`), instead of a file path.
+pub fn load_gltf_from_slice(device: &mut VkDevice, info: GltfSliceInfo) -> VkResult<VkglTFModel> {
+
+    let base_dir = info.base_dir;
+    let gltf::Gltf { document: doc, blob } = gltf::Gltf::from_slice(info.bytes)
+        .map_err(VkErrorKind::ParseGltf)?;
+
+    let mut buffers = Vec::new();
+    for doc_buffer in doc.buffers() {
+
+        let mut data = match doc_buffer.source() {
+            | gltf::buffer::Source::Bin => {
+                blob.clone().ok_or(VkError::custom("Missing binary chunk in glTF blob."))?
+            },
+            | gltf::buffer::Source::Uri(uri) => {
+                read_uri(uri, base_dir)?
+            },
+        };
+
+        if data.len() < doc_buffer.length() {
+            return Err(VkError::custom(format!("glTF buffer {} is shorter than its byteLength({} < {}).", doc_buffer.index(), data.len(), doc_buffer.length())))
+        }
+        // the buffer may be padded to 4 bytes.
+        data.truncate(doc_buffer.length());
+        buffers.push(gltf::buffer::Data(data));
+    }
+
+    let mut images = Vec::new();
+    for doc_image in doc.images() {
+
+        let encoded_image = match doc_image.source() {
+            | gltf::image::Source::View { view, .. } => {
+                let buffer = &buffers[view.buffer().index()].0;
+                buffer.get(view.offset()..(view.offset() + view.length()))
+                    .ok_or(VkError::custom(format!("glTF buffer view {} of image {} is out of the range of its buffer.", view.index(), doc_image.index())))?
+                    .to_vec()
+            },
+            | gltf::image::Source::Uri { uri, .. } => {
+                read_uri(uri, base_dir)?
+            },
+        };
+
+        let rgba_image = image::load_from_memory(&encoded_image)
+            .map_err(VkErrorKind::Image)?
+            .to_rgba();
+        let (width, height) = rgba_image.dimensions();
+
+        images.push(gltf::image::Data {
+            pixels: rgba_image.into_raw(),
+            format: gltf::image::Format::R8G8B8A8,
+            width, height,
+        });
+    }
+
+    let document = GltfDocument {
+        doc, buffers, images,
+        transform: info.transform,
+        is_transform_baked: false,
+    };
+
+    load_document(device, document, info.attribute, info.node, info.index_type)
+}
+
+fn load_document(device: &mut VkDevice, mut document: GltfDocument, attribute: AttributeFlags, node: NodeAttachmentFlags, index_type: IndexPrecision) -> VkResult<VkglTFModel> {
+
+    // without the node transforms in shader, the root matrix would be lost, so fall back to bake it into positions.
+    document.is_transform_baked = (node & NodeAttachmentFlags::TRANSFORM_MATRIX) != NodeAttachmentFlags::TRANSFORM_MATRIX;
+
     // Only support loading the default scene or first scene in glTF file.
     let dst_scene = document.doc.default_scene()
         .or(document.doc.scenes().next())
         .ok_or(VkError::custom("glTF Scene is missing."))?;
 
-    let scene = Scene::from_doc(dst_scene);
-    let mut asset_repo = AssetRepository::new(info.attribute, info.node)?;
+    let bounds = read_scene_bounds(&dst_scene, &document);
+
+    let root_transform = if document.is_transform_baked { None } else { document.transform };
+    let scene = Scene::from_doc(dst_scene, root_transform);
+    let mut asset_repo = AssetRepository::new(attribute, node)?;
     asset_repo.meshes.read_doc(&document, &scene)?;
     asset_repo.nodes.read_doc(&document, &scene)?;
     asset_repo.materials.read_doc(&document, &scene)?;
+    asset_repo.skins.read_doc(&document, &scene)?;
+    asset_repo.animations.read_doc(&document, &scene)?;
 
-    let result = asset_repo.allocate(device, scene)?;
+    asset_repo.meshes.set_index_precision(index_type);
+
+    let mut result = asset_repo.allocate(device, scene)?;
+    result.bounds = bounds;
     Ok(result)
 }
 
+/// Read the content referenced by `uri`, which is either a base64 data URI or a file path relative to `base_dir`.
+fn read_uri(uri: &str, base_dir: Option<&Path>) -> VkResult<Vec<u8>> {
+
+    if uri.starts_with("data:") {
+
+        let encoded = uri.splitn(2, ";base64,").nth(1)
+            .ok_or(VkError::unsupported("Non-base64 data URI in glTF"))?;
+        decode_base64(encoded)
+    } else {
+
+        let base_dir = base_dir
+            .ok_or(VkError::custom(format!("glTF references external file {}, but base directory is not specified.", uri)))?;
+        let path = base_dir.join(uri);
+
+        ::std::fs::read(&path)
+            .map_err(|_| VkError::path(path))
+    }
+}
+
+fn decode_base64(encoded: &str) -> VkResult<Vec<u8>> {
+
+    fn sextet(c: u8) -> Option<u32> {
+        match c {
+            | b'A'..=b'Z' => Some((c - b'A') as u32),
+            | b'a'..=b'z' => Some((c - b'a') as u32 + 26),
+            | b'0'..=b'9' => Some((c - b'0') as u32 + 52),
+            | b'+' | b'-' => Some(62),
+            | b'/' | b'_' => Some(63),
+            | _ => None,
+        }
+    }
+
+    let mut decoded = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut accumulator = 0_u32;
+    let mut bits = 0;
+    let mut sextet_count = 0;
+
+    for c in encoded.bytes().filter(|&c| c != b'=' && !c.is_ascii_whitespace()) {
+
+        let value = sextet(c)
+            .ok_or(VkError::custom("Invalid base64 data URI in glTF."))?;
+        sextet_count += 1;
+        accumulator = (accumulator << 6) | value;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((accumulator >> bits) as u8);
+            accumulator &= (1 << bits) - 1;
+        }
+    }
+
+    // a single trailing character can not make up a whole byte.
+    if sextet_count % 4 == 1 {
+        return Err(VkError::custom("Truncated base64 data URI in glTF."))
+    }
+
+    Ok(decoded)
+}
+
+/// Calculate the axis-aligned bounding box of all primitives in `doc_scene`, in model space.
+///
+/// The bounding box of each primitive comes from the min/max property of its position accessor.
+fn read_scene_bounds(doc_scene: &gltf::Scene, source: &GltfDocument) -> (Vec3F, Vec3F) {
+
+    fn read_node_bounds(doc_node: gltf::Node, source: &GltfDocument, parent_transform: &Mat4F, bounds: &mut Option<(Vec3F, Vec3F)>) {
+
+        let node_transform = (*parent_transform) * Mat4F::from_col_arrays(doc_node.transform().matrix());
+
+        if let Some(doc_mesh) = doc_node.mesh() {
+            for doc_primitive in doc_mesh.primitives() {
+
+                let bounding_box = doc_primitive.bounding_box();
+                let (min, max) = (Vec3F::from(bounding_box.min), Vec3F::from(bounding_box.max));
+
+                // transform the 8 corners of the bounding box.
+                for i in 0..8 {
+                    let mut corner = Vec3F::new(
+                        if i & 1 == 0 { min.x } else { max.x },
+                        if i & 2 == 0 { min.y } else { max.y },
+                        if i & 4 == 0 { min.z } else { max.z });
+
+                    // the baked transform is applied to positions before node transform.
+                    if let Some(transform) = source.baked_transform() {
+                        corner = transform.mul_point(corner);
+                    }
+                    let corner = node_transform.mul_point(corner);
+
+                    *bounds = match bounds.take() {
+                        | Some((min, max)) => Some((Vec3F::partial_min(min, corner), Vec3F::partial_max(max, corner))),
+                        | None => Some((corner, corner)),
+                    };
+                }
+            }
+        }
+
+        for child_node in doc_node.children() {
+            read_node_bounds(child_node, source, &node_transform, bounds);
+        }
+    }
+
+    let root_transform = match source.baked_transform() {
+        | Some(_) => Mat4F::identity(),
+        | None => source.transform.unwrap_or(Mat4F::identity()),
+    };
+
+    let mut bounds = None;
+    for doc_node in doc_scene.nodes() {
+        read_node_bounds(doc_node, source, &root_transform, &mut bounds);
+    }
+
+    bounds.unwrap_or((Vec3F::zero(), Vec3F::zero()))
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::decode_base64;
+
+    #[test]
+    fn decode_base64_with_padding() {
+
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("TWFu").unwrap(), b"Man");
+        assert_eq!(decode_base64("TWE=").unwrap(), b"Ma");
+        assert_eq!(decode_base64("TQ==").unwrap(), b"M");
+        // the padding is optional.
+        assert_eq!(decode_base64("TWE").unwrap(), b"Ma");
+        assert_eq!(decode_base64("TQ").unwrap(), b"M");
+    }
+
+    #[test]
+    fn decode_base64_url_safe_and_whitespace() {
+
+        assert_eq!(decode_base64("+/8=").unwrap(), vec![0xFB, 0xFF]);
+        assert_eq!(decode_base64("-_8=").unwrap(), vec![0xFB, 0xFF]);
+        assert_eq!(decode_base64("TW\nFu").unwrap(), b"Man");
+        assert_eq!(decode_base64("TW\r\nFu").unwrap(), b"Man");
+    }
+
+    #[test]
+    fn decode_base64_rejects_invalid_input() {
+
+        assert!(decode_base64("TW*u").is_err());
+        assert!(decode_base64("TWFuT").is_err());
+        assert!(decode_base64("T===").is_err());
+    }
+}
@@ -1,16 +1,18 @@
 
+use ash::vk;
 use serde_derive::Serialize;
 use std::collections::HashMap;
 
-use crate::gltf::asset::{GltfDocument, AssetAbstract};
+use crate::gltf::asset::{GltfDocument, AssetAbstract, AssetElementList};
 use crate::gltf::asset::ReferenceIndex;
 use crate::gltf::scene::Scene;
+use crate::context::VkDevice;
+use crate::texture::Texture2D;
 use crate::error::{VkResult, VkError};
 use crate::{vkfloat, vkuint};
 
 pub type MatSerializedData = Vec<u8>;
 pub type MaterialSlice<'a> = &'a [u8];
-pub type MaterialResource = MaterialAsset;
 
 const DEFAULT_MATERIAL_INDEX : usize = usize::max_value();
 const DEFAULT_MATERIAL_OFFSET: usize = 0;
@@ -71,6 +73,49 @@ pub struct MaterialAsset {
     material_count: usize,
 
     material_mapping: HashMap<ReferenceIndex, MaterialOffset>,
+
+    /// the mapping from material index to the image index of its base color texture.
+    base_color_mapping: HashMap<ReferenceIndex, ReferenceIndex>,
+    /// the RGBA8 pixels of images referenced by materials, waiting to be uploaded.
+    images: HashMap<ReferenceIndex, MaterialImage>,
+}
+
+/// The pixels of an image referenced by material.
+struct MaterialImage {
+    /// pixels in RGBA8 format.
+    pixels: Vec<u8>,
+    width : vkuint,
+    height: vkuint,
+}
+
+impl MaterialImage {
+
+    fn from_data(data: &gltf::image::Data) -> MaterialImage {
+
+        use gltf::image::Format;
+
+        let channels = match data.format {
+            | Format::R8       => 1,
+            | Format::R8G8     => 2,
+            | Format::R8G8B8   => 3,
+            | Format::R8G8B8A8 => 4,
+        };
+
+        // expand the pixels to 4 channels, and fill missing alpha channel with opaque value.
+        let pixels = if channels == 4 {
+            data.pixels.clone()
+        } else {
+            let mut pixels = Vec::with_capacity(data.pixels.len() / channels * 4);
+            for texel in data.pixels.chunks(channels) {
+                let mut rgba = [0, 0, 0, 255_u8];
+                rgba[..channels].copy_from_slice(texel);
+                pixels.extend_from_slice(&rgba);
+            }
+            pixels
+        };
+
+        MaterialImage { pixels, width: data.width, height: data.height }
+    }
 }
 
 impl MaterialAsset {
@@ -85,19 +130,32 @@ impl MaterialAsset {
         material_mapping.insert(DEFAULT_MATERIAL_INDEX, DEFAULT_MATERIAL_OFFSET);
         let material_count = 1;
 
-        let result = MaterialAsset { data_content, material_count, material_mapping };
+        let result = MaterialAsset {
+            base_color_mapping: HashMap::new(),
+            images: HashMap::new(),
+            data_content, material_count, material_mapping,
+        };
         Ok(result)
     }
 
-    pub const fn material_size(&self) -> vkuint {
-        MATERIAL_SIZE as vkuint
-    }
+    /// Upload the images referenced by materials to textures.
+    pub fn allocate(self, device: &mut VkDevice) -> VkResult<MaterialResource> {
 
-    pub fn get_material_serialized(&self, material_index: &Option<ReferenceIndex>) -> MaterialSlice {
+        let mut textures = AssetElementList::default();
 
-        let offset = self.material_mapping.get(&material_index.unwrap_or(DEFAULT_MATERIAL_INDEX)).cloned()
-            .unwrap_or(DEFAULT_MATERIAL_OFFSET);
-        &self.data_content[offset..(offset + MATERIAL_SIZE)]
+        for (image_index, image) in self.images.into_iter() {
+            // base color textures are authored in sRGB color space.
+            let texture = Texture2D::from_base_level(device, &image.pixels, image.width, image.height, vk::Format::R8G8B8A8_SRGB)?;
+            textures.push(image_index, texture);
+        }
+
+        let result = MaterialResource {
+            data_content: self.data_content,
+            material_mapping: self.material_mapping,
+            base_color_mapping: self.base_color_mapping,
+            textures,
+        };
+        Ok(result)
     }
 }
 
@@ -110,6 +168,18 @@ impl AssetAbstract for MaterialAsset {
 
             if let Some(json_index) = doc_material.index() {
 
+                if let Some(texture_info) = doc_material.pbr_metallic_roughness().base_color_texture() {
+
+                    let image_index = texture_info.texture().source().index();
+                    self.base_color_mapping.insert(json_index, image_index);
+
+                    if !self.images.contains_key(&image_index) {
+                        let image_data = source.images.get(image_index)
+                            .ok_or(VkError::custom("glTF image referenced by material is missing."))?;
+                        self.images.insert(image_index, MaterialImage::from_data(image_data));
+                    }
+                }
+
                 let material = MaterialData::from(doc_material);
                 let material_serialized = material.serialize()?;
                 self.data_content.extend(material_serialized);
@@ -125,3 +195,49 @@ impl AssetAbstract for MaterialAsset {
     }
 }
 // ------------------------------------------------------------------------------------
+
+
+// ------------------------------------------------------------------------------------
+pub struct MaterialResource {
+
+    data_content: MatSerializedData,
+    material_mapping: HashMap<ReferenceIndex, MaterialOffset>,
+
+    base_color_mapping: HashMap<ReferenceIndex, ReferenceIndex>,
+    textures: AssetElementList<Texture2D>,
+}
+
+impl MaterialResource {
+
+    pub const fn material_size(&self) -> vkuint {
+        MATERIAL_SIZE as vkuint
+    }
+
+    pub fn get_material_serialized(&self, material_index: &Option<ReferenceIndex>) -> MaterialSlice {
+
+        let offset = self.material_mapping.get(&material_index.unwrap_or(DEFAULT_MATERIAL_INDEX)).cloned()
+            .unwrap_or(DEFAULT_MATERIAL_OFFSET);
+        &self.data_content[offset..(offset + MATERIAL_SIZE)]
+    }
+
+    /// Return the base color texture of the material at `material_index`, or `None` if this material does not use texture.
+    pub fn base_color_texture(&self, material_index: ReferenceIndex) -> Option<&Texture2D> {
+
+        self.base_color_mapping.get(&material_index)
+            .map(|&image_index| self.textures.get(image_index))
+    }
+
+    /// Return whether any texture is loaded for the materials.
+    pub fn has_textures(&self) -> bool {
+        self.textures.is_empty() == false
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        for texture in self.textures.into_iter() {
+            texture.discard_by(device)?;
+        }
+        Ok(())
+    }
+}
+// ------------------------------------------------------------------------------------
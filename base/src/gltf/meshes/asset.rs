@@ -5,7 +5,7 @@ use crate::gltf::asset::{GltfDocument, AssetAbstract, AssetElementList};
 use crate::gltf::scene::Scene;
 use crate::gltf::meshes::mesh::Mesh;
 use crate::gltf::meshes::attributes::{AttributesData, AttributeFlags};
-use crate::gltf::meshes::indices::IndicesData;
+use crate::gltf::meshes::indices::{IndicesData, IndexPrecision};
 
 use crate::ci::buffer::BufferCI;
 use crate::ci::vma::{VmaAllocationCI, VmaBuffer};
@@ -24,6 +24,7 @@ pub struct MeshAsset {
 
     attributes: AttributesData,
     indices: IndicesData,
+    index_precision: IndexPrecision,
 
     meshes: AssetElementList<Mesh>,
 }
@@ -40,6 +41,7 @@ pub struct MeshResource {
 
     vertices: VmaBuffer,
     indices: Option<VmaBuffer>,
+    index_type: vk::IndexType,
 
     pub vertex_input: VertexInputSCI,
 }
@@ -52,6 +54,7 @@ impl TryFrom<AttributeFlags> for MeshAsset {
         let result = MeshAsset {
             attributes: AttributesData::try_from(flag)?,
             indices: Default::default(),
+            index_precision: IndexPrecision::default(),
             meshes : Default::default(),
         };
         Ok(result)
@@ -77,7 +80,16 @@ impl AssetAbstract for MeshAsset {
 
 impl MeshAsset {
 
-    pub fn allocate(self, device: &mut VkDevice) -> VkResult<MeshResource> {
+    /// Set the requested precision of index buffer. See `IndexPrecision` for detail.
+    #[inline]
+    pub fn set_index_precision(&mut self, precision: IndexPrecision) {
+        self.index_precision = precision;
+    }
+
+    pub fn allocate(mut self, device: &mut VkDevice) -> VkResult<MeshResource> {
+
+        // the final precision depends on the number of vertices, so resolve it after all indices have been read.
+        self.indices.set_precision(self.index_precision);
 
         // allocate staging buffer.
         let staging_block = self.allocate_staging(&mut device.vma)?;
@@ -93,6 +105,7 @@ impl MeshAsset {
         let result = MeshResource {
             vertices: mesh_block.vertices,
             indices: mesh_block.indices,
+            index_type: self.indices.precision().index_type(),
             list: self.meshes,
             vertex_input: self.attributes.input_descriptions(),
         };
@@ -200,7 +213,7 @@ impl MeshAsset {
             size      : staging.vertices.info.get_size() as _,
         };
         // copy vertices data to target buffer.
-        cmd_recorder.copy_buf2buf(staging.vertices.handle, meshes.vertices.handle, &[vertex_copy_region]);
+        cmd_recorder.copy_buf2buf(&staging.vertices, &meshes.vertices, &[vertex_copy_region]);
 
         // copy index data to target buffer.
         if let Some(ref staging_index) = staging.indices {
@@ -210,7 +223,7 @@ impl MeshAsset {
                     dst_offset: 0,
                     size      : staging_index.info.get_size() as _,
                 };
-                cmd_recorder.copy_buf2buf(staging_index.handle, meshes_indices.handle, &[index_copy_region]);
+                cmd_recorder.copy_buf2buf(staging_index, meshes_indices, &[index_copy_region]);
             }
         }
 
@@ -246,7 +259,7 @@ impl MeshResource {
         recorder.bind_vertex_buffers(0, &[self.vertices.handle], &[0]);
 
         if let Some(ref index_buffer) = self.indices {
-            recorder.bind_index_buffer(index_buffer.handle, vk::IndexType::UINT32, 0);
+            recorder.bind_index_buffer(index_buffer, self.index_type, 0);
         }
     }
 
@@ -77,6 +77,16 @@ impl AttributeFlags {
     pub const ATTR_PTE0: AttributeFlags = AttributeFlags(0b1001);
     // POSITION, NORMAL, TEXCOORD_0.
     pub const ATTR_PNTE0: AttributeFlags = AttributeFlags(0b1011);
+    // POSITION, NORMAL, TEXCOORD_0, TANGENT.
+    pub const ATTR_PNTE0TA: AttributeFlags = AttributeFlags(0b1111);
+    // POSITION, COLOR_0.
+    pub const ATTR_PC0: AttributeFlags = AttributeFlags(0b100001);
+    // POSITION, NORMAL, COLOR_0.
+    pub const ATTR_PNC0: AttributeFlags = AttributeFlags(0b100011);
+    // POSITION, NORMAL, TEXCOORD_0, COLOR_0.
+    pub const ATTR_PNTE0C0: AttributeFlags = AttributeFlags(0b101011);
+    // POSITION, NORMAL, TEXCOORD_0, JOINTS_0, WEIGHTS_0.
+    pub const ATTR_PNTE0JW: AttributeFlags = AttributeFlags(0b11001011);
     // POSITION, NORMAL, TANGENT, TEXCOORD_0, TEXCOORD_1, COLOR_0, JOINTS_0, WEIGHTS_0.
     pub const ATTR_ALL: AttributeFlags = AttributeFlags(0b11111111);
 
@@ -87,6 +97,11 @@ impl AttributeFlags {
             | AttributeFlags::ATTR_PN    => Some(size_of::<Attr_PN>()    as _),
             | AttributeFlags::ATTR_PTE0  => Some(size_of::<Attr_PTe0>()  as _),
             | AttributeFlags::ATTR_PNTE0 => Some(size_of::<Attr_PNTe0>() as _),
+            | AttributeFlags::ATTR_PNTE0TA => Some(size_of::<Attr_PNTe0Ta>() as _),
+            | AttributeFlags::ATTR_PC0     => Some(size_of::<Attr_PC0>()     as _),
+            | AttributeFlags::ATTR_PNC0    => Some(size_of::<Attr_PNC0>()    as _),
+            | AttributeFlags::ATTR_PNTE0C0 => Some(size_of::<Attr_PNTe0C0>() as _),
+            | AttributeFlags::ATTR_PNTE0JW => Some(size_of::<Attr_PNTe0JW>() as _),
             | AttributeFlags::ATTR_ALL   => Some(size_of::<Attr_All>()   as _),
             | _ => None,
         }
@@ -110,6 +125,26 @@ impl AttributeFlags {
                 let attributes = Box::new(Attr_PNTe0::default());
                 Some(attributes as Box<dyn VertexAttributes>)
             },
+            | AttributeFlags::ATTR_PNTE0TA => {
+                let attributes = Box::new(Attr_PNTe0Ta::default());
+                Some(attributes as Box<dyn VertexAttributes>)
+            },
+            | AttributeFlags::ATTR_PC0 => {
+                let attributes = Box::new(Attr_PC0::default());
+                Some(attributes as Box<dyn VertexAttributes>)
+            },
+            | AttributeFlags::ATTR_PNC0 => {
+                let attributes = Box::new(Attr_PNC0::default());
+                Some(attributes as Box<dyn VertexAttributes>)
+            },
+            | AttributeFlags::ATTR_PNTE0C0 => {
+                let attributes = Box::new(Attr_PNTe0C0::default());
+                Some(attributes as Box<dyn VertexAttributes>)
+            },
+            | AttributeFlags::ATTR_PNTE0JW => {
+                let attributes = Box::new(Attr_PNTe0JW::default());
+                Some(attributes as Box<dyn VertexAttributes>)
+            },
             | AttributeFlags::ATTR_ALL => {
                 let attributes = Box::new(Attr_All::default());
                 Some(attributes as Box<dyn VertexAttributes>)
@@ -181,7 +216,8 @@ macro_rules! attribute_default {
     (tangents)   => { Vec4F::zero() };
     (texcoord_0) => { Vec2F::zero() };
     (texcoord_1) => { Vec2F::zero() };
-    (color_0)    => { Vec4F::zero() };
+    // opaque white, so that the vertex color has no effect if it is missing in glTF file.
+    (color_0)    => { Vec4F::one() };
     (joints_0)   => { Vec4U::zero() };
     (weights_0)  => { Vec4F::zero() };
 }
@@ -193,7 +229,8 @@ macro_rules! attribute_format {
     (texcoord_0) => { vk::Format::R32G32_SFLOAT };
     (texcoord_1) => { vk::Format::R32G32_SFLOAT };
     (color_0)    => { vk::Format::R32G32B32A32_SFLOAT };
-    (joints_0)   => { vk::Format::R16G16B16A16_UNORM };
+    // joint indices are read as integer(`uvec4`) in shader.
+    (joints_0)   => { vk::Format::R16G16B16A16_UINT };
     (weights_0)  => { vk::Format::R32G32B32A32_SFLOAT };
 }
 
@@ -202,18 +239,20 @@ macro_rules! read_attribute {
 
         if let Some(pos_iter) = $reader.read_positions() {
 
+            // the model transform is baked into positions if it is not applied by node transforms.
+            let read_position = |pos: [f32; 3]| {
+                let position = Vec3F::from(pos);
+                $source.baked_transform().map_or(position, |transform| transform.mul_point(position))
+            };
+
             if $target.data.len() == $origin_length {
                 let vertex_iter = pos_iter.map(|pos| {
-                    let mut position = Vec3F::from(pos);
-                    if let Some(ref transform) = $source.transform {
-                        position = transform.mul_point(position);
-                    }
-                    $VertexType { position, ..Default::default() }
+                    $VertexType { position: read_position(pos), ..Default::default() }
                 });
                 $target.data.extend(vertex_iter);
             } else {
                 for (i, pos) in pos_iter.enumerate() {
-                    $target.data[i + $origin_length].position = Vec3F::from(pos);
+                    $target.data[i + $origin_length].position = read_position(pos);
                 }
             }
         }
@@ -341,6 +380,30 @@ macro_rules! read_attribute {
     };
 }
 
+/// Fill the attribute that is requested but missing in glTF file, after all attributes have been read.
+macro_rules! complete_attribute {
+    ($target:ident, $reader:ident, $origin_length:ident, tangents) => {
+
+        if $reader.read_tangents().is_none() {
+
+            let vertices = &mut $target.data[$origin_length..];
+
+            let positions: Vec<Vec3F> = vertices.iter().map(|v| v.position).collect();
+            let normals  : Vec<Vec3F> = vertices.iter().map(|v| v.normal).collect();
+            let texcoords: Vec<Vec2F> = vertices.iter().map(|v| v.texcoord_0).collect();
+            let indices: Vec<u32> = $reader.read_indices()
+                .map(|indices| indices.into_u32().collect())
+                .unwrap_or_else(|| (0..vertices.len() as u32).collect());
+
+            let tangents = generate_tangents(&positions, &normals, &texcoords, &indices);
+            for (vertex, tangent) in vertices.iter_mut().zip(tangents.into_iter()) {
+                vertex.tangents = tangent;
+            }
+        }
+    };
+    ($target:ident, $reader:ident, $origin_length:ident, $attribute:ident) => {};
+}
+
 macro_rules! define_attributes {
     ($name_attributes:ident, $name_vertex:ident, {
         $(
@@ -385,6 +448,10 @@ macro_rules! define_attributes {
                     read_attribute!(self, reader, start_vertex_index, $name_vertex, source, $attribute);
                 )*
 
+                $(
+                    complete_attribute!(self, reader, start_vertex_index, $attribute);
+                )*
+
                 AttributeExtendInfo {
                     first_vertex: start_vertex_index,
                     vertex_count: self.data.len() - start_vertex_index,
@@ -442,6 +509,102 @@ define_attributes!(Attr_PTe0, AttrVertexPTe0, { position, texcoord_0, });
 /// glTF Primitive with position, normal and texcoord_0 attributes.
 define_attributes!(Attr_PNTe0, AttrVertex_PNTe0, { position, normal, texcoord_0, });
 
+/// glTF Primitive with position, normal, texcoord_0 and tangent attributes.
+///
+/// The tangent is appended after the other attributes, so the attribute locations are:
+/// ```ignore
+/// layout (location = 0) in vec3 inPos;
+/// layout (location = 1) in vec3 inNormal;
+/// layout (location = 2) in vec2 inUV;
+/// layout (location = 3) in vec4 inTangent; // w component is the handedness of bitangent.
+/// ```
+///
+/// Tangents are generated from positions, normals and texcoords if the glTF file does not provide them.
+define_attributes!(Attr_PNTe0Ta, AttrVertex_PNTe0Ta, { position, normal, texcoord_0, tangents, });
+
+/// glTF Primitive with position and color_0 attributes.
+///
+/// The color is read as `vec4`(normalized from `u8`/`u16` if needed), and is opaque white if missing.
+define_attributes!(Attr_PC0, AttrVertex_PC0, { position, color_0, });
+
+/// glTF Primitive with position, normal and color_0 attributes.
+define_attributes!(Attr_PNC0, AttrVertex_PNC0, { position, normal, color_0, });
+
+/// glTF Primitive with position, normal, texcoord_0 and color_0 attributes.
+define_attributes!(Attr_PNTe0C0, AttrVertex_PNTe0C0, { position, normal, texcoord_0, color_0, });
+
+/// glTF Primitive with position, normal, texcoord_0, joints_0 and weights_0 attributes, used for skinned mesh.
+///
+/// The attribute locations are:
+/// ```ignore
+/// layout (location = 0) in vec3 inPos;
+/// layout (location = 1) in vec3 inNormal;
+/// layout (location = 2) in vec2 inUV;
+/// layout (location = 3) in uvec4 inJointIndices; // index into the joint matrices of `VkglTFModel::skins`.
+/// layout (location = 4) in vec4 inJointWeights;
+/// ```
+define_attributes!(Attr_PNTe0JW, AttrVertex_PNTe0JW, { position, normal, texcoord_0, joints_0, weights_0, });
+
 /// glTF Primitive with all attributes.
+///
+/// The attribute locations are:
+/// ```ignore
+/// layout (location = 0) in vec3 inPos;
+/// layout (location = 1) in vec3 inNormal;
+/// layout (location = 2) in vec4 inTangent;
+/// layout (location = 3) in vec2 inUV0;
+/// layout (location = 4) in vec2 inUV1;
+/// layout (location = 5) in vec4 inColor;
+/// layout (location = 6) in uvec4 inJointIndices; // `R16G16B16A16_UINT`, which was `R16G16B16A16_UNORM`(`vec4`) before skinning was supported.
+/// layout (location = 7) in vec4 inJointWeights;
+/// ```
 define_attributes!(Attr_All, AttrVertex_Ultimate, { position, normal, tangents, texcoord_0, texcoord_1, color_0, joints_0, weights_0, });
+
+/// Generate per-vertex tangent(with handedness in w component) for triangle list.
+fn generate_tangents(positions: &[Vec3F], normals: &[Vec3F], texcoords: &[Vec2F], indices: &[u32]) -> Vec<Vec4F> {
+
+    let mut tangents   = vec![Vec3F::zero(); positions.len()];
+    let mut bitangents = vec![Vec3F::zero(); positions.len()];
+
+    // accumulate the tangent of each triangle to its vertices.
+    for triangle in indices.chunks(3).filter(|t| t.len() == 3) {
+
+        let (i0, i1, i2) = (triangle[0] as usize, triangle[1] as usize, triangle[2] as usize);
+
+        let edge1 = positions[i1] - positions[i0];
+        let edge2 = positions[i2] - positions[i0];
+        let delta_uv1 = texcoords[i1] - texcoords[i0];
+        let delta_uv2 = texcoords[i2] - texcoords[i0];
+
+        let det = delta_uv1.x * delta_uv2.y - delta_uv2.x * delta_uv1.y;
+        if det.abs() <= ::std::f32::EPSILON {
+            // degenerate texture coordinates.
+            continue
+        }
+
+        let r = 1.0 / det;
+        let tangent   = (edge1 * delta_uv2.y - edge2 * delta_uv1.y) * r;
+        let bitangent = (edge2 * delta_uv1.x - edge1 * delta_uv2.x) * r;
+
+        for &i in [i0, i1, i2].iter() {
+            tangents[i]   += tangent;
+            bitangents[i] += bitangent;
+        }
+    }
+
+    // orthogonalize the tangent to normal(Gram-Schmidt) and calculate the handedness.
+    tangents.into_iter().zip(bitangents.into_iter()).zip(normals.iter())
+        .map(|((tangent, bitangent), normal)| {
+
+            let orthogonal = tangent - *normal * normal.dot(tangent);
+            if orthogonal.magnitude_squared() <= ::std::f32::EPSILON {
+                // fallback to an arbitrary tangent.
+                return Vec4F::new(1.0, 0.0, 0.0, 1.0)
+            }
+
+            let orthogonal = orthogonal.normalized();
+            let handedness = if normal.cross(orthogonal).dot(bitangent) < 0.0 { -1.0 } else { 1.0 };
+            Vec4F::new(orthogonal.x, orthogonal.y, orthogonal.z, handedness)
+        }).collect()
+}
 // --------------------------------------------------------------------------------------
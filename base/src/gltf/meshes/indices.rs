@@ -2,12 +2,53 @@
 use crate::gltf::asset::GltfDocument;
 use crate::error::{VkResult, VkError};
 
+use ash::vk;
+
 use crate::{vkuint, vkbytes, vkptr};
 
+/// The integer type of index buffer used by glTF model.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum IndexPrecision {
+    /// 16-bit indices, which halves the memory of index buffer.
+    ///
+    /// It is promoted to `U32` automatically if the model contains too many vertices.
+    U16,
+    /// 32-bit indices.
+    U32,
+}
+
+impl Default for IndexPrecision {
+
+    fn default() -> IndexPrecision {
+        IndexPrecision::U32
+    }
+}
+
+impl IndexPrecision {
+
+    #[inline]
+    pub fn index_type(&self) -> vk::IndexType {
+        match self {
+            | IndexPrecision::U16 => vk::IndexType::UINT16,
+            | IndexPrecision::U32 => vk::IndexType::UINT32,
+        }
+    }
+
+    #[inline]
+    fn element_size(&self) -> usize {
+        match self {
+            | IndexPrecision::U16 => ::std::mem::size_of::<u16>(),
+            | IndexPrecision::U32 => ::std::mem::size_of::<u32>(),
+        }
+    }
+}
+
 pub struct IndicesData {
 
     start_index: u32,
     data_content: Vec<vkuint>,
+
+    precision: IndexPrecision,
 }
 
 pub struct IndicesExtendInfo {
@@ -42,10 +83,35 @@ impl IndicesData {
         Ok(result)
     }
 
+    /// Set the index precision of index buffer.
+    ///
+    /// Must be called after all indices have been read, since `IndexPrecision::U16` is promoted to `IndexPrecision::U32` if any index does not fit in 16 bits.
+    pub fn set_precision(&mut self, request: IndexPrecision) {
+
+        self.precision = match request {
+            | IndexPrecision::U16 => {
+                // the maximum value is reserved for primitive restart.
+                let index_max = self.data_content.iter().cloned().max().unwrap_or(0);
+                if index_max < (u16::max_value() as vkuint) {
+                    IndexPrecision::U16
+                } else {
+                    println!("[Info] glTF model contains {} vertices which exceeds the range of 16-bit indices, use 32-bit indices instead.", index_max + 1);
+                    IndexPrecision::U32
+                }
+            },
+            | IndexPrecision::U32 => IndexPrecision::U32,
+        };
+    }
+
+    #[inline]
+    pub fn precision(&self) -> IndexPrecision {
+        self.precision
+    }
+
     pub fn buffer_size_estimated(&self) -> Option<vkbytes> {
 
         if self.start_index > 0 {
-            let indices_size = (self.data_content.len() * ::std::mem::size_of::<vkuint>()) as vkbytes;
+            let indices_size = (self.data_content.len() * self.precision.element_size()) as vkbytes;
             Some(indices_size)
         } else {
             None
@@ -54,8 +120,18 @@ impl IndicesData {
 
     pub fn map_data(&self, memory_ptr: vkptr) {
 
-        unsafe {
-            (memory_ptr as vkptr<vkuint>).copy_from(self.data_content.as_ptr(), self.data_content.len());
+        match self.precision {
+            | IndexPrecision::U16 => {
+                let indices: Vec<u16> = self.data_content.iter().map(|&index| index as u16).collect();
+                unsafe {
+                    (memory_ptr as vkptr<u16>).copy_from(indices.as_ptr(), indices.len());
+                }
+            },
+            | IndexPrecision::U32 => {
+                unsafe {
+                    (memory_ptr as vkptr<vkuint>).copy_from(self.data_content.as_ptr(), self.data_content.len());
+                }
+            },
         }
     }
 }
@@ -66,6 +142,7 @@ impl Default for IndicesData {
         IndicesData {
             start_index: 0,
             data_content: Vec::new(),
+            precision: IndexPrecision::default(),
         }
     }
 }
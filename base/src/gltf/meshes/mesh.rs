@@ -1,4 +1,6 @@
 
+use ash::vk;
+
 use crate::gltf::asset::GltfDocument;
 use crate::gltf::asset::{VkglTFModel, ModelRenderParams};
 
@@ -6,9 +8,10 @@ use crate::gltf::meshes::primitive::Primitive;
 use crate::gltf::meshes::attributes::AttributesData;
 use crate::gltf::meshes::indices::IndicesData;
 
-use crate::command::{VkCmdRecorder, IGraphics};
+use crate::command::{VkCmdRecorder, IGraphics, CmdGraphicsApi};
 
 use crate::error::VkResult;
+use crate::{vkuint, vkbytes};
 
 // --------------------------------------------------------------------------------------
 /// A wrapper class for mesh level in glTF, containing the render parameters read from glTF file.
@@ -42,5 +45,41 @@ impl Mesh {
             primitive.record_command(recorder, model, params);
         }
     }
+
+    /// Append the indirect draw parameters of the indexed primitives in this mesh to `commands`.
+    pub(crate) fn indirect_commands(&self, commands: &mut Vec<vk::DrawIndexedIndirectCommand>) {
+        commands.extend(self.primitives.iter().filter_map(Primitive::indirect_command));
+    }
+
+    /// Record the draws of this mesh, reading the parameters of indexed primitives from `buffer` at `offset`.
+    ///
+    /// `offset` is advanced past the commands of this mesh, in the same order as `indirect_commands`.
+    pub(crate) fn record_indirect(&self, recorder: &VkCmdRecorder<IGraphics>, model: &VkglTFModel, params: &ModelRenderParams, buffer: vk::Buffer, offset: &mut vkbytes) {
+
+        let stride = ::std::mem::size_of::<vk::DrawIndexedIndirectCommand>() as vkuint;
+
+        if params.material_stage.is_some() {
+            // the material is pushed per primitive, so each primitive must be drawn by its own command.
+            for primitive in self.primitives.iter() {
+                if primitive.record_direct_part(recorder, model, params) {
+                    recorder.draw_indexed_indirect(buffer, *offset, 1, stride);
+                    *offset += stride as vkbytes;
+                }
+            }
+        } else {
+            // draw all the indexed primitives of this mesh with one multi-draw.
+            let mut draw_count = 0;
+            for primitive in self.primitives.iter() {
+                if primitive.record_direct_part(recorder, model, params) {
+                    draw_count += 1;
+                }
+            }
+
+            if draw_count > 0 {
+                recorder.draw_indexed_indirect(buffer, *offset, draw_count, stride);
+                *offset += (draw_count * stride) as vkbytes;
+            }
+        }
+    }
 }
 // --------------------------------------------------------------------------------------
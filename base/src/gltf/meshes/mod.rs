@@ -1,6 +1,8 @@
 
 pub use self::asset::{MeshAsset, MeshResource};
 pub use self::attributes::AttributeFlags;
+pub use self::indices::IndexPrecision;
+pub(crate) use self::mesh::Mesh;
 
 mod asset;
 mod attributes;
@@ -1,4 +1,6 @@
 
+use ash::vk;
+
 use crate::gltf::asset::{GltfDocument, ReferenceIndex};
 use crate::gltf::asset::{VkglTFModel, ModelRenderParams};
 
@@ -76,6 +78,44 @@ impl Primitive {
             },
         }
     }
+
+    /// Return the indirect draw parameters of this primitive, or `None` if it is not drawn with indices.
+    pub(crate) fn indirect_command(&self) -> Option<vk::DrawIndexedIndirectCommand> {
+
+        match self.params {
+            | RenderParams::DrawArray { .. } => None,
+            | RenderParams::DrawIndex { index_count, first_index } => {
+                let command = vk::DrawIndexedIndirectCommand {
+                    index_count,
+                    instance_count: 1,
+                    first_index,
+                    vertex_offset : 0,
+                    first_instance: 0,
+                };
+                Some(command)
+            },
+        }
+    }
+
+    /// Push the material of this primitive if `params` requires it, and draw it directly if it is not drawn with indices.
+    ///
+    /// Return `true` if this primitive should be drawn by indirect command instead.
+    pub(crate) fn record_direct_part(&self, recorder: &VkCmdRecorder<IGraphics>, model: &VkglTFModel, params: &ModelRenderParams) -> bool {
+
+        if let Some(material_stage) = params.material_stage {
+
+            let material_data = model.materials.get_material_serialized(&self.material);
+            recorder.push_constants(params.pipeline_layout, material_stage, 0, material_data);
+        }
+
+        match self.params {
+            | RenderParams::DrawArray { vertex_count, first_vertex } => {
+                recorder.draw(vertex_count, 1, first_vertex, 0);
+                false
+            },
+            | RenderParams::DrawIndex { .. } => true,
+        }
+    }
 }
 // --------------------------------------------------------------------------------------
 
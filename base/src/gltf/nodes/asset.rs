@@ -11,10 +11,11 @@ use crate::ci::vma::VmaBuffer;
 use crate::context::{VkDevice, VmaResourceDiscardable};
 use crate::command::CmdTransferApi;
 use crate::error::{VkResult, VkError, VkErrorKind};
-use crate::{vkbytes, vkptr};
+use crate::{vkuint, vkbytes, vkptr, Mat4F};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
+use std::ptr;
 
 pub struct NodeAsset {
 
@@ -30,6 +31,19 @@ pub struct NodeResource {
     pub(crate) attachment_mapping: HashMap<ReferenceIndex, usize>,
 
     buffer: VmaBuffer,
+    /// the size of the attachments of all nodes, which is the stride between two per-frame copies.
+    block_size: vkbytes,
+    /// the per-frame copies of node attachments, which are allocated for animation.
+    frames: Option<NodeFrames>,
+}
+
+/// The persistently mapped copies of node attachments, one for each frame in flight.
+struct NodeFrames {
+
+    buffer: VmaBuffer,
+    frame_count: usize,
+    /// the json index and attachment position of the nodes which may be moved by animations.
+    animated_slots: Vec<(ReferenceIndex, usize)>,
 }
 
 impl TryFrom<NodeAttachmentFlags> for NodeAsset {
@@ -119,7 +133,7 @@ impl NodeAsset {
             };
 
             cmd_recorder.begin_record()?
-                .copy_buf2buf(staging_buffer.handle, attachments_buffer.handle, &[copy_region])
+                .copy_buf2buf(&staging_buffer, &attachments_buffer, &[copy_region])
                 .end_record()?;
 
             device.flush_transfer(cmd_recorder)?;
@@ -135,6 +149,8 @@ impl NodeAsset {
             buffer: attachments_buffer,
             attachment_mapping: self.attachments.attachments_mapping,
             attachment_size_aligned,
+            block_size: request_attachments_size,
+            frames: None,
         };
         Ok(result)
     }
@@ -142,14 +158,115 @@ impl NodeAsset {
 
 impl NodeResource {
 
+    /// Return the descriptor of node attachments, which is bound as `UNIFORM_BUFFER_DYNAMIC`.
+    ///
+    /// If `enable_animation` has been called, the descriptor refers to the per-frame copies,
+    /// so the descriptor set must be written after that.
     pub fn node_descriptor(&self) -> vk::DescriptorBufferInfo {
 
+        let buffer = self.frames.as_ref()
+            .map_or(self.buffer.handle, |frames| frames.buffer.handle);
+
         vk::DescriptorBufferInfo {
-            buffer: self.buffer.handle,
+            buffer,
             offset: 0,
             range : self.attachment_size_aligned,
         }
     }
+
+    /// The dynamic offset of the attachment of node at `node_index`, in the copy of `frame_index`.
+    ///
+    /// `frame_index` is ignored if the per-frame copies have not been allocated.
+    pub(crate) fn dynamic_offset(&self, node_index: ReferenceIndex, frame_index: usize) -> vkuint {
+
+        let attachment_position = self.attachment_mapping.get(&node_index).cloned().unwrap();
+        let frame_offset = match self.frames {
+            | Some(ref frames) => {
+                debug_assert!(frame_index < frames.frame_count, "Frame index {} is out of the {} node attachment copies!", frame_index, frames.frame_count);
+                self.block_size * (frame_index as vkbytes)
+            },
+            | None => 0,
+        };
+
+        (frame_offset + self.attachment_size_aligned * (attachment_position as vkbytes)) as vkuint
+    }
+
+    /// Allocate a persistently mapped copy of node attachments for each frame in flight, seeded with `global_transforms`.
+    ///
+    /// Only the attachments of nodes in `animated_nodes` are rewritten by `update_transforms` later.
+    pub(crate) fn allocate_frames(&mut self, device: &mut VkDevice, frame_in_flight: usize, global_transforms: &HashMap<ReferenceIndex, Mat4F>, animated_nodes: &HashSet<ReferenceIndex>) -> VkResult<()> {
+
+        use crate::ci::buffer::BufferCI;
+        use crate::ci::vma::VmaAllocationCI;
+
+        debug_assert!(frame_in_flight > 0, "Frames in flight must be greater than 0!");
+
+        if self.frames.is_some() {
+            return Err(VkError::custom("The per-frame node attachments have been allocated."))
+        }
+
+        let frames_ci = BufferCI::new(self.block_size * (frame_in_flight as vkbytes))
+            .usage(vk::BufferUsageFlags::UNIFORM_BUFFER);
+        let allocate_ci = VmaAllocationCI::new(vma::MemoryUsage::CpuToGpu, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT)
+            .flags(vma::AllocationCreateFlags::MAPPED);
+        let frames_allocation = device.vma.create_buffer(
+            frames_ci.as_ref(), allocate_ci.as_ref())
+            .map_err(VkErrorKind::Vma)?;
+
+        let animated_slots = self.attachment_mapping.iter()
+            .filter(|(node_index, _)| animated_nodes.contains(*node_index))
+            .map(|(&node_index, &attachment_position)| (node_index, attachment_position))
+            .collect();
+
+        let frames = NodeFrames {
+            buffer: VmaBuffer::from(frames_allocation),
+            frame_count: frame_in_flight,
+            animated_slots,
+        };
+
+        // every attachment element is written once here, so that the nodes never animated keep their static transforms.
+        for frame_index in 0..frame_in_flight {
+            for (node_index, &attachment_position) in self.attachment_mapping.iter() {
+                if let Some(transform) = global_transforms.get(node_index) {
+                    self.write_transform(&frames, frame_index, attachment_position, transform);
+                }
+            }
+        }
+
+        self.frames = Some(frames);
+        Ok(())
+    }
+
+    /// Rewrite the transform matrices of animated nodes in the copy of `frame_index` with `global_transforms`(indexed by json index of node).
+    ///
+    /// Only write the copy that is not read by GPU anymore(etc. the copy of `image_index` in `RenderWorkflow::render_frame`).
+    pub(crate) fn update_transforms(&self, frame_index: usize, global_transforms: &HashMap<ReferenceIndex, Mat4F>) -> VkResult<()> {
+
+        let frames = self.frames.as_ref()
+            .ok_or(VkError::custom("Call `VkglTFModel::enable_animation` before updating the animation."))?;
+        debug_assert!(frame_index < frames.frame_count, "Frame index {} is out of the {} node attachment copies!", frame_index, frames.frame_count);
+
+        for &(node_index, attachment_position) in frames.animated_slots.iter() {
+            if let Some(transform) = global_transforms.get(&node_index) {
+                self.write_transform(frames, frame_index, attachment_position, transform);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The transform matrix is the first member of each attachment element.
+    fn write_transform(&self, frames: &NodeFrames, frame_index: usize, attachment_position: usize, transform: &Mat4F) {
+
+        unsafe {
+            let data_ptr = frames.buffer.info.get_mapped_data() as vkptr<u8>;
+            debug_assert_ne!(data_ptr, ptr::null_mut());
+
+            let element_offset = self.block_size * (frame_index as vkbytes) + self.attachment_size_aligned * (attachment_position as vkbytes);
+            let element_ptr = data_ptr.offset(element_offset as isize) as vkptr<Mat4F>;
+            element_ptr.write_unaligned(*transform);
+        }
+    }
 }
 
 impl VmaResourceDiscardable for NodeResource {
@@ -157,6 +274,11 @@ impl VmaResourceDiscardable for NodeResource {
     fn discard_by(self, vma: &mut vma::Allocator) -> VkResult<()> {
         vma.destroy_buffer(self.buffer.handle, &self.buffer.allocation)
             .map_err(VkErrorKind::Vma)?;
+
+        if let Some(frames) = self.frames {
+            vma.destroy_buffer(frames.buffer.handle, &frames.buffer.allocation)
+                .map_err(VkErrorKind::Vma)?;
+        }
         Ok(())
     }
 }
@@ -1,10 +1,15 @@
 
+use ash::vk;
+
 use crate::gltf::asset::{ReferenceIndex, AssetElementList};
 use crate::gltf::asset::{VkglTFModel, ModelRenderParams};
 use crate::gltf::nodes::attachment::{NodeAttachments, AttachmentContent};
+use crate::gltf::meshes::Mesh;
 use crate::command::{VkCmdRecorder, IGraphics, CmdGraphicsApi};
 use crate::error::VkResult;
-use crate::{vkuint, Mat4F};
+use crate::{vkbytes, Mat4F, Vec3F};
+
+use std::collections::{HashMap, HashSet};
 
 
 // --------------------------------------------------------------------------------------
@@ -21,6 +26,11 @@ pub struct Node {
     children: Vec<ReferenceIndex>,
     /// the transform property of current node.
     local_transform: Mat4F,
+
+    /// the decomposed transform property of current node, which may be changed by animation.
+    translation: Vec3F,
+    rotation: vek::Quaternion<f32>,
+    scale: Vec3F,
 }
 
 impl Node {
@@ -33,6 +43,7 @@ impl Node {
 
         // read the transform matrix of Node.
         let local_transform = Mat4F::from_col_arrays(node.transform().matrix());
+        let (translation, rotation, scale) = node.transform().decomposed();
 
         // first, read json index of specific mesh referenced by current node.
         let local_mesh = node.mesh().and_then(|doc_mesh| Some(doc_mesh.index()));
@@ -42,10 +53,57 @@ impl Node {
             .map(|doc_node| doc_node.index())
             .collect();
 
-        let result = Node { _name: name, json_index, local_mesh, children, local_transform };
+        let result = Node {
+            _name: name, json_index, local_mesh, children, local_transform,
+            translation: Vec3F::from(translation),
+            rotation: vek::Quaternion::from_xyzw(rotation[0], rotation[1], rotation[2], rotation[3]),
+            scale: Vec3F::from(scale),
+        };
         Ok(result)
     }
 
+    pub fn set_translation(&mut self, translation: Vec3F) {
+        self.translation = translation;
+        self.update_local_transform();
+    }
+
+    pub fn set_rotation(&mut self, rotation: vek::Quaternion<f32>) {
+        self.rotation = rotation;
+        self.update_local_transform();
+    }
+
+    pub fn set_scale(&mut self, scale: Vec3F) {
+        self.scale = scale;
+        self.update_local_transform();
+    }
+
+    fn update_local_transform(&mut self) {
+        self.local_transform = Mat4F::translation_3d(self.translation)
+            * Mat4F::from(self.rotation)
+            * Mat4F::scaling_3d(self.scale);
+    }
+
+    pub fn read_global_transform(&self, nodes: &AssetElementList<Node>, transforms: &mut HashMap<ReferenceIndex, Mat4F>, parent_transform: &Mat4F) {
+
+        let node_transform: Mat4F = (*parent_transform) * self.local_transform;
+        transforms.insert(self.json_index, node_transform);
+
+        for child_json_index in self.children.iter().cloned() {
+            let child_node = nodes.get(child_json_index);
+            child_node.read_global_transform(nodes, transforms, &node_transform);
+        }
+    }
+
+    /// Insert the json index of this node and all its descendants into `subtree`.
+    pub(crate) fn collect_subtree(&self, nodes: &AssetElementList<Node>, subtree: &mut HashSet<ReferenceIndex>) {
+
+        if subtree.insert(self.json_index) {
+            for child_json_index in self.children.iter().cloned() {
+                nodes.get(child_json_index).collect_subtree(nodes, subtree);
+            }
+        }
+    }
+
     pub fn read_attachment(&self, nodes: &AssetElementList<Node>, attachments: &mut NodeAttachments, parent_transform: &Mat4F) {
 
         // apply parent node's transformation to current node level.
@@ -67,12 +125,12 @@ impl Node {
         }
     }
 
-    pub fn record_command(&self, recorder: &VkCmdRecorder<IGraphics>, model: &VkglTFModel, params: &ModelRenderParams) {
+    pub fn record_command(&self, recorder: &VkCmdRecorder<IGraphics>, model: &VkglTFModel, params: &ModelRenderParams, frame_index: usize) {
 
         if let Some(local_mesh) = self.local_mesh {
 
             // calculate the dynamic offset.
-            let dyn_offset = (model.nodes.attachment_size_aligned as vkuint) * (model.nodes.attachment_mapping.get(&self.json_index).unwrap().clone() as vkuint);
+            let dyn_offset = model.nodes.dynamic_offset(self.json_index, frame_index);
             // bind descriptors with dynamic offset for node attachment.
             recorder.bind_descriptor_sets(params.pipeline_layout, 0, &[params.descriptor_set], &[dyn_offset]);
 
@@ -82,7 +140,36 @@ impl Node {
 
         for child_node_index in self.children.iter().cloned() {
             let child_node = model.nodes.list.get(child_node_index);
-            child_node.record_command(recorder, model, params);
+            child_node.record_command(recorder, model, params, frame_index);
+        }
+    }
+
+    /// Append the indirect draw parameters of this node and its children to `commands`, in the same order as `record_indirect`.
+    pub(crate) fn indirect_commands(&self, nodes: &AssetElementList<Node>, meshes: &AssetElementList<Mesh>, commands: &mut Vec<vk::DrawIndexedIndirectCommand>) {
+
+        if let Some(local_mesh) = self.local_mesh {
+            meshes.get(local_mesh).indirect_commands(commands);
+        }
+
+        for child_node_index in self.children.iter().cloned() {
+            nodes.get(child_node_index).indirect_commands(nodes, meshes, commands);
+        }
+    }
+
+    pub(crate) fn record_indirect(&self, recorder: &VkCmdRecorder<IGraphics>, model: &VkglTFModel, params: &ModelRenderParams, buffer: vk::Buffer, offset: &mut vkbytes, frame_index: usize) {
+
+        if let Some(local_mesh) = self.local_mesh {
+
+            let dyn_offset = model.nodes.dynamic_offset(self.json_index, frame_index);
+            recorder.bind_descriptor_sets(params.pipeline_layout, 0, &[params.descriptor_set], &[dyn_offset]);
+
+            let mesh = model.meshes.list.get(local_mesh);
+            mesh.record_indirect(recorder, model, params, buffer, offset);
+        }
+
+        for child_node_index in self.children.iter().cloned() {
+            let child_node = model.nodes.list.get(child_node_index);
+            child_node.record_indirect(recorder, model, params, buffer, offset, frame_index);
         }
     }
 }
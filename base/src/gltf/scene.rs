@@ -1,43 +1,135 @@
 
+use ash::vk;
+
 use crate::gltf::asset::{ReferenceIndex, AssetElementList};
 use crate::gltf::asset::{VkglTFModel, ModelRenderParams};
 use crate::gltf::nodes::{Node, NodeAttachments};
+use crate::gltf::meshes::Mesh;
 use crate::command::{VkCmdRecorder, IGraphics};
-use crate::Mat4F;
+use crate::{Mat4F, vkbytes};
+
+use std::collections::HashMap;
 
 
 pub struct Scene {
 
     /// a scene may contain multiple glTF::Node.
     nodes: Vec<ReferenceIndex>,
+    /// the root matrix applied before the transform of each root node.
+    root_transform: Mat4F,
 }
 
 impl Scene {
 
-    pub fn from_doc(doc_scene: gltf::Scene) -> Scene {
+    pub fn from_doc(doc_scene: gltf::Scene, root_transform: Option<Mat4F>) -> Scene {
 
         let nodes = doc_scene.nodes()
             .map(|doc_node| doc_node.index())
             .collect();
 
-        Scene { nodes }
+        Scene {
+            nodes,
+            root_transform: root_transform.unwrap_or(Mat4F::identity()),
+        }
     }
 
     pub fn read_node_attachment(&self, nodes: &AssetElementList<Node>, attachments: &mut NodeAttachments) {
 
         for node_json_index in self.nodes.iter().cloned() {
             let node = nodes.get(node_json_index);
-            node.read_attachment(nodes, attachments, &Mat4F::identity());
+            node.read_attachment(nodes, attachments, &self.root_transform);
         }
     }
 
-    pub fn record_command(&self, recorder: &VkCmdRecorder<IGraphics>, model: &VkglTFModel, params: &ModelRenderParams) {
+    /// Calculate the global transform of every node in this scene, indexed by the json index of node.
+    pub fn global_transforms(&self, nodes: &AssetElementList<Node>) -> HashMap<ReferenceIndex, Mat4F> {
+
+        let mut transforms = HashMap::new();
+
+        for node_json_index in self.nodes.iter().cloned() {
+            let node = nodes.get(node_json_index);
+            node.read_global_transform(nodes, &mut transforms, &self.root_transform);
+        }
+
+        transforms
+    }
+
+    pub fn record_command(&self, recorder: &VkCmdRecorder<IGraphics>, model: &VkglTFModel, params: &ModelRenderParams, frame_index: usize) {
 
         for node_json_index in self.nodes.iter().cloned() {
 
             let node = model.nodes.list.get(node_json_index);
-            node.record_command(recorder, model, params);
+            node.record_command(recorder, model, params, frame_index);
+        }
+    }
+
+    pub fn indirect_commands(&self, nodes: &AssetElementList<Node>, meshes: &AssetElementList<Mesh>) -> Vec<vk::DrawIndexedIndirectCommand> {
+
+        let mut commands = Vec::new();
+
+        for node_json_index in self.nodes.iter().cloned() {
+            let node = nodes.get(node_json_index);
+            node.indirect_commands(nodes, meshes, &mut commands);
+        }
+
+        commands
+    }
+
+    pub fn record_indirect(&self, recorder: &VkCmdRecorder<IGraphics>, model: &VkglTFModel, params: &ModelRenderParams, buffer: vk::Buffer, offset: vkbytes, frame_index: usize) {
+
+        let mut offset = offset;
+
+        for node_json_index in self.nodes.iter().cloned() {
+
+            let node = model.nodes.list.get(node_json_index);
+            node.record_indirect(recorder, model, params, buffer, &mut offset, frame_index);
         }
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+
+    use super::Scene;
+    use crate::gltf::asset::AssetElementList;
+    use crate::gltf::nodes::Node;
+    use crate::{Mat4F, Vec3F};
+
+    /// A cube hanging under a parent node, without mesh data since only the node hierarchy is tested.
+    const CUBE_HIERARCHY: &str = r#"{
+        "asset": { "version": "2.0" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [
+            { "name": "Root", "translation": [1.0, 0.0, 0.0], "children": [1] },
+            { "name": "Cube", "translation": [0.0, 1.0, 0.0] }
+        ]
+    }"#;
+
+    fn assert_near(actual: Vec3F, expected: Vec3F) {
+        assert!((actual - expected).magnitude() < 1e-5, "expected {:?}, but got {:?}", expected, actual);
+    }
+
+    #[test]
+    fn root_transform_applies_to_leaf_node() {
+
+        let gltf = gltf::Gltf::from_slice(CUBE_HIERARCHY.as_bytes()).unwrap();
+
+        let mut nodes = AssetElementList::default();
+        for doc_node in gltf.document.nodes() {
+            nodes.push(doc_node.index(), Node::from_doc(doc_node).unwrap());
+        }
+
+        let doc_scene = gltf.document.default_scene().unwrap();
+        let scene = Scene::from_doc(doc_scene, Some(Mat4F::scaling_3d(Vec3F::broadcast(2.0))));
+
+        let transforms = scene.global_transforms(&nodes);
+        let leaf_transform = transforms[&1];
+
+        // scale(2) * translate(1, 0, 0) * translate(0, 1, 0).
+        assert_near(leaf_transform.mul_point(Vec3F::zero()), Vec3F::new(2.0, 2.0, 0.0));
+        assert_near(leaf_transform.mul_point(Vec3F::new(1.0, 0.0, 0.0)), Vec3F::new(4.0, 2.0, 0.0));
+        assert_near(transforms[&0].mul_point(Vec3F::zero()), Vec3F::new(2.0, 0.0, 0.0));
+    }
+}
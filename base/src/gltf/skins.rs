@@ -0,0 +1,271 @@
+
+use ash::vk;
+
+use crate::gltf::asset::{GltfDocument, AssetAbstract, AssetElementList};
+use crate::gltf::asset::ReferenceIndex;
+use crate::gltf::scene::Scene;
+
+use crate::ci::buffer::BufferCI;
+use crate::ci::vma::{VmaBuffer, VmaAllocationCI};
+use crate::context::{VkDevice, VmaResourceDiscardable};
+use crate::utils::memory::align_up;
+use crate::error::{VkResult, VkErrorKind};
+use crate::{vkbytes, vkuint, vkptr, Mat4F};
+
+use std::collections::HashMap;
+use std::ptr;
+
+
+// ------------------------------------------------------------------------------------
+/// A wrapper class for skin level in glTF, containing the joints read from glTF file.
+pub struct Skin {
+
+    /// the name property of current skin.
+    _name: Option<String>,
+    /// the json index of the nodes used as joints of this skin.
+    joints: Vec<ReferenceIndex>,
+    /// the inverse bind matrix of each joint.
+    inverse_bind_matrices: Vec<Mat4F>,
+    /// the json index of the node whose mesh is deformed by this skin.
+    owner_node: Option<ReferenceIndex>,
+}
+
+impl Skin {
+
+    fn from_doc(doc_skin: gltf::Skin, source: &GltfDocument) -> Skin {
+
+        let name = doc_skin.name().and_then(|n| Some(n.to_string()));
+
+        let joints: Vec<ReferenceIndex> = doc_skin.joints()
+            .map(|doc_node| doc_node.index())
+            .collect();
+
+        // the inverse bind matrices are identity matrices if they are not provided.
+        let inverse_bind_matrices = doc_skin.reader(|b| Some(&source.buffers[b.index()]))
+            .read_inverse_bind_matrices()
+            .map(|matrices| matrices.map(Mat4F::from_col_arrays).collect())
+            .unwrap_or_else(|| vec![Mat4F::identity(); joints.len()]);
+
+        // find the node that references this skin.
+        let owner_node = source.doc.nodes()
+            .find(|doc_node| doc_node.skin().map(|s| s.index()) == Some(doc_skin.index()))
+            .map(|doc_node| doc_node.index());
+
+        Skin { _name: name, joints, inverse_bind_matrices, owner_node }
+    }
+
+    /// Calculate the joint matrices of this skin from the global transform of each node.
+    ///
+    /// The joint matrix is `inverse(owner node) * joint node * inverse bind matrix`,
+    /// so that the owner node's transform can still be applied to skinned vertices in shader.
+    fn joint_matrices(&self, global_transforms: &HashMap<ReferenceIndex, Mat4F>) -> Vec<Mat4F> {
+
+        let inverse_owner = self.owner_node
+            .and_then(|node_index| global_transforms.get(&node_index))
+            .map(|transform| transform.inverted())
+            .unwrap_or(Mat4F::identity());
+
+        self.joints.iter().zip(self.inverse_bind_matrices.iter())
+            .map(|(joint_index, inverse_bind)| {
+                let joint_transform = global_transforms.get(joint_index).cloned()
+                    .unwrap_or(Mat4F::identity());
+                inverse_owner * joint_transform * (*inverse_bind)
+            }).collect()
+    }
+}
+// ------------------------------------------------------------------------------------
+
+
+// ------------------------------------------------------------------------------------
+#[derive(Default)]
+pub struct SkinAsset {
+
+    skins: AssetElementList<Skin>,
+    skin_count: usize,
+}
+
+impl AssetAbstract for SkinAsset {
+    const ASSET_NAME: &'static str = "Skins";
+
+    fn read_doc(&mut self, source: &GltfDocument, _scene: &Scene) -> VkResult<()> {
+
+        for doc_skin in source.doc.skins() {
+
+            let json_index = doc_skin.index();
+
+            let skin = Skin::from_doc(doc_skin, source);
+            self.skins.push(json_index, skin);
+            self.skin_count += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl SkinAsset {
+
+    /// Allocate a persistently mapped storage buffer for the joint matrices of each skin, which is written with the bind pose.
+    pub fn allocate(self, device: &mut VkDevice, global_transforms: &HashMap<ReferenceIndex, Mat4F>) -> VkResult<SkinResource> {
+
+        let buffers = SkinResource::create_buffers(device, &self.skins, 1)?;
+
+        let result = SkinResource {
+            list: self.skins,
+            buffers,
+            frame_count: 1,
+            skin_count: self.skin_count,
+        };
+        result.write_all_frames(global_transforms);
+
+        Ok(result)
+    }
+}
+// ------------------------------------------------------------------------------------
+
+
+// ------------------------------------------------------------------------------------
+pub struct SkinResource {
+
+    pub(crate) list: AssetElementList<Skin>,
+    buffers: AssetElementList<JointBuffer>,
+    /// the count of joint matrices copies in each buffer.
+    frame_count: usize,
+    skin_count: usize,
+}
+
+/// The joint matrices of a skin, containing one copy for each frame in flight.
+struct JointBuffer {
+
+    buffer: VmaBuffer,
+    /// the size of joint matrices in bytes.
+    joints_size: vkbytes,
+    /// the stride between two copies, aligned to `minStorageBufferOffsetAlignment`.
+    aligned_size: vkbytes,
+}
+
+impl SkinResource {
+
+    fn create_buffers(device: &mut VkDevice, skins: &AssetElementList<Skin>, frame_count: usize) -> VkResult<AssetElementList<JointBuffer>> {
+
+        let mut buffers = AssetElementList::default();
+
+        for (skin_index, skin) in skins.iter_indexed() {
+
+            let joints_size = (::std::mem::size_of::<Mat4F>() * skin.joints.len().max(1)) as vkbytes;
+            let aligned_size = align_up(joints_size, device.phy.limits.min_storage_buffer_offset_alignment);
+
+            let joints_ci = BufferCI::new(aligned_size * frame_count as vkbytes)
+                .usage(vk::BufferUsageFlags::STORAGE_BUFFER);
+            let allocate_ci = VmaAllocationCI::new(vma::MemoryUsage::CpuToGpu, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT)
+                .flags(vma::AllocationCreateFlags::MAPPED);
+            let joints_allocation = device.vma.create_buffer(
+                joints_ci.as_ref(), allocate_ci.as_ref())
+                .map_err(VkErrorKind::Vma)?;
+
+            let joint_buffer = JointBuffer {
+                buffer: VmaBuffer::from(joints_allocation),
+                joints_size, aligned_size,
+            };
+            buffers.push(skin_index, joint_buffer);
+        }
+
+        Ok(buffers)
+    }
+
+    /// The number of skins in this model.
+    #[inline]
+    pub fn skin_count(&self) -> usize {
+        self.skin_count
+    }
+
+    /// Return the descriptor of the joint matrices storage buffer of skin at `skin_index`, whose range covers a single copy.
+    ///
+    /// The buffer contains an array of `mat4`, indexed by the `JOINTS_0` attribute.
+    /// Bind it as `STORAGE_BUFFER_DYNAMIC` and select the copy by `joints_dynamic_offset`,
+    /// or as `STORAGE_BUFFER` if `VkglTFModel::enable_animation` is not called(there is only one copy).
+    pub fn joints_descriptor(&self, skin_index: ReferenceIndex) -> vk::DescriptorBufferInfo {
+
+        let joint_buffer = self.buffers.get(skin_index);
+
+        vk::DescriptorBufferInfo {
+            buffer: joint_buffer.buffer.handle,
+            offset: 0,
+            range : joint_buffer.joints_size,
+        }
+    }
+
+    /// The dynamic offset to bind the joint matrices of skin at `skin_index` written for `frame_index`.
+    #[inline]
+    pub fn joints_dynamic_offset(&self, skin_index: ReferenceIndex, frame_index: usize) -> vkuint {
+        debug_assert!(frame_index < self.frame_count, "Frame index {} is out of the {} joint matrices copies!", frame_index, self.frame_count);
+        (self.buffers.get(skin_index).aligned_size * frame_index as vkbytes) as vkuint
+    }
+
+    /// Reallocate the buffers with a copy of joint matrices for each frame in flight, so that updating a frame never writes the data still read by GPU.
+    ///
+    /// The device must be idle, since the previous buffers are destroyed.
+    pub(crate) fn allocate_frames(&mut self, device: &mut VkDevice, frame_in_flight: usize, global_transforms: &HashMap<ReferenceIndex, Mat4F>) -> VkResult<()> {
+
+        debug_assert!(frame_in_flight > 0, "Frames in flight must be greater than 0!");
+
+        let buffers = SkinResource::create_buffers(device, &self.list, frame_in_flight)?;
+        let previous_buffers = ::std::mem::replace(&mut self.buffers, buffers);
+        for joint_buffer in previous_buffers.into_iter() {
+            device.vma_discard(joint_buffer.buffer)?;
+        }
+
+        self.frame_count = frame_in_flight;
+        self.write_all_frames(global_transforms);
+
+        Ok(())
+    }
+
+    /// Calculate the joint matrices of all skins, and write them to the copy of `frame_index`.
+    ///
+    /// Only write the copy that is not read by GPU anymore(etc. the copy of `image_index` in `RenderWorkflow::render_frame`).
+    pub(crate) fn update_joints(&self, frame_index: usize, global_transforms: &HashMap<ReferenceIndex, Mat4F>) {
+
+        debug_assert!(frame_index < self.frame_count, "Frame index {} is out of the {} joint matrices copies!", frame_index, self.frame_count);
+
+        for (skin_index, skin) in self.list.iter_indexed() {
+            let joint_matrices = skin.joint_matrices(global_transforms);
+            self.write_joints(skin_index, frame_index, &joint_matrices);
+        }
+    }
+
+    fn write_all_frames(&self, global_transforms: &HashMap<ReferenceIndex, Mat4F>) {
+
+        for (skin_index, skin) in self.list.iter_indexed() {
+            let joint_matrices = skin.joint_matrices(global_transforms);
+            for frame_index in 0..self.frame_count {
+                self.write_joints(skin_index, frame_index, &joint_matrices);
+            }
+        }
+    }
+
+    fn write_joints(&self, skin_index: ReferenceIndex, frame_index: usize, joint_matrices: &[Mat4F]) {
+
+        let joint_buffer = self.buffers.get(skin_index);
+
+        unsafe {
+            let data_ptr = joint_buffer.buffer.info.get_mapped_data() as vkptr<u8>;
+            debug_assert_ne!(data_ptr, ptr::null_mut());
+
+            let dst_ptr = data_ptr.offset((joint_buffer.aligned_size * frame_index as vkbytes) as isize) as vkptr<Mat4F>;
+            dst_ptr.copy_from_nonoverlapping(joint_matrices.as_ptr(), joint_matrices.len());
+        }
+    }
+}
+
+impl VmaResourceDiscardable for SkinResource {
+
+    fn discard_by(self, vma: &mut vma::Allocator) -> VkResult<()> {
+
+        for joint_buffer in self.buffers.into_iter() {
+            vma.destroy_buffer(joint_buffer.buffer.handle, &joint_buffer.buffer.allocation)
+                .map_err(VkErrorKind::Vma)?;
+        }
+        Ok(())
+    }
+}
+// ------------------------------------------------------------------------------------
@@ -1,18 +1,27 @@
 
 use smallvec::SmallVec;
+use winit::VirtualKeyCode;
 use crate::utils::frame::FrameAction;
 use crate::utils::fps::FpsCounter;
+use crate::Vec2F;
+
+use std::collections::HashMap;
 
 const SIMULTANEOUS_KEY_COUNT: usize = 12;
+/// The number of pixels treated as one line when converting pixel scroll delta.
+const PIXELS_PER_SCROLL_LINE: f32 = 20.0;
 
 
 pub struct EventController {
 
     pub key: KeyHeap,
     pub cursor: CursorMotion,
+    pub bindings: KeyBindings,
     pub fps_counter: FpsCounter,
 
     action: FrameAction,
+    /// indicate if the key of `KeyAction::Screenshot` is pressed in current frame.
+    is_capture_requested: bool,
     is_toggle_key: bool,
     is_toggle_cursor: bool,
 }
@@ -24,9 +33,11 @@ impl Default for EventController {
         EventController {
             key: Default::default(),
             cursor: Default::default(),
+            bindings: Default::default(),
             fps_counter: FpsCounter::new(),
 
             action: FrameAction::Rendering,
+            is_capture_requested: false,
             is_toggle_key: false,
             is_toggle_cursor: false,
         }
@@ -53,6 +64,10 @@ impl EventController {
                         if let Some(code) = input.virtual_keycode {
                             match input.state {
                                 | winit::ElementState::Pressed  => {
+                                    // only respond to the first press, not the repeated ones while holding the key.
+                                    if self.key.is_key_pressed(code) == false && self.bindings.keys_of(KeyAction::Screenshot).contains(&code) {
+                                        self.is_capture_requested = true;
+                                    }
                                     self.key.key_press(code);
                                     self.is_toggle_key = true;
                                 },
@@ -62,10 +77,34 @@ impl EventController {
                             }
                         }
                     },
+                    | winit::WindowEvent::CursorMoved { position, .. } => {
+                        self.cursor.record_position(position.x, position.y);
+                    },
+                    | winit::WindowEvent::MouseInput { state, button, .. } => {
+                        match state {
+                            | winit::ElementState::Pressed  => self.cursor.button_press(button),
+                            | winit::ElementState::Released => self.cursor.button_release(button),
+                        }
+                    },
+                    | winit::WindowEvent::MouseWheel { delta, .. } => {
+                        let lines = match delta {
+                            | winit::MouseScrollDelta::LineDelta(_, lines) => lines,
+                            // touchpad reports the scrolled pixels, convert it to lines.
+                            | winit::MouseScrollDelta::PixelDelta(position) => (position.y as f32) / PIXELS_PER_SCROLL_LINE,
+                        };
+                        self.cursor.record_wheel(lines);
+                    },
                     | winit::WindowEvent::Resized(_) => {
-
-                        // TODO: When window was created, Resized event will be toggled.
-                        // self.action = FrameAction::SwapchainRecreate;
+                        // the resize event is also sent when window was created, and `ProcPipeline` ignores it if the dimension is unchanged.
+                        if self.action != FrameAction::Terminal {
+                            self.action = FrameAction::SwapchainRecreate;
+                        }
+                    },
+                    | winit::WindowEvent::HiDpiFactorChanged(_) => {
+                        // the window is moved to a monitor of different DPI, so the swapchain and UI must be reloaded.
+                        if self.action != FrameAction::Terminal {
+                            self.action = FrameAction::SwapchainRecreate;
+                        }
                     },
                     | winit::WindowEvent::CloseRequested => {
                         self.action = FrameAction::Terminal;
@@ -77,6 +116,40 @@ impl EventController {
         }
     }
 
+    pub(crate) fn with_bindings(bindings: KeyBindings) -> EventController {
+        EventController { bindings, ..Default::default() }
+    }
+
+    /// Check if any key bound to `action` is pressed.
+    pub fn is_action_pressed(&self, action: KeyAction) -> bool {
+        self.bindings.keys_of(action).iter()
+            .any(|&code| self.key.is_key_pressed(code))
+    }
+
+    /// The mouse wheel scrolled in current frame, measured in lines(positive when scrolling up).
+    ///
+    /// The scroll events between two frames are accumulated, and the value is reset at the end of each frame.
+    /// Since every reader in the same frame sees the same value, let only one consumer(etc. the camera or the UI) respond to it.
+    pub fn scroll_delta(&self) -> f32 {
+        self.cursor.get_wheel_motion()
+    }
+
+    /// The cursor position relative to the top-left corner of the window, measured in logical pixels.
+    ///
+    /// The position persists between frames until the cursor moves again, so it can be used to hit-test the UI or models.
+    pub fn cursor_position(&self) -> Vec2F {
+        self.cursor.get_position()
+    }
+
+    /// The raw mouse motion in current frame(scaled by the cursor scale factor).
+    ///
+    /// The motions between two frames are accumulated, and the value is reset at the end of each frame.
+    /// Unlike `cursor_position`, it is still reported when the cursor is grabbed or hidden.
+    pub fn cursor_delta(&self) -> Vec2F {
+        let (delta_x, delta_y) = self.cursor.get_cursor_motion();
+        Vec2F::new(delta_x, delta_y)
+    }
+
     pub fn is_key_active(&self) -> bool {
         self.is_toggle_key
     }
@@ -88,17 +161,89 @@ impl EventController {
     pub(crate) fn tick_frame(&mut self) {
 
         self.fps_counter.tick_frame();
+        self.cursor.reset_wheel();
+        self.cursor.reset_motion();
         self.is_toggle_key = false;
         self.is_toggle_cursor = false;
+        self.is_capture_requested = false;
         self.action = FrameAction::Rendering;
     }
 
     pub(crate) fn current_action(&self) -> FrameAction {
         self.action
     }
+
+    /// Check if a screenshot is requested by the key of `KeyAction::Screenshot` in current frame.
+    pub fn is_capture_requested(&self) -> bool {
+        self.is_capture_requested
+    }
+}
+
+
+
+
+/// The logical actions that are triggered by keyboard.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum KeyAction {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Quit,
+    /// Save the current frame to a PNG file in the working directory.
+    Screenshot,
+}
+
+/// The mapping from logical actions to keys, so that the keys can be remapped without editing the camera or examples.
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+
+    bindings: HashMap<KeyAction, Vec<VirtualKeyCode>>,
 }
 
+impl Default for KeyBindings {
+
+    fn default() -> KeyBindings {
+
+        let mut bindings = KeyBindings { bindings: HashMap::new() };
+        bindings.bind(KeyAction::MoveForward, VirtualKeyCode::Up);
+        bindings.bind(KeyAction::MoveBack,    VirtualKeyCode::Down);
+        bindings.bind(KeyAction::MoveLeft,    VirtualKeyCode::Left);
+        bindings.bind(KeyAction::MoveRight,   VirtualKeyCode::Right);
+        bindings.bind(KeyAction::Quit,        VirtualKeyCode::Escape);
+        bindings.bind(KeyAction::Screenshot,  VirtualKeyCode::F12);
+        bindings
+    }
+}
 
+impl KeyBindings {
+
+    /// Add `code` as a key to trigger `action`. An action can be bound to multiple keys.
+    pub fn bind(&mut self, action: KeyAction, code: VirtualKeyCode) {
+
+        let codes = self.bindings.entry(action).or_insert_with(Vec::new);
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+
+    /// Remove all the keys bound to `action`.
+    pub fn unbind(&mut self, action: KeyAction) {
+        self.bindings.remove(&action);
+    }
+
+    /// Replace the keys bound to `action` with `code`.
+    pub fn rebind(&mut self, action: KeyAction, code: VirtualKeyCode) {
+        self.unbind(action);
+        self.bind(action, code);
+    }
+
+    pub fn keys_of(&self, action: KeyAction) -> &[VirtualKeyCode] {
+        self.bindings.get(&action)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
 
 
 pub struct KeyHeap {
@@ -145,8 +290,15 @@ impl KeyHeap {
 
 pub struct CursorMotion {
 
+    /// the accumulated mouse motion in current frame.
     delta_x: f32,
     delta_y: f32,
+    /// the last cursor position in window.
+    position: Vec2F,
+    /// the scrolled lines of mouse wheel in current frame.
+    wheel: f32,
+
+    buttons: SmallVec<[winit::MouseButton; 4]>,
 
     scale_factor: f32,
 }
@@ -158,6 +310,9 @@ impl Default for CursorMotion {
         CursorMotion {
             delta_x: 0.0,
             delta_y: 0.0,
+            position: Vec2F::zero(),
+            wheel  : 0.0,
+            buttons: SmallVec::new(),
             scale_factor: 1.0,
         }
     }
@@ -166,11 +321,54 @@ impl Default for CursorMotion {
 impl CursorMotion {
 
     fn record_motion(&mut self, delta_x: f64, delta_y: f64) {
-        self.delta_x = (delta_x as f32) * self.scale_factor;
-        self.delta_y = (delta_y as f32) * self.scale_factor;
+        self.delta_x += (delta_x as f32) * self.scale_factor;
+        self.delta_y += (delta_y as f32) * self.scale_factor;
+    }
+
+    fn reset_motion(&mut self) {
+        self.delta_x = 0.0;
+        self.delta_y = 0.0;
+    }
+
+    fn record_position(&mut self, x: f64, y: f64) {
+        self.position = Vec2F::new(x as f32, y as f32);
+    }
+
+    fn record_wheel(&mut self, lines: f32) {
+        self.wheel += lines;
+    }
+
+    fn reset_wheel(&mut self) {
+        self.wheel = 0.0;
+    }
+
+    fn button_press(&mut self, button: winit::MouseButton) {
+        if !self.is_button_pressed(button) {
+            self.buttons.push(button);
+        }
+    }
+
+    fn button_release(&mut self, button: winit::MouseButton) {
+        if let Some(index) = self.buttons.iter().position(|&b| b == button) {
+            self.buttons.swap_remove(index);
+        }
     }
 
     pub fn get_cursor_motion(&self) -> (f32, f32) {
         (self.delta_x, self.delta_y)
     }
+
+    /// The last cursor position in window, measured in logical pixels.
+    pub fn get_position(&self) -> Vec2F {
+        self.position
+    }
+
+    /// The scrolled lines of mouse wheel in current frame.
+    pub fn get_wheel_motion(&self) -> f32 {
+        self.wheel
+    }
+
+    pub fn is_button_pressed(&self, button: winit::MouseButton) -> bool {
+        self.buttons.iter().any(|&b| b == button)
+    }
 }
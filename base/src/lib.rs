@@ -1,10 +1,10 @@
 
-pub use self::workflow::{RenderWorkflow, WindowContext, WindowConfig};
-pub use self::workflow::ProcPipeline;
+pub use self::workflow::{RenderWorkflow, WindowContext, WindowConfig, WindowMode, FullscreenMode};
+pub use self::workflow::{ProcPipeline, FrameStats};
 pub use self::error::{VkResult, VkError, VkErrorKind};
 pub use self::utils::frame::FrameAction;
-pub use self::input::EventController;
-pub use self::camera::FlightCamera;
+pub use self::input::{EventController, KeyBindings, KeyAction};
+pub use self::camera::{FlightCamera, OrbitCamera};
 
 pub mod context;
 pub mod ci;
@@ -14,6 +14,7 @@ pub mod platforms;
 pub mod gltf;
 pub mod texture;
 pub mod ui;
+pub mod postprocess;
 
 mod error;
 mod camera;
@@ -0,0 +1,17 @@
+//! Fullscreen passes which process offscreen images(etc. `RenderTarget`) after the scene is rendered.
+
+pub use self::pass::{PostProcess, PostProcessConfig, PostProcessInput, PostProcessOutput};
+pub use self::fxaa::{Fxaa, FxaaParams};
+pub use self::color_grade::ColorGrade;
+pub use self::bloom::{Bloom, BloomParams, BloomInput};
+pub use self::ssao::{Ssao, SsaoParams};
+pub use self::tonemap::{Tonemap, TonemapParams, TonemapOperator};
+pub use self::exposure::{AutoExposure, AutoExposureParams, AutoExposureInput};
+
+mod pass;
+mod fxaa;
+mod color_grade;
+mod bloom;
+mod ssao;
+mod tonemap;
+mod exposure;
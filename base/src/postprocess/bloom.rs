@@ -0,0 +1,308 @@
+
+use ash::vk;
+
+use crate::postprocess::{PostProcess, PostProcessConfig, PostProcessInput, PostProcessOutput};
+use crate::texture::RenderTarget;
+use crate::command::{VkCmdRecorder, IGraphics};
+use crate::context::VkDevice;
+use crate::utils::memory::any_as_u8_slice;
+
+use crate::VkResult;
+use crate::{vkfloat, vkuint};
+
+
+// ----------------------------------------------------------------------------------------------
+/// The parameters of bloom, which can be adjusted at runtime.
+#[derive(Debug, Clone, Copy)]
+pub struct BloomParams {
+
+    /// the brightness above which the pixels start to glow.
+    pub threshold: vkfloat,
+    /// the width of the soft transition around `threshold`(0.0 is a hard cut).
+    pub knee: vkfloat,
+    /// the strength of the glow added to the scene.
+    pub intensity: vkfloat,
+    /// the scale of upsample filter, larger value spreads the glow wider.
+    pub radius: vkfloat,
+}
+
+impl Default for BloomParams {
+
+    fn default() -> BloomParams {
+        BloomParams {
+            threshold: 1.0,
+            knee: 0.5,
+            intensity: 0.8,
+            radius: 1.0,
+        }
+    }
+}
+
+/// The sampled images of `Bloom`, which is created by `Bloom::create_input`.
+#[derive(Debug, Clone, Copy)]
+pub struct BloomInput {
+    /// sample the HDR scene to extract the bright pixels.
+    prefilter: PostProcessInput,
+    /// sample the HDR scene and the first level of mip chain.
+    composite: PostProcessInput,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct PrefilterPushConstants {
+    inverse_dimension: [vkfloat; 2],
+    threshold: vkfloat,
+    knee: vkfloat,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct SamplePushConstants {
+    inverse_dimension: [vkfloat; 2],
+    radius: vkfloat,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct CompositePushConstants {
+    intensity: vkfloat,
+}
+
+/// A level of the mip chain, whose dimension is half of the previous level.
+struct BloomLevel {
+    target: RenderTarget,
+    /// written by prefilter pass(for the first level) or downsample pass.
+    down_output: PostProcessOutput,
+    /// added by upsample pass from the next level.
+    up_output: PostProcessOutput,
+}
+// ----------------------------------------------------------------------------------------------
+
+
+// ----------------------------------------------------------------------------------------------
+/// A bloom effect, which makes the bright area of an HDR image glow.
+///
+/// The bright pixels are extracted into a mip chain of `RenderTarget`s, which are progressively downsampled and blurred,
+/// then upsampled and accumulated back to the first level, and finally added to the scene.
+/// Apply it before tone mapping, since it relies on the brightness above 1.0.
+pub struct Bloom {
+
+    prefilter : PostProcess,
+    downsample: PostProcess,
+    upsample  : PostProcess,
+    composite : PostProcess,
+
+    levels: Vec<BloomLevel>,
+    /// `down_inputs[i]` samples `levels[i]` to produce `levels[i + 1]`.
+    down_inputs: Vec<PostProcessInput>,
+    /// `up_inputs[i]` samples `levels[i + 1]` to add to `levels[i]`.
+    up_inputs: Vec<PostProcessInput>,
+
+    chain_format: vk::Format,
+    /// the dimension of the HDR input.
+    dimension: vk::Extent2D,
+
+    pub params: BloomParams,
+}
+
+impl Bloom {
+
+    /// Create the bloom for HDR images of `dimension` and `hdr_format`(etc. `R16G16B16A16_SFLOAT`), with `level_count` levels of mip chain.
+    ///
+    /// The result is written to images of `output_format`, which is transitioned to `output_final_layout` after this pass.
+    pub fn new(device: &mut VkDevice, hdr_format: vk::Format, output_format: vk::Format, output_final_layout: vk::ImageLayout, dimension: vk::Extent2D, level_count: usize) -> VkResult<Bloom> {
+
+        let level_count = level_count.max(1);
+        let chain_input_count = (level_count - 1).max(1) as vkuint;
+
+        let prefilter = PostProcess::new(device, include_str!("bloom_prefilter.frag.glsl"), PostProcessConfig {
+            output_format: hdr_format,
+            push_constant_size: ::std::mem::size_of::<PrefilterPushConstants>() as _,
+            ..Default::default()
+        })?;
+
+        let downsample = PostProcess::new(device, include_str!("bloom_downsample.frag.glsl"), PostProcessConfig {
+            output_format: hdr_format,
+            max_inputs: chain_input_count,
+            push_constant_size: ::std::mem::size_of::<SamplePushConstants>() as _,
+            ..Default::default()
+        })?;
+
+        // accumulate onto the downsampled content, which is left in SHADER_READ_ONLY_OPTIMAL by downsample pass.
+        let upsample = PostProcess::new(device, include_str!("bloom_upsample.frag.glsl"), PostProcessConfig {
+            output_format: hdr_format,
+            is_additive_blend: true,
+            output_initial_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            max_inputs: chain_input_count,
+            push_constant_size: ::std::mem::size_of::<SamplePushConstants>() as _,
+            ..Default::default()
+        })?;
+
+        let composite = PostProcess::new(device, include_str!("bloom_composite.frag.glsl"), PostProcessConfig {
+            output_format, output_final_layout,
+            input_count: 2,
+            push_constant_size: ::std::mem::size_of::<CompositePushConstants>() as _,
+            ..Default::default()
+        })?;
+
+        let mut bloom = Bloom {
+            prefilter, downsample, upsample, composite,
+            levels: Vec::with_capacity(level_count),
+            down_inputs: Vec::with_capacity(level_count - 1),
+            up_inputs: Vec::with_capacity(level_count - 1),
+            chain_format: hdr_format,
+            dimension,
+            params: BloomParams::default(),
+        };
+
+        bloom.levels = bloom.create_levels(device, dimension, level_count)?;
+
+        for i in 0..(level_count - 1) {
+            let down_input = bloom.downsample.create_input(device, &[bloom.levels[i].target.descriptor])?;
+            let up_input = bloom.upsample.create_input(device, &[bloom.levels[i + 1].target.descriptor])?;
+            bloom.down_inputs.push(down_input);
+            bloom.up_inputs.push(up_input);
+        }
+
+        Ok(bloom)
+    }
+
+    /// Create the input sampling `hdr`(the HDR scene), whose dimension must match the dimension of this pass.
+    pub fn create_input(&self, device: &VkDevice, hdr: &RenderTarget) -> VkResult<BloomInput> {
+
+        let input = BloomInput {
+            prefilter: self.prefilter.create_input(device, &[hdr.descriptor])?,
+            composite: self.composite.create_input(device, &[hdr.descriptor, self.levels[0].target.descriptor])?,
+        };
+        Ok(input)
+    }
+
+    /// Point `input` to `hdr` after the HDR image or this pass is resized.
+    pub fn update_input(&self, device: &VkDevice, input: &BloomInput, hdr: &RenderTarget) -> VkResult<()> {
+
+        self.prefilter.update_input(device, &input.prefilter, &[hdr.descriptor])?;
+        self.composite.update_input(device, &input.composite, &[hdr.descriptor, self.levels[0].target.descriptor])
+    }
+
+    /// Create the output writing `view`, whose format must be the `output_format` of this pass.
+    pub fn create_output(&self, device: &VkDevice, view: vk::ImageView, dimension: vk::Extent2D) -> VkResult<PostProcessOutput> {
+        self.composite.create_output(device, view, dimension)
+    }
+
+    /// Recreate the mip chain for HDR images of `dimension`.
+    ///
+    /// Call `update_input` for each `BloomInput` afterwards, since they refer to the old mip chain.
+    pub fn resize(&mut self, device: &mut VkDevice, dimension: vk::Extent2D) -> VkResult<()> {
+
+        let levels = self.create_levels(device, dimension, self.levels.len())?;
+        let old_levels = ::std::mem::replace(&mut self.levels, levels);
+        self.dimension = dimension;
+
+        for (i, (down_input, up_input)) in self.down_inputs.iter().zip(self.up_inputs.iter()).enumerate() {
+            self.downsample.update_input(device, down_input, &[self.levels[i].target.descriptor])?;
+            self.upsample.update_input(device, up_input, &[self.levels[i + 1].target.descriptor])?;
+        }
+
+        for level in old_levels {
+            level.discard_by(device)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Record the commands to apply bloom on `hdr_input` and write the result to `output`.
+    pub fn record(&self, recorder: &VkCmdRecorder<IGraphics>, hdr_input: &BloomInput, output: &PostProcessOutput) {
+
+        // extract the bright pixels into the first level.
+        let prefilter_constants = PrefilterPushConstants {
+            inverse_dimension: inverse_dimension(self.dimension),
+            threshold: self.params.threshold,
+            knee: self.params.knee,
+        };
+        self.prefilter.record(recorder, &hdr_input.prefilter, &self.levels[0].down_output, unsafe { any_as_u8_slice(&prefilter_constants) });
+
+        // blur down the mip chain.
+        for i in 1..self.levels.len() {
+            let downsample_constants = SamplePushConstants {
+                inverse_dimension: inverse_dimension(self.levels[i - 1].target.dimension),
+                radius: 1.0,
+            };
+            self.downsample.record(recorder, &self.down_inputs[i - 1], &self.levels[i].down_output, unsafe { any_as_u8_slice(&downsample_constants) });
+        }
+
+        // accumulate up the mip chain.
+        for i in (1..self.levels.len()).rev() {
+            let upsample_constants = SamplePushConstants {
+                inverse_dimension: inverse_dimension(self.levels[i].target.dimension),
+                radius: self.params.radius,
+            };
+            self.upsample.record(recorder, &self.up_inputs[i - 1], &self.levels[i - 1].up_output, unsafe { any_as_u8_slice(&upsample_constants) });
+        }
+
+        let composite_constants = CompositePushConstants {
+            intensity: self.params.intensity,
+        };
+        self.composite.record(recorder, &hdr_input.composite, output, unsafe { any_as_u8_slice(&composite_constants) });
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        for level in self.levels {
+            level.discard_by(device)?;
+        }
+
+        self.prefilter.discard_by(device);
+        self.downsample.discard_by(device);
+        self.upsample.discard_by(device);
+        self.composite.discard_by(device);
+
+        Ok(())
+    }
+
+    fn create_levels(&self, device: &mut VkDevice, dimension: vk::Extent2D, level_count: usize) -> VkResult<Vec<BloomLevel>> {
+
+        let mut levels = Vec::with_capacity(level_count);
+        let mut level_dimension = dimension;
+
+        for i in 0..level_count {
+
+            level_dimension = half_dimension(level_dimension);
+
+            let target = RenderTarget::new(device, self.chain_format, level_dimension)?;
+            let down_pass = if i == 0 { &self.prefilter } else { &self.downsample };
+            let down_output = down_pass.create_output(device, target.view, level_dimension)?;
+            let up_output = self.upsample.create_output(device, target.view, level_dimension)?;
+
+            levels.push(BloomLevel { target, down_output, up_output });
+        }
+
+        Ok(levels)
+    }
+}
+
+impl BloomLevel {
+
+    fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        self.down_output.discard_by(device);
+        self.up_output.discard_by(device);
+        self.target.discard_by(device)
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+fn half_dimension(dimension: vk::Extent2D) -> vk::Extent2D {
+    vk::Extent2D {
+        width : (dimension.width  / 2).max(1),
+        height: (dimension.height / 2).max(1),
+    }
+}
+
+fn inverse_dimension(dimension: vk::Extent2D) -> [vkfloat; 2] {
+    [1.0 / dimension.width as vkfloat, 1.0 / dimension.height as vkfloat]
+}
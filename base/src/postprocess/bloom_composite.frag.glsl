@@ -0,0 +1,21 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D scene;
+layout (binding = 1) uniform sampler2D bloom;
+
+layout (push_constant) uniform CompositeParams {
+    float intensity;
+} params;
+
+void main() {
+
+    vec4 color = texture(scene, inUV);
+    vec3 glow = texture(bloom, inUV).rgb;
+
+    outColor = vec4(color.rgb + glow * params.intensity, color.a);
+}
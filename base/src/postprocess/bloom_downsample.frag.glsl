@@ -0,0 +1,27 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D source;
+
+layout (push_constant) uniform DownsampleParams {
+    // the size of a texel in source image.
+    vec2 inverse_dimension;
+} params;
+
+void main() {
+
+    // the dual filter downsample: the center with 4 diagonal bilinear taps.
+    vec2 offset = params.inverse_dimension;
+
+    vec3 color = texture(source, inUV).rgb * 4.0;
+    color += texture(source, inUV + vec2(-offset.x, -offset.y)).rgb;
+    color += texture(source, inUV + vec2( offset.x, -offset.y)).rgb;
+    color += texture(source, inUV + vec2(-offset.x,  offset.y)).rgb;
+    color += texture(source, inUV + vec2( offset.x,  offset.y)).rgb;
+
+    outColor = vec4(color / 8.0, 1.0);
+}
@@ -0,0 +1,35 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D source;
+
+layout (push_constant) uniform BloomParams {
+    // the size of a texel in source image.
+    vec2  inverse_dimension;
+    float threshold;
+    // the width of the soft transition around threshold.
+    float knee;
+} params;
+
+void main() {
+
+    // average a 4x4 block of source, since the output is in half resolution.
+    vec2 offset = params.inverse_dimension;
+    vec3 color = (
+        texture(source, inUV + vec2(-offset.x, -offset.y)).rgb +
+        texture(source, inUV + vec2( offset.x, -offset.y)).rgb +
+        texture(source, inUV + vec2(-offset.x,  offset.y)).rgb +
+        texture(source, inUV + vec2( offset.x,  offset.y)).rgb) * 0.25;
+
+    // soft threshold with a quadratic curve around the knee.
+    float brightness = max(color.r, max(color.g, color.b));
+    float soft = clamp(brightness - params.threshold + params.knee, 0.0, 2.0 * params.knee);
+    soft = soft * soft / (4.0 * params.knee + 1e-5);
+    float contribution = max(soft, brightness - params.threshold) / max(brightness, 1e-5);
+
+    outColor = vec4(color * contribution, 1.0);
+}
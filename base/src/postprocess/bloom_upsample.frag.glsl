@@ -0,0 +1,35 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D source;
+
+layout (push_constant) uniform UpsampleParams {
+    // the size of a texel in source image.
+    vec2  inverse_dimension;
+    // the scale of filter footprint, which controls how far the bloom spreads.
+    float radius;
+} params;
+
+void main() {
+
+    // 3x3 tent filter, the result is added to the destination level by blending.
+    vec2 offset = params.inverse_dimension * params.radius;
+
+    vec3 color = texture(source, inUV).rgb * 4.0;
+    color += (
+        texture(source, inUV + vec2(-offset.x, 0.0)).rgb +
+        texture(source, inUV + vec2( offset.x, 0.0)).rgb +
+        texture(source, inUV + vec2(0.0, -offset.y)).rgb +
+        texture(source, inUV + vec2(0.0,  offset.y)).rgb) * 2.0;
+    color += (
+        texture(source, inUV + vec2(-offset.x, -offset.y)).rgb +
+        texture(source, inUV + vec2( offset.x, -offset.y)).rgb +
+        texture(source, inUV + vec2(-offset.x,  offset.y)).rgb +
+        texture(source, inUV + vec2( offset.x,  offset.y)).rgb);
+
+    outColor = vec4(color / 16.0, 1.0);
+}
@@ -0,0 +1,32 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D source;
+layout (binding = 1) uniform sampler3D lut;
+
+layout (push_constant) uniform ColorGradeParams {
+    // the input range of LUT, which is DOMAIN_MIN and DOMAIN_MAX in .cube file.
+    vec4  domain_min;
+    vec4  domain_max;
+    // the number of entries along each axis of LUT.
+    float lut_size;
+    // the blend factor between the source color(0.0) and the graded color(1.0).
+    float intensity;
+} params;
+
+void main() {
+
+    vec4 color = texture(source, inUV);
+
+    vec3 normalized = clamp((color.rgb - params.domain_min.rgb) / (params.domain_max.rgb - params.domain_min.rgb), 0.0, 1.0);
+    // remap to the texel centers, so that the first and the last entries are sampled exactly.
+    vec3 uvw = normalized * ((params.lut_size - 1.0) / params.lut_size) + 0.5 / params.lut_size;
+
+    vec3 graded = texture(lut, uvw).rgb;
+
+    outColor = vec4(mix(color.rgb, graded, params.intensity), color.a);
+}
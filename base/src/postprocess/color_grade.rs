@@ -0,0 +1,255 @@
+
+use ash::vk;
+
+use crate::postprocess::{PostProcess, PostProcessConfig, PostProcessInput, PostProcessOutput};
+use crate::texture::{Texture3D, RenderTarget};
+use crate::command::{VkCmdRecorder, IGraphics};
+use crate::context::VkDevice;
+use crate::utils::memory::any_as_u8_slice;
+
+use crate::{VkResult, VkError};
+use crate::{vkfloat, vkuint};
+
+use std::path::Path;
+
+/// The format of LUT texture. The entries of LUT are quantized to 8 bits, which is enough for LDR images.
+const LUT_FORMAT: vk::Format = vk::Format::R8G8B8A8_UNORM;
+/// The size of LUT used before any `.cube` file is loaded.
+const IDENTITY_LUT_SIZE: vkuint = 16;
+
+
+// ----------------------------------------------------------------------------------------------
+/// A 3D color lookup table parsed from `.cube` file(the format defined by Adobe).
+struct CubeLut {
+    /// the number of entries along each axis.
+    size: vkuint,
+    domain_min: [vkfloat; 3],
+    domain_max: [vkfloat; 3],
+    /// the output colors, ordered by red, then green, then blue.
+    table: Vec<[vkfloat; 3]>,
+}
+
+impl CubeLut {
+
+    fn identity(size: vkuint) -> CubeLut {
+
+        let max_index = (size - 1) as vkfloat;
+        let mut table = Vec::with_capacity((size * size * size) as usize);
+
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    table.push([r as vkfloat / max_index, g as vkfloat / max_index, b as vkfloat / max_index]);
+                }
+            }
+        }
+
+        CubeLut { size, domain_min: [0.0; 3], domain_max: [1.0; 3], table }
+    }
+
+    fn parse(content: &str) -> VkResult<CubeLut> {
+
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+
+        for line in content.lines().map(str::trim) {
+
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                | Some("TITLE") => {},
+                | Some("LUT_1D_SIZE") => {
+                    return Err(VkError::unsupported("1D LUT in .cube file"))
+                },
+                | Some("LUT_3D_SIZE") => {
+                    let lut_size = tokens.next()
+                        .and_then(|token| token.parse::<vkuint>().ok())
+                        .filter(|&lut_size| lut_size >= 2 && lut_size <= 256)
+                        .ok_or_else(|| VkError::custom(format!("Invalid LUT size in .cube file: {}", line)))?;
+                    size = Some(lut_size);
+                },
+                | Some("DOMAIN_MIN") => domain_min = parse_triple(tokens, line)?,
+                | Some("DOMAIN_MAX") => domain_max = parse_triple(tokens, line)?,
+                | Some(first) => {
+                    table.push(parse_triple(Some(first).into_iter().chain(tokens), line)?);
+                },
+                | None => {},
+            }
+        }
+
+        let size = size.ok_or_else(|| VkError::custom("Missing LUT_3D_SIZE in .cube file."))?;
+
+        if table.len() != (size * size * size) as usize {
+            return Err(VkError::custom(format!("Expect {} entries for LUT of size {}, but {} are found in .cube file.", size * size * size, size, table.len())))
+        }
+
+        if (0..3).any(|i| domain_max[i] <= domain_min[i]) {
+            return Err(VkError::custom("DOMAIN_MAX must be greater than DOMAIN_MIN in .cube file."))
+        }
+
+        Ok(CubeLut { size, domain_min, domain_max, table })
+    }
+
+    /// Quantize the table into RGBA8 pixels.
+    fn to_pixels(&self) -> Vec<u8> {
+
+        let mut pixels = Vec::with_capacity(self.table.len() * 4);
+        for entry in self.table.iter() {
+            for &channel in entry.iter() {
+                pixels.push((channel.max(0.0).min(1.0) * 255.0).round() as u8);
+            }
+            pixels.push(255);
+        }
+        pixels
+    }
+}
+
+fn parse_triple<'a>(mut tokens: impl Iterator<Item = &'a str>, line: &str) -> VkResult<[vkfloat; 3]> {
+
+    let mut values = [0.0; 3];
+    for value in values.iter_mut() {
+        *value = tokens.next()
+            .and_then(|token| token.parse::<vkfloat>().ok())
+            .ok_or_else(|| VkError::custom(format!("Invalid line in .cube file: {}", line)))?;
+    }
+    Ok(values)
+}
+
+/// The push constants of `color_grade.frag.glsl`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct ColorGradePushConstants {
+    domain_min: [vkfloat; 4],
+    domain_max: [vkfloat; 4],
+    lut_size: vkfloat,
+    intensity: vkfloat,
+}
+// ----------------------------------------------------------------------------------------------
+
+
+// ----------------------------------------------------------------------------------------------
+/// A post-process pass which remaps the colors of source image through a 3D lookup table.
+///
+/// It starts with an identity LUT, and a graded LUT can be loaded from `.cube` file by `ColorGrade::load_lut`.
+pub struct ColorGrade {
+
+    pass: PostProcess,
+
+    lut: Texture3D,
+    lut_size: vkuint,
+    domain_min: [vkfloat; 3],
+    domain_max: [vkfloat; 3],
+
+    /// the inputs created by this pass with their source images, which are rewritten when the LUT is replaced.
+    inputs: Vec<(PostProcessInput, vk::DescriptorImageInfo)>,
+
+    /// the blend factor between the source color(0.0) and the graded color(1.0).
+    pub intensity: vkfloat,
+}
+
+impl ColorGrade {
+
+    /// Create the pass writing to images of `output_format`, which is transitioned to `output_final_layout` after this pass.
+    pub fn new(device: &mut VkDevice, output_format: vk::Format, output_final_layout: vk::ImageLayout) -> VkResult<ColorGrade> {
+
+        let config = PostProcessConfig {
+            output_format, output_final_layout,
+            input_count: 2,
+            push_constant_size: ::std::mem::size_of::<ColorGradePushConstants>() as _,
+            ..Default::default()
+        };
+        let pass = PostProcess::new(device, include_str!("color_grade.frag.glsl"), config)?;
+
+        let identity = CubeLut::identity(IDENTITY_LUT_SIZE);
+        let lut = upload_lut(device, &identity)?;
+
+        let color_grade = ColorGrade {
+            pass, lut,
+            lut_size: identity.size,
+            domain_min: identity.domain_min,
+            domain_max: identity.domain_max,
+            inputs: Vec::new(),
+            intensity: 1.0,
+        };
+        Ok(color_grade)
+    }
+
+    /// Load a 3D LUT from `.cube` file at `path`, and replace the current LUT.
+    ///
+    /// Make sure the device is idle(or the old LUT is not in use) before calling this method.
+    pub fn load_lut(&mut self, device: &mut VkDevice, path: impl AsRef<Path>) -> VkResult<()> {
+
+        let content = ::std::fs::read_to_string(path.as_ref())
+            .map_err(|_| VkError::path(path.as_ref()))?;
+        let cube = CubeLut::parse(&content)?;
+
+        let lut = upload_lut(device, &cube)?;
+        let old_lut = ::std::mem::replace(&mut self.lut, lut);
+
+        self.lut_size = cube.size;
+        self.domain_min = cube.domain_min;
+        self.domain_max = cube.domain_max;
+
+        for (input, source) in self.inputs.iter() {
+            self.pass.update_input(device, input, &[*source, self.lut.descriptor])?;
+        }
+
+        old_lut.discard_by(device)
+    }
+
+    /// Create the input sampling `source`(etc. the tone mapped scene).
+    pub fn create_input(&mut self, device: &VkDevice, source: &RenderTarget) -> VkResult<PostProcessInput> {
+
+        let input = self.pass.create_input(device, &[source.descriptor, self.lut.descriptor])?;
+        self.inputs.push((input, source.descriptor));
+
+        Ok(input)
+    }
+
+    /// Point `input` to `source` after the source image is recreated.
+    pub fn update_input(&mut self, device: &VkDevice, input: &PostProcessInput, source: &RenderTarget) -> VkResult<()> {
+
+        self.pass.update_input(device, input, &[source.descriptor, self.lut.descriptor])?;
+
+        if let Some(record) = self.inputs.iter_mut().find(|(created, _)| created.descriptor_set == input.descriptor_set) {
+            record.1 = source.descriptor;
+        }
+
+        Ok(())
+    }
+
+    /// Create the output writing `view`, whose format must be the `output_format` of this pass.
+    pub fn create_output(&self, device: &VkDevice, view: vk::ImageView, dimension: vk::Extent2D) -> VkResult<PostProcessOutput> {
+        self.pass.create_output(device, view, dimension)
+    }
+
+    /// Record the commands to grade `input` into `output`.
+    pub fn record(&self, recorder: &VkCmdRecorder<IGraphics>, input: &PostProcessInput, output: &PostProcessOutput) {
+
+        let push_constants = ColorGradePushConstants {
+            domain_min: [self.domain_min[0], self.domain_min[1], self.domain_min[2], 0.0],
+            domain_max: [self.domain_max[0], self.domain_max[1], self.domain_max[2], 1.0],
+            lut_size: self.lut_size as vkfloat,
+            intensity: self.intensity,
+        };
+
+        let push_bytes = unsafe { any_as_u8_slice(&push_constants) };
+        self.pass.record(recorder, input, output, push_bytes);
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        self.pass.discard_by(device);
+        self.lut.discard_by(device)
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+fn upload_lut(device: &mut VkDevice, cube: &CubeLut) -> VkResult<Texture3D> {
+    Texture3D::from_bytes(device, &cube.to_pixels(), cube.size, cube.size, cube.size, LUT_FORMAT)
+}
@@ -0,0 +1,358 @@
+
+use ash::vk;
+
+use crate::ci::buffer::BufferCI;
+use crate::ci::vma::{VmaBuffer, VmaAllocationCI};
+use crate::ci::pipeline::{PipelineLayoutCI, ComputePipelineCI};
+use crate::ci::shader::{ShaderModuleCI, ShaderStageCI};
+use crate::ci::VkObjectBuildableCI;
+
+use crate::texture::RenderTarget;
+use crate::command::{VkCmdRecorder, ICompute, CmdComputeApi};
+use crate::context::VkDevice;
+use crate::utils::memory::any_as_u8_slice;
+
+use crate::{VkResult, VkErrorKind};
+use crate::{vkfloat, vkuint, vkbytes, vkptr};
+
+use std::time::Instant;
+use std::ptr;
+
+/// The count of luminance bins, which must match `BIN_COUNT` in the compute shaders.
+const HISTOGRAM_BIN_COUNT: usize = 256;
+/// The size of work group of `exposure_histogram.comp.glsl` in each dimension.
+const HISTOGRAM_GROUP_SIZE: vkuint = 16;
+
+
+// ----------------------------------------------------------------------------------------------
+/// The parameters of eye adaptation, which can be adjusted at runtime.
+#[derive(Debug, Clone, Copy)]
+pub struct AutoExposureParams {
+
+    /// the log2 luminance mapped to the first bin of histogram.
+    pub min_log_luminance: vkfloat,
+    /// the log2 luminance mapped to the last bin of histogram.
+    pub max_log_luminance: vkfloat,
+    /// how fast the exposure adapts to the scene, larger value adapts faster.
+    pub adaptation_speed: vkfloat,
+    /// the middle gray that the average luminance is mapped to.
+    pub key_value: vkfloat,
+    pub min_exposure: vkfloat,
+    pub max_exposure: vkfloat,
+}
+
+impl Default for AutoExposureParams {
+
+    fn default() -> AutoExposureParams {
+        AutoExposureParams {
+            min_log_luminance: -8.0,
+            max_log_luminance: 4.0,
+            adaptation_speed: 1.5,
+            key_value: 0.18,
+            min_exposure: 0.03,
+            max_exposure: 16.0,
+        }
+    }
+}
+
+/// The sampled HDR image of `AutoExposure`, which is created by `AutoExposure::create_input`.
+#[derive(Debug, Clone, Copy)]
+pub struct AutoExposureInput {
+    descriptor_set: vk::DescriptorSet,
+    dimension: vk::Extent2D,
+}
+
+/// The storage buffer shared by the compute shaders, which lives across frames.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct ExposureStorage {
+    histogram: [vkuint; HISTOGRAM_BIN_COUNT],
+    average_luminance: vkfloat,
+    exposure: vkfloat,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct ExposurePushConstants {
+    min_log_luminance: vkfloat,
+    log_luminance_range: vkfloat,
+    adaptation: vkfloat,
+    key_value: vkfloat,
+    min_exposure: vkfloat,
+    max_exposure: vkfloat,
+    dimension: [vkuint; 2],
+}
+// ----------------------------------------------------------------------------------------------
+
+
+// ----------------------------------------------------------------------------------------------
+/// An eye adaptation pass, which computes the exposure of HDR scene on GPU.
+///
+/// A compute shader builds the histogram of log luminance over the HDR image,
+/// then another one averages the histogram and moves the adapted luminance toward it smoothly across frames.
+/// The resulting exposure is read back to feed `TonemapParams::exposure`.
+///
+/// The commands must be recorded outside of render pass, after the HDR image is rendered.
+/// Since the exposure is read back from previous frames, it lags a frame or more behind the scene,
+/// and the commands should be re-recorded every frame to pass the latest exposure to the tonemap pass.
+pub struct AutoExposure {
+
+    histogram_pipeline: vk::Pipeline,
+    average_pipeline: vk::Pipeline,
+    pipeline_layout: vk::PipelineLayout,
+
+    descriptor_pool: vk::DescriptorPool,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+
+    /// persistently mapped, so that the exposure can be read back without extra copy.
+    storage: VmaBuffer,
+
+    /// the time of last update, or `None` if it has never been updated.
+    last_update: Option<Instant>,
+
+    pub params: AutoExposureParams,
+}
+
+impl AutoExposure {
+
+    /// Create the pass, which can create at most `max_inputs` `AutoExposureInput`s.
+    pub fn new(device: &mut VkDevice, max_inputs: vkuint) -> VkResult<AutoExposure> {
+
+        let params = AutoExposureParams::default();
+
+        let storage = allocate_storage(device, &params)?;
+        let (descriptor_pool, descriptor_set_layout) = setup_descriptor(device, max_inputs)?;
+        let (histogram_pipeline, average_pipeline, pipeline_layout) = prepare_pipelines(device, descriptor_set_layout)?;
+
+        let exposure = AutoExposure {
+            histogram_pipeline, average_pipeline, pipeline_layout,
+            descriptor_pool, descriptor_set_layout,
+            storage,
+            last_update: None,
+            params,
+        };
+        Ok(exposure)
+    }
+
+    /// Create the input sampling `hdr`(the HDR scene), which must be in `SHADER_READ_ONLY_OPTIMAL` layout when the pass is recorded.
+    pub fn create_input(&self, device: &VkDevice, hdr: &RenderTarget) -> VkResult<AutoExposureInput> {
+
+        let descriptor_set = device.allocate_descriptor_set(self.descriptor_pool, self.descriptor_set_layout)?;
+        let mut input = AutoExposureInput { descriptor_set, dimension: hdr.dimension };
+        self.update_input(device, &mut input, hdr);
+
+        Ok(input)
+    }
+
+    /// Point `input` to `hdr` after it is recreated.
+    pub fn update_input(&self, device: &VkDevice, input: &mut AutoExposureInput, hdr: &RenderTarget) {
+
+        use crate::ci::descriptor::{DescriptorImageSetWI, DescriptorBufferSetWI, DescriptorSetsUpdateCI};
+
+        input.dimension = hdr.dimension;
+
+        let image_write_info = DescriptorImageSetWI::new(input.descriptor_set, 0, vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .add_image(hdr.descriptor);
+        let buffer_write_info = DescriptorBufferSetWI::new(input.descriptor_set, 1, vk::DescriptorType::STORAGE_BUFFER)
+            .add_buffer(vk::DescriptorBufferInfo {
+                buffer: self.storage.handle,
+                offset: 0,
+                range : vk::WHOLE_SIZE,
+            });
+
+        DescriptorSetsUpdateCI::new()
+            .add_write(&image_write_info)
+            .add_write(&buffer_write_info)
+            .update(device);
+    }
+
+    /// Record the commands to compute the exposure of `hdr_input`, and return the latest exposure computed by GPU.
+    ///
+    /// The time between two calls is used to adapt the exposure smoothly, so call it once per frame.
+    pub fn update(&mut self, recorder: &VkCmdRecorder<ICompute>, hdr_input: &AutoExposureInput) -> vkfloat {
+
+        let now = Instant::now();
+        // snap to the scene luminance on the first update.
+        let adaptation = match self.last_update {
+            | Some(last_update) => {
+                let delta_time = now.duration_since(last_update);
+                let delta_seconds = delta_time.as_secs() as vkfloat + delta_time.subsec_nanos() as vkfloat * 1e-9;
+                1.0 - (-delta_seconds * self.params.adaptation_speed).exp()
+            },
+            | None => 1.0,
+        };
+        self.last_update = Some(now);
+
+        let push_constants = ExposurePushConstants {
+            min_log_luminance: self.params.min_log_luminance,
+            log_luminance_range: (self.params.max_log_luminance - self.params.min_log_luminance).max(0.001),
+            adaptation,
+            key_value: self.params.key_value,
+            min_exposure: self.params.min_exposure,
+            max_exposure: self.params.max_exposure,
+            dimension: [hdr_input.dimension.width, hdr_input.dimension.height],
+        };
+
+        // wait for the HDR image to be rendered, and the previous frame to finish with the storage buffer.
+        let begin_barrier = vk::MemoryBarrier {
+            src_access_mask: vk::AccessFlags::COLOR_ATTACHMENT_WRITE | vk::AccessFlags::SHADER_WRITE,
+            dst_access_mask: vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE,
+            ..Default::default()
+        };
+        let histogram_barrier = self.storage_barrier(vk::AccessFlags::SHADER_WRITE, vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE);
+        let readback_barrier = self.storage_barrier(vk::AccessFlags::SHADER_WRITE, vk::AccessFlags::HOST_READ);
+
+        let group_count_x = (hdr_input.dimension.width  + HISTOGRAM_GROUP_SIZE - 1) / HISTOGRAM_GROUP_SIZE;
+        let group_count_y = (hdr_input.dimension.height + HISTOGRAM_GROUP_SIZE - 1) / HISTOGRAM_GROUP_SIZE;
+
+        recorder
+            .memory_pipeline_barrier(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | vk::PipelineStageFlags::COMPUTE_SHADER, vk::PipelineStageFlags::COMPUTE_SHADER, vk::DependencyFlags::empty(), &[begin_barrier], &[])
+            .bind_descriptor_sets(self.pipeline_layout, 0, &[hdr_input.descriptor_set], &[])
+            .push_constants(self.pipeline_layout, vk::ShaderStageFlags::COMPUTE, 0, unsafe { any_as_u8_slice(&push_constants) })
+            .bind_pipeline(self.histogram_pipeline)
+            .dispatch(group_count_x, group_count_y, 1)
+            .memory_pipeline_barrier(vk::PipelineStageFlags::COMPUTE_SHADER, vk::PipelineStageFlags::COMPUTE_SHADER, vk::DependencyFlags::empty(), &[], &[histogram_barrier])
+            .bind_pipeline(self.average_pipeline)
+            .dispatch(1, 1, 1)
+            .memory_pipeline_barrier(vk::PipelineStageFlags::COMPUTE_SHADER, vk::PipelineStageFlags::HOST, vk::DependencyFlags::empty(), &[], &[readback_barrier]);
+
+        self.exposure()
+    }
+
+    /// The latest exposure computed by GPU.
+    pub fn exposure(&self) -> vkfloat {
+
+        unsafe {
+            let storage_ptr = self.storage.info.get_mapped_data() as vkptr<ExposureStorage>;
+            debug_assert_ne!(storage_ptr, ptr::null_mut());
+
+            ptr::read_volatile(&(*storage_ptr).exposure)
+        }
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        device.discard(self.histogram_pipeline);
+        device.discard(self.average_pipeline);
+        device.discard(self.pipeline_layout);
+
+        device.discard(self.descriptor_set_layout);
+        device.discard(self.descriptor_pool);
+
+        device.vma_discard(self.storage)
+    }
+
+    fn storage_barrier(&self, src_access: vk::AccessFlags, dst_access: vk::AccessFlags) -> vk::BufferMemoryBarrier {
+        vk::BufferMemoryBarrier {
+            src_access_mask: src_access,
+            dst_access_mask: dst_access,
+            src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            buffer: self.storage.handle,
+            offset: 0,
+            size  : vk::WHOLE_SIZE,
+            ..Default::default()
+        }
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+fn allocate_storage(device: &mut VkDevice, params: &AutoExposureParams) -> VkResult<VmaBuffer> {
+
+    let buffer_ci = BufferCI::new(::std::mem::size_of::<ExposureStorage>() as vkbytes)
+        .usage(vk::BufferUsageFlags::STORAGE_BUFFER);
+    let allocation_ci = VmaAllocationCI::new(vma::MemoryUsage::CpuToGpu, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT)
+        .flags(vma::AllocationCreateFlags::MAPPED);
+    let buffer_allocation = device.vma.create_buffer(buffer_ci.as_ref(), allocation_ci.as_ref())
+        .map_err(VkErrorKind::Vma)?;
+    let storage = VmaBuffer::from(buffer_allocation);
+
+    // start from an empty histogram and the luminance of middle gray(the exposure is 1.0).
+    let initial_storage = ExposureStorage {
+        histogram: [0; HISTOGRAM_BIN_COUNT],
+        average_luminance: params.key_value,
+        exposure: 1.0,
+    };
+
+    unsafe {
+        let storage_ptr = storage.info.get_mapped_data() as vkptr<ExposureStorage>;
+        debug_assert_ne!(storage_ptr, ptr::null_mut());
+        storage_ptr.copy_from_nonoverlapping(&initial_storage, 1);
+    }
+
+    Ok(storage)
+}
+
+fn setup_descriptor(device: &VkDevice, max_inputs: vkuint) -> VkResult<(vk::DescriptorPool, vk::DescriptorSetLayout)> {
+
+    use crate::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
+
+    let max_inputs = max_inputs.max(1);
+
+    let descriptor_pool = DescriptorPoolCI::new(max_inputs)
+        .add_descriptor(vk::DescriptorType::COMBINED_IMAGE_SAMPLER, max_inputs)
+        .add_descriptor(vk::DescriptorType::STORAGE_BUFFER, max_inputs)
+        .build(device)?;
+
+    // layout (binding = 0) uniform sampler2D hdr_source;
+    let hdr_binding = vk::DescriptorSetLayoutBinding {
+        binding: 0,
+        descriptor_type: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+        descriptor_count: 1,
+        stage_flags: vk::ShaderStageFlags::COMPUTE,
+        p_immutable_samplers: ptr::null(),
+    };
+    // layout (std430, binding = 1) buffer ExposureStorage;
+    let storage_binding = vk::DescriptorSetLayoutBinding {
+        binding: 1,
+        descriptor_type: vk::DescriptorType::STORAGE_BUFFER,
+        descriptor_count: 1,
+        stage_flags: vk::ShaderStageFlags::COMPUTE,
+        p_immutable_samplers: ptr::null(),
+    };
+
+    let set_layout = DescriptorSetLayoutCI::new()
+        .add_binding(hdr_binding)
+        .add_binding(storage_binding)
+        .build(device)?;
+
+    Ok((descriptor_pool, set_layout))
+}
+
+fn prepare_pipelines(device: &VkDevice, set_layout: vk::DescriptorSetLayout) -> VkResult<(vk::Pipeline, vk::Pipeline, vk::PipelineLayout)> {
+
+    // both shaders share the same descriptor set and push constants.
+    let pipeline_layout = PipelineLayoutCI::new()
+        .add_set_layout(set_layout)
+        .add_push_constants(vk::PushConstantRange {
+            stage_flags: vk::ShaderStageFlags::COMPUTE,
+            offset: 0,
+            size: ::std::mem::size_of::<ExposurePushConstants>() as _,
+        }).build(device)?;
+
+    let mut shader_compiler = crate::utils::shaderc::VkShaderCompiler::new()?;
+    let histogram_codes = shader_compiler.compile_from_str(
+        include_str!("exposure_histogram.comp.glsl"),
+        shaderc::ShaderKind::Compute,
+        "[Compute Shader]",
+        "main")?;
+    let average_codes = shader_compiler.compile_from_str(
+        include_str!("exposure_average.comp.glsl"),
+        shaderc::ShaderKind::Compute,
+        "[Compute Shader]",
+        "main")?;
+
+    let histogram_module = ShaderModuleCI::new(histogram_codes).build(device)?;
+    let average_module = ShaderModuleCI::new(average_codes).build(device)?;
+
+    let histogram_shader = ShaderStageCI::new(vk::ShaderStageFlags::COMPUTE, histogram_module);
+    let average_shader = ShaderStageCI::new(vk::ShaderStageFlags::COMPUTE, average_module);
+
+    let histogram_pipeline = ComputePipelineCI::new(&histogram_shader, pipeline_layout).build(device)?;
+    let average_pipeline = ComputePipelineCI::new(&average_shader, pipeline_layout).build(device)?;
+
+    device.discard(histogram_module);
+    device.discard(average_module);
+
+    Ok((histogram_pipeline, average_pipeline, pipeline_layout))
+}
@@ -0,0 +1,59 @@
+#version 450 core
+
+// the count of histogram bins, which must match the size of work group.
+#define BIN_COUNT 256
+
+layout (local_size_x = BIN_COUNT) in;
+
+layout (std430, binding = 1) buffer ExposureStorage {
+    uint  histogram[BIN_COUNT];
+    float average_luminance;
+    float exposure;
+} storage;
+
+layout (push_constant) uniform ExposureParams {
+    float min_log_luminance;
+    float log_luminance_range;
+    float adaptation;
+    float key_value;
+    float min_exposure;
+    float max_exposure;
+    uvec2 dimension;
+} params;
+
+shared float weighted_bins[BIN_COUNT];
+
+void main() {
+
+    uint bin = gl_LocalInvocationIndex;
+    uint bin_count = storage.histogram[bin];
+
+    weighted_bins[bin] = float(bin_count) * float(bin);
+    // clear the histogram for the next frame.
+    storage.histogram[bin] = 0;
+    barrier();
+
+    // parallel reduction to sum the weighted bins.
+    for (uint cutoff = BIN_COUNT / 2; cutoff > 0; cutoff >>= 1) {
+        if (bin < cutoff) {
+            weighted_bins[bin] += weighted_bins[bin + cutoff];
+        }
+        barrier();
+    }
+
+    if (bin == 0) {
+        // here `bin_count` is the count of black pixels, which are ignored.
+        float pixel_count = float(params.dimension.x * params.dimension.y);
+        float lit_count = max(pixel_count - float(bin_count), 1.0);
+
+        float average_bin = weighted_bins[0] / lit_count;
+        float average_log = (average_bin - 1.0) / float(BIN_COUNT - 2) * params.log_luminance_range + params.min_log_luminance;
+        float luminance = exp2(average_log);
+
+        // move toward the luminance of current frame gradually, as the eyes adapt to the light.
+        float adapted = mix(storage.average_luminance, luminance, params.adaptation);
+
+        storage.average_luminance = adapted;
+        storage.exposure = clamp(params.key_value / max(adapted, 0.0001), params.min_exposure, params.max_exposure);
+    }
+}
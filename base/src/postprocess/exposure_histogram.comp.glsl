@@ -0,0 +1,54 @@
+#version 450 core
+
+// the count of histogram bins, which must match the size of work group.
+#define BIN_COUNT 256
+
+layout (local_size_x = 16, local_size_y = 16) in;
+
+layout (binding = 0) uniform sampler2D hdr_source;
+
+layout (std430, binding = 1) buffer ExposureStorage {
+    uint  histogram[BIN_COUNT];
+    float average_luminance;
+    float exposure;
+} storage;
+
+layout (push_constant) uniform ExposureParams {
+    float min_log_luminance;
+    float log_luminance_range;
+    float adaptation;
+    float key_value;
+    float min_exposure;
+    float max_exposure;
+    uvec2 dimension;
+} params;
+
+shared uint local_histogram[BIN_COUNT];
+
+uint luminance_bin(vec3 color) {
+
+    float luminance = dot(color, vec3(0.2126, 0.7152, 0.0722));
+
+    // the nearly black pixels are collected in the first bin, which is excluded from the average.
+    if (luminance < 0.0001) {
+        return 0;
+    }
+
+    float log_luminance = clamp((log2(luminance) - params.min_log_luminance) / params.log_luminance_range, 0.0, 1.0);
+    return uint(log_luminance * float(BIN_COUNT - 2) + 1.0);
+}
+
+void main() {
+
+    local_histogram[gl_LocalInvocationIndex] = 0;
+    barrier();
+
+    if (all(lessThan(gl_GlobalInvocationID.xy, params.dimension))) {
+        vec3 color = texelFetch(hdr_source, ivec2(gl_GlobalInvocationID.xy), 0).rgb;
+        atomicAdd(local_histogram[luminance_bin(color)], 1);
+    }
+    barrier();
+
+    // merge the histogram of this work group to the global histogram.
+    atomicAdd(storage.histogram[gl_LocalInvocationIndex], local_histogram[gl_LocalInvocationIndex]);
+}
@@ -0,0 +1,15 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) out vec2 outUV;
+
+out gl_PerVertex {
+    vec4 gl_Position;
+};
+
+void main() {
+
+    // generate a triangle covering the whole screen from vertex index 0, 1, 2.
+    outUV = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(outUV * 2.0 - 1.0, 0.0, 1.0);
+}
@@ -0,0 +1,163 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+// A compact version of FXAA 3.11 quality preset by Timothy Lottes.
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D source;
+
+layout (push_constant) uniform FxaaParams {
+    vec2  inverse_dimension;
+    float subpixel;
+    float edge_threshold;
+    float edge_threshold_min;
+    // non-zero if the luma has been stored in alpha channel by the luma prepass.
+    uint  is_luma_in_alpha;
+    // zero to pass the source through without anti-aliasing.
+    uint  is_enable;
+} params;
+
+const int   SEARCH_STEPS = 12;
+const float SEARCH_QUALITY[SEARCH_STEPS] = float[](1.0, 1.0, 1.0, 1.0, 1.0, 1.5, 2.0, 2.0, 2.0, 2.0, 4.0, 8.0);
+
+float luma_of(vec4 color) {
+    return (params.is_luma_in_alpha != 0) ? color.a : sqrt(dot(color.rgb, vec3(0.299, 0.587, 0.114)));
+}
+
+float luma_at(vec2 uv) {
+    return luma_of(textureLod(source, uv, 0.0));
+}
+
+float luma_offset(vec2 uv, ivec2 offset) {
+    return luma_of(textureLodOffset(source, uv, 0.0, offset));
+}
+
+void main() {
+
+    vec4 center_color = textureLod(source, inUV, 0.0);
+
+    if (params.is_enable == 0) {
+        outColor = vec4(center_color.rgb, 1.0);
+        return;
+    }
+
+    vec2 texel = params.inverse_dimension;
+
+    // local contrast check.
+    float luma_m = luma_of(center_color);
+    float luma_s = luma_offset(inUV, ivec2( 0,  1));
+    float luma_e = luma_offset(inUV, ivec2( 1,  0));
+    float luma_n = luma_offset(inUV, ivec2( 0, -1));
+    float luma_w = luma_offset(inUV, ivec2(-1,  0));
+
+    float range_max = max(max(max(luma_s, luma_e), max(luma_n, luma_w)), luma_m);
+    float range_min = min(min(min(luma_s, luma_e), min(luma_n, luma_w)), luma_m);
+    float range = range_max - range_min;
+
+    if (range < max(params.edge_threshold_min, range_max * params.edge_threshold)) {
+        outColor = vec4(center_color.rgb, 1.0);
+        return;
+    }
+
+    float luma_nw = luma_offset(inUV, ivec2(-1, -1));
+    float luma_se = luma_offset(inUV, ivec2( 1,  1));
+    float luma_ne = luma_offset(inUV, ivec2( 1, -1));
+    float luma_sw = luma_offset(inUV, ivec2(-1,  1));
+
+    // subpixel aliasing blend factor.
+    float luma_ns = luma_n + luma_s;
+    float luma_we = luma_w + luma_e;
+    float subpixel_average = (2.0 * (luma_ns + luma_we) + (luma_nw + luma_ne + luma_sw + luma_se)) / 12.0;
+    float subpixel_offset = clamp(abs(subpixel_average - luma_m) / range, 0.0, 1.0);
+    subpixel_offset = smoothstep(0.0, 1.0, subpixel_offset);
+    float subpixel_blend = subpixel_offset * subpixel_offset * params.subpixel;
+
+    // determine whether the edge is horizontal or vertical.
+    float edge_horizontal =
+        abs(luma_nw + luma_sw - 2.0 * luma_w) +
+        abs(luma_ns - 2.0 * luma_m) * 2.0 +
+        abs(luma_ne + luma_se - 2.0 * luma_e);
+    float edge_vertical =
+        abs(luma_nw + luma_ne - 2.0 * luma_n) +
+        abs(luma_we - 2.0 * luma_m) * 2.0 +
+        abs(luma_sw + luma_se - 2.0 * luma_s);
+    bool is_horizontal = edge_horizontal >= edge_vertical;
+
+    // choose the side of the edge with the larger gradient.
+    float luma_positive = is_horizontal ? luma_s : luma_e;
+    float luma_negative = is_horizontal ? luma_n : luma_w;
+    float gradient_positive = abs(luma_positive - luma_m);
+    float gradient_negative = abs(luma_negative - luma_m);
+
+    float step_length = is_horizontal ? texel.y : texel.x;
+    float luma_opposite;
+    float gradient;
+    if (gradient_positive < gradient_negative) {
+        step_length   = -step_length;
+        luma_opposite = luma_negative;
+        gradient      = gradient_negative;
+    } else {
+        luma_opposite = luma_positive;
+        gradient      = gradient_positive;
+    }
+
+    // search along the edge in both directions for its end points.
+    vec2 edge_uv = inUV;
+    vec2 edge_step;
+    if (is_horizontal) {
+        edge_uv.y += step_length * 0.5;
+        edge_step = vec2(texel.x, 0.0);
+    } else {
+        edge_uv.x += step_length * 0.5;
+        edge_step = vec2(0.0, texel.y);
+    }
+
+    float edge_luma = (luma_m + luma_opposite) * 0.5;
+    float gradient_threshold = gradient * 0.25;
+
+    vec2 uv_positive = edge_uv + edge_step;
+    vec2 uv_negative = edge_uv - edge_step;
+    float delta_positive = luma_at(uv_positive) - edge_luma;
+    float delta_negative = luma_at(uv_negative) - edge_luma;
+    bool is_positive_end = abs(delta_positive) >= gradient_threshold;
+    bool is_negative_end = abs(delta_negative) >= gradient_threshold;
+
+    for (int i = 1; i < SEARCH_STEPS && !(is_positive_end && is_negative_end); i++) {
+        if (!is_positive_end) {
+            uv_positive += edge_step * SEARCH_QUALITY[i];
+            delta_positive = luma_at(uv_positive) - edge_luma;
+            is_positive_end = abs(delta_positive) >= gradient_threshold;
+        }
+        if (!is_negative_end) {
+            uv_negative -= edge_step * SEARCH_QUALITY[i];
+            delta_negative = luma_at(uv_negative) - edge_luma;
+            is_negative_end = abs(delta_negative) >= gradient_threshold;
+        }
+    }
+
+    // estimate the blend factor from the distance to the nearest end point.
+    float distance_positive = is_horizontal ? (uv_positive.x - inUV.x) : (uv_positive.y - inUV.y);
+    float distance_negative = is_horizontal ? (inUV.x - uv_negative.x) : (inUV.y - uv_negative.y);
+    bool is_positive_nearer = distance_positive <= distance_negative;
+    float distance_nearest = min(distance_positive, distance_negative);
+    float edge_length = distance_positive + distance_negative;
+
+    bool is_center_smaller = (luma_m - edge_luma) < 0.0;
+    float delta_end = is_positive_nearer ? delta_positive : delta_negative;
+    bool is_correct_variation = (delta_end < 0.0) != is_center_smaller;
+
+    float edge_blend = is_correct_variation ? (0.5 - distance_nearest / edge_length) : 0.0;
+    float final_blend = max(edge_blend, subpixel_blend);
+
+    vec2 final_uv = inUV;
+    if (is_horizontal) {
+        final_uv.y += final_blend * step_length;
+    } else {
+        final_uv.x += final_blend * step_length;
+    }
+
+    outColor = vec4(textureLod(source, final_uv, 0.0).rgb, 1.0);
+}
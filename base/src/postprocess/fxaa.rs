@@ -0,0 +1,233 @@
+
+use ash::vk;
+
+use crate::postprocess::{PostProcess, PostProcessConfig, PostProcessInput, PostProcessOutput};
+use crate::texture::RenderTarget;
+use crate::command::{VkCmdRecorder, IGraphics};
+use crate::context::VkDevice;
+use crate::utils::memory::any_as_u8_slice;
+
+use crate::VkResult;
+use crate::{vkfloat, vkuint};
+
+/// The format of the intermediate image written by the luma prepass.
+const LUMA_TARGET_FORMAT: vk::Format = vk::Format::R8G8B8A8_UNORM;
+
+
+// ----------------------------------------------------------------------------------------------
+/// The tuning parameters of FXAA, whose default values are the default quality preset of FXAA 3.11.
+#[derive(Debug, Clone, Copy)]
+pub struct FxaaParams {
+
+    /// the amount of subpixel aliasing removal, in range [0, 1](0 is off, 1 is softer).
+    pub subpixel: vkfloat,
+    /// the minimum amount of local contrast to apply the algorithm(etc. 0.333 is too little but faster, 0.063 is overkill and slower).
+    pub edge_threshold: vkfloat,
+    /// the threshold that trims the algorithm from processing darks.
+    pub edge_threshold_min: vkfloat,
+}
+
+impl Default for FxaaParams {
+
+    fn default() -> FxaaParams {
+        FxaaParams {
+            subpixel: 0.75,
+            edge_threshold: 0.166,
+            edge_threshold_min: 0.0833,
+        }
+    }
+}
+
+/// The push constants of `fxaa.frag.glsl`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct FxaaPushConstants {
+    inverse_dimension: [vkfloat; 2],
+    subpixel: vkfloat,
+    edge_threshold: vkfloat,
+    edge_threshold_min: vkfloat,
+    is_luma_in_alpha: vkuint,
+    is_enable: vkuint,
+}
+
+/// The pass computing luma into alpha channel before FXAA, with its intermediate image.
+struct LumaPrepass {
+    pass: PostProcess,
+    target: RenderTarget,
+    output: PostProcessOutput,
+    /// the input of FXAA pass, which samples `target`.
+    fxaa_input: PostProcessInput,
+}
+// ----------------------------------------------------------------------------------------------
+
+
+// ----------------------------------------------------------------------------------------------
+/// A ready-made FXAA(Fast Approximate Anti-Aliasing) pass, which smooths the edges of an LDR image.
+///
+/// It is a cheaper alternative to MSAA, and should be applied after tone mapping.
+/// If it is created with luma prepass, the luma of source image is computed into alpha channel of an intermediate image first,
+/// so that FXAA does not need to compute it for each sample.
+///
+/// FXAA can be toggled by `set_enabled` at runtime. When disabled, the source image is copied to the output unchanged.
+pub struct Fxaa {
+
+    fxaa_pass: PostProcess,
+    luma: Option<LumaPrepass>,
+
+    /// the dimension of the source image.
+    dimension: vk::Extent2D,
+    is_enabled: bool,
+
+    pub params: FxaaParams,
+}
+
+impl Fxaa {
+
+    /// Create the FXAA pass for source images of `dimension`, writing to images of `output_format`.
+    ///
+    /// `output_final_layout` is the layout of output image after this pass(see `PostProcessConfig::output_final_layout`).
+    pub fn new(device: &mut VkDevice, output_format: vk::Format, output_final_layout: vk::ImageLayout, dimension: vk::Extent2D, is_luma_prepass: bool) -> VkResult<Fxaa> {
+
+        let fxaa_config = PostProcessConfig {
+            output_format, output_final_layout,
+            push_constant_size: ::std::mem::size_of::<FxaaPushConstants>() as _,
+            ..Default::default()
+        };
+        let fxaa_pass = PostProcess::new(device, include_str!("fxaa.frag.glsl"), fxaa_config)?;
+
+        let luma = if is_luma_prepass {
+            Some(LumaPrepass::new(device, &fxaa_pass, dimension)?)
+        } else {
+            None
+        };
+
+        let fxaa = Fxaa { fxaa_pass, luma, dimension, is_enabled: true, params: FxaaParams::default() };
+        Ok(fxaa)
+    }
+
+    /// Create the input sampling `source`(etc. the tone mapped scene), whose dimension must match the dimension of this pass.
+    pub fn create_input(&self, device: &VkDevice, source: &RenderTarget) -> VkResult<PostProcessInput> {
+        self.first_pass().create_input(device, &[source.descriptor])
+    }
+
+    /// Point `input` to `source` after the source image is recreated.
+    pub fn update_input(&self, device: &VkDevice, input: &PostProcessInput, source: &RenderTarget) -> VkResult<()> {
+        self.first_pass().update_input(device, input, &[source.descriptor])
+    }
+
+    /// Create the output writing `view`(etc. a swapchain image view), whose format must be the `output_format` of this pass.
+    pub fn create_output(&self, device: &VkDevice, view: vk::ImageView, dimension: vk::Extent2D) -> VkResult<PostProcessOutput> {
+        self.fxaa_pass.create_output(device, view, dimension)
+    }
+
+    /// Update the dimension of source image, and recreate the intermediate image of luma prepass if necessary.
+    ///
+    /// Call this method in `RenderWorkflow::swapchain_reload` if the source image is resized.
+    pub fn resize(&mut self, device: &mut VkDevice, dimension: vk::Extent2D) -> VkResult<()> {
+
+        self.dimension = dimension;
+
+        if let Some(luma) = self.luma.as_mut() {
+            luma.resize(device, &self.fxaa_pass, dimension)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn set_enabled(&mut self, is_enabled: bool) {
+        self.is_enabled = is_enabled;
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+
+    #[inline]
+    pub fn is_luma_prepass(&self) -> bool {
+        self.luma.is_some()
+    }
+
+    /// Record the commands to anti-alias `input` into `output`.
+    ///
+    /// `input` must be created by `Fxaa::create_input` and `output` must be created by `Fxaa::create_output`.
+    pub fn record(&self, recorder: &VkCmdRecorder<IGraphics>, input: &PostProcessInput, output: &PostProcessOutput) {
+
+        let push_constants = FxaaPushConstants {
+            inverse_dimension: [1.0 / self.dimension.width as vkfloat, 1.0 / self.dimension.height as vkfloat],
+            subpixel: self.params.subpixel,
+            edge_threshold: self.params.edge_threshold,
+            edge_threshold_min: self.params.edge_threshold_min,
+            is_luma_in_alpha: self.luma.is_some() as vkuint,
+            is_enable: self.is_enabled as vkuint,
+        };
+        let push_bytes = unsafe { any_as_u8_slice(&push_constants) };
+
+        match self.luma {
+            | Some(ref luma) => {
+                luma.pass.record(recorder, input, &luma.output, &[]);
+                self.fxaa_pass.record(recorder, &luma.fxaa_input, output, push_bytes);
+            },
+            | None => {
+                self.fxaa_pass.record(recorder, input, output, push_bytes);
+            },
+        }
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        if let Some(luma) = self.luma {
+            luma.discard_by(device)?;
+        }
+
+        self.fxaa_pass.discard_by(device);
+        Ok(())
+    }
+
+    /// The pass sampling the source image.
+    fn first_pass(&self) -> &PostProcess {
+        self.luma.as_ref().map(|luma| &luma.pass).unwrap_or(&self.fxaa_pass)
+    }
+}
+
+impl LumaPrepass {
+
+    fn new(device: &mut VkDevice, fxaa_pass: &PostProcess, dimension: vk::Extent2D) -> VkResult<LumaPrepass> {
+
+        let luma_config = PostProcessConfig {
+            output_format: LUMA_TARGET_FORMAT,
+            output_final_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            ..Default::default()
+        };
+        let pass = PostProcess::new(device, include_str!("fxaa_luma.frag.glsl"), luma_config)?;
+
+        let target = RenderTarget::new(device, LUMA_TARGET_FORMAT, dimension)?;
+        let output = pass.create_output(device, target.view, dimension)?;
+        let fxaa_input = fxaa_pass.create_input(device, &[target.descriptor])?;
+
+        let prepass = LumaPrepass { pass, target, output, fxaa_input };
+        Ok(prepass)
+    }
+
+    fn resize(&mut self, device: &mut VkDevice, fxaa_pass: &PostProcess, dimension: vk::Extent2D) -> VkResult<()> {
+
+        let target = RenderTarget::new(device, LUMA_TARGET_FORMAT, dimension)?;
+        let output = self.pass.create_output(device, target.view, dimension)?;
+        fxaa_pass.update_input(device, &self.fxaa_input, &[target.descriptor])?;
+
+        let old_target = ::std::mem::replace(&mut self.target, target);
+        let old_output = ::std::mem::replace(&mut self.output, output);
+
+        old_output.discard_by(device);
+        old_target.discard_by(device)
+    }
+
+    fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        self.output.discard_by(device);
+        self.pass.discard_by(device);
+        self.target.discard_by(device)
+    }
+}
+// ----------------------------------------------------------------------------------------------
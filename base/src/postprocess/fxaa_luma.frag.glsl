@@ -0,0 +1,16 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D source;
+
+void main() {
+
+    vec3 color = texture(source, inUV).rgb;
+
+    // store the perceptual luma in alpha channel, which is read by FXAA pass.
+    outColor = vec4(color, sqrt(dot(color, vec3(0.299, 0.587, 0.114))));
+}
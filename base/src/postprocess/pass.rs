@@ -0,0 +1,347 @@
+
+use ash::vk;
+
+use crate::ci::pipeline::{RenderPassCI, AttachmentDescCI, SubpassDescCI, SubpassDependencyCI};
+use crate::ci::pipeline::{FramebufferCI, RenderPassBI};
+use crate::ci::shader::{ShaderModuleCI, ShaderStageCI};
+use crate::ci::VkObjectBuildableCI;
+
+use crate::command::{VkCmdRecorder, IGraphics, CmdGraphicsApi};
+use crate::context::VkDevice;
+
+use crate::{VkResult, VkError};
+use crate::vkuint;
+
+
+// ----------------------------------------------------------------------------------------------
+/// The configuration of a `PostProcess` pass.
+#[derive(Debug, Clone)]
+pub struct PostProcessConfig {
+
+    /// the format of the image this pass writes to.
+    pub output_format: vk::Format,
+    /// the layout of the output image after this pass.
+    ///
+    /// Use `SHADER_READ_ONLY_OPTIMAL` if the output is sampled by the next pass, or `PRESENT_SRC_KHR` if it is a swapchain image.
+    pub output_final_layout: vk::ImageLayout,
+    /// whether to add the result to the previous content of output image, instead of overwriting it.
+    ///
+    /// If enabled, `output_initial_layout` must be the actual layout of output image before this pass.
+    pub is_additive_blend: bool,
+    /// the layout of output image before this pass, which is only used when `is_additive_blend` is enabled.
+    pub output_initial_layout: vk::ImageLayout,
+    /// the number of sampled images, which are bound to `binding = 0, 1, ...` in the fragment shader.
+    pub input_count: vkuint,
+    /// the maximum number of `PostProcessInput` that can be created from this pass.
+    pub max_inputs: vkuint,
+    /// the size of push constants in bytes(visible to fragment shader), or 0 if the shader does not use push constants.
+    pub push_constant_size: vkuint,
+}
+
+impl Default for PostProcessConfig {
+
+    fn default() -> PostProcessConfig {
+        PostProcessConfig {
+            output_format: vk::Format::R8G8B8A8_UNORM,
+            output_final_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            is_additive_blend: false,
+            output_initial_layout: vk::ImageLayout::UNDEFINED,
+            input_count: 1,
+            max_inputs : 4,
+            push_constant_size: 0,
+        }
+    }
+}
+
+/// The sampled images of a `PostProcess` pass(a descriptor set of its set layout).
+#[derive(Debug, Clone, Copy)]
+pub struct PostProcessInput {
+    pub descriptor_set: vk::DescriptorSet,
+}
+
+/// The image that a `PostProcess` pass writes to(a framebuffer of its render pass).
+#[derive(Debug, Clone, Copy)]
+pub struct PostProcessOutput {
+    pub framebuffer: vk::Framebuffer,
+    pub dimension: vk::Extent2D,
+}
+
+impl PostProcessOutput {
+
+    pub fn discard_by(self, device: &VkDevice) {
+        device.discard(self.framebuffer);
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+
+// ----------------------------------------------------------------------------------------------
+/// A fullscreen pass which runs a fragment shader over its output image, and samples its inputs(etc. a `RenderTarget`).
+///
+/// The vertex shader draws a single triangle covering the screen, and passes the uv coordinate at `location = 0` to the fragment shader.
+/// The sampled images must be in `SHADER_READ_ONLY_OPTIMAL` layout when the pass is recorded.
+///
+/// The viewport and scissor are dynamic states, so it is not necessary to rebuild the pass when the window is resized,
+/// but the inputs and outputs referring to the old images must be updated or recreated.
+pub struct PostProcess {
+
+    pub render_pass: vk::RenderPass,
+
+    pipeline: vk::Pipeline,
+    pipeline_layout: vk::PipelineLayout,
+
+    descriptor_pool: vk::DescriptorPool,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+
+    config: PostProcessConfig,
+}
+
+impl PostProcess {
+
+    /// Create a pass running `fragment_source`(GLSL source codes).
+    pub fn new(device: &VkDevice, fragment_source: &str, config: PostProcessConfig) -> VkResult<PostProcess> {
+
+        let render_pass = setup_renderpass(device, &config)?;
+        let (descriptor_pool, descriptor_set_layout) = setup_descriptor(device, &config)?;
+        let (pipeline, pipeline_layout) = prepare_pipeline(device, fragment_source, render_pass, descriptor_set_layout, &config)?;
+
+        let pass = PostProcess { render_pass, pipeline, pipeline_layout, descriptor_pool, descriptor_set_layout, config };
+        Ok(pass)
+    }
+
+    #[inline]
+    pub fn config(&self) -> &PostProcessConfig {
+        &self.config
+    }
+
+    /// Allocate the descriptor set for `images`, whose length must be `PostProcessConfig::input_count`.
+    ///
+    /// The input lives as long as this pass, so call `update_input` instead of creating a new one when the images are recreated.
+    pub fn create_input(&self, device: &VkDevice, images: &[vk::DescriptorImageInfo]) -> VkResult<PostProcessInput> {
+
+        let descriptor_set = device.allocate_descriptor_set(self.descriptor_pool, self.descriptor_set_layout)?;
+        let input = PostProcessInput { descriptor_set };
+        self.update_input(device, &input, images)?;
+
+        Ok(input)
+    }
+
+    /// Point `input` to `images`(etc. after the render targets are recreated in `RenderWorkflow::swapchain_reload`).
+    pub fn update_input(&self, device: &VkDevice, input: &PostProcessInput, images: &[vk::DescriptorImageInfo]) -> VkResult<()> {
+
+        use crate::ci::descriptor::{DescriptorImageSetWI, DescriptorSetsUpdateCI};
+
+        if images.len() != self.config.input_count as usize {
+            return Err(VkError::custom(format!("PostProcess expects {} input images, but {} are given.", self.config.input_count, images.len())))
+        }
+
+        let write_infos: Vec<DescriptorImageSetWI> = images.iter().enumerate()
+            .map(|(binding, image)| {
+                DescriptorImageSetWI::new(input.descriptor_set, binding as _, vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+                    .add_image(*image)
+            }).collect();
+
+        let mut update_ci = DescriptorSetsUpdateCI::new();
+        for write_info in write_infos.iter() {
+            update_ci = update_ci.add_write(write_info);
+        }
+        update_ci.update(device);
+
+        Ok(())
+    }
+
+    /// Create the framebuffer to write `view`, whose format must be `PostProcessConfig::output_format`.
+    pub fn create_output(&self, device: &VkDevice, view: vk::ImageView, dimension: vk::Extent2D) -> VkResult<PostProcessOutput> {
+
+        let framebuffer = FramebufferCI::new_2d(self.render_pass, dimension)
+            .add_attachment(view)
+            .build(device)?;
+
+        let output = PostProcessOutput { framebuffer, dimension };
+        Ok(output)
+    }
+
+    /// Record the commands to run this pass from `input` to `output`.
+    ///
+    /// `push_constants` is ignored if `PostProcessConfig::push_constant_size` is 0.
+    pub fn record(&self, recorder: &VkCmdRecorder<IGraphics>, input: &PostProcessInput, output: &PostProcessOutput, push_constants: &[u8]) {
+
+        let viewport = vk::Viewport {
+            x: 0.0, y: 0.0,
+            width: output.dimension.width as f32, height: output.dimension.height as f32,
+            min_depth: 0.0, max_depth: 1.0,
+        };
+
+        let scissor = vk::Rect2D {
+            offset: vk::Offset2D { x: 0, y: 0 },
+            extent: output.dimension,
+        };
+
+        // the whole output is overwritten(or blended), so no clear value is needed.
+        let render_pass_bi = RenderPassBI::new(self.render_pass, output.framebuffer)
+            .render_extent(output.dimension);
+
+        recorder.begin_render_pass(render_pass_bi)
+            .set_viewport(0, &[viewport])
+            .set_scissor(0, &[scissor])
+            .bind_pipeline(self.pipeline)
+            .bind_descriptor_sets(self.pipeline_layout, 0, &[input.descriptor_set], &[]);
+
+        if self.config.push_constant_size > 0 {
+            recorder.push_constants(self.pipeline_layout, vk::ShaderStageFlags::FRAGMENT, 0, push_constants);
+        }
+
+        recorder
+            .draw(3, 1, 0, 0)
+            .end_render_pass();
+    }
+
+    pub fn discard_by(self, device: &VkDevice) {
+
+        device.discard(self.pipeline);
+        device.discard(self.pipeline_layout);
+
+        device.discard(self.descriptor_set_layout);
+        device.discard(self.descriptor_pool);
+
+        device.discard(self.render_pass);
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+fn setup_renderpass(device: &VkDevice, config: &PostProcessConfig) -> VkResult<vk::RenderPass> {
+
+    // the previous content of output is overwritten by the fullscreen triangle, unless it is blended additively.
+    let color_attachment = if config.is_additive_blend {
+        AttachmentDescCI::new(config.output_format)
+            .op(vk::AttachmentLoadOp::LOAD, vk::AttachmentStoreOp::STORE)
+            .layout(config.output_initial_layout, config.output_final_layout)
+    } else {
+        AttachmentDescCI::new(config.output_format)
+            .op(vk::AttachmentLoadOp::DONT_CARE, vk::AttachmentStoreOp::STORE)
+            .layout(vk::ImageLayout::UNDEFINED, config.output_final_layout)
+    };
+
+    let subpass_description = SubpassDescCI::new(vk::PipelineBindPoint::GRAPHICS)
+        .add_color_attachment(0, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+
+    // wait for the previous passes writing the inputs(including the depth attachment).
+    let dependency0 = SubpassDependencyCI::new(vk::SUBPASS_EXTERNAL, 0)
+        .stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS, vk::PipelineStageFlags::FRAGMENT_SHADER | vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+        .access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE, vk::AccessFlags::SHADER_READ | vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE);
+
+    // make the output visible to the following passes sampling it.
+    let dependency1 = SubpassDependencyCI::new(0, vk::SUBPASS_EXTERNAL)
+        .stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, vk::PipelineStageFlags::FRAGMENT_SHADER | vk::PipelineStageFlags::BOTTOM_OF_PIPE)
+        .access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE, vk::AccessFlags::SHADER_READ | vk::AccessFlags::MEMORY_READ);
+
+    RenderPassCI::new()
+        .add_attachment(color_attachment)
+        .add_subpass(subpass_description)
+        .add_dependency(dependency0)
+        .add_dependency(dependency1)
+        .build(device)
+}
+
+fn setup_descriptor(device: &VkDevice, config: &PostProcessConfig) -> VkResult<(vk::DescriptorPool, vk::DescriptorSetLayout)> {
+
+    use crate::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
+
+    let descriptor_pool = DescriptorPoolCI::new(config.max_inputs)
+        .add_descriptor(vk::DescriptorType::COMBINED_IMAGE_SAMPLER, config.max_inputs * config.input_count)
+        .build(device)?;
+
+    // each input represents shader codes as follows:
+    // layout (binding = i) uniform sampler2D input_i;
+    let set_layout = (0..config.input_count).fold(DescriptorSetLayoutCI::new(), |layout_ci, binding| {
+        layout_ci.add_binding(vk::DescriptorSetLayoutBinding {
+            binding,
+            descriptor_type: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+            descriptor_count: 1,
+            stage_flags: vk::ShaderStageFlags::FRAGMENT,
+            p_immutable_samplers: ::std::ptr::null(),
+        })
+    }).build(device)?;
+
+    Ok((descriptor_pool, set_layout))
+}
+
+fn prepare_pipeline(device: &VkDevice, fragment_source: &str, render_pass: vk::RenderPass, set_layout: vk::DescriptorSetLayout, config: &PostProcessConfig) -> VkResult<(vk::Pipeline, vk::PipelineLayout)> {
+
+    use crate::ci::pipeline::*;
+
+    let viewport_state = ViewportSCI::new()
+        .add_viewport(vk::Viewport::default())
+        .add_scissor(vk::Rect2D::default());
+
+    // the fullscreen triangle is never culled.
+    let rasterization_state = RasterizationSCI::new()
+        .polygon(vk::PolygonMode::FILL)
+        .cull_face(vk::CullModeFlags::NONE, vk::FrontFace::COUNTER_CLOCKWISE);
+
+    let blend_attachment = if config.is_additive_blend {
+        BlendAttachmentSCI::new()
+            .blend_enable(true)
+            .color(vk::BlendOp::ADD, vk::BlendFactor::ONE, vk::BlendFactor::ONE)
+            .alpha(vk::BlendOp::ADD, vk::BlendFactor::ONE, vk::BlendFactor::ONE)
+    } else {
+        BlendAttachmentSCI::new()
+    };
+    let blend_state = ColorBlendSCI::new()
+        .add_attachment(blend_attachment);
+
+    let dynamic_state = DynamicSCI::new()
+        .add_dynamic(vk::DynamicState::VIEWPORT)
+        .add_dynamic(vk::DynamicState::SCISSOR);
+
+    // Pipeline Layout.
+    let mut pipeline_layout_ci = PipelineLayoutCI::new()
+        .add_set_layout(set_layout);
+
+    if config.push_constant_size > 0 {
+        pipeline_layout_ci = pipeline_layout_ci.add_push_constants(vk::PushConstantRange {
+            stage_flags: vk::ShaderStageFlags::FRAGMENT,
+            offset: 0,
+            size: config.push_constant_size,
+        });
+    }
+
+    let pipeline_layout = pipeline_layout_ci.build(device)?;
+
+    // the vertices of fullscreen triangle are generated in vertex shader, so no vertex input is needed.
+    let mut pipeline_ci = GraphicsPipelineCI::new(render_pass, pipeline_layout);
+
+    pipeline_ci.set_viewport(viewport_state);
+    pipeline_ci.set_rasterization(rasterization_state);
+    pipeline_ci.set_color_blend(blend_state);
+    pipeline_ci.set_dynamic(dynamic_state);
+
+    let mut shader_compiler = crate::utils::shaderc::VkShaderCompiler::new()?;
+    let vert_codes = shader_compiler.compile_from_str(
+        include_str!("fullscreen.vert.glsl"),
+        shaderc::ShaderKind::Vertex,
+        "[Vertex Shader]",
+        "main")?;
+    let frag_codes = shader_compiler.compile_from_str(
+        fragment_source,
+        shaderc::ShaderKind::Fragment,
+        "[Fragment Shader]",
+        "main")?;
+
+    let vert_module = ShaderModuleCI::new(vert_codes).build(device)?;
+    let frag_module = ShaderModuleCI::new(frag_codes).build(device)?;
+
+    let shaders = [
+        ShaderStageCI::new(vk::ShaderStageFlags::VERTEX, vert_module),
+        ShaderStageCI::new(vk::ShaderStageFlags::FRAGMENT, frag_module),
+    ];
+
+    pipeline_ci.set_shaders(&shaders);
+
+    let pipeline = device.build(&pipeline_ci)?;
+
+    device.discard(vert_module);
+    device.discard(frag_module);
+
+    Ok((pipeline, pipeline_layout))
+}
@@ -0,0 +1,84 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D depth_map;
+// view-space normals encoded as `normal * 0.5 + 0.5`.
+layout (binding = 1) uniform sampler2D normal_map;
+
+layout (push_constant) uniform SsaoParams {
+    // the (0, 0), (1, 1), (2, 2) and (3, 2) elements of projection matrix.
+    vec4  proj_info;
+    // the dimension of depth map.
+    vec2  dimension;
+    // the radius of sampling hemisphere in view space.
+    float radius;
+    // the depth bias avoiding self-occlusion.
+    float bias;
+    float intensity;
+    int   sample_count;
+} params;
+
+const int MAX_SAMPLE_COUNT = 64;
+
+float hash(vec2 seed) {
+    return fract(sin(dot(seed, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+vec3 view_position(vec2 uv) {
+
+    float depth = texture(depth_map, uv).r;
+    vec2 ndc = uv * 2.0 - 1.0;
+
+    // invert the perspective projection(Vulkan depth range in [0, 1]).
+    float view_z = -params.proj_info.w / (depth + params.proj_info.z);
+    return vec3(ndc.x * -view_z / params.proj_info.x, ndc.y * -view_z / params.proj_info.y, view_z);
+}
+
+vec2 project_to_uv(vec3 position) {
+    vec2 ndc = vec2(position.x * params.proj_info.x, position.y * params.proj_info.y) / -position.z;
+    return ndc * 0.5 + 0.5;
+}
+
+void main() {
+
+    vec3 position = view_position(inUV);
+    vec3 normal = normalize(texture(normal_map, inUV).rgb * 2.0 - 1.0);
+
+    // rotate the sample kernel randomly for each pixel, and build the TBN basis around normal.
+    vec2 pixel = floor(inUV * params.dimension);
+    float angle = hash(pixel) * 6.2831853;
+    vec3 random_vec = vec3(cos(angle), sin(angle), 0.0);
+    vec3 tangent = normalize(random_vec - normal * dot(random_vec, normal));
+    vec3 bitangent = cross(normal, tangent);
+    mat3 tbn = mat3(tangent, bitangent, normal);
+
+    int sample_count = clamp(params.sample_count, 1, MAX_SAMPLE_COUNT);
+    float occlusion = 0.0;
+
+    for (int i = 0; i < sample_count; i++) {
+
+        // generate a sample in the hemisphere, distributed closer to the origin.
+        float seed = float(i);
+        vec3 kernel = normalize(vec3(
+            hash(vec2(seed, 1.0)) * 2.0 - 1.0,
+            hash(vec2(seed, 2.0)) * 2.0 - 1.0,
+            hash(vec2(seed, 3.0))));
+        float scale = float(i) / float(sample_count);
+        kernel *= hash(vec2(seed, 4.0)) * mix(0.1, 1.0, scale * scale);
+
+        vec3 sample_position = position + tbn * kernel * params.radius;
+        vec2 sample_uv = project_to_uv(sample_position);
+        float sample_depth = view_position(sample_uv).z;
+
+        // fade out the occluders far away from the origin.
+        float range_check = smoothstep(0.0, 1.0, params.radius / abs(position.z - sample_depth));
+        occlusion += ((sample_depth >= sample_position.z + params.bias) ? 1.0 : 0.0) * range_check;
+    }
+
+    float ambient = 1.0 - occlusion / float(sample_count) * params.intensity;
+    outColor = vec4(vec3(clamp(ambient, 0.0, 1.0)), 1.0);
+}
@@ -0,0 +1,184 @@
+
+use ash::vk;
+
+use crate::postprocess::{PostProcess, PostProcessConfig, PostProcessInput, PostProcessOutput};
+use crate::texture::{RenderTarget, DepthTarget};
+use crate::command::{VkCmdRecorder, IGraphics};
+use crate::context::VkDevice;
+use crate::utils::memory::any_as_u8_slice;
+
+use crate::{VkResult, Mat4F};
+use crate::{vkfloat, vksint};
+
+/// The format of the noisy occlusion before blur.
+const OCCLUSION_FORMAT: vk::Format = vk::Format::R8_UNORM;
+
+
+// ----------------------------------------------------------------------------------------------
+/// The parameters of SSAO, which can be adjusted at runtime.
+#[derive(Debug, Clone, Copy)]
+pub struct SsaoParams {
+
+    /// the radius of sampling hemisphere in view space.
+    pub radius: vkfloat,
+    /// the depth bias avoiding self-occlusion on flat surface.
+    pub bias: vkfloat,
+    /// the strength of occlusion(0.0 disables the effect).
+    pub intensity: vkfloat,
+    /// the number of samples for each pixel, in range [1, 64].
+    pub sample_count: vksint,
+}
+
+impl Default for SsaoParams {
+
+    fn default() -> SsaoParams {
+        SsaoParams {
+            radius: 0.5,
+            bias: 0.025,
+            intensity: 1.0,
+            sample_count: 16,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct SsaoPushConstants {
+    proj_info: [vkfloat; 4],
+    dimension: [vkfloat; 2],
+    radius: vkfloat,
+    bias: vkfloat,
+    intensity: vkfloat,
+    sample_count: vksint,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct BlurPushConstants {
+    inverse_dimension: [vkfloat; 2],
+}
+// ----------------------------------------------------------------------------------------------
+
+
+// ----------------------------------------------------------------------------------------------
+/// A screen-space ambient occlusion pass, which estimates the occlusion from the depth and normals of the scene.
+///
+/// The inputs are the depth of scene(a `DepthTarget` in `DEPTH_STENCIL_READ_ONLY_OPTIMAL` layout),
+/// and the view-space normals(a `RenderTarget`, whose rgb channels store `normal * 0.5 + 0.5`).
+/// The output is the ambient factor in range [0, 1](1.0 means not occluded) in all color channels,
+/// which is computed at the dimension of inputs and then blurred to remove the noise.
+///
+/// The projection matrix used to render the scene must be set by `set_projection` to reconstruct the view-space positions.
+pub struct Ssao {
+
+    ssao_pass: PostProcess,
+    blur_pass: PostProcess,
+
+    /// the noisy occlusion written by `ssao_pass`.
+    occlusion: RenderTarget,
+    occlusion_output: PostProcessOutput,
+    blur_input: PostProcessInput,
+
+    /// the elements of projection matrix used by the reconstruction.
+    proj_info: [vkfloat; 4],
+
+    pub params: SsaoParams,
+}
+
+impl Ssao {
+
+    /// Create the SSAO pass for inputs of `dimension`, writing to images of `output_format`(etc. `R8_UNORM`).
+    ///
+    /// `output_final_layout` is the layout of output image after this pass.
+    pub fn new(device: &mut VkDevice, output_format: vk::Format, output_final_layout: vk::ImageLayout, dimension: vk::Extent2D) -> VkResult<Ssao> {
+
+        let ssao_pass = PostProcess::new(device, include_str!("ssao.frag.glsl"), PostProcessConfig {
+            output_format: OCCLUSION_FORMAT,
+            input_count: 2,
+            push_constant_size: ::std::mem::size_of::<SsaoPushConstants>() as _,
+            ..Default::default()
+        })?;
+
+        let blur_pass = PostProcess::new(device, include_str!("ssao_blur.frag.glsl"), PostProcessConfig {
+            output_format, output_final_layout,
+            push_constant_size: ::std::mem::size_of::<BlurPushConstants>() as _,
+            ..Default::default()
+        })?;
+
+        let occlusion = RenderTarget::new(device, OCCLUSION_FORMAT, dimension)?;
+        let occlusion_output = ssao_pass.create_output(device, occlusion.view, dimension)?;
+        let blur_input = blur_pass.create_input(device, &[occlusion.descriptor])?;
+
+        let ssao = Ssao {
+            ssao_pass, blur_pass,
+            occlusion, occlusion_output, blur_input,
+            proj_info: [1.0, 1.0, 0.0, 0.0],
+            params: SsaoParams::default(),
+        };
+        Ok(ssao)
+    }
+
+    /// Set the perspective projection matrix that the depth and normals are rendered with(etc. `FlightCamera::proj_matrix`).
+    pub fn set_projection(&mut self, projection: Mat4F) {
+        self.proj_info = [projection.cols.x.x, projection.cols.y.y, projection.cols.z.z, projection.cols.w.z];
+    }
+
+    /// Create the input sampling `depth` and `normals`, whose dimension must match the dimension of this pass.
+    pub fn create_input(&self, device: &VkDevice, depth: &DepthTarget, normals: &RenderTarget) -> VkResult<PostProcessInput> {
+        self.ssao_pass.create_input(device, &[depth.descriptor, normals.descriptor])
+    }
+
+    /// Point `input` to `depth` and `normals` after they are recreated.
+    pub fn update_input(&self, device: &VkDevice, input: &PostProcessInput, depth: &DepthTarget, normals: &RenderTarget) -> VkResult<()> {
+        self.ssao_pass.update_input(device, input, &[depth.descriptor, normals.descriptor])
+    }
+
+    /// Create the output writing `view`, whose format must be the `output_format` of this pass.
+    pub fn create_output(&self, device: &VkDevice, view: vk::ImageView, dimension: vk::Extent2D) -> VkResult<PostProcessOutput> {
+        self.blur_pass.create_output(device, view, dimension)
+    }
+
+    /// Recreate the intermediate occlusion image for inputs of `dimension`.
+    pub fn resize(&mut self, device: &mut VkDevice, dimension: vk::Extent2D) -> VkResult<()> {
+
+        let occlusion = RenderTarget::new(device, OCCLUSION_FORMAT, dimension)?;
+        let occlusion_output = self.ssao_pass.create_output(device, occlusion.view, dimension)?;
+        self.blur_pass.update_input(device, &self.blur_input, &[occlusion.descriptor])?;
+
+        let old_occlusion = ::std::mem::replace(&mut self.occlusion, occlusion);
+        let old_output = ::std::mem::replace(&mut self.occlusion_output, occlusion_output);
+
+        old_output.discard_by(device);
+        old_occlusion.discard_by(device)
+    }
+
+    /// Record the commands to compute the ambient occlusion of `input`(the depth and normals) into `output`.
+    pub fn record(&self, recorder: &VkCmdRecorder<IGraphics>, input: &PostProcessInput, output: &PostProcessOutput) {
+
+        let dimension = self.occlusion.dimension;
+
+        let ssao_constants = SsaoPushConstants {
+            proj_info: self.proj_info,
+            dimension: [dimension.width as vkfloat, dimension.height as vkfloat],
+            radius: self.params.radius,
+            bias: self.params.bias,
+            intensity: self.params.intensity,
+            sample_count: self.params.sample_count,
+        };
+        self.ssao_pass.record(recorder, input, &self.occlusion_output, unsafe { any_as_u8_slice(&ssao_constants) });
+
+        let blur_constants = BlurPushConstants {
+            inverse_dimension: [1.0 / dimension.width as vkfloat, 1.0 / dimension.height as vkfloat],
+        };
+        self.blur_pass.record(recorder, &self.blur_input, output, unsafe { any_as_u8_slice(&blur_constants) });
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        self.occlusion_output.discard_by(device);
+        self.ssao_pass.discard_by(device);
+        self.blur_pass.discard_by(device);
+        self.occlusion.discard_by(device)
+    }
+}
+// ----------------------------------------------------------------------------------------------
@@ -0,0 +1,27 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D occlusion_map;
+
+layout (push_constant) uniform BlurParams {
+    // the size of a texel in occlusion map.
+    vec2 inverse_dimension;
+} params;
+
+void main() {
+
+    // 4x4 box blur, which removes the noise pattern from the random kernel rotation.
+    float result = 0.0;
+    for (int x = -2; x < 2; x++) {
+        for (int y = -2; y < 2; y++) {
+            vec2 offset = vec2(float(x), float(y)) * params.inverse_dimension;
+            result += texture(occlusion_map, inUV + offset).r;
+        }
+    }
+
+    outColor = vec4(vec3(result / 16.0), 1.0);
+}
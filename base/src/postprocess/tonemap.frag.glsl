@@ -0,0 +1,74 @@
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+
+layout (location = 0) out vec4 outColor;
+
+layout (binding = 0) uniform sampler2D hdr_source;
+
+layout (push_constant) uniform TonemapParams {
+    // 0: Reinhard, 1: ACES filmic, 2: Uncharted2.
+    int   operator_type;
+    float exposure;
+    // the smallest linear value mapped to pure white.
+    float white_point;
+    // non-zero if the output is not an sRGB format, so the gamma must be encoded in shader.
+    uint  is_encode_gamma;
+} params;
+
+vec3 reinhard(vec3 color) {
+    // the extended Reinhard operator with white point.
+    float white_sqr = params.white_point * params.white_point;
+    return color * (1.0 + color / white_sqr) / (1.0 + color);
+}
+
+vec3 aces_filmic(vec3 color) {
+    // the curve fitted by Krzysztof Narkowicz.
+    const float a = 2.51;
+    const float b = 0.03;
+    const float c = 2.43;
+    const float d = 0.59;
+    const float e = 0.14;
+    return clamp((color * (a * color + b)) / (color * (c * color + d) + e), 0.0, 1.0);
+}
+
+vec3 uncharted2_curve(vec3 x) {
+    const float A = 0.15;
+    const float B = 0.50;
+    const float C = 0.10;
+    const float D = 0.20;
+    const float E = 0.02;
+    const float F = 0.30;
+    return ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F;
+}
+
+vec3 uncharted2(vec3 color) {
+    const float exposure_bias = 2.0;
+    vec3 curr = uncharted2_curve(color * exposure_bias);
+    vec3 white_scale = 1.0 / uncharted2_curve(vec3(params.white_point));
+    return curr * white_scale;
+}
+
+void main() {
+
+    vec4 hdr = texture(hdr_source, inUV);
+    vec3 color = hdr.rgb * params.exposure;
+
+    vec3 mapped;
+    if (params.operator_type == 0) {
+        mapped = reinhard(color);
+    } else if (params.operator_type == 1) {
+        mapped = aces_filmic(color);
+    } else {
+        mapped = uncharted2(color);
+    }
+
+    mapped = clamp(mapped, 0.0, 1.0);
+
+    if (params.is_encode_gamma != 0) {
+        mapped = pow(mapped, vec3(1.0 / 2.2));
+    }
+
+    outColor = vec4(mapped, hdr.a);
+}
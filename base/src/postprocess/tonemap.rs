@@ -0,0 +1,149 @@
+
+use ash::vk;
+
+use crate::postprocess::{PostProcess, PostProcessConfig, PostProcessInput, PostProcessOutput};
+use crate::texture::RenderTarget;
+use crate::command::{VkCmdRecorder, IGraphics};
+use crate::context::VkDevice;
+use crate::utils::memory::any_as_u8_slice;
+
+use crate::VkResult;
+use crate::{vkfloat, vksint, vkuint};
+
+
+// ----------------------------------------------------------------------------------------------
+/// The curve mapping HDR colors to displayable range.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TonemapOperator {
+    /// the extended Reinhard operator, which keeps the colors below white point.
+    Reinhard,
+    /// the ACES filmic curve(fitted by Krzysztof Narkowicz), with higher contrast and saturation.
+    AcesFilmic,
+    /// the filmic curve of Uncharted 2(by John Hable).
+    Uncharted2,
+}
+
+impl TonemapOperator {
+
+    /// The next operator in cycle, which is convenient to switch operators by a key at runtime.
+    pub fn next(self) -> TonemapOperator {
+        match self {
+            | TonemapOperator::Reinhard   => TonemapOperator::AcesFilmic,
+            | TonemapOperator::AcesFilmic => TonemapOperator::Uncharted2,
+            | TonemapOperator::Uncharted2 => TonemapOperator::Reinhard,
+        }
+    }
+
+    fn shader_index(self) -> vksint {
+        match self {
+            | TonemapOperator::Reinhard   => 0,
+            | TonemapOperator::AcesFilmic => 1,
+            | TonemapOperator::Uncharted2 => 2,
+        }
+    }
+}
+
+/// The parameters of tone mapping, which can be adjusted at runtime.
+#[derive(Debug, Clone, Copy)]
+pub struct TonemapParams {
+
+    pub operator: TonemapOperator,
+    /// the scale applied to HDR colors before tone mapping.
+    pub exposure: vkfloat,
+    /// the smallest linear value mapped to pure white(only used by Reinhard and Uncharted2).
+    pub white_point: vkfloat,
+}
+
+impl Default for TonemapParams {
+
+    fn default() -> TonemapParams {
+        TonemapParams {
+            operator: TonemapOperator::AcesFilmic,
+            exposure: 1.0,
+            white_point: 11.2,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct TonemapPushConstants {
+    operator_type: vksint,
+    exposure: vkfloat,
+    white_point: vkfloat,
+    is_encode_gamma: vkuint,
+}
+// ----------------------------------------------------------------------------------------------
+
+
+// ----------------------------------------------------------------------------------------------
+/// A tone mapping pass, which converts the linear HDR scene into LDR colors for presentation.
+///
+/// If the output format is sRGB, the hardware encodes the gamma on writing. Otherwise the gamma is encoded in shader.
+pub struct Tonemap {
+
+    pass: PostProcess,
+    is_encode_gamma: bool,
+}
+
+impl Tonemap {
+
+    /// Create the pass writing to images of `output_format`(etc. the swapchain format), which is transitioned to `output_final_layout` after this pass.
+    pub fn new(device: &VkDevice, output_format: vk::Format, output_final_layout: vk::ImageLayout) -> VkResult<Tonemap> {
+
+        let pass = PostProcess::new(device, include_str!("tonemap.frag.glsl"), PostProcessConfig {
+            output_format, output_final_layout,
+            push_constant_size: ::std::mem::size_of::<TonemapPushConstants>() as _,
+            ..Default::default()
+        })?;
+
+        let tonemap = Tonemap { pass, is_encode_gamma: !is_srgb_format(output_format) };
+        Ok(tonemap)
+    }
+
+    /// Create the input sampling `hdr`(the HDR scene).
+    pub fn create_input(&self, device: &VkDevice, hdr: &RenderTarget) -> VkResult<PostProcessInput> {
+        self.pass.create_input(device, &[hdr.descriptor])
+    }
+
+    /// Point `input` to `hdr` after it is recreated.
+    pub fn update_input(&self, device: &VkDevice, input: &PostProcessInput, hdr: &RenderTarget) -> VkResult<()> {
+        self.pass.update_input(device, input, &[hdr.descriptor])
+    }
+
+    /// Create the output writing `view`, whose format must be the `output_format` of this pass.
+    pub fn create_output(&self, device: &VkDevice, view: vk::ImageView, dimension: vk::Extent2D) -> VkResult<PostProcessOutput> {
+        self.pass.create_output(device, view, dimension)
+    }
+
+    /// Record the commands to tone map `hdr_input` into `ldr_output` with `params`.
+    pub fn record(&self, recorder: &VkCmdRecorder<IGraphics>, hdr_input: &PostProcessInput, ldr_output: &PostProcessOutput, params: &TonemapParams) {
+
+        let push_constants = TonemapPushConstants {
+            operator_type: params.operator.shader_index(),
+            exposure: params.exposure,
+            white_point: params.white_point,
+            is_encode_gamma: self.is_encode_gamma as vkuint,
+        };
+
+        self.pass.record(recorder, hdr_input, ldr_output, unsafe { any_as_u8_slice(&push_constants) });
+    }
+
+    pub fn discard_by(self, device: &VkDevice) {
+        self.pass.discard_by(device);
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+fn is_srgb_format(format: vk::Format) -> bool {
+    match format {
+        | vk::Format::R8_SRGB
+        | vk::Format::R8G8_SRGB
+        | vk::Format::R8G8B8_SRGB
+        | vk::Format::B8G8R8_SRGB
+        | vk::Format::R8G8B8A8_SRGB
+        | vk::Format::B8G8R8A8_SRGB
+        | vk::Format::A8B8G8R8_SRGB_PACK32 => true,
+        | _ => false,
+    }
+}
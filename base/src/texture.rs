@@ -1,17 +1,24 @@
 
+pub mod ktx;
+
+pub use self::target::{RenderTarget, DepthTarget, scale_dimension};
+
+mod target;
+
 use ash::vk;
 
 use gli::GliTexture;
 
-use crate::ci::vma::{VmaImage, VmaBuffer, VmaAllocationCI};
+use crate::ci::vma::{VmaImage, VmaBuffer, VmaAllocationCI, VkImageHandle};
 use crate::ci::image::{ImageCI, ImageViewCI, ImageBarrierCI, SamplerCI};
 use crate::ci::buffer::BufferCI;
 use crate::ci::VkObjectBuildableCI;
 
 use crate::command::CmdTransferApi;
 use crate::context::VkDevice;
+use crate::texture::ktx::KtxReader;
 
-use crate::{VkResult, VkErrorKind};
+use crate::{VkResult, VkError, VkErrorKind};
 use crate::{vkuint, vkbytes, vkfloat};
 
 use std::path::Path;
@@ -50,69 +57,462 @@ impl Texture2D {
         // Support for linear tiling is mostly limited, so prefer to use optimal tiling instead.
         // On most implementations linear tiling will only support a very limited amount of formats and features (mip maps, cubemap, arrays, etc.).
 
-        let staging_buffer = {
+        // create a host-visible staging buffer that contains the raw image data.
+        // This buffer is used as a transfer source for the buffer copy.
+        let staging_buffer = create_staging_buffer(device, tex_2d.data() as *const u8, tex_2d.size())?;
+
+        // setup buffer copy regions for each mip level.
+        let buffer_copy_regions = layered_copy_regions(::std::slice::from_ref(&tex_2d));
 
-            // create a host-visible staging buffer that contains the raw image data.
-            // This buffer is used as a transfer source for the buffer copy.
+        // create optimal tiled target image on the device.
+        let dst_image = {
 
-            let staging_ci = BufferCI::new(tex_2d.size() as vkbytes)
-                .usage(vk::BufferUsageFlags::TRANSFER_SRC);
-            let allocation_ci = VmaAllocationCI::new(vma::MemoryUsage::CpuOnly, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT);
-            let staging_allocation = device.vma.create_buffer(
-                staging_ci.as_ref(), allocation_ci.as_ref())
-                .map_err(VkErrorKind::Vma)?;
+            let image_ci = ImageCI::new_2d(format, vk::Extent2D { width, height })
+                .mip_levels(tex_2d.levels() as vkuint)
+                .array_layers(1)
+                .samples(vk::SampleCountFlags::TYPE_1)
+                .tiling(vk::ImageTiling::OPTIMAL)
+                .initial_layout(vk::ImageLayout::UNDEFINED)
+                .usages(vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED);
 
-            // Copy texture data into staging buffer.
-            let data_ptr = device.vma.map_memory(&staging_allocation.1)
+            let allocation_ci = VmaAllocationCI::new(
+                vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
+            let image_allocation = device.vma.create_image(
+                image_ci.as_ref(), allocation_ci.as_ref())
                 .map_err(VkErrorKind::Vma)?;
-            debug_assert_ne!(data_ptr, ptr::null_mut());
 
-            unsafe {
-                data_ptr.copy_from(tex_2d.data() as *const u8, tex_2d.size());
-            }
+            VmaImage::from(image_allocation)
+        };
 
-            device.vma.unmap_memory(&staging_allocation.1)
-                .map_err(VkErrorKind::Vma)?;
 
-            VmaBuffer::from(staging_allocation)
+        { // transfer image data from staging buffer to dst image.
+
+            let sub_range = vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                base_mip_level: 0,
+                level_count: tex_2d.levels() as vkuint,
+                base_array_layer: 0,
+                layer_count: 1,
+            };
+
+            // Image barrier for optimal image (target).
+            // Optimal image will be used as destination for the copy.
+            let barrier1 = ImageBarrierCI::new(&dst_image, sub_range)
+                .access_mask(vk::AccessFlags::empty(), vk::AccessFlags::TRANSFER_WRITE)
+                .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+
+            // Change texture image layout to shader read after all mip levels have been copied.
+            let barrier2 = ImageBarrierCI::new(&dst_image, sub_range)
+                .access_mask(vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::SHADER_READ)
+                .layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+
+            let cmd_recorder = device.get_transfer_recorder();
+
+            cmd_recorder.begin_record()?
+                .image_pipeline_barrier(vk::PipelineStageFlags::HOST, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[barrier1.into()])
+                // Copy mip levels from staging buffer.
+                .copy_buf2img(&staging_buffer, &dst_image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &buffer_copy_regions)
+                .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::ALL_COMMANDS, vk::DependencyFlags::empty(), &[barrier2.into()])
+                .end_record()?;
+
+            device.flush_transfer(cmd_recorder)?;
+        }
+
+
+        { // clean up staging resources.
+            device.vma_discard(staging_buffer)?;
+        }
+
+        let dst_sampler = create_sampler(device, tex_2d.levels() as vkuint, vk::SamplerAddressMode::REPEAT)?;
+
+        let dst_image_view = create_image_view(device, &dst_image, vk::ImageViewType::TYPE_2D, format, tex_2d.levels() as vkuint, 1)?;
+
+
+        let result = Texture2D {
+            image: dst_image,
+            view : dst_image_view,
+            mip_levels: tex_2d.levels() as vkuint,
+            sampler: dst_sampler,
+            descriptor: vk::DescriptorImageInfo {
+                sampler: dst_sampler,
+                image_view: dst_image_view,
+                image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            },
+            width, height,
         };
+        Ok(result)
+    }
 
-        // setup buffer copy regions for each mip level.
-        let mut buffer_copy_regions = Vec::with_capacity(tex_2d.levels());
-        let mut staging_offset = 0;
+    /// Load texture from an image file, and the file format is detected from its extension.
+    ///
+    /// PNG and JPEG images are decoded into RGBA8 pixels, so `format` must be `R8G8B8A8_UNORM` or `R8G8B8A8_SRGB` for them, otherwise an error is returned.
+    ///
+    /// `.ktx` files fall back to `Texture2D::load_ktx`.
+    pub fn load_image(device: &mut VkDevice, path: impl AsRef<Path>, format: vk::Format) -> VkResult<Texture2D> {
+
+        let path = path.as_ref();
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase());
+
+        match extension.as_ref().map(String::as_str) {
+            | Some("ktx") => {
+                Texture2D::load_ktx(device, path, format)
+            },
+            | Some("png") | Some("jpg") | Some("jpeg") => {
+
+                // the pixels are always decoded to RGBA8, so other formats would misinterpret them.
+                if format != vk::Format::R8G8B8A8_UNORM && format != vk::Format::R8G8B8A8_SRGB {
+                    return Err(VkError::unsupported("Loading PNG or JPEG texture in format other than R8G8B8A8_UNORM or R8G8B8A8_SRGB"))
+                }
+
+                let rgba_image = image::open(path)
+                    .map_err(VkErrorKind::Image)?
+                    .to_rgba();
+                let (width, height) = rgba_image.dimensions();
+
+                Texture2D::from_base_level(device, &rgba_image.into_raw(), width, height, format)
+            },
+            | _ => {
+                Err(VkError::unsupported("Texture file extension"))
+            },
+        }
+    }
+
+    /// Load texture by `Texture2D::load_image`, or create a magenta checkerboard texture if the loading fails.
+    ///
+    /// This keeps application running with an obvious "missing texture" look when some assets are not available.
+    /// The fallback texture is always in `R8G8B8A8_UNORM` format, regardless of `format`.
+    pub fn load_or_fallback(device: &mut VkDevice, path: impl AsRef<Path>, format: vk::Format) -> VkResult<Texture2D> {
+
+        let path = path.as_ref();
+
+        match Texture2D::load_image(device, path, format) {
+            | Ok(texture) => Ok(texture),
+            | Err(error) => {
+                println!("[Warning] Failed to load texture {:?}({}), use fallback texture instead.", path, error);
+                Texture2D::fallback(device)
+            },
+        }
+    }
+
+    /// Create a 64x64 magenta-black checkerboard texture, which indicates a missing texture.
+    pub fn fallback(device: &mut VkDevice) -> VkResult<Texture2D> {
+
+        const FALLBACK_DIMENSION: vkuint = 64;
+        const FALLBACK_CELL_SIZE: vkuint = 8;
+
+        let pixels = crate::utils::texture_gen::generate_checkerboard(
+            FALLBACK_DIMENSION, FALLBACK_CELL_SIZE, [255, 0, 255, 255], [0, 0, 0, 255]);
+
+        Texture2D::from_base_level(device, &pixels, FALLBACK_DIMENSION, FALLBACK_DIMENSION, vk::Format::R8G8B8A8_UNORM)
+    }
+
+    /// Upload tightly packed pixels in `bytes` as a texture with single mip level.
+    ///
+    /// The size of `bytes` must match `width`, `height` and `format`(etc. generated by `utils::texture_gen` in `R8G8B8A8_UNORM`), otherwise an error is returned.
+    /// Block-compressed formats are not supported.
+    #[inline]
+    pub fn from_bytes(device: &mut VkDevice, bytes: &[u8], width: vkuint, height: vkuint, format: vk::Format) -> VkResult<Texture2D> {
+        Texture2D::from_base_level(device, bytes, width, height, format)
+    }
+
+    /// Upload `bytes` as a texture with single mip level.
+    ///
+    /// An error is returned if the size of `bytes` does not match `width`, `height` and `format`.
+    pub(crate) fn from_base_level(device: &mut VkDevice, bytes: &[u8], width: vkuint, height: vkuint, format: vk::Format) -> VkResult<Texture2D> {
 
-        for i in 0..tex_2d.levels() {
+        check_texel_bytes(bytes, width as usize * height as usize, format)?;
 
-            let image_level_i = tex_2d.get_level(i);
+        let staging_buffer = create_staging_buffer(device, bytes.as_ptr(), bytes.len())?;
+
+        let dst_image = {
+
+            let image_ci = ImageCI::new_2d(format, vk::Extent2D { width, height })
+                .tiling(vk::ImageTiling::OPTIMAL)
+                .initial_layout(vk::ImageLayout::UNDEFINED)
+                .usages(vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED);
+
+            let allocation_ci = VmaAllocationCI::new(
+                vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
+            let image_allocation = device.vma.create_image(
+                image_ci.as_ref(), allocation_ci.as_ref())
+                .map_err(VkErrorKind::Vma)?;
+
+            VmaImage::from(image_allocation)
+        };
+
+        { // transfer image data from staging buffer to dst image.
+
+            let sub_range = vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                base_mip_level: 0,
+                level_count: 1,
+                base_array_layer: 0,
+                layer_count: 1,
+            };
 
             let copy_region = vk::BufferImageCopy {
-                buffer_offset: staging_offset,
+                buffer_offset: 0,
                 buffer_row_length  : 0,
                 buffer_image_height: 0,
                 image_subresource: vk::ImageSubresourceLayers {
                     aspect_mask: vk::ImageAspectFlags::COLOR,
-                    mip_level: i as vkuint,
+                    mip_level: 0,
                     base_array_layer: 0,
                     layer_count     : 1,
                 },
                 image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
-                image_extent: vk::Extent3D {
-                    width : image_level_i.extent().width,
-                    height: image_level_i.extent().height,
-                    depth : 1,
-                },
+                image_extent: vk::Extent3D { width, height, depth: 1 },
             };
 
-            buffer_copy_regions.push(copy_region);
-            staging_offset += image_level_i.size() as vkbytes;
+            let copy_dst_barrier = ImageBarrierCI::new(&dst_image, sub_range)
+                .access_mask(vk::AccessFlags::empty(), vk::AccessFlags::TRANSFER_WRITE)
+                .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+            let shader_read_barrier = ImageBarrierCI::new(&dst_image, sub_range)
+                .access_mask(vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::SHADER_READ)
+                .layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+            let cmd_recorder = device.get_transfer_recorder();
+
+            cmd_recorder.begin_record()?
+                .image_pipeline_barrier(vk::PipelineStageFlags::HOST, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[copy_dst_barrier.into()])
+                .copy_buf2img(&staging_buffer, &dst_image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[copy_region])
+                .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::ALL_COMMANDS, vk::DependencyFlags::empty(), &[shader_read_barrier.into()])
+                .end_record()?;
+
+            device.flush_transfer(cmd_recorder)?;
+        }
+
+        device.vma_discard(staging_buffer)?;
+
+        let dst_sampler = create_sampler(device, 1, vk::SamplerAddressMode::REPEAT)?;
+        let dst_image_view = create_image_view(device, &dst_image, vk::ImageViewType::TYPE_2D, format, 1, 1)?;
+
+        let result = Texture2D {
+            image: dst_image,
+            view : dst_image_view,
+            mip_levels: 1,
+            sampler: dst_sampler,
+            descriptor: vk::DescriptorImageInfo {
+                sampler: dst_sampler,
+                image_view: dst_image_view,
+                image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            },
+            width, height,
+        };
+        Ok(result)
+    }
+
+    /// Load the base level of a KTX texture, and generate the rest of its mip levels in runtime.
+    ///
+    /// The number of mip levels is `floor(log2(max(width, height))) + 1`, and each level is blit from its previous level.
+    ///
+    /// `format` must support linear filtering in optimal tiling, otherwise an error will be returned.
+    pub fn load_with_mipmaps(device: &mut VkDevice, path: impl AsRef<Path>, format: vk::Format) -> VkResult<Texture2D> {
+
+        let tex_2d: gli::Texture2D = gli::load_ktx(path)
+            .map_err(VkErrorKind::Gli)?;
+
+        debug_assert!(!tex_2d.empty());
+
+        let base_image = tex_2d.get_level(0);
+        let (width, height) = (base_image.extent().width, base_image.extent().height);
+
+        // the base level is always placed at the beginning of texture data.
+        let base_bytes = unsafe {
+            ::std::slice::from_raw_parts(tex_2d.data() as *const u8, base_image.size())
+        };
+
+        Texture2D::generate_from_base_level(device, base_bytes, width, height, format)
+    }
+
+    /// Upload `bytes` as the base level of a new texture and build the mip pyramid by chain of `vkCmdBlitImage`.
+    fn generate_from_base_level(device: &mut VkDevice, bytes: &[u8], width: vkuint, height: vkuint, format: vk::Format) -> VkResult<Texture2D> {
+
+        // Mip-chain generation requires image blit with linear filter, which depends on the support of format.
+        let format_properties = device.phy.format_properties(format);
+        let required_features = vk::FormatFeatureFlags::BLIT_SRC | vk::FormatFeatureFlags::BLIT_DST | vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR;
+        if !format_properties.optimal_tiling_features.contains(required_features) {
+            return Err(VkError::unsupported("Linear blitting of texture format"))
+        }
+
+        let mip_levels = (width.max(height) as vkfloat).log2().floor() as vkuint + 1;
+
+        let staging_buffer = create_staging_buffer(device, bytes.as_ptr(), bytes.len())?;
+
+        let dst_image = {
+
+            // the image will be both source and destination of blit operations.
+            let image_ci = ImageCI::new_2d(format, vk::Extent2D { width, height })
+                .mip_levels(mip_levels)
+                .tiling(vk::ImageTiling::OPTIMAL)
+                .initial_layout(vk::ImageLayout::UNDEFINED)
+                .usages(vk::ImageUsageFlags::TRANSFER_SRC | vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED);
+
+            let allocation_ci = VmaAllocationCI::new(
+                vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
+            let image_allocation = device.vma.create_image(
+                image_ci.as_ref(), allocation_ci.as_ref())
+                .map_err(VkErrorKind::Vma)?;
+
+            VmaImage::from(image_allocation)
+        };
+
+        { // copy base level and generate the rest levels.
+
+            let level_range = |level: vkuint, count: vkuint| vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                base_mip_level: level,
+                level_count: count,
+                base_array_layer: 0,
+                layer_count: 1,
+            };
+            let level_layers = |level: vkuint| vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: level,
+                base_array_layer: 0,
+                layer_count: 1,
+            };
+            let level_extent = |level: vkuint| vk::Offset3D {
+                x: (width  >> level).max(1) as _,
+                y: (height >> level).max(1) as _,
+                z: 1,
+            };
+
+            // transition all levels to transfer destination.
+            let copy_dst_barrier = ImageBarrierCI::new(&dst_image, level_range(0, mip_levels))
+                .access_mask(vk::AccessFlags::empty(), vk::AccessFlags::TRANSFER_WRITE)
+                .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+
+            let copy_region = vk::BufferImageCopy {
+                buffer_offset: 0,
+                buffer_row_length  : 0,
+                buffer_image_height: 0,
+                image_subresource: level_layers(0),
+                image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+                image_extent: vk::Extent3D { width, height, depth: 1 },
+            };
+
+            let cmd_recorder = device.get_blit_recorder();
+
+            cmd_recorder.begin_record()?
+                .image_pipeline_barrier(vk::PipelineStageFlags::HOST, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[copy_dst_barrier.into()])
+                .copy_buf2img(&staging_buffer, &dst_image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[copy_region]);
+
+            for level in 1..mip_levels {
+
+                // the previous level becomes the blit source.
+                let blit_src_barrier = ImageBarrierCI::new(&dst_image, level_range(level - 1, 1))
+                    .access_mask(vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::TRANSFER_READ)
+                    .layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::TRANSFER_SRC_OPTIMAL);
+                // the previous level has been done after blit.
+                let shader_read_barrier = ImageBarrierCI::new(&dst_image, level_range(level - 1, 1))
+                    .access_mask(vk::AccessFlags::TRANSFER_READ, vk::AccessFlags::SHADER_READ)
+                    .layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+                let blit_region = vk::ImageBlit {
+                    src_subresource: level_layers(level - 1),
+                    src_offsets: [vk::Offset3D { x: 0, y: 0, z: 0 }, level_extent(level - 1)],
+                    dst_subresource: level_layers(level),
+                    dst_offsets: [vk::Offset3D { x: 0, y: 0, z: 0 }, level_extent(level)],
+                };
+
+                cmd_recorder
+                    .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[blit_src_barrier.into()])
+                    .blit_image(&dst_image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, &dst_image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[blit_region], vk::Filter::LINEAR)
+                    .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, vk::DependencyFlags::empty(), &[shader_read_barrier.into()]);
+            }
+
+            // the last level is never used as blit source.
+            let last_level_barrier = ImageBarrierCI::new(&dst_image, level_range(mip_levels - 1, 1))
+                .access_mask(vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::SHADER_READ)
+                .layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+            cmd_recorder
+                .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, vk::DependencyFlags::empty(), &[last_level_barrier.into()])
+                .end_record()?;
+
+            // blit command must be submitted to a queue with graphics capability.
+            device.flush_blit(cmd_recorder)?;
+        }
+
+        device.vma_discard(staging_buffer)?;
+
+        let dst_sampler = create_sampler(device, mip_levels, vk::SamplerAddressMode::REPEAT)?;
+        let dst_image_view = create_image_view(device, &dst_image, vk::ImageViewType::TYPE_2D, format, mip_levels, 1)?;
+
+        let result = Texture2D {
+            image: dst_image,
+            view : dst_image_view,
+            sampler: dst_sampler,
+            descriptor: vk::DescriptorImageInfo {
+                sampler: dst_sampler,
+                image_view: dst_image_view,
+                image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            },
+            width, height, mip_levels,
+        };
+        Ok(result)
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        device.discard(self.sampler);
+        device.discard(self.view);
+        device.vma_discard(self.image)
+    }
+}
+
+
+/// There are 6 faces for each cube.
+const CUBE_FACES_COUNT: usize = 6;
+
+/// Cube map texture, whose faces are stored as 6 array layers.
+pub struct TextureCube {
+
+    pub image: VmaImage,
+    pub view : vk::ImageView,
+
+    pub width      : vkuint,
+    pub height     : vkuint,
+    pub mip_levels : vkuint,
+
+    pub sampler: vk::Sampler,
+    pub descriptor: vk::DescriptorImageInfo,
+}
+
+impl TextureCube {
+
+    pub fn load_ktx(device: &mut VkDevice, path: impl AsRef<Path>, format: vk::Format) -> VkResult<TextureCube> {
+
+        let reader = KtxReader::open(path.as_ref())?;
+
+        if reader.header().is_cubemap() == false || reader.header().array_elements != 0 {
+            return Err(VkError::custom(format!("{:?} is not a cube map.", path.as_ref())))
         }
 
+        let (width, height, mip_levels) = {
+            let header = reader.header();
+            (header.pixel_width, header.pixel_height, header.level_count())
+        };
+
+        // pack all faces and levels into staging bytes, which is aligned to the largest texel block size(16 bytes) of the supported formats.
+        let (texture_bytes, buffer_copy_regions) = reader.pack(16);
+
+        // this buffer will be the data source for copying texture data to the optimal tiled image on the device.
+        let staging_buffer = create_staging_buffer(device, texture_bytes.as_ptr(), texture_bytes.len())?;
+
         // create optimal tiled target image on the device.
         let dst_image = {
 
             let image_ci = ImageCI::new_2d(format, vk::Extent2D { width, height })
-                .mip_levels(tex_2d.levels() as vkuint)
-                .array_layers(1)
+                // This flag is required for cube map images.
+                .flags(vk::ImageCreateFlags::CUBE_COMPATIBLE)
+                // Cube faces count as array layers in Vulkan.
+                .array_layers(CUBE_FACES_COUNT as vkuint)
+                .mip_levels(mip_levels)
                 .samples(vk::SampleCountFlags::TYPE_1)
                 .tiling(vk::ImageTiling::OPTIMAL)
                 .initial_layout(vk::ImageLayout::UNDEFINED)
@@ -127,96 +527,314 @@ impl Texture2D {
             VmaImage::from(image_allocation)
         };
 
-
         { // transfer image data from staging buffer to dst image.
 
+            // Set barrier range between levels and layers across all the cube map image.
             let sub_range = vk::ImageSubresourceRange {
                 aspect_mask: vk::ImageAspectFlags::COLOR,
                 base_mip_level: 0,
-                level_count: tex_2d.levels() as vkuint,
+                level_count   : mip_levels,
                 base_array_layer: 0,
-                layer_count: 1,
+                layer_count     : CUBE_FACES_COUNT as vkuint,
             };
 
-            // Image barrier for optimal image (target).
-            // Optimal image will be used as destination for the copy.
-            let barrier1 = ImageBarrierCI::new(dst_image.handle, sub_range)
+            let barrier1 = ImageBarrierCI::new(&dst_image, sub_range)
                 .access_mask(vk::AccessFlags::empty(), vk::AccessFlags::TRANSFER_WRITE)
                 .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
 
-            // Change texture image layout to shader read after all mip levels have been copied.
-            let barrier2 = ImageBarrierCI::new(dst_image.handle, sub_range)
+            // change texture image layout to shader read after all faces have been copied.
+            let barrier2 = ImageBarrierCI::new(&dst_image, sub_range)
                 .access_mask(vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::SHADER_READ)
                 .layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
 
-
             let cmd_recorder = device.get_transfer_recorder();
 
             cmd_recorder.begin_record()?
                 .image_pipeline_barrier(vk::PipelineStageFlags::HOST, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[barrier1.into()])
-                // Copy mip levels from staging buffer.
-                .copy_buf2img(staging_buffer.handle, dst_image.handle, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &buffer_copy_regions)
+                .copy_buf2img(&staging_buffer, &dst_image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &buffer_copy_regions)
                 .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::ALL_COMMANDS, vk::DependencyFlags::empty(), &[barrier2.into()])
                 .end_record()?;
 
             device.flush_transfer(cmd_recorder)?;
         }
 
-
         { // clean up staging resources.
             device.vma_discard(staging_buffer)?;
         }
 
-        let dst_sampler = {
+        let dst_sampler = create_sampler(device, mip_levels, vk::SamplerAddressMode::CLAMP_TO_EDGE)?;
+        let dst_image_view = create_image_view(device, &dst_image, vk::ImageViewType::CUBE, format, mip_levels, CUBE_FACES_COUNT as vkuint)?;
+
+        let result = TextureCube {
+            image: dst_image,
+            view : dst_image_view,
+            sampler: dst_sampler,
+            descriptor: vk::DescriptorImageInfo {
+                sampler: dst_sampler,
+                image_view: dst_image_view,
+                image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            },
+            width, height, mip_levels,
+        };
+        Ok(result)
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        device.discard(self.sampler);
+        device.discard(self.view);
+        device.vma_discard(self.image)
+    }
+}
+
+/// 2D texture array, whose layers are loaded from separate files.
+pub struct Texture2DArray {
+
+    pub image: VmaImage,
+    pub view : vk::ImageView,
+
+    pub width       : vkuint,
+    pub height      : vkuint,
+    pub layer_count : vkuint,
+
+    pub sampler: vk::Sampler,
+    pub descriptor: vk::DescriptorImageInfo,
+}
+
+impl Texture2DArray {
+
+    /// Load the base level of each KTX file in `paths` as a layer of the texture array.
+    ///
+    /// All the images must share the same dimension, otherwise an error will be returned.
+    pub fn load(device: &mut VkDevice, paths: &[&Path], format: vk::Format) -> VkResult<Texture2DArray> {
+
+        if paths.is_empty() {
+            return Err(VkError::custom("Texture2DArray requires at least one layer."))
+        }
+
+        let mut layers = Vec::with_capacity(paths.len());
+        for path in paths.iter() {
+            layers.push(KtxReader::open(path)?);
+        }
+
+        let (width, height) = {
+            let header = layers[0].header();
+            (header.pixel_width, header.pixel_height)
+        };
+        let layer_count = layers.len() as vkuint;
 
-            // Create a default sampler.
-            let mut sampler_ci = SamplerCI::new()
-                .filter(vk::Filter::LINEAR, vk::Filter::LINEAR)
-                .mipmap(vk::SamplerMipmapMode::LINEAR)
-                .address(vk::SamplerAddressMode::REPEAT, vk::SamplerAddressMode::REPEAT, vk::SamplerAddressMode::REPEAT)
-                // max level-of-detail should match mip level count.
-                .lod(0.0, 0.0, tex_2d.levels() as vkfloat)
-                .compare_op(Some(vk::CompareOp::NEVER))
-                .border_color(vk::BorderColor::FLOAT_OPAQUE_WHITE);
+        // pack the base level of all layers into one staging buffer.
+        let mut layer_bytes = Vec::new();
+        let mut buffer_copy_regions = Vec::with_capacity(layers.len());
 
-            // Only enable anisotropic filtering if enabled on the device.
-            sampler_ci = if device.phy.features_enabled().sampler_anisotropy == vk::TRUE {
-                sampler_ci.anisotropy(Some(device.phy.limits.max_sampler_anisotropy))
-            } else {
-                sampler_ci.anisotropy(None)
+        for (i, (reader, path)) in layers.iter().zip(paths.iter()).enumerate() {
+
+            if reader.header().pixel_width != width || reader.header().pixel_height != height {
+                return Err(VkError::custom(format!("The dimension of texture layer {:?} does not match the first layer.", path)))
+            }
+
+            // the base level of the first layer and face is always the first region of KTX file.
+            let base_region = reader.regions().next()
+                .ok_or_else(|| VkError::custom(format!("Texture layer {:?} contains no image data.", path)))?;
+
+            let copy_region = vk::BufferImageCopy {
+                buffer_offset: layer_bytes.len() as vkbytes,
+                // specify the following two member to 0 to tell vulkan the image is tightly packed.
+                buffer_row_length  : 0,
+                buffer_image_height: 0,
+                image_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: 0,
+                    base_array_layer: i as vkuint,
+                    layer_count     : 1,
+                },
+                image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+                image_extent: vk::Extent3D { width, height, depth: 1 },
             };
+            buffer_copy_regions.push(copy_region);
+
+            layer_bytes.extend_from_slice(reader.region_bytes(base_region));
+        }
+
+        let staging_buffer = create_staging_buffer(device, layer_bytes.as_ptr(), layer_bytes.len())?;
+
+        // create optimal tiled target image on the device.
+        let dst_image = {
+
+            let image_ci = ImageCI::new_2d(format, vk::Extent2D { width, height })
+                .mip_levels(1)
+                .array_layers(layer_count)
+                .samples(vk::SampleCountFlags::TYPE_1)
+                .tiling(vk::ImageTiling::OPTIMAL)
+                .initial_layout(vk::ImageLayout::UNDEFINED)
+                .usages(vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED);
+
+            let allocation_ci = VmaAllocationCI::new(
+                vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
+            let image_allocation = device.vma.create_image(
+                image_ci.as_ref(), allocation_ci.as_ref())
+                .map_err(VkErrorKind::Vma)?;
 
-            sampler_ci.build(device)?
+            VmaImage::from(image_allocation)
         };
 
-        let dst_image_view = ImageViewCI::new(dst_image.handle, vk::ImageViewType::TYPE_2D, format)
-            .components(vk::ComponentMapping {
-                r: vk::ComponentSwizzle::R,
-                g: vk::ComponentSwizzle::G,
-                b: vk::ComponentSwizzle::B,
-                a: vk::ComponentSwizzle::A,
-            })
-            .sub_range(vk::ImageSubresourceRange {
+        { // transfer all layers from staging buffer to dst image.
+
+            let sub_range = vk::ImageSubresourceRange {
                 aspect_mask: vk::ImageAspectFlags::COLOR,
                 base_mip_level: 0,
-                level_count: tex_2d.levels() as vkuint,
+                level_count   : 1,
+                base_array_layer: 0,
+                layer_count,
+            };
+
+            let barrier1 = ImageBarrierCI::new(&dst_image, sub_range)
+                .access_mask(vk::AccessFlags::empty(), vk::AccessFlags::TRANSFER_WRITE)
+                .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+
+            let barrier2 = ImageBarrierCI::new(&dst_image, sub_range)
+                .access_mask(vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::SHADER_READ)
+                .layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+            let cmd_recorder = device.get_transfer_recorder();
+
+            cmd_recorder.begin_record()?
+                .image_pipeline_barrier(vk::PipelineStageFlags::HOST, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[barrier1.into()])
+                .copy_buf2img(&staging_buffer, &dst_image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &buffer_copy_regions)
+                .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::ALL_COMMANDS, vk::DependencyFlags::empty(), &[barrier2.into()])
+                .end_record()?;
+
+            device.flush_transfer(cmd_recorder)?;
+        }
+
+        { // clean up staging resources.
+            device.vma_discard(staging_buffer)?;
+        }
+
+        let dst_sampler = create_sampler(device, 1, vk::SamplerAddressMode::CLAMP_TO_EDGE)?;
+        let dst_image_view = create_image_view(device, &dst_image, vk::ImageViewType::TYPE_2D_ARRAY, format, 1, layer_count)?;
+
+        let result = Texture2DArray {
+            image: dst_image,
+            view : dst_image_view,
+            sampler: dst_sampler,
+            descriptor: vk::DescriptorImageInfo {
+                sampler: dst_sampler,
+                image_view: dst_image_view,
+                image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            },
+            width, height, layer_count,
+        };
+        Ok(result)
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        device.discard(self.sampler);
+        device.discard(self.view);
+        device.vma_discard(self.image)
+    }
+}
+
+/// 3D texture(etc. a color lookup table), which is sampled with `sampler3D` in shader.
+pub struct Texture3D {
+
+    pub image: VmaImage,
+    pub view : vk::ImageView,
+
+    pub width : vkuint,
+    pub height: vkuint,
+    pub depth : vkuint,
+
+    pub sampler: vk::Sampler,
+    pub descriptor: vk::DescriptorImageInfo,
+}
+
+impl Texture3D {
+
+    /// Upload tightly packed pixels in `bytes` as a 3D texture with single mip level.
+    ///
+    /// The pixels are ordered by x, then y, then z, and the size of `bytes` must match `width`, `height`, `depth` and `format`, otherwise an error is returned.
+    /// The texture is sampled with linear filter, and its coordinates are clamped to edge.
+    pub fn from_bytes(device: &mut VkDevice, bytes: &[u8], width: vkuint, height: vkuint, depth: vkuint, format: vk::Format) -> VkResult<Texture3D> {
+
+        check_texel_bytes(bytes, width as usize * height as usize * depth as usize, format)?;
+
+        let staging_buffer = create_staging_buffer(device, bytes.as_ptr(), bytes.len())?;
+
+        let dst_image = {
+
+            let image_ci = ImageCI::new(vk::ImageType::TYPE_3D, format, vk::Extent3D { width, height, depth })
+                .tiling(vk::ImageTiling::OPTIMAL)
+                .initial_layout(vk::ImageLayout::UNDEFINED)
+                .usages(vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED);
+
+            let allocation_ci = VmaAllocationCI::new(
+                vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
+            let image_allocation = device.vma.create_image(
+                image_ci.as_ref(), allocation_ci.as_ref())
+                .map_err(VkErrorKind::Vma)?;
+
+            VmaImage::from(image_allocation)
+        };
+
+        { // transfer image data from staging buffer to dst image.
+
+            let sub_range = vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                base_mip_level: 0,
+                level_count: 1,
                 base_array_layer: 0,
                 layer_count: 1,
-            })
-            .build(device)?;
+            };
 
+            let copy_region = vk::BufferImageCopy {
+                buffer_offset: 0,
+                buffer_row_length  : 0,
+                buffer_image_height: 0,
+                image_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: 0,
+                    base_array_layer: 0,
+                    layer_count     : 1,
+                },
+                image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+                image_extent: vk::Extent3D { width, height, depth },
+            };
 
-        let result = Texture2D {
+            let copy_dst_barrier = ImageBarrierCI::new(&dst_image, sub_range)
+                .access_mask(vk::AccessFlags::empty(), vk::AccessFlags::TRANSFER_WRITE)
+                .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+            let shader_read_barrier = ImageBarrierCI::new(&dst_image, sub_range)
+                .access_mask(vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::SHADER_READ)
+                .layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+            let cmd_recorder = device.get_transfer_recorder();
+
+            cmd_recorder.begin_record()?
+                .image_pipeline_barrier(vk::PipelineStageFlags::HOST, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[copy_dst_barrier.into()])
+                .copy_buf2img(&staging_buffer, &dst_image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[copy_region])
+                .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::ALL_COMMANDS, vk::DependencyFlags::empty(), &[shader_read_barrier.into()])
+                .end_record()?;
+
+            device.flush_transfer(cmd_recorder)?;
+        }
+
+        device.vma_discard(staging_buffer)?;
+
+        let dst_sampler = create_sampler(device, 1, vk::SamplerAddressMode::CLAMP_TO_EDGE)?;
+        let dst_image_view = create_image_view(device, &dst_image, vk::ImageViewType::TYPE_3D, format, 1, 1)?;
+
+        let result = Texture3D {
             image: dst_image,
             view : dst_image_view,
-            mip_levels: tex_2d.levels() as vkuint,
             sampler: dst_sampler,
             descriptor: vk::DescriptorImageInfo {
                 sampler: dst_sampler,
                 image_view: dst_image_view,
                 image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
             },
-            width, height,
+            width, height, depth,
         };
         Ok(result)
     }
@@ -228,3 +846,161 @@ impl Texture2D {
         device.vma_discard(self.image)
     }
 }
+
+/// Build the buffer copy regions for all mip levels of each image in `layers`.
+///
+/// The i-th element of `layers` is copied to the i-th array layer of destination image.
+/// The staging buffer is assumed to be tightly packed layer by layer, with all the levels of a layer stored contiguously(etc. layer 0 level 0, layer 0 level 1, ..., layer 1 level 0).
+/// This is the memory layout of gli textures, but not the layout of KTX files, which store all the layers of a level together(see `KtxReader::pack` for that case).
+pub fn layered_copy_regions(layers: &[gli::Texture2D]) -> Vec<vk::BufferImageCopy> {
+
+    let mut buffer_copy_regions = Vec::new();
+    let mut staging_offset = 0;
+
+    for (layer, texture_layer) in layers.iter().enumerate() {
+
+        for level in 0..texture_layer.levels() {
+
+            let layer_level_i = texture_layer.get_level(level);
+
+            let copy_region = vk::BufferImageCopy {
+                buffer_offset: staging_offset,
+                // specify the following two member to 0 to tell vulkan the image is tightly packed.
+                buffer_row_length  : 0,
+                buffer_image_height: 0,
+                image_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: level as vkuint,
+                    base_array_layer: layer as vkuint,
+                    layer_count     : 1,
+                },
+                image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+                image_extent: vk::Extent3D {
+                    width : layer_level_i.extent().width,
+                    height: layer_level_i.extent().height,
+                    depth : 1,
+                },
+            };
+
+            buffer_copy_regions.push(copy_region);
+            // Increase offset into staging buffer for next level/layer.
+            staging_offset += layer_level_i.size() as vkbytes;
+        }
+    }
+
+    buffer_copy_regions
+}
+
+/// The size of a texel of uncompressed color `format` in bytes, or `None` for other formats(etc. block-compressed formats).
+fn texel_size(format: vk::Format) -> Option<usize> {
+
+    let size = match format {
+        | vk::Format::R8_UNORM
+        | vk::Format::R8_SNORM
+        | vk::Format::R8_UINT
+        | vk::Format::R8_SRGB => 1,
+        | vk::Format::R8G8_UNORM
+        | vk::Format::R8G8_SNORM
+        | vk::Format::R8G8_UINT
+        | vk::Format::R16_UNORM
+        | vk::Format::R16_SFLOAT => 2,
+        | vk::Format::R8G8B8A8_UNORM
+        | vk::Format::R8G8B8A8_SNORM
+        | vk::Format::R8G8B8A8_UINT
+        | vk::Format::R8G8B8A8_SRGB
+        | vk::Format::B8G8R8A8_UNORM
+        | vk::Format::B8G8R8A8_SRGB
+        | vk::Format::A2B10G10R10_UNORM_PACK32
+        | vk::Format::R16G16_UNORM
+        | vk::Format::R16G16_SFLOAT
+        | vk::Format::R32_UINT
+        | vk::Format::R32_SFLOAT => 4,
+        | vk::Format::R16G16B16A16_UNORM
+        | vk::Format::R16G16B16A16_SFLOAT
+        | vk::Format::R32G32_SFLOAT => 8,
+        | vk::Format::R32G32B32A32_SFLOAT => 16,
+        | _ => return None,
+    };
+    Some(size)
+}
+
+/// Check that `bytes` contains exactly `texel_count` tightly packed texels of `format`, before they are copied to staging buffer.
+fn check_texel_bytes(bytes: &[u8], texel_count: usize, format: vk::Format) -> VkResult<()> {
+
+    let texel_size = texel_size(format)
+        .ok_or(VkError::unsupported("Uploading texture of compressed or unknown format from bytes"))?;
+
+    if bytes.len() != texel_count * texel_size {
+        return Err(VkError::custom(format!("The size of texture bytes is {}, but {} bytes are required for {} texels of {:?}.",
+            bytes.len(), texel_count * texel_size, texel_count, format)))
+    }
+
+    Ok(())
+}
+
+/// Create a host-visible staging buffer and copy `size` bytes from `data` to it.
+fn create_staging_buffer(device: &mut VkDevice, data: *const u8, size: usize) -> VkResult<VmaBuffer> {
+
+    let staging_ci = BufferCI::new(size as vkbytes)
+        .usage(vk::BufferUsageFlags::TRANSFER_SRC);
+    let allocation_ci = VmaAllocationCI::new(vma::MemoryUsage::CpuOnly, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT);
+    let staging_allocation = device.vma.create_buffer(
+        staging_ci.as_ref(), allocation_ci.as_ref())
+        .map_err(VkErrorKind::Vma)?;
+
+    // Copy texture data into staging buffer.
+    let data_ptr = device.vma.map_memory(&staging_allocation.1)
+        .map_err(VkErrorKind::Vma)?;
+    debug_assert_ne!(data_ptr, ptr::null_mut());
+
+    unsafe {
+        data_ptr.copy_from(data, size);
+    }
+
+    device.vma.unmap_memory(&staging_allocation.1)
+        .map_err(VkErrorKind::Vma)?;
+
+    Ok(VmaBuffer::from(staging_allocation))
+}
+
+/// Create a default sampler for texture with `mip_levels` levels.
+fn create_sampler(device: &VkDevice, mip_levels: vkuint, address_mode: vk::SamplerAddressMode) -> VkResult<vk::Sampler> {
+
+    let mut sampler_ci = SamplerCI::new()
+        .filter(vk::Filter::LINEAR, vk::Filter::LINEAR)
+        .mipmap(vk::SamplerMipmapMode::LINEAR)
+        .address(address_mode, address_mode, address_mode)
+        // max level-of-detail should match mip level count.
+        .lod(0.0, 0.0, mip_levels as vkfloat)
+        .compare_op(Some(vk::CompareOp::NEVER))
+        .border_color(vk::BorderColor::FLOAT_OPAQUE_WHITE);
+
+    // Only enable anisotropic filtering if enabled on the device.
+    sampler_ci = if device.phy.features_enabled().sampler_anisotropy == vk::TRUE {
+        sampler_ci.anisotropy(Some(device.phy.limits.max_sampler_anisotropy))
+    } else {
+        sampler_ci.anisotropy(None)
+    };
+
+    sampler_ci.build(device)
+}
+
+/// Create an image view covering all `mip_levels` levels and `layers` array layers of `image`.
+fn create_image_view(device: &VkDevice, image: impl VkImageHandle, view_type: vk::ImageViewType, format: vk::Format, mip_levels: vkuint, layers: vkuint) -> VkResult<vk::ImageView> {
+
+    ImageViewCI::new(image, view_type, format)
+        .components(vk::ComponentMapping {
+            r: vk::ComponentSwizzle::R,
+            g: vk::ComponentSwizzle::G,
+            b: vk::ComponentSwizzle::B,
+            a: vk::ComponentSwizzle::A,
+        })
+        .sub_range(vk::ImageSubresourceRange {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            base_mip_level: 0,
+            level_count: mip_levels,
+            base_array_layer: 0,
+            layer_count: layers,
+        })
+        .build(device)
+}
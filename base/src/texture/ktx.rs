@@ -0,0 +1,269 @@
+//! A minimal reader for KTX(version 1.1) files.
+//!
+//! See [KTX File Format Specification](https://www.khronos.org/opengles/sdk/tools/KTX/file_format_spec/) for more detail.
+
+use ash::vk;
+
+use crate::error::{VkResult, VkError};
+use crate::{vkuint, vkbytes};
+
+use std::path::Path;
+
+/// The 12 bytes identifier at the beginning of every KTX file.
+const KTX_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+/// The size of KTX header in bytes(including the identifier).
+const KTX_HEADER_SIZE: usize = 64;
+/// The value of endianness field when the file has the same endianness with reader.
+const KTX_ENDIAN_REF: u32 = 0x04030201;
+/// The value of endianness field when the file has the opposite endianness with reader.
+const KTX_ENDIAN_REF_REV: u32 = 0x01020304;
+
+/// The header fields of KTX file.
+#[derive(Debug, Clone)]
+pub struct KtxHeader {
+    pub gl_type: vkuint,
+    pub gl_type_size: vkuint,
+    pub gl_format: vkuint,
+    pub gl_internal_format: vkuint,
+    pub gl_base_internal_format: vkuint,
+    pub pixel_width : vkuint,
+    pub pixel_height: vkuint,
+    pub pixel_depth : vkuint,
+    pub array_elements: vkuint,
+    pub faces: vkuint,
+    pub mip_levels: vkuint,
+    pub bytes_of_key_value: vkuint,
+}
+
+impl KtxHeader {
+
+    /// The number of array layers, which is at least 1.
+    #[inline]
+    pub fn layer_count(&self) -> vkuint {
+        self.array_elements.max(1)
+    }
+
+    /// The number of mip levels, which is at least 1.
+    #[inline]
+    pub fn level_count(&self) -> vkuint {
+        self.mip_levels.max(1)
+    }
+
+    /// Return whether the file contains a cube map(or cube map array).
+    #[inline]
+    pub fn is_cubemap(&self) -> bool {
+        self.faces == 6
+    }
+
+    /// The dimension of the image at mip level `level`.
+    pub fn level_extent(&self, level: vkuint) -> vk::Extent3D {
+        vk::Extent3D {
+            width : (self.pixel_width  >> level).max(1),
+            height: (self.pixel_height >> level).max(1),
+            depth : (self.pixel_depth  >> level).max(1),
+        }
+    }
+}
+
+/// The location of image data for a specific (level, layer, face) in KTX file.
+#[derive(Debug, Clone)]
+pub struct KtxRegion {
+    pub level: vkuint,
+    pub layer: vkuint,
+    pub face : vkuint,
+    /// the byte offset of image data from the beginning of file.
+    pub offset: usize,
+    /// the size of image data in bytes.
+    pub size: usize,
+    /// the dimension of this image.
+    pub extent: vk::Extent3D,
+}
+
+/// Parse the layout of KTX file from its bytes.
+pub struct KtxReader {
+
+    header: KtxHeader,
+    bytes: Vec<u8>,
+    regions: Vec<KtxRegion>,
+}
+
+impl KtxReader {
+
+    /// Read and parse the KTX file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> VkResult<KtxReader> {
+
+        let bytes = ::std::fs::read(path.as_ref())
+            .map_err(|_| VkError::path(path))?;
+        KtxReader::parse(bytes)
+    }
+
+    /// Parse the header and image layout of KTX file from `bytes`.
+    pub fn parse(bytes: Vec<u8>) -> VkResult<KtxReader> {
+
+        if bytes.len() < KTX_HEADER_SIZE || bytes[0..12] != KTX_IDENTIFIER {
+            return Err(VkError::custom("Invalid KTX file identifier."))
+        }
+
+        let is_swap = match read_u32(&bytes, 12, false)? {
+            | KTX_ENDIAN_REF     => false,
+            | KTX_ENDIAN_REF_REV => true,
+            | _ => return Err(VkError::custom("Invalid KTX endianness.")),
+        };
+
+        let field = |i: usize| read_u32(&bytes, 16 + i * 4, is_swap);
+
+        let header = KtxHeader {
+            gl_type                 : field(0)?,
+            gl_type_size            : field(1)?,
+            gl_format               : field(2)?,
+            gl_internal_format      : field(3)?,
+            gl_base_internal_format : field(4)?,
+            pixel_width             : field(5)?,
+            pixel_height            : field(6)?,
+            pixel_depth             : field(7)?,
+            array_elements          : field(8)?,
+            faces                   : field(9)?,
+            mip_levels              : field(10)?,
+            bytes_of_key_value      : field(11)?,
+        };
+
+        if header.faces != 1 && header.faces != 6 {
+            return Err(VkError::custom("Invalid face count in KTX file."))
+        }
+
+        let regions = read_regions(&bytes, &header, is_swap)?;
+
+        let reader = KtxReader { header, bytes, regions };
+        Ok(reader)
+    }
+
+    #[inline]
+    pub fn header(&self) -> &KtxHeader {
+        &self.header
+    }
+
+    /// The raw bytes of the whole file.
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Return the image data of `region`.
+    #[inline]
+    pub fn region_bytes(&self, region: &KtxRegion) -> &[u8] {
+        &self.bytes[region.offset..(region.offset + region.size)]
+    }
+
+    /// Iterate the location of image data for each (level, layer, face) in the order of file layout.
+    #[inline]
+    pub fn regions(&self) -> impl Iterator<Item=&KtxRegion> {
+        self.regions.iter()
+    }
+
+    /// Pack all image data into a tightly packed bytes, and generate the corresponding buffer copy regions.
+    ///
+    /// The offset of each region in packed bytes is aligned to `alignment`,
+    /// which should be the texel block size of image format(and must be a multiple of 4).
+    pub fn pack(&self, alignment: usize) -> (Vec<u8>, Vec<vk::BufferImageCopy>) {
+
+        debug_assert!(alignment > 0 && alignment % 4 == 0);
+
+        let mut packed = Vec::with_capacity(self.bytes.len());
+        let mut copy_regions = Vec::with_capacity(self.regions.len());
+
+        // cube map faces are uploaded to array layers in the order of layer * 6 + face.
+        let faces = self.header.faces;
+
+        for region in self.regions.iter() {
+
+            let padding = (alignment - packed.len() % alignment) % alignment;
+            packed.resize(packed.len() + padding, 0);
+
+            let copy_region = vk::BufferImageCopy {
+                buffer_offset: packed.len() as vkbytes,
+                buffer_row_length  : 0,
+                buffer_image_height: 0,
+                image_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: region.level,
+                    base_array_layer: region.layer * faces + region.face,
+                    layer_count: 1,
+                },
+                image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+                image_extent: region.extent,
+            };
+
+            copy_regions.push(copy_region);
+            packed.extend_from_slice(self.region_bytes(region));
+        }
+
+        (packed, copy_regions)
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize, is_swap: bool) -> VkResult<u32> {
+
+    if offset + 4 > bytes.len() {
+        return Err(VkError::custom("Unexpected end of KTX file."))
+    }
+
+    let mut raw = [0_u8; 4];
+    raw.copy_from_slice(&bytes[offset..(offset + 4)]);
+
+    let value = u32::from_ne_bytes(raw);
+    if is_swap {
+        Ok(value.swap_bytes())
+    } else {
+        Ok(value)
+    }
+}
+
+/// Round `size` up to multiple of 4, as required by the padding rules of KTX.
+#[inline]
+fn pad4(size: usize) -> usize {
+    (size + 3) & !3
+}
+
+fn read_regions(bytes: &[u8], header: &KtxHeader, is_swap: bool) -> VkResult<Vec<KtxRegion>> {
+
+    let layer_count = header.layer_count();
+    let face_count  = header.faces;
+    // for non-array cube map, `imageSize` is the size of one face, and each face is padded.
+    let is_non_array_cubemap = header.is_cubemap() && header.array_elements == 0;
+
+    let mut regions = Vec::new();
+    let mut offset = KTX_HEADER_SIZE + header.bytes_of_key_value as usize;
+
+    for level in 0..header.level_count() {
+
+        let image_size = read_u32(bytes, offset, is_swap)? as usize;
+        offset += 4;
+
+        let extent = header.level_extent(level);
+        let level_start = offset;
+
+        let region_size = if is_non_array_cubemap {
+            image_size
+        } else {
+            image_size / (layer_count * face_count) as usize
+        };
+
+        for layer in 0..layer_count {
+            for face in 0..face_count {
+
+                if offset + region_size > bytes.len() {
+                    return Err(VkError::custom("Unexpected end of KTX file."))
+                }
+
+                regions.push(KtxRegion { level, layer, face, offset, size: region_size, extent });
+
+                offset += if is_non_array_cubemap { pad4(region_size) } else { region_size };
+            }
+        }
+
+        // skip the mip padding.
+        offset = level_start + pad4(offset - level_start);
+    }
+
+    Ok(regions)
+}
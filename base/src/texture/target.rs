@@ -0,0 +1,209 @@
+
+use ash::vk;
+
+use crate::ci::vma::{VmaImage, VmaAllocationCI, VkImageHandle};
+use crate::ci::image::{ImageCI, ImageViewCI, ImageBarrierCI, SamplerCI};
+use crate::ci::VkObjectBuildableCI;
+
+use crate::command::{VkCmdRecorder, ITransfer, CmdTransferApi};
+use crate::context::VkDevice;
+
+use crate::{VkResult, VkErrorKind};
+use crate::vkuint;
+
+
+/// Scale `dimension` by `scale`, and keep each side at least 1 pixel.
+pub fn scale_dimension(dimension: vk::Extent2D, scale: f32) -> vk::Extent2D {
+
+    vk::Extent2D {
+        width : ((dimension.width  as f32 * scale).round() as vkuint).max(1),
+        height: ((dimension.height as f32 * scale).round() as vkuint).max(1),
+    }
+}
+
+/// An offscreen color image that can be rendered to, sampled in shader, and blitted to other images.
+///
+/// It is mainly used to render the scene at a resolution independent of the swapchain(see `WindowConfig::render_scale`).
+/// Recreate it in `RenderWorkflow::swapchain_reload` when the window is resized.
+pub struct RenderTarget {
+
+    pub image: VmaImage,
+    pub view : vk::ImageView,
+
+    pub format: vk::Format,
+    pub dimension: vk::Extent2D,
+
+    pub sampler: vk::Sampler,
+    pub descriptor: vk::DescriptorImageInfo,
+}
+
+impl RenderTarget {
+
+    pub fn new(device: &mut VkDevice, format: vk::Format, dimension: vk::Extent2D) -> VkResult<RenderTarget> {
+
+        let image = {
+
+            let image_ci = ImageCI::new_2d(format, dimension)
+                .usages(vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_SRC);
+
+            let allocation_ci = VmaAllocationCI::new(
+                vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
+            let image_allocation = device.vma.create_image(
+                image_ci.as_ref(), allocation_ci.as_ref())
+                .map_err(VkErrorKind::Vma)?;
+
+            VmaImage::from(image_allocation)
+        };
+
+        let view = ImageViewCI::new(&image, vk::ImageViewType::TYPE_2D, format)
+            .sub_range(color_subrange())
+            .build(device)?;
+
+        let sampler = SamplerCI::new()
+            .filter(vk::Filter::LINEAR, vk::Filter::LINEAR)
+            .address(vk::SamplerAddressMode::CLAMP_TO_EDGE, vk::SamplerAddressMode::CLAMP_TO_EDGE, vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .build(device)?;
+
+        let descriptor = vk::DescriptorImageInfo {
+            sampler,
+            image_view: view,
+            image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        };
+
+        let target = RenderTarget { image, view, format, dimension, sampler, descriptor };
+        Ok(target)
+    }
+
+    /// Record the commands to blit this target onto `dst`(etc. a swapchain image) with linear filter, which upscales or downscales the content.
+    ///
+    /// `src_layout` is the current layout of this target(etc. the final layout of the render pass rendering to it), and it is restored after the blit.
+    /// The previous content of `dst` is discarded, and `dst` is transitioned to `dst_final_layout` after the blit.
+    pub fn record_blit(&self, device: &VkDevice, command: vk::CommandBuffer, src_layout: vk::ImageLayout, dst: impl VkImageHandle, dst_dimension: vk::Extent2D, dst_final_layout: vk::ImageLayout) {
+
+        let dst = dst.image_handle();
+
+        let src_to_transfer = ImageBarrierCI::new(&self.image, color_subrange())
+            .access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE, vk::AccessFlags::TRANSFER_READ)
+            .layout(src_layout, vk::ImageLayout::TRANSFER_SRC_OPTIMAL);
+        let dst_to_transfer = ImageBarrierCI::new(dst, color_subrange())
+            .access_mask(vk::AccessFlags::empty(), vk::AccessFlags::TRANSFER_WRITE)
+            .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+
+        let src_restore = ImageBarrierCI::new(&self.image, color_subrange())
+            .access_mask(vk::AccessFlags::TRANSFER_READ, vk::AccessFlags::SHADER_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+            .layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL, src_layout);
+        let dst_to_final = ImageBarrierCI::new(dst, color_subrange())
+            .access_mask(vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+            .layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL, dst_final_layout);
+
+        let blit_region = vk::ImageBlit {
+            src_subresource: color_layers(),
+            src_offsets: [vk::Offset3D { x: 0, y: 0, z: 0 }, extent_offset(self.dimension)],
+            dst_subresource: color_layers(),
+            dst_offsets: [vk::Offset3D { x: 0, y: 0, z: 0 }, extent_offset(dst_dimension)],
+        };
+
+        let recorder: VkCmdRecorder<ITransfer> = VkCmdRecorder::new(&device.logic, command);
+
+        recorder
+            .image_pipeline_barrier(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[src_to_transfer.into(), dst_to_transfer.into()])
+            .blit_image(&self.image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, dst, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[blit_region], vk::Filter::LINEAR)
+            .image_pipeline_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER | vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, vk::DependencyFlags::empty(), &[src_restore.into(), dst_to_final.into()]);
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        device.discard(self.sampler);
+        device.discard(self.view);
+        device.vma_discard(self.image)
+    }
+}
+
+/// An offscreen depth image that can be used as depth attachment, and sampled in shader(etc. by SSAO).
+///
+/// Its view only covers the depth aspect, so it can be bound as `sampler2D`.
+/// Set the final layout of the depth attachment to `DEPTH_STENCIL_READ_ONLY_OPTIMAL` before it is sampled.
+pub struct DepthTarget {
+
+    pub image: VmaImage,
+    pub view : vk::ImageView,
+
+    pub format: vk::Format,
+    pub dimension: vk::Extent2D,
+
+    pub sampler: vk::Sampler,
+    pub descriptor: vk::DescriptorImageInfo,
+}
+
+impl DepthTarget {
+
+    /// Create the depth target with `format`(etc. `VkDevice::phy.depth_format`).
+    pub fn new(device: &mut VkDevice, format: vk::Format, dimension: vk::Extent2D) -> VkResult<DepthTarget> {
+
+        let image = {
+
+            let image_ci = ImageCI::new_2d(format, dimension)
+                .usages(vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT | vk::ImageUsageFlags::SAMPLED);
+
+            let allocation_ci = VmaAllocationCI::new(
+                vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
+            let image_allocation = device.vma.create_image(
+                image_ci.as_ref(), allocation_ci.as_ref())
+                .map_err(VkErrorKind::Vma)?;
+
+            VmaImage::from(image_allocation)
+        };
+
+        let view = ImageViewCI::new(&image, vk::ImageViewType::TYPE_2D, format)
+            .sub_range(vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::DEPTH,
+                ..color_subrange()
+            })
+            .build(device)?;
+
+        // depth values should not be interpolated between texels.
+        let sampler = SamplerCI::new()
+            .filter(vk::Filter::NEAREST, vk::Filter::NEAREST)
+            .address(vk::SamplerAddressMode::CLAMP_TO_EDGE, vk::SamplerAddressMode::CLAMP_TO_EDGE, vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .build(device)?;
+
+        let descriptor = vk::DescriptorImageInfo {
+            sampler,
+            image_view: view,
+            image_layout: vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL,
+        };
+
+        let target = DepthTarget { image, view, format, dimension, sampler, descriptor };
+        Ok(target)
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        device.discard(self.sampler);
+        device.discard(self.view);
+        device.vma_discard(self.image)
+    }
+}
+
+fn color_subrange() -> vk::ImageSubresourceRange {
+    vk::ImageSubresourceRange {
+        aspect_mask: vk::ImageAspectFlags::COLOR,
+        base_mip_level: 0,
+        level_count   : 1,
+        base_array_layer: 0,
+        layer_count     : 1,
+    }
+}
+
+fn color_layers() -> vk::ImageSubresourceLayers {
+    vk::ImageSubresourceLayers {
+        aspect_mask: vk::ImageAspectFlags::COLOR,
+        mip_level: 0,
+        base_array_layer: 0,
+        layer_count     : 1,
+    }
+}
+
+fn extent_offset(dimension: vk::Extent2D) -> vk::Offset3D {
+    vk::Offset3D { x: dimension.width as _, y: dimension.height as _, z: 1 }
+}
@@ -1,16 +1,23 @@
 
-pub use self::text::{TextInfo, TextID, TextType, TextHAlign};
+pub use self::text::{TextInfo, TextID, TextType, TextHAlign, TextVAlign, TextLimits};
+pub use self::text::ascii_characters;
 
+mod atlas_cache;
 mod pipeline;
+mod shape;
 mod text;
 
 
 use ash::vk;
 
+use std::path::Path;
+
 use crate::context::{VkDevice, VkSwapchain};
 use crate::command::{VkCmdRecorder, IGraphics, CmdGraphicsApi};
 use crate::ui::pipeline::UIPipelineAsset;
-use crate::ui::text::TextPool;
+use crate::ui::shape::ShapePool;
+use crate::ui::text::{TextPool, GlyphImages, EMBEDDED_FONT_BYTES};
+use crate::utils::color::VkColor;
 use crate::VkResult;
 
 
@@ -21,35 +28,109 @@ pub struct UIRenderer {
     pipeline_asset: UIPipelineAsset,
 
     text_pool: TextPool,
+    /// the lines and rectangles drawn in current recording.
+    shape_pool: ShapePool,
 }
 
 impl UIRenderer {
 
-    pub fn new(device: &mut VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass) -> VkResult<UIRenderer> {
+    /// Create the renderer, whose text pool is sized by `text_limits`(use `TextLimits::default()` for the default limits).
+    pub fn new(device: &mut VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass, text_limits: TextLimits) -> VkResult<UIRenderer> {
+
+        let text_pool = TextPool::new(device, swapchain.dimension, text_limits)?;
+        UIRenderer::with_text_pool(device, swapchain, renderpass, text_pool)
+    }
+
+    /// Create the renderer using the font of `font_bytes`(the content of a `.ttf` or `.otf` file) instead of the embedded Roboto font.
+    ///
+    /// An error is returned if the bytes can not be parsed as a font.
+    pub fn with_font(device: &mut VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass, font_bytes: &[u8], text_limits: TextLimits) -> VkResult<UIRenderer> {
+
+        let text_pool = TextPool::from_font_bytes(device, swapchain.dimension, text_limits, font_bytes, ascii_characters())?;
+        UIRenderer::with_text_pool(device, swapchain, renderpass, text_pool)
+    }
+
+    /// Create the renderer whose glyphs are sampled as signed distance field if `is_sdf` is true,
+    /// so that the text stays crisp at any scale.
+    pub fn with_sdf(device: &mut VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass, is_sdf: bool, text_limits: TextLimits) -> VkResult<UIRenderer> {
+
+        if is_sdf {
+            let glyphs = GlyphImages::from_font_sdf(device, EMBEDDED_FONT_BYTES, ascii_characters())?;
+            let text_pool = TextPool::from_glyphs(device, swapchain.dimension, text_limits, glyphs)?;
+            UIRenderer::with_text_pool(device, swapchain, renderpass, text_pool)
+        } else {
+            UIRenderer::new(device, swapchain, renderpass, text_limits)
+        }
+    }
+
+    /// Create the renderer whose glyph atlas is loaded from the cache file at `cache_path`,
+    /// or sampled and written to `cache_path` if the cache does not exist or is outdated.
+    pub fn with_atlas_cache(device: &mut VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass, cache_path: impl AsRef<Path>, text_limits: TextLimits) -> VkResult<UIRenderer> {
+
+        let glyphs = GlyphImages::from_font_cached(device, EMBEDDED_FONT_BYTES, ascii_characters(), false, cache_path)?;
+        let text_pool = TextPool::from_glyphs(device, swapchain.dimension, text_limits, glyphs)?;
+        UIRenderer::with_text_pool(device, swapchain, renderpass, text_pool)
+    }
+
+    fn with_text_pool(device: &mut VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass, mut text_pool: TextPool) -> VkResult<UIRenderer> {
+
+        // scale the text by the HiDPI factor of window.
+        text_pool.swapchain_reload(swapchain.dimension, swapchain.hidpi_factor());
 
-        let text_pool = TextPool::new(device, swapchain.dimension)?;
         let pipeline_asset = pipeline::UIPipelineAsset::new(device, swapchain, renderpass, text_pool.glyphs_ref())?;
 
-        let renderer = UIRenderer { pipeline_asset, text_pool };
+        let shape_pool = ShapePool::new(device, swapchain.dimension)?;
+
+        let renderer = UIRenderer { pipeline_asset, text_pool, shape_pool };
         Ok(renderer)
     }
 
     pub fn record_command(&self, recorder: &VkCmdRecorder<IGraphics>) {
 
-        recorder.bind_pipeline(self.pipeline_asset.pipeline)
+        recorder.begin_label("UI overlay", VkColor::GREEN)
+            .bind_pipeline(self.pipeline_asset.pipeline)
             .bind_descriptor_sets(self.pipeline_asset.pipeline_layout, 0, &[self.pipeline_asset.descriptor_set], &[]);
 
-        self.text_pool.record_command(recorder);
+        self.text_pool.record_command(recorder, self.pipeline_asset.pipeline_layout);
+
+        recorder.bind_pipeline(self.pipeline_asset.shape_pipeline);
+        self.shape_pool.record_command(recorder);
+
+        recorder.end_label();
+    }
+
+    /// Draw a line from `p0` to `p1`(in pixels, with the origin at the top left of window).
+    ///
+    /// The shapes are immediate-mode: they are only rendered by the next call of `record_command`,
+    /// so call this method each time before the commands are recorded.
+    ///
+    /// The vertices of shapes are shared by all command buffers, so the shapes should keep the same among them.
+    pub fn draw_line(&self, p0: vk::Offset2D, p1: vk::Offset2D, color: VkColor) {
+        self.shape_pool.draw_line(p0, p1, color);
+    }
+
+    /// Draw the outline of `rect`(in pixels, with the origin at the top left of window). See `draw_line` for details.
+    pub fn draw_rect(&self, rect: vk::Rect2D, color: VkColor) {
+        self.shape_pool.draw_rect(rect, color);
     }
 
+    /// Reload the UI for `new_chain`, which also updates the text size if the window is moved to a display of different DPI.
     pub fn swapchain_reload(&mut self, device: &VkDevice, new_chain: &VkSwapchain, renderpass: vk::RenderPass) -> VkResult<()> {
 
         self.pipeline_asset.swapchain_reload(device, new_chain, renderpass)?;
-        self.text_pool.swapchain_reload();
+        self.text_pool.swapchain_reload(new_chain.dimension, new_chain.hidpi_factor());
+        self.shape_pool.swapchain_reload(new_chain.dimension);
 
         Ok(())
     }
 
+    /// Recreate the UI pipelines to render into `renderpass` whose color attachment has `sample_count` samples(etc. for MSAA).
+    ///
+    /// The pipelines use single sample by default.
+    pub fn set_sample_count(&mut self, device: &VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass, sample_count: vk::SampleCountFlags) -> VkResult<()> {
+        self.pipeline_asset.set_sample_count(device, swapchain, renderpass, sample_count)
+    }
+
     pub fn add_text(&mut self, text: TextInfo) -> VkResult<TextID> {
         self.text_pool.add_text(text)
     }
@@ -58,9 +139,18 @@ impl UIRenderer {
         self.text_pool.change_text(content, update_text);
     }
 
+    pub fn remove_text(&mut self, text_id: TextID) {
+        self.text_pool.remove_text(text_id);
+    }
+
+    pub fn set_visible(&mut self, text_id: TextID, is_visible: bool) {
+        self.text_pool.set_visible(text_id, is_visible);
+    }
+
     pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
 
         self.pipeline_asset.discard(device);
+        self.shape_pool.discard(device);
         self.text_pool.discard_by(device)
     }
 }
@@ -0,0 +1,158 @@
+//! Store the sampled glyph atlas to disk, so that the glyphs are not rasterized again on next launch.
+
+use ash::vk;
+use serde_derive::{Serialize, Deserialize};
+use rusttype::{Rect, HMetrics, point};
+
+use std::path::Path;
+
+use crate::ui::text::{GlyphLayout, GlyphLayouts, generate_glyphs_bytes};
+use crate::utils::hash::FnvHasher;
+use crate::error::{VkResult, VkError};
+use crate::vkuint;
+
+/// Increase this number when the layout of cache file or the way to sample glyphs changes.
+const CACHE_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct GlyphAtlasCache {
+
+    /// the hash of the parameters to sample the glyphs, used to check if the cache is outdated.
+    key: u64,
+    line_height: f32,
+
+    image_width : vkuint,
+    image_height: vkuint,
+    image_bytes : Vec<u8>,
+
+    glyphs: Vec<CachedGlyph>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedGlyph {
+
+    character: char,
+    min_uv: [f32; 2],
+    max_uv: [f32; 2],
+
+    advance_width: f32,
+    left_side_bearing: f32,
+    /// the bounding box in (min x, min y, max x, max y).
+    bounding_box: [f32; 4],
+}
+
+/// Load the glyph atlas from `cache_path` if it matches the font and characters, otherwise sample the glyphs and write them to `cache_path`.
+///
+/// Failing to read or write the cache file is not an error, since the glyphs can always be sampled again.
+pub(super) fn load_or_generate(cache_path: &Path, font_bytes: &[u8], font_scale: f32, characters: &[char], is_sdf: bool) -> VkResult<(GlyphLayouts, f32, Vec<u8>, vk::Extent2D)> {
+
+    let key = cache_key(font_bytes, font_scale, characters, is_sdf);
+
+    if cache_path.exists() {
+        match load_cache(cache_path, key) {
+            | Ok(Some(atlas)) => return Ok(atlas),
+            | Ok(None) => println!("[Info] The glyph atlas cache at {:?} is outdated and will be regenerated.", cache_path),
+            | Err(e) => println!("[Warning] Failed to load the glyph atlas cache at {:?}: {}", cache_path, e),
+        }
+    }
+
+    let atlas = generate_glyphs_bytes(font_bytes, font_scale, characters, is_sdf)?;
+
+    if let Err(e) = save_cache(cache_path, key, &atlas) {
+        println!("[Warning] Failed to write the glyph atlas cache to {:?}: {}", cache_path, e);
+    }
+
+    Ok(atlas)
+}
+
+fn cache_key(font_bytes: &[u8], font_scale: f32, characters: &[char], is_sdf: bool) -> u64 {
+
+    let mut characters = characters.to_vec();
+    characters.sort();
+    characters.dedup();
+
+    let characters: String = characters.into_iter().collect();
+
+    let mut hasher = FnvHasher::new();
+    hasher.write(&CACHE_VERSION.to_le_bytes());
+    hasher.write(font_bytes);
+    hasher.write(&font_scale.to_bits().to_le_bytes());
+    hasher.write(characters.as_bytes());
+    hasher.write(&[is_sdf as u8]);
+    hasher.finish()
+}
+
+/// Return `None` if the cache does not match `key`.
+fn load_cache(cache_path: &Path, key: u64) -> VkResult<Option<(GlyphLayouts, f32, Vec<u8>, vk::Extent2D)>> {
+
+    let bytes = ::std::fs::read(cache_path)
+        .map_err(|_| VkError::path(cache_path))?;
+    let cache: GlyphAtlasCache = bincode::deserialize(&bytes)
+        .map_err(VkError::serialize)?;
+
+    if cache.key != key {
+        return Ok(None)
+    }
+
+    // the atlas stores one byte per pixel.
+    let expected_length = cache.image_width as usize * cache.image_height as usize;
+    if cache.image_bytes.len() != expected_length {
+        return Err(VkError::custom(format!("The atlas image contains {} bytes, but {} bytes are expected for {}x{} pixels",
+            cache.image_bytes.len(), expected_length, cache.image_width, cache.image_height)))
+    }
+
+    let layouts = cache.glyphs.into_iter().map(|glyph| {
+        let layout = GlyphLayout {
+            min_uv: glyph.min_uv,
+            max_uv: glyph.max_uv,
+            h_metrics: HMetrics {
+                advance_width: glyph.advance_width,
+                left_side_bearing: glyph.left_side_bearing,
+            },
+            bounding_box: Rect {
+                min: point(glyph.bounding_box[0], glyph.bounding_box[1]),
+                max: point(glyph.bounding_box[2], glyph.bounding_box[3]),
+            },
+        };
+        (glyph.character, layout)
+    }).collect();
+
+    let dimension = vk::Extent2D {
+        width : cache.image_width,
+        height: cache.image_height,
+    };
+    Ok(Some((layouts, cache.line_height, cache.image_bytes, dimension)))
+}
+
+fn save_cache(cache_path: &Path, key: u64, atlas: &(GlyphLayouts, f32, Vec<u8>, vk::Extent2D)) -> VkResult<()> {
+
+    let (layouts, line_height, image_bytes, dimension) = atlas;
+
+    let glyphs = layouts.iter().map(|(character, layout)| {
+        CachedGlyph {
+            character: *character,
+            min_uv: layout.min_uv,
+            max_uv: layout.max_uv,
+            advance_width: layout.h_metrics.advance_width,
+            left_side_bearing: layout.h_metrics.left_side_bearing,
+            bounding_box: [
+                layout.bounding_box.min.x, layout.bounding_box.min.y,
+                layout.bounding_box.max.x, layout.bounding_box.max.y,
+            ],
+        }
+    }).collect();
+
+    let cache = GlyphAtlasCache {
+        key,
+        line_height: *line_height,
+        image_width : dimension.width,
+        image_height: dimension.height,
+        image_bytes : image_bytes.clone(),
+        glyphs,
+    };
+
+    let bytes = bincode::serialize(&cache)
+        .map_err(VkError::serialize)?;
+    ::std::fs::write(cache_path, bytes)
+        .map_err(|_| VkError::path(cache_path))
+}
@@ -17,6 +17,14 @@ pub(super) struct UIPipelineAsset {
 
     pub pipeline: vk::Pipeline,
     pub pipeline_layout: vk::PipelineLayout,
+
+    pub shape_pipeline: vk::Pipeline,
+    pub shape_pipeline_layout: vk::PipelineLayout,
+
+    /// indicate if the pipeline uses the fragment shader for SDF glyphs.
+    is_sdf: bool,
+    /// the sample count of the color attachment in render pass.
+    sample_count: vk::SampleCountFlags,
 }
 
 impl UIPipelineAsset {
@@ -24,13 +32,17 @@ impl UIPipelineAsset {
     pub fn new(device: &VkDevice, swapchain: &VkSwapchain, render_pass: vk::RenderPass, glyphs: &GlyphImages) -> VkResult<UIPipelineAsset> {
 
         let (desc_pool, desc_set, desc_set_layout) = setup_descriptor(device, glyphs)?;
-        let (pipeline, pipeline_layout) = prepare_pipelines(device, swapchain.dimension, render_pass, desc_set_layout)?;
+        let is_sdf = glyphs.is_sdf();
+        let sample_count = vk::SampleCountFlags::TYPE_1;
+        let (pipeline, pipeline_layout) = prepare_pipelines(device, swapchain.dimension, render_pass, desc_set_layout, is_sdf, sample_count)?;
+        let (shape_pipeline, shape_pipeline_layout) = prepare_shape_pipeline(device, swapchain.dimension, render_pass, sample_count)?;
 
         let result = UIPipelineAsset {
             descriptor_pool: desc_pool,
             descriptor_set: desc_set,
             descriptor_set_layout: desc_set_layout,
-            pipeline, pipeline_layout,
+            pipeline, pipeline_layout, is_sdf, sample_count,
+            shape_pipeline, shape_pipeline_layout,
         };
         Ok(result)
     }
@@ -40,13 +52,27 @@ impl UIPipelineAsset {
         device.discard(self.pipeline);
         device.discard(self.pipeline_layout);
 
-        let (pipeline, pipeline_layout) = prepare_pipelines(device, new_chain.dimension, renderpass, self.descriptor_set_layout)?;
+        let (pipeline, pipeline_layout) = prepare_pipelines(device, new_chain.dimension, renderpass, self.descriptor_set_layout, self.is_sdf, self.sample_count)?;
         self.pipeline = pipeline;
         self.pipeline_layout = pipeline_layout;
 
+        device.discard(self.shape_pipeline);
+        device.discard(self.shape_pipeline_layout);
+
+        let (shape_pipeline, shape_pipeline_layout) = prepare_shape_pipeline(device, new_chain.dimension, renderpass, self.sample_count)?;
+        self.shape_pipeline = shape_pipeline;
+        self.shape_pipeline_layout = shape_pipeline_layout;
+
         Ok(())
     }
 
+    /// Recreate the pipelines for the render pass whose color attachment has `sample_count` samples.
+    pub fn set_sample_count(&mut self, device: &VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass, sample_count: vk::SampleCountFlags) -> VkResult<()> {
+
+        self.sample_count = sample_count;
+        self.swapchain_reload(device, swapchain, renderpass)
+    }
+
     pub fn discard(&self, device: &VkDevice) {
 
         device.discard(self.descriptor_set_layout);
@@ -54,13 +80,16 @@ impl UIPipelineAsset {
 
         device.discard(self.pipeline);
         device.discard(self.pipeline_layout);
+
+        device.discard(self.shape_pipeline);
+        device.discard(self.shape_pipeline_layout);
     }
 }
 
 fn setup_descriptor(device: &VkDevice, glyphs: &GlyphImages) -> VkResult<(vk::DescriptorPool, vk::DescriptorSet, vk::DescriptorSetLayout)> {
 
     use crate::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use crate::ci::descriptor::{DescriptorSetAI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
+    use crate::ci::descriptor::{DescriptorImageSetWI, DescriptorSetsUpdateCI};
 
     // Descriptor Pool.
     let descriptor_pool = DescriptorPoolCI::new(1)
@@ -83,10 +112,7 @@ fn setup_descriptor(device: &VkDevice, glyphs: &GlyphImages) -> VkResult<(vk::De
         .build(device)?;
 
     // Descriptor set.
-    let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-        .add_set_layout(set_layout)
-        .build(device)?;
-    let descriptor_set = descriptor_sets.remove(0);
+    let descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
     // update descriptorsets.
     let sampled_image_write_info = DescriptorImageSetWI::new(descriptor_set, 0, vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
@@ -103,7 +129,7 @@ fn setup_descriptor(device: &VkDevice, glyphs: &GlyphImages) -> VkResult<(vk::De
     Ok((descriptor_pool, descriptor_set, set_layout))
 }
 
-fn prepare_pipelines(device: &VkDevice, dimension: vk::Extent2D, render_pass: vk::RenderPass, set_layout: vk::DescriptorSetLayout) -> VkResult<(vk::Pipeline, vk::PipelineLayout)> {
+fn prepare_pipelines(device: &VkDevice, dimension: vk::Extent2D, render_pass: vk::RenderPass, set_layout: vk::DescriptorSetLayout, is_sdf: bool, sample_count: vk::SampleCountFlags) -> VkResult<(vk::Pipeline, vk::PipelineLayout)> {
 
     use crate::ci::pipeline::*;
 
@@ -132,6 +158,7 @@ fn prepare_pipelines(device: &VkDevice, dimension: vk::Extent2D, render_pass: vk
     // Pipeline Layout.
     let pipeline_layout = PipelineLayoutCI::new()
         .add_set_layout(set_layout)
+        .add_push_constants(crate::ui::text::push_constant_range())
         .build(device)?;
 
     // base pipeline.
@@ -141,6 +168,7 @@ fn prepare_pipelines(device: &VkDevice, dimension: vk::Extent2D, render_pass: vk
     pipeline_ci.set_viewport(viewport_state);
     pipeline_ci.set_rasterization(rasterization_state);
     pipeline_ci.set_color_blend(blend_state);
+    pipeline_ci.set_multisample(MultisampleSCI::new().sample_count(sample_count));
 
     let mut shader_compiler = crate::utils::shaderc::VkShaderCompiler::new()?;
     let vert_codes = shader_compiler.compile_from_str(
@@ -148,8 +176,13 @@ fn prepare_pipelines(device: &VkDevice, dimension: vk::Extent2D, render_pass: vk
         shaderc::ShaderKind::Vertex,
         "[Vertex Shader]",
         "main")?;
+    let frag_source = if is_sdf {
+        include_str!("text_sdf.frag.glsl")
+    } else {
+        include_str!("text.frag.glsl")
+    };
     let frag_codes = shader_compiler.compile_from_str(
-        include_str!("text.frag.glsl"),
+        frag_source,
         shaderc::ShaderKind::Fragment,
         "[Fragment Shader]",
         "main")?;
@@ -171,3 +204,75 @@ fn prepare_pipelines(device: &VkDevice, dimension: vk::Extent2D, render_pass: vk
 
     Ok((text_pipeline, pipeline_layout))
 }
+
+fn prepare_shape_pipeline(device: &VkDevice, dimension: vk::Extent2D, render_pass: vk::RenderPass, sample_count: vk::SampleCountFlags) -> VkResult<(vk::Pipeline, vk::PipelineLayout)> {
+
+    use crate::ci::pipeline::*;
+
+    let viewport_state = ViewportSCI::new()
+        .add_viewport(vk::Viewport {
+            x: 0.0, y: 0.0,
+            width: dimension.width as f32, height: dimension.height as f32,
+            min_depth: 0.0, max_depth: 1.0,
+        })
+        .add_scissor(vk::Rect2D {
+            offset: vk::Offset2D { x: 0, y: 0 },
+            extent: dimension,
+        });
+
+    let input_assembly = InputAssemblySCI::new()
+        .topology(vk::PrimitiveTopology::LINE_LIST);
+
+    let rasterization_state = RasterizationSCI::new()
+        .polygon(vk::PolygonMode::FILL)
+        .cull_face(vk::CullModeFlags::NONE, vk::FrontFace::COUNTER_CLOCKWISE);
+
+    let blend_attachment = BlendAttachmentSCI::new()
+        .blend_enable(true)
+        .color(vk::BlendOp::ADD, vk::BlendFactor::SRC_ALPHA, vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+        .alpha(vk::BlendOp::ADD, vk::BlendFactor::SRC_ALPHA, vk::BlendFactor::ONE_MINUS_SRC_ALPHA);
+    let blend_state = ColorBlendSCI::new()
+        .add_attachment(blend_attachment);
+
+    // the shapes use neither descriptor nor push constant.
+    let pipeline_layout = PipelineLayoutCI::new()
+        .build(device)?;
+
+    let mut pipeline_ci = GraphicsPipelineCI::new(render_pass, pipeline_layout);
+
+    pipeline_ci.set_vertex_input(crate::ui::shape::input_descriptions());
+    pipeline_ci.set_input_assembly(input_assembly);
+    pipeline_ci.set_viewport(viewport_state);
+    pipeline_ci.set_rasterization(rasterization_state);
+    pipeline_ci.set_color_blend(blend_state);
+    pipeline_ci.set_multisample(MultisampleSCI::new().sample_count(sample_count));
+
+    let mut shader_compiler = crate::utils::shaderc::VkShaderCompiler::new()?;
+    let vert_codes = shader_compiler.compile_from_str(
+        include_str!("shape.vert.glsl"),
+        shaderc::ShaderKind::Vertex,
+        "[Vertex Shader]",
+        "main")?;
+    let frag_codes = shader_compiler.compile_from_str(
+        include_str!("shape.frag.glsl"),
+        shaderc::ShaderKind::Fragment,
+        "[Fragment Shader]",
+        "main")?;
+
+    let vert_module = ShaderModuleCI::new(vert_codes).build(device)?;
+    let frag_module = ShaderModuleCI::new(frag_codes).build(device)?;
+
+    let shaders = [
+        ShaderStageCI::new(vk::ShaderStageFlags::VERTEX, vert_module),
+        ShaderStageCI::new(vk::ShaderStageFlags::FRAGMENT, frag_module),
+    ];
+
+    pipeline_ci.set_shaders(&shaders);
+
+    let shape_pipeline = device.build(&pipeline_ci)?;
+
+    device.discard(vert_module);
+    device.discard(frag_module);
+
+    Ok((shape_pipeline, pipeline_layout))
+}
@@ -0,0 +1,164 @@
+//! Immediate-mode drawing of colored lines and rectangles(etc. crosshair or bounding box) in UI layer.
+
+use ash::vk;
+use memoffset::offset_of;
+
+use std::cell::RefCell;
+
+use crate::ci::buffer::BufferCI;
+use crate::ci::pipeline::VertexInputSCI;
+
+use crate::context::VkDevice;
+use crate::command::{VkCmdRecorder, IGraphics, CmdGraphicsApi};
+
+use crate::utils::color::VkColor;
+use crate::{vkuint, vkbytes, vkptr};
+use crate::VkResult;
+
+
+/// the maximum count of lines that can be drawn in one recording.
+const MAXIMUM_LINE_COUNT: usize = 2048;
+/// each line use 2 vertices to draw.
+const VERTEX_PER_LINE: usize = 2;
+
+/// The vertices attributes for each endpoint of lines.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct ShapeVertex {
+    pos  : [f32; 2],
+    color: [f32; 4],
+}
+
+/// A line in screen space(in pixels).
+struct ShapeLine {
+    p0: vk::Offset2D,
+    p1: vk::Offset2D,
+    color: VkColor,
+}
+
+pub struct ShapePool {
+
+    /// screen dimension of current window.
+    dimension: vk::Extent2D,
+    /// the lines waiting to be recorded, which are added during the recording of commands.
+    lines: RefCell<Vec<ShapeLine>>,
+
+    /// the starting pointer of the memory of vertices.
+    data_ptr: vkptr,
+    /// the buffer which store the vertices of lines.
+    buffer: vk::Buffer,
+    memory: vk::DeviceMemory,
+}
+
+impl ShapePool {
+
+    pub fn new(device: &VkDevice, dimension: vk::Extent2D) -> VkResult<ShapePool> {
+
+        let buffer_size = (::std::mem::size_of::<ShapeVertex>() * VERTEX_PER_LINE * MAXIMUM_LINE_COUNT) as vkbytes;
+        let (buffer, memory) = BufferCI::new(buffer_size)
+            .usage(vk::BufferUsageFlags::VERTEX_BUFFER)
+            .build_with_memory(device, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT)?;
+        // keep the memory mapping during the whole program running.
+        let data_ptr = device.map_memory(memory, 0, vk::WHOLE_SIZE)?;
+
+        let result = ShapePool {
+            lines: RefCell::new(Vec::new()),
+            dimension, data_ptr, buffer, memory,
+        };
+        Ok(result)
+    }
+
+    pub fn draw_line(&self, p0: vk::Offset2D, p1: vk::Offset2D, color: VkColor) {
+
+        let mut lines = self.lines.borrow_mut();
+        if lines.len() < MAXIMUM_LINE_COUNT {
+            lines.push(ShapeLine { p0, p1, color });
+        } else {
+            println!("[Warning] The count of lines exceeds the limit({}) of UIRenderer, the extra lines are ignored.", MAXIMUM_LINE_COUNT);
+        }
+    }
+
+    pub fn draw_rect(&self, rect: vk::Rect2D, color: VkColor) {
+
+        let (left, top) = (rect.offset.x, rect.offset.y);
+        let right  = left + rect.extent.width  as i32;
+        let bottom = top  + rect.extent.height as i32;
+
+        let top_left     = vk::Offset2D { x: left,  y: top };
+        let top_right    = vk::Offset2D { x: right, y: top };
+        let bottom_left  = vk::Offset2D { x: left,  y: bottom };
+        let bottom_right = vk::Offset2D { x: right, y: bottom };
+
+        self.draw_line(top_left, top_right, color);
+        self.draw_line(top_right, bottom_right, color);
+        self.draw_line(bottom_right, bottom_left, color);
+        self.draw_line(bottom_left, top_left, color);
+    }
+
+    /// Upload the lines drawn since last recording and record the draw command for them, then the lines are cleared.
+    ///
+    /// The pipeline for shapes must have been bound.
+    pub fn record_command(&self, recorder: &VkCmdRecorder<IGraphics>) {
+
+        let mut lines = self.lines.borrow_mut();
+        if lines.is_empty() {
+            return
+        }
+
+        let vertices: Vec<ShapeVertex> = lines.drain(..).flat_map(|line| {
+            let color: [f32; 4] = line.color.into();
+            let p0 = ShapeVertex { pos: to_ndc(line.p0, self.dimension), color };
+            let p1 = ShapeVertex { pos: to_ndc(line.p1, self.dimension), color };
+            vec![p0, p1]
+        }).collect();
+
+        // upload vertices attributes to memory.
+        unsafe {
+            (self.data_ptr as vkptr<ShapeVertex>).copy_from(vertices.as_ptr(), vertices.len());
+        }
+
+        recorder.bind_vertex_buffers(0, &[self.buffer], &[0])
+            .draw(vertices.len() as vkuint, 1, 0, 0);
+    }
+
+    pub fn swapchain_reload(&mut self, dimension: vk::Extent2D) {
+        self.dimension = dimension;
+    }
+
+    pub fn discard(self, device: &VkDevice) {
+
+        device.unmap_memory(self.memory);
+        device.discard(self.buffer);
+        device.discard(self.memory);
+    }
+}
+
+/// Convert the position in pixels to the position in normalized device coordinate.
+fn to_ndc(position: vk::Offset2D, dimension: vk::Extent2D) -> [f32; 2] {
+    [
+        position.x as f32 * 2.0 / dimension.width  as f32 - 1.0,
+        position.y as f32 * 2.0 / dimension.height as f32 - 1.0,
+    ]
+}
+
+pub fn input_descriptions() -> VertexInputSCI {
+
+    VertexInputSCI::new()
+        .add_binding(vk::VertexInputBindingDescription {
+            binding: 0,
+            stride : ::std::mem::size_of::<ShapeVertex>() as _,
+            input_rate: vk::VertexInputRate::VERTEX,
+        })
+        .add_attribute(vk::VertexInputAttributeDescription {
+            location: 0,
+            binding : 0,
+            format  : vk::Format::R32G32_SFLOAT,
+            offset  : offset_of!(ShapeVertex, pos) as _,
+        })
+        .add_attribute(vk::VertexInputAttributeDescription {
+            location: 1,
+            binding : 0,
+            format  : vk::Format::R32G32B32A32_SFLOAT,
+            offset  : offset_of!(ShapeVertex, color) as _,
+        })
+}
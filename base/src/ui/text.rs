@@ -7,9 +7,10 @@ use rusttype::{Rect, VMetrics, HMetrics};
 use std::ops::Range;
 use std::collections::HashMap;
 use std::iter::Iterator;
+use std::str::Chars;
+use std::path::Path;
 
 use crate::ci::buffer::BufferCI;
-use crate::ci::memory::MemoryAI;
 use crate::ci::image::{ImageCI, ImageViewCI, SamplerCI, ImageBarrierCI};
 use crate::ci::vma::{VmaBuffer, VmaImage, VmaAllocationCI};
 use crate::ci::pipeline::VertexInputSCI;
@@ -19,26 +20,42 @@ use crate::context::VkDevice;
 use crate::command::{VkCmdRecorder, IGraphics, CmdGraphicsApi, CmdTransferApi};
 
 use crate::utils::color::VkColor;
+use crate::utils::memory::any_as_u8_slice;
 use crate::{vkuint, vkbytes, vkptr};
 use crate::{VkResult, VkError, VkErrorKind};
 
 
 /// each character use 6 vertices to draw.
 const VERTEX_PER_CHARACTER: usize = 6;
-/// the maximum sentence count that the buffer can contain.
+/// the default maximum sentence count that the buffer can contain.
 const MAXIMUM_SENTENCE_COUNT: usize = 10;
-/// the maximum character count that a sentence may contain.
+/// the default maximum character count that a sentence may contain.
 const MAXIMUM_SENTENCE_TEXT_COUNT: usize = 100;
 /// Control the font size of sampled glyph.
 const FONT_SCALE: f32 = 48.0;
 /// A magic number.
 const DISPLAY_SCALE_FIX: f32 = 1.0 / 768.0;
+/// The window width(in logical pixels) that `DISPLAY_SCALE_FIX` was tuned for.
+/// The text keeps the size at this width in logical pixels for any window width and HiDPI factor.
+const REFERENCE_WINDOW_WIDTH: f32 = 1280.0;
 /// The padding attach to sampled glyph image.
 const IMAGE_PADDING: usize = 20;
+/// The distance(in pixels at `FONT_SCALE`) from the glyph edge that the signed distance field covers, which must not exceed `IMAGE_PADDING / 2`.
+const SDF_SPREAD: f32 = 8.0;
+/// The squared distance of the pixels that are not reached yet during distance transform.
+const DISTANCE_INFINITY: f64 = 1e20;
+/// The maximum width of sampled glyph image, the glyphs beyond this width are placed in the next row.
+const MAXIMUM_IMAGE_WIDTH: usize = 2048;
+/// the ascii character range that render to sampled glyph by default.
+const ASCII_RANGE: Range<u8> = (33..127_u8);
+/// the font used by `TextPool` if no font is specified.
+pub(crate) const EMBEDDED_FONT_BYTES: &[u8] = include_bytes!("../../../assets/fonts/Roboto-Regular.ttf");
+/// the character to render when a character is not contained in sampled glyph.
+const REPLACEMENT_CHARACTER: CharacterID = '?';
 
 pub type TextID = usize;
 type CharacterID = char;
-type GlyphLayouts = HashMap<CharacterID, GlyphLayout>;
+pub(super) type GlyphLayouts = HashMap<CharacterID, GlyphLayout>;
 
 /// The vertices attributes for each character.
 #[repr(C)]
@@ -49,14 +66,35 @@ struct CharacterVertex {
     color : [f32; 4],
 }
 
+impl CharacterVertex {
+    /// the vertex of a degenerate triangle, which renders nothing.
+    const EMPTY: CharacterVertex = CharacterVertex { pos: [0.0; 2], uv: [0.0; 2], color: [0.0; 4] };
+}
+
+/// The push constants of `text.vert.glsl` for each draw of text.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct TextDrawParams {
+    /// the color replacing the color of vertices, if `is_override_color` is 1.0.
+    override_color: [f32; 4],
+    /// the offset added to the position of vertices(in range [-1.0, 1.0]).
+    offset: [f32; 2],
+    is_override_color: f32,
+}
+
+impl TextDrawParams {
+    /// draw the text as it is.
+    const PLAIN: TextDrawParams = TextDrawParams { override_color: [0.0; 4], offset: [0.0; 2], is_override_color: 0.0 };
+}
+
 #[derive(Debug, Clone)]
-struct GlyphLayout {
+pub(super) struct GlyphLayout {
 
-    min_uv: [f32; 2],
-    max_uv: [f32; 2],
+    pub(super) min_uv: [f32; 2],
+    pub(super) max_uv: [f32; 2],
 
-    h_metrics: HMetrics,
-    bounding_box: Rect<f32>,
+    pub(super) h_metrics: HMetrics,
+    pub(super) bounding_box: Rect<f32>,
 }
 
 pub struct GlyphImages {
@@ -66,14 +104,47 @@ pub struct GlyphImages {
     pub glyph_view : vk::ImageView,
 
     layouts: GlyphLayouts,
+    /// the distance between the baselines of two lines(in pixels at `FONT_SCALE`).
+    line_height: f32,
+    /// indicate if `glyph_image` stores the signed distance field of glyphs instead of their coverage.
+    is_sdf: bool,
 }
 
 impl GlyphImages {
 
+    /// Sample the printable ASCII characters of the font.
     pub fn from_font(device: &mut VkDevice, bytes: &[u8]) -> VkResult<GlyphImages> {
+        GlyphImages::from_font_characters(device, bytes, ascii_characters())
+    }
+
+    /// Sample the glyphs of `characters`(an explicit set of Unicode code points, etc. `"àéü中文".chars()`) from the font.
+    ///
+    /// The characters that are not sampled are rendered as '?'.
+    pub fn from_font_characters(device: &mut VkDevice, bytes: &[u8], characters: impl IntoIterator<Item = char>) -> VkResult<GlyphImages> {
+        GlyphImages::sample(device, bytes, characters, false, None)
+    }
 
-        let (layouts, image_bytes, image_dimension) =
-            generate_ascii_glyphs_bytes(bytes, FONT_SCALE)?;
+    /// Sample the signed distance field of the glyphs of `characters` from the font.
+    ///
+    /// The text rendered from the distance field stays crisp at any scale, but it requires the fragment shader for SDF(see `UIRenderer::with_sdf`).
+    pub fn from_font_sdf(device: &mut VkDevice, bytes: &[u8], characters: impl IntoIterator<Item = char>) -> VkResult<GlyphImages> {
+        GlyphImages::sample(device, bytes, characters, true, None)
+    }
+
+    /// Load the glyphs from the atlas cache file at `cache_path`, or sample them and write the cache file if it does not exist or is outdated.
+    ///
+    /// The cache is regenerated whenever the font, `characters` or `is_sdf` changes.
+    pub fn from_font_cached(device: &mut VkDevice, bytes: &[u8], characters: impl IntoIterator<Item = char>, is_sdf: bool, cache_path: impl AsRef<Path>) -> VkResult<GlyphImages> {
+        GlyphImages::sample(device, bytes, characters, is_sdf, Some(cache_path.as_ref()))
+    }
+
+    fn sample(device: &mut VkDevice, bytes: &[u8], characters: impl IntoIterator<Item = char>, is_sdf: bool, cache_path: Option<&Path>) -> VkResult<GlyphImages> {
+
+        let characters: Vec<char> = characters.into_iter().collect();
+        let (layouts, line_height, image_bytes, image_dimension) = match cache_path {
+            | Some(cache_path) => crate::ui::atlas_cache::load_or_generate(cache_path, bytes, FONT_SCALE, &characters, is_sdf)?,
+            | None => generate_glyphs_bytes(bytes, FONT_SCALE, &characters, is_sdf)?,
+        };
         let glyph_image = allocate_glyph_image(device, image_bytes, image_dimension)?;
 
         // Just store alpha value in the image.
@@ -89,10 +160,27 @@ impl GlyphImages {
         let text_sampler = SamplerCI::new()
             .build(device)?;
 
-        let result = GlyphImages { text_sampler, glyph_image, glyph_view, layouts };
+        let result = GlyphImages { text_sampler, glyph_image, glyph_view, layouts, line_height, is_sdf };
         Ok(result)
     }
 
+    /// Check if the glyphs are sampled as signed distance field.
+    pub fn is_sdf(&self) -> bool {
+        self.is_sdf
+    }
+
+    /// Check if the glyph of `character` has been sampled.
+    pub fn contains(&self, character: char) -> bool {
+        self.layouts.contains_key(&character)
+    }
+
+    fn layout_of(&self, character: CharacterID) -> &GlyphLayout {
+
+        self.layouts.get(&character)
+            .or_else(|| self.layouts.get(&REPLACEMENT_CHARACTER))
+            .unwrap_or_else(|| &self.layouts[&' '])
+    }
+
     pub fn discard(self, device: &mut VkDevice) -> VkResult<()> {
 
         device.discard(self.text_sampler);
@@ -101,6 +189,50 @@ impl GlyphImages {
     }
 }
 
+/// The capacity of `TextPool`, which determines the size of its vertex buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct TextLimits {
+    /// the maximum count of texts that can be added to the pool.
+    pub max_sentence_count: usize,
+    /// the maximum count of characters that each text may contain(or the maximum `capacity` of dynamic text).
+    pub max_sentence_length: usize,
+}
+
+impl Default for TextLimits {
+
+    fn default() -> TextLimits {
+        TextLimits {
+            max_sentence_count : MAXIMUM_SENTENCE_COUNT,
+            max_sentence_length: MAXIMUM_SENTENCE_TEXT_COUNT,
+        }
+    }
+}
+
+impl TextLimits {
+
+    /// The count of vertices reserved for each text.
+    #[inline]
+    fn vertices_per_sentence(&self) -> usize {
+        self.max_sentence_length * VERTEX_PER_CHARACTER
+    }
+
+    /// The size of vertex buffer in bytes to contain all the texts.
+    fn buffer_size(&self) -> VkResult<vkbytes> {
+
+        if self.max_sentence_count == 0 || self.max_sentence_length == 0 {
+            return Err(VkError::custom("The limits of text pool must be greater than 0."))
+        }
+
+        self.max_sentence_length.checked_mul(VERTEX_PER_CHARACTER)
+            .and_then(|sentence_vertices| sentence_vertices.checked_mul(self.max_sentence_count))
+            // the first vertex of each text is passed to vkCmdDraw as u32.
+            .filter(|&vertex_count| vertex_count <= vkuint::max_value() as usize)
+            .and_then(|vertex_count| vertex_count.checked_mul(::std::mem::size_of::<CharacterVertex>()))
+            .map(|size| size as vkbytes)
+            .ok_or_else(|| VkError::custom(format!("The text pool of {} sentences with {} characters is too large.", self.max_sentence_count, self.max_sentence_length)))
+    }
+}
+
 struct TextAttrStorage {
     /// the starting pointer of the memory of text attributes.
     data_ptr: vkptr,
@@ -111,17 +243,12 @@ struct TextAttrStorage {
 
 impl TextAttrStorage {
 
-    fn new(device: &VkDevice) -> VkResult<TextAttrStorage> {
+    fn new(device: &VkDevice, limits: &TextLimits) -> VkResult<TextAttrStorage> {
 
-        let pool_size = (::std::mem::size_of::<CharacterVertex>() * MAXIMUM_SENTENCE_COUNT * MAXIMUM_SENTENCE_TEXT_COUNT * VERTEX_PER_CHARACTER) as vkbytes;
-        let (buffer, requirement) = BufferCI::new(pool_size)
+        let pool_size = limits.buffer_size()?;
+        let (buffer, memory) = BufferCI::new(pool_size)
             .usage(vk::BufferUsageFlags::VERTEX_BUFFER)
-            .build(device)?;
-
-        let memory_type = device.get_memory_type(requirement.memory_type_bits, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT);
-        let memory = MemoryAI::new(requirement.size, memory_type)
-            .build(device)?;
-        device.bind_memory(buffer, memory, 0)?;
+            .build_with_memory(device, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT)?;
         // keep the memory mapping during the whole program running.
         let data_ptr = device.map_memory(memory, 0, vk::WHOLE_SIZE)?;
 
@@ -144,26 +271,49 @@ pub struct TextPool {
     dimension: vk::Extent2D,
     // the aspect ratio of current screen dimension.
     aspect_ratio: f32,
+    /// the HiDPI factor of current window, which scales the text to keep its physical size.
+    hidpi_factor: f32,
 
-    /// all the texts to be rendered.
-    texts: Vec<TextInfo>,
+    /// all the texts to be rendered, indexed by `TextID`. The slot of a removed text is `None` and can be reused.
+    texts: Vec<Option<TextSlot>>,
+    /// the capacity of this pool.
+    limits: TextLimits,
     /// `attributes` contains the resource for rendering texts.
     attributes: TextAttrStorage,
     /// `glyph_layouts` records the layout information to generate text attributes.
     glyphs: GlyphImages,
 }
 
+/// A text added to `TextPool`.
+struct TextSlot {
+    info: TextInfo,
+    is_visible: bool,
+}
+
 pub struct TextInfo {
     /// `content` is the content of text to render.
     pub content: String,
     /// `scale` defines the font size of this text.
     pub scale  : f32,
-    /// `align` the align method for this text.
+    /// `align` the horizontal align method for this text.
     pub align  : TextHAlign,
+    /// `valign` the vertical align method for this text.
+    ///
+    /// The text is broken into lines by '\n', and each line is aligned horizontally by `align`.
+    pub valign : TextVAlign,
     /// `color` is color value of this text.
     pub color  : VkColor,
     /// `location` is the starting position of the first character.
     pub location: vk::Offset2D,
+    /// `outline` is the color and width(in pixels) of the outline around the glyphs, or `None` to render without outline.
+    ///
+    /// The outline is rendered by drawing the glyphs several times with small offsets before the text itself.
+    pub outline: Option<(VkColor, f32)>,
+    /// `shadow` is the color and offset(in pixels, positive to the right and down) of the drop shadow behind the text,
+    /// or `None` to render without shadow.
+    ///
+    /// The shadow is rendered before the outline, so that it can be combined with the outline.
+    pub shadow: Option<(VkColor, [f32; 2])>,
 
     pub r#type: TextType,
 }
@@ -179,11 +329,11 @@ pub enum TextType {
     Dynamic { capacity: usize },
 }
 
+/// Iterate the characters of a text, and yield `None` after the end of content until the capacity of text is reached.
 pub struct TextIter<'a> {
-    content: &'a [u8],
-    current: usize,
-    len: usize,
-    capacity: usize,
+    characters: Chars<'a>,
+    /// the count of characters remaining to yield.
+    remain: usize,
 }
 
 impl<'a> Iterator for TextIter<'a> {
@@ -191,116 +341,228 @@ impl<'a> Iterator for TextIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
 
-        let result = if self.current < self.len {
-            Some(self.content[self.current] as char)
-        } else {
-            if self.current < self.capacity {
-                None
-            } else {
-                return None
-            }
-        };
+        if self.remain == 0 {
+            return None
+        }
 
-        self.current += 1;
-        Some(result)
+        self.remain -= 1;
+        Some(self.characters.next())
     }
 }
 
 impl TextInfo {
 
     fn iter(&self) -> TextIter {
+        TextIter {
+            characters: self.content.chars(),
+            remain: self.character_capacity(),
+        }
+    }
 
-        let char_bytes = self.content.as_bytes();
-        let bytes_length = char_bytes.len();
-
-        let mut iter = TextIter {
-            content: char_bytes,
-            current: 0,
-            len: bytes_length,
-            capacity: 0,
-        };
-
-        iter.capacity = match self.r#type {
-            | TextType::Static => bytes_length,
+    /// The count of characters to render for this text.
+    fn character_capacity(&self) -> usize {
+        match self.r#type {
+            | TextType::Static => self.content.chars().count(),
             | TextType::Dynamic { capacity } => capacity,
-        };
-        iter
+        }
     }
 }
 
-/// The horizontal align of a specific text.
+/// The horizontal align of a specific text, which applies to each line.
 pub enum TextHAlign {
     Left,
     Center,
     Right,
 }
 
+/// The vertical align of a specific text, relative to its `location`.
+pub enum TextVAlign {
+    /// the first line starts at `location`.
+    Top,
+    /// the center of all lines is at `location`.
+    Center,
+    /// the last line ends at `location`.
+    Bottom,
+}
+
+/// Move the vertices of a line horizontally, where `line_length` is the length of line in range [0.0, 1.0].
+fn align_line(line_vertices: &mut [CharacterVertex], align: &TextHAlign, line_length: f32) {
+
+    // the length in range [-1.0, 1.0] is twice of `line_length`.
+    let x_shift = match align {
+        | TextHAlign::Left   => return, // currently the text is left align.
+        | TextHAlign::Center => line_length,
+        | TextHAlign::Right  => line_length * 2.0,
+    };
+
+    for char_vertex in line_vertices.iter_mut() {
+        char_vertex.pos[0] -= x_shift; // pos[0] is the x coordinate.
+    }
+}
+
 impl TextPool {
 
-    pub fn new(device: &mut VkDevice, dimension: vk::Extent2D) -> VkResult<TextPool> {
+    /// Create the text pool containing at most `limits.max_sentence_count` texts(use `TextLimits::default()` for the default limits).
+    pub fn new(device: &mut VkDevice, dimension: vk::Extent2D, limits: TextLimits) -> VkResult<TextPool> {
+        TextPool::with_characters(device, dimension, limits, ascii_characters())
+    }
 
-        let attributes = TextAttrStorage::new(device)?;
+    /// Create the text pool that is able to render `characters`, in addition to space.
+    pub fn with_characters(device: &mut VkDevice, dimension: vk::Extent2D, limits: TextLimits, characters: impl IntoIterator<Item = char>) -> VkResult<TextPool> {
+        TextPool::from_font_bytes(device, dimension, limits, EMBEDDED_FONT_BYTES, characters)
+    }
 
-        let font_bytes = include_bytes!("../../../assets/fonts/Roboto-Regular.ttf");
-        let glyphs = GlyphImages::from_font(device, font_bytes)?;
+    /// Create the text pool rendering `characters` with the font of `font_bytes`(the content of a `.ttf` or `.otf` file),
+    /// instead of the embedded Roboto font.
+    ///
+    /// Use `ascii_characters()` to render the printable ASCII characters.
+    pub fn from_font_bytes(device: &mut VkDevice, dimension: vk::Extent2D, limits: TextLimits, font_bytes: &[u8], characters: impl IntoIterator<Item = char>) -> VkResult<TextPool> {
+
+        // check the font before any resource is allocated.
+        validate_font(font_bytes)?;
+
+        let glyphs = GlyphImages::from_font_characters(device, font_bytes, characters)?;
+        TextPool::from_glyphs(device, dimension, limits, glyphs)
+    }
+
+    /// Create the text pool rendering the sampled `glyphs`(etc. the glyphs from `GlyphImages::from_font_sdf`).
+    pub fn from_glyphs(device: &mut VkDevice, dimension: vk::Extent2D, limits: TextLimits, glyphs: GlyphImages) -> VkResult<TextPool> {
+
+        let attributes = TextAttrStorage::new(device, &limits)?;
 
         let result = TextPool {
-            texts: Vec::new(),
+            texts: Vec::with_capacity(limits.max_sentence_count),
+            limits,
             aspect_ratio: dimension.width as f32 / dimension.height as f32,
+            hidpi_factor: 1.0,
             attributes, glyphs, dimension,
         };
         Ok(result)
     }
 
-    pub fn add_text(&mut self, mut text: TextInfo) -> VkResult<TextID> {
-
-        if self.texts.len() < MAXIMUM_SENTENCE_COUNT {
-            if text.content.len() <= MAXIMUM_SENTENCE_TEXT_COUNT {
-
-                text.scale *= DISPLAY_SCALE_FIX / FONT_SCALE;
-
-                let new_text_id = self.texts.len();
-                self.texts.push(text);
+    /// Add `text` to the pool, which reuses the slot of a removed text if any.
+    ///
+    /// Record the commands again after adding texts, since the draw of new text has not been recorded yet.
+    pub fn add_text(&mut self, text: TextInfo) -> VkResult<TextID> {
+
+        let free_slot = self.texts.iter().position(Option::is_none);
+
+        if free_slot.is_some() || self.texts.len() < self.limits.max_sentence_count {
+            if text.character_capacity() <= self.limits.max_sentence_length {
+
+                let slot = TextSlot { info: text, is_visible: true };
+                let new_text_id = match free_slot {
+                    | Some(free_id) => {
+                        self.texts[free_id] = Some(slot);
+                        free_id
+                    },
+                    | None => {
+                        self.texts.push(Some(slot));
+                        self.texts.len() - 1
+                    },
+                };
                 // update the text that is newly added.
                 self.update_texts(new_text_id);
 
                 Ok(new_text_id)
             } else {
-                Err(VkError::custom(format!("Each sentence can't contain more that {} character.", MAXIMUM_SENTENCE_TEXT_COUNT)))
+                Err(VkError::custom(format!("Each sentence can't contain more that {} character.", self.limits.max_sentence_length)))
             }
         } else {
-            Err(VkError::custom(format!("The text pool can't contain more than {} sentence.", MAXIMUM_SENTENCE_COUNT)))
+            Err(VkError::custom(format!("The text pool can't contain more than {} sentence.", self.limits.max_sentence_count)))
         }
     }
 
     pub fn change_text(&mut self, content: String, update_text: TextID) {
 
-        self.texts[update_text].content = content;
+        self.slot_mut(update_text).info.content = content;
         self.update_texts(update_text);
     }
 
-    fn update_texts(&self, update_text: TextID) {
+    /// Remove the text of `text_id`, whose slot may be reused by the texts added later.
+    ///
+    /// The text disappears immediately, and `text_id` becomes invalid.
+    pub fn remove_text(&mut self, text_id: TextID) {
 
-        // calculate vertices attributes of rendering texts.
-        let mut char_vertices = Vec::with_capacity(self.texts.len() * MAXIMUM_SENTENCE_TEXT_COUNT * VERTEX_PER_CHARACTER);
+        self.texts.get_mut(text_id)
+            .and_then(Option::take)
+            .unwrap_or_else(|| panic!("Invalid TextID: {}, the text may have been removed.", text_id));
+        self.clear_vertices(text_id);
+    }
+
+    /// Show or hide the text of `text_id`.
+    ///
+    /// Hiding takes effect immediately, but the commands must be recorded again after a text is shown,
+    /// since the hidden texts are skipped by `record_command`.
+    pub fn set_visible(&mut self, text_id: TextID, is_visible: bool) {
+
+        self.slot_mut(text_id).is_visible = is_visible;
+        self.update_texts(text_id);
+    }
 
-        let text = &self.texts[update_text];
+    pub fn is_visible(&self, text_id: TextID) -> bool {
+        self.texts.get(text_id)
+            .and_then(Option::as_ref)
+            .map_or(false, |slot| slot.is_visible)
+    }
 
-        let mut origin_x = text.location.x as f32 / self.dimension.width as f32;
-        let origin_y = text.location.y as f32 / self.dimension.height as f32;
+    fn slot_mut(&mut self, text_id: TextID) -> &mut TextSlot {
+        self.texts.get_mut(text_id)
+            .and_then(Option::as_mut)
+            .unwrap_or_else(|| panic!("Invalid TextID: {}, the text may have been removed.", text_id))
+    }
 
-        for ch in text.iter() {
+    fn update_texts(&self, update_text: TextID) {
 
-            // use ' '(space) character instead if all the characters of current text has been rendered, but not yet reached its capacity.
-            let character_id = ch.unwrap_or(' ');
+        let text = match self.texts[update_text] {
+            | Some(ref slot) if slot.is_visible => &slot.info,
+            | _ => return self.clear_vertices(update_text),
+        };
 
-            let glyph_layout = self.glyphs.layouts.get(&character_id)
-                .expect(&format!("Find invalid character: {}({}).", character_id, character_id as u8));
+        // calculate vertices attributes of rendering texts.
+        let mut char_vertices = Vec::with_capacity(self.limits.vertices_per_sentence());
+
+        let line_start_x = text.location.x as f32 / self.dimension.width as f32;
+        let mut origin_x = line_start_x;
+        let mut origin_y = text.location.y as f32 / self.dimension.height as f32;
+
+        // the scale from the glyph layouts to the normalized screen space.
+        let scale = text.scale * self.display_scale();
+        let line_advance = self.glyphs.line_height * scale * self.aspect_ratio;
+        let mut line_count = 1;
+        // the index of the first vertex of current line in `char_vertices`.
+        let mut line_first_vertex = 0;
+
+        // the characters beyond the limits are discarded, since they do not fit in the slot of this text.
+        for ch in text.iter().take(self.limits.max_sentence_length) {
+
+            let character_id = match ch {
+                | Some('\n') => {
+                    align_line(&mut char_vertices[line_first_vertex..], &text.align, origin_x - line_start_x);
+
+                    // each character always takes the vertices of a character, so that the vertex count of a text keeps unchanged.
+                    char_vertices.extend_from_slice(&[CharacterVertex::EMPTY; VERTEX_PER_CHARACTER]);
+
+                    line_first_vertex = char_vertices.len();
+                    origin_x = line_start_x;
+                    origin_y += line_advance;
+                    line_count += 1;
+                    continue
+                },
+                | Some('\r') | None => {
+                    // render nothing if all the characters of current text has been rendered, but not yet reached its capacity.
+                    char_vertices.extend_from_slice(&[CharacterVertex::EMPTY; VERTEX_PER_CHARACTER]);
+                    continue
+                },
+                | Some(character) => character,
+            };
 
-            let x_offset     = glyph_layout.bounding_box.min.x    * text.scale;
-            let y_offset     = glyph_layout.bounding_box.min.y    * text.scale * self.aspect_ratio;
-            let glyph_width  = glyph_layout.bounding_box.width()  * text.scale;
-            let glyph_height = glyph_layout.bounding_box.height() * text.scale * self.aspect_ratio;
+            let glyph_layout = self.glyphs.layout_of(character_id);
+
+            let x_offset     = glyph_layout.bounding_box.min.x    * scale;
+            let y_offset     = glyph_layout.bounding_box.min.y    * scale * self.aspect_ratio;
+            let glyph_width  = glyph_layout.bounding_box.width()  * scale;
+            let glyph_height = glyph_layout.bounding_box.height() * scale * self.aspect_ratio;
 
             // the x coordinate of top-left position(map to range [-1.0, 1.0]).
             let min_x = (origin_x + x_offset) * 2.0 - 1.0;
@@ -343,63 +605,147 @@ impl TextPool {
                 top_left, bottom_right, top_right,   // triangle 2
             ]);
 
-            origin_x += glyph_layout.h_metrics.advance_width * text.scale;
+            origin_x += glyph_layout.h_metrics.advance_width * scale;
         }
 
-        // adjust the position of each vertices to make text alignment.
-        match text.align {
-            | TextHAlign::Left => {
-                // currently the text is left align.
-            },
-            | TextHAlign::Center => {
-                // move the text the center position.
-                let text_half_length = origin_x - text.location.x as f32 / self.dimension.width as f32;
-                for char_vertex in char_vertices.iter_mut() {
-                    char_vertex.pos[0] -= text_half_length;
-                }
-            },
-            | TextHAlign::Right => {
-                // make text align to right.
-                let text_half_length = origin_x - text.location.x as f32 / self.dimension.width as f32;
-                let text_length = text_half_length * 2.0;
-                for char_vertex in char_vertices.iter_mut() {
-                    char_vertex.pos[0] -= text_length; // pos[0] is the x coordinate.
-                }
-            },
+        // adjust the position of each vertices of the last line to make text alignment.
+        align_line(&mut char_vertices[line_first_vertex..], &text.align, origin_x - line_start_x);
+
+        // move all the lines to make vertical alignment.
+        let text_height = line_advance * line_count as f32;
+        let y_shift = match text.valign {
+            | TextVAlign::Top    => 0.0,
+            | TextVAlign::Center => text_height, // half of the height, in range [-1.0, 1.0].
+            | TextVAlign::Bottom => text_height * 2.0,
+        };
+        if y_shift != 0.0 {
+            for char_vertex in char_vertices.iter_mut() {
+                char_vertex.pos[1] -= y_shift; // pos[1] is the y coordinate.
+            }
         }
 
+        // fill the rest of the slot with empty vertices, so that it can be spanned by the draw call of static texts.
+        char_vertices.resize(self.limits.vertices_per_sentence(), CharacterVertex::EMPTY);
+
+        self.write_vertices(update_text, &char_vertices);
+    }
+
+    /// Fill the slot of `text_id` with empty vertices, so that nothing is rendered for it.
+    fn clear_vertices(&self, text_id: TextID) {
+        self.write_vertices(text_id, &vec![CharacterVertex::EMPTY; self.limits.vertices_per_sentence()]);
+    }
+
+    fn write_vertices(&self, text_id: TextID, char_vertices: &[CharacterVertex]) {
+
+        debug_assert!(char_vertices.len() <= self.limits.vertices_per_sentence());
+
         // upload vertices attributes to memory.
         unsafe {
             let target_ptr = (self.attributes.data_ptr as vkptr<CharacterVertex>)
-                .offset((MAXIMUM_SENTENCE_TEXT_COUNT * VERTEX_PER_CHARACTER * update_text) as isize);
+                .offset((self.limits.vertices_per_sentence() * text_id) as isize);
             target_ptr.copy_from(char_vertices.as_ptr(), char_vertices.len());
         }
     }
 
-    pub fn record_command(&self, recorder: &VkCmdRecorder<IGraphics>) {
+    /// `pipeline_layout` must contain the push constants of `TextDrawParams` in vertex stage.
+    ///
+    /// The consecutive static texts without outline and shadow are drawn in one draw call.
+    pub fn record_command(&self, recorder: &VkCmdRecorder<IGraphics>, pipeline_layout: vk::PipelineLayout) {
 
         recorder.bind_vertex_buffers(0, &[self.attributes.buffer], &[0]);
 
-        let mut first_vertex = 0;
-        for text in self.texts.iter() {
+        // the first vertex and the end vertex of the static texts waiting to be drawn.
+        let mut static_batch: Option<(vkuint, vkuint)> = None;
+
+        for (text_id, slot) in self.texts.iter().enumerate() {
 
-            let character_count = match text.r#type {
-                | TextType::Static => text.content.len(),
-                | TextType::Dynamic { capacity } => capacity,
+            let text = match slot {
+                | Some(slot) if slot.is_visible => &slot.info,
+                | _ => continue,
             };
+
+            let first_vertex = (self.limits.vertices_per_sentence() * text_id) as vkuint;
+            let character_count = text.character_capacity().min(self.limits.max_sentence_length);
             let render_vertex_count = (character_count * VERTEX_PER_CHARACTER) as vkuint;
-            recorder.draw(render_vertex_count, 1, first_vertex, 0);
-            first_vertex += (MAXIMUM_SENTENCE_TEXT_COUNT * VERTEX_PER_CHARACTER) as vkuint;
+
+            let is_batchable = text.outline.is_none() && text.shadow.is_none() && if let TextType::Static = text.r#type { true } else { false };
+            if is_batchable {
+                // the vertices between two texts(the unused part of slots and the slots of hidden or removed texts) are all empty,
+                // so the batch can span them by the slot stride.
+                let batch_first_vertex = static_batch.map_or(first_vertex, |(batch_first, _)| batch_first);
+                static_batch = Some((batch_first_vertex, first_vertex + render_vertex_count));
+                continue;
+            }
+
+            // draw the preceding static texts first to keep the drawing order.
+            if let Some((batch_first, batch_end)) = static_batch.take() {
+                draw_text_vertices(recorder, pipeline_layout, &TextDrawParams::PLAIN, batch_first, batch_end - batch_first);
+            }
+
+            // draw the shadow at the bottom.
+            if let Some((shadow_color, shadow_offset)) = text.shadow {
+                let shadow_params = TextDrawParams {
+                    override_color: shadow_color.into(),
+                    offset: [
+                        shadow_offset[0] * 2.0 / self.dimension.width  as f32,
+                        shadow_offset[1] * 2.0 / self.dimension.height as f32,
+                    ],
+                    is_override_color: 1.0,
+                };
+                draw_text_vertices(recorder, pipeline_layout, &shadow_params, first_vertex, render_vertex_count);
+            }
+
+            // draw the glyphs around the text in the outline color, and then the text covers them.
+            if let Some((outline_color, outline_width)) = text.outline {
+                for offset in self.outline_offsets(outline_width).iter() {
+                    let outline_params = TextDrawParams {
+                        override_color: outline_color.into(),
+                        offset: *offset,
+                        is_override_color: 1.0,
+                    };
+                    draw_text_vertices(recorder, pipeline_layout, &outline_params, first_vertex, render_vertex_count);
+                }
+            }
+
+            draw_text_vertices(recorder, pipeline_layout, &TextDrawParams::PLAIN, first_vertex, render_vertex_count);
+        }
+
+        if let Some((batch_first, batch_end)) = static_batch {
+            draw_text_vertices(recorder, pipeline_layout, &TextDrawParams::PLAIN, batch_first, batch_end - batch_first);
         }
     }
 
-    pub fn swapchain_reload(&mut self) {
+    /// The offsets in range [-1.0, 1.0] to draw the outline of `width` pixels, in 8 directions.
+    fn outline_offsets(&self, width: f32) -> [[f32; 2]; 8] {
+
+        let dx = width * 2.0 / self.dimension.width  as f32;
+        let dy = width * 2.0 / self.dimension.height as f32;
+        let (diagonal_x, diagonal_y) = (dx * ::std::f32::consts::FRAC_1_SQRT_2, dy * ::std::f32::consts::FRAC_1_SQRT_2);
+
+        [
+            [dx, 0.0], [-dx, 0.0], [0.0, dy], [0.0, -dy],
+            [diagonal_x, diagonal_y], [-diagonal_x, diagonal_y], [diagonal_x, -diagonal_y], [-diagonal_x, -diagonal_y],
+        ]
+    }
+
+    /// Update the screen `dimension`(in physical pixels) and `hidpi_factor` of the new swapchain, and regenerate all texts.
+    pub fn swapchain_reload(&mut self, dimension: vk::Extent2D, hidpi_factor: f32) {
+
+        self.dimension = dimension;
+        self.aspect_ratio = dimension.width as f32 / dimension.height as f32;
+        self.hidpi_factor = hidpi_factor;
 
         for i in 0..self.texts.len() {
             self.update_texts(i);
         }
     }
 
+    /// The factor converting the `scale` of text to the width ratio of screen,
+    /// so that the text has the same physical size on any window width and display DPI.
+    fn display_scale(&self) -> f32 {
+        DISPLAY_SCALE_FIX / FONT_SCALE * self.hidpi_factor * REFERENCE_WINDOW_WIDTH / self.dimension.width as f32
+    }
+
     pub fn glyphs_ref(&self) -> &GlyphImages {
         &self.glyphs
     }
@@ -411,47 +757,77 @@ impl TextPool {
     }
 }
 
-fn generate_ascii_glyphs_bytes(font_bytes: &[u8], font_scale: f32) -> VkResult<(GlyphLayouts, Vec<u8>, vk::Extent2D)> {
-
-    use rusttype::{Font, Scale, PositionedGlyph, point};
-
-    /// the ascii character range that render to sampled glyph.
-    const ASCII_RANGE: Range<u8> = (33..127_u8);
+pub(super) fn generate_glyphs_bytes(font_bytes: &[u8], font_scale: f32, characters: &[char], is_sdf: bool) -> VkResult<(GlyphLayouts, f32, Vec<u8>, vk::Extent2D)> {
 
-    let font = Font::from_bytes(font_bytes)
-        .map_err(|e| VkError::custom(e.to_string()))?;
-    let ascii_bytes: Vec<u8> = ASCII_RANGE.collect();
+    use rusttype::{Scale, PositionedGlyph, point};
 
-    let ascii_characters = unsafe { String::from_utf8_unchecked(ascii_bytes.clone()) };
+    let font = validate_font(font_bytes)?;
 
     let scale = Scale::uniform(font_scale);
     let v_metrics = font.v_metrics(scale);
+    let row_height = (v_metrics.ascent - v_metrics.descent).ceil() as usize + IMAGE_PADDING;
 
-    let glyphs_start_point = point(IMAGE_PADDING as f32, IMAGE_PADDING as f32 + v_metrics.ascent);
-    let glyphs: Vec<PositionedGlyph> = font.layout(&ascii_characters, scale, glyphs_start_point)
-        .collect();
-    let glyphs_height = (v_metrics.ascent - v_metrics.descent).ceil() as usize;
-    let glyphs_width = {
-        let min_x = glyphs.first()
-            .map(|g| g.pixel_bounding_box().unwrap().min.x)
-            .unwrap();
-        let max_x = glyphs.last()
-            .map(|g| g.pixel_bounding_box().unwrap().max.x)
-            .unwrap();
-        (max_x - min_x) as usize
-    };
+    let mut glyph_layouts = GlyphLayouts::new();
+    let mut glyphs: Vec<(CharacterID, PositionedGlyph)> = Vec::with_capacity(characters.len());
+
+    // place the glyphs row by row, and wrap to next row if a row is too wide.
+    let mut cursor_x = IMAGE_PADDING;
+    let mut cursor_y = IMAGE_PADDING;
+    let mut image_width = IMAGE_PADDING;
+
+    // space is always required to fill the capacity of dynamic text.
+    let mut characters: Vec<char> = characters.iter().cloned().chain(Some(' ')).collect();
+    characters.sort_unstable();
+    characters.dedup();
+
+    for character in characters.into_iter() {
+
+        let glyph = font.glyph(character);
+        if glyph.id().0 == 0 {
+            println!("[Warning] The font does not contain the glyph of character {:?}(U+{:04X}), which will be skipped.", character, character as u32);
+            continue
+        }
+
+        let glyph_scaled = glyph.scaled(scale);
+
+        let bounding_box = match glyph_scaled.exact_bounding_box() {
+            | Some(bounding_box) => bounding_box,
+            | None => {
+                // the whitespace characters does not have a bounding box, so they only advance the position.
+                // set the same uv for min and max position, so that nothing will be render for them.
+                let glyph_layout = GlyphLayout {
+                    min_uv: [0.0, 0.0],
+                    max_uv: [0.0, 0.0],
+                    h_metrics: glyph_scaled.h_metrics(),
+                    bounding_box: Rect { min: point(0.0, 0.0), max: point(0.0, 0.0) },
+                };
+                glyph_layouts.insert(character, glyph_layout);
+                continue
+            },
+        };
+
+        let glyph_width = (bounding_box.max.x - bounding_box.min.x).ceil() as usize + IMAGE_PADDING;
+        if cursor_x + glyph_width > MAXIMUM_IMAGE_WIDTH && cursor_x > IMAGE_PADDING {
+            cursor_x = IMAGE_PADDING;
+            cursor_y += row_height;
+        }
+
+        let glyph_start_point = point(cursor_x as f32 - bounding_box.min.x.floor(), cursor_y as f32 + v_metrics.ascent);
+        glyphs.push((character, glyph_scaled.positioned(glyph_start_point)));
+
+        cursor_x += glyph_width;
+        image_width = image_width.max(cursor_x);
+    }
 
-    let image_width  = (2 * IMAGE_PADDING) + glyphs_width;
-    let image_height = (2 * IMAGE_PADDING) + glyphs_height;
+    let image_width  = image_width + IMAGE_PADDING;
+    let image_height = cursor_y + row_height + IMAGE_PADDING;
     let bytes_per_pixel = 1; // only store the alpha value.
 
     // fill image data with empty bytes.
     let mut image_bytes = vec![0_u8; image_width * image_height * bytes_per_pixel];
 
-    let mut glyph_layouts = GlyphLayouts::new();
-
     // fill color to image data.
-    for (glyph, character) in glyphs.iter().zip(ascii_bytes.into_iter()) {
+    for (character, glyph) in glyphs.iter() {
 
         if let Some(bounding_box) = glyph.pixel_bounding_box() {
             // Draw the glyph into the image per-pixel by using the draw closure.
@@ -479,22 +855,129 @@ fn generate_ascii_glyphs_bytes(font_bytes: &[u8], font_scale: f32) -> VkResult<(
                 h_metrics: glyph_unpositioned.h_metrics(),
                 bounding_box: fix_bounding_box_positive(glyph_unpositioned.exact_bounding_box().unwrap(), &v_metrics),
             };
-            glyph_layouts.insert(character as CharacterID, glyph_layout);
+            glyph_layouts.insert(*character, glyph_layout);
         }
     }
 
-    // set the layout of space the same with 't', since space does not have a bounding box.
-    let mut space_layout = glyph_layouts.get(&'t').unwrap().clone();
-    // set the same uv for min and max position, so that nothing will be render for space.
-    space_layout.max_uv = space_layout.min_uv;
-    glyph_layouts.insert(' ', space_layout);
+    if glyph_layouts.contains_key(&' ') == false {
+        return Err(VkError::custom("The font does not contain the glyph of space character."))
+    }
 
+    // the padding between glyphs leaves enough space for the distance field to spread.
+    let image_bytes = if is_sdf {
+        generate_distance_field(&image_bytes, image_width, image_height)
+    } else {
+        image_bytes
+    };
 
     let dimension = vk::Extent2D {
         width : image_width  as vkuint,
         height: image_height as vkuint,
     };
-    Ok((glyph_layouts, image_bytes, dimension))
+    let line_height = v_metrics.ascent - v_metrics.descent + v_metrics.line_gap;
+    Ok((glyph_layouts, line_height, image_bytes, dimension))
+}
+
+/// Convert the glyph coverage of `image_bytes` to the signed distance field.
+///
+/// The distance is positive inside the glyphs, and it is mapped from `[-SDF_SPREAD, SDF_SPREAD]` to `[0, 255]`, so that the glyph edge lies on 128.
+fn generate_distance_field(image_bytes: &[u8], width: usize, height: usize) -> Vec<u8> {
+
+    // the squared distance to the nearest pixel inside glyphs.
+    let mut to_inside: Vec<f64> = image_bytes.iter()
+        .map(|&v| if v >= 128 { 0.0 } else { DISTANCE_INFINITY })
+        .collect();
+    // the squared distance to the nearest pixel outside glyphs.
+    let mut to_outside: Vec<f64> = image_bytes.iter()
+        .map(|&v| if v >= 128 { DISTANCE_INFINITY } else { 0.0 })
+        .collect();
+
+    distance_transform_2d(&mut to_inside, width, height);
+    distance_transform_2d(&mut to_outside, width, height);
+
+    to_inside.iter().zip(to_outside.iter()).map(|(&inside, &outside)| {
+        let signed_distance = (outside.sqrt() - inside.sqrt()) as f32;
+        let normalized = 0.5 + signed_distance / (2.0 * SDF_SPREAD);
+        (normalized.max(0.0).min(1.0) * 255.0).round() as u8
+    }).collect()
+}
+
+/// The exact squared euclidean distance transform of `grid`(Felzenszwalb and Huttenlocher), applied to columns and then rows.
+fn distance_transform_2d(grid: &mut [f64], width: usize, height: usize) {
+
+    let length = width.max(height);
+    let mut f = vec![0.0; length];
+    let mut d = vec![0.0; length];
+    let mut v = vec![0_usize; length];
+    let mut z = vec![0.0; length + 1];
+
+    for x in 0..width {
+        for y in 0..height {
+            f[y] = grid[y * width + x];
+        }
+        distance_transform_1d(&f[..height], &mut d[..height], &mut v, &mut z);
+        for y in 0..height {
+            grid[y * width + x] = d[y];
+        }
+    }
+
+    for y in 0..height {
+        let row = &mut grid[(y * width)..((y + 1) * width)];
+        f[..width].copy_from_slice(row);
+        distance_transform_1d(&f[..width], row, &mut v, &mut z);
+    }
+}
+
+/// Compute the lower envelope of the parabolas rooted at each sample of `f`.
+fn distance_transform_1d(f: &[f64], d: &mut [f64], v: &mut [usize], z: &mut [f64]) {
+
+    let intersection = |q: usize, p: usize| {
+        ((f[q] + (q * q) as f64) - (f[p] + (p * p) as f64)) / (2.0 * q as f64 - 2.0 * p as f64)
+    };
+
+    let mut k = 0;
+    v[0] = 0;
+    z[0] = -DISTANCE_INFINITY;
+    z[1] = DISTANCE_INFINITY;
+
+    for q in 1..f.len() {
+        let mut s = intersection(q, v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = intersection(q, v[k]);
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = DISTANCE_INFINITY;
+    }
+
+    k = 0;
+    for q in 0..f.len() {
+        while z[k + 1] < q as f64 {
+            k += 1;
+        }
+        let offset = q as f64 - v[k] as f64;
+        d[q] = offset * offset + f[v[k]];
+    }
+}
+
+fn draw_text_vertices(recorder: &VkCmdRecorder<IGraphics>, pipeline_layout: vk::PipelineLayout, params: &TextDrawParams, first_vertex: vkuint, vertex_count: vkuint) {
+
+    recorder.push_constants(pipeline_layout, vk::ShaderStageFlags::VERTEX, 0, unsafe { any_as_u8_slice(params) })
+        .draw(vertex_count, 1, first_vertex, 0);
+}
+
+/// The printable ASCII characters, which are sampled by default.
+pub fn ascii_characters() -> impl Iterator<Item = char> {
+    ASCII_RANGE.map(char::from)
+}
+
+/// Parse `font_bytes` to check if it is a valid font.
+fn validate_font(font_bytes: &[u8]) -> VkResult<rusttype::Font> {
+
+    rusttype::Font::from_bytes(font_bytes)
+        .map_err(|e| VkError::custom(format!("Failed to parse the font: {}", e)))
 }
 
 fn allocate_glyph_image(device: &mut VkDevice, image_bytes: Vec<u8>, image_dimension: vk::Extent2D) -> VkResult<VmaImage> {
@@ -583,6 +1066,15 @@ fn fix_bounding_box_positive(mut rect: Rect<f32>, v_metrics: &VMetrics) -> Rect<
     rect
 }
 
+/// The push constant range of `TextDrawParams`.
+pub fn push_constant_range() -> vk::PushConstantRange {
+    vk::PushConstantRange {
+        stage_flags: vk::ShaderStageFlags::VERTEX,
+        offset: 0,
+        size: ::std::mem::size_of::<TextDrawParams>() as _,
+    }
+}
+
 pub fn input_descriptions() -> VertexInputSCI {
 
     VertexInputSCI::new()
@@ -1,4 +1,3 @@
-
 #version 450 core
 #extension GL_ARB_separate_shader_objects : enable
 
@@ -9,10 +8,19 @@ layout (location = 2) in vec4 inColor;
 layout (location = 0) out vec2 outUV;
 layout (location = 1) out vec4 outColor;
 
+layout (push_constant) uniform TextDrawParams {
+    // the color replacing `inColor`(etc. the color of outline).
+    vec4  override_color;
+    // the offset added to `inPos`.
+    vec2  offset;
+    // 1.0 to use `override_color`, 0.0 to use `inColor`.
+    float is_override_color;
+} params;
+
 void main() {
 
-    gl_Position = vec4(inPos, 0.0, 1.0);
+    gl_Position = vec4(inPos + params.offset, 0.0, 1.0);
 
     outUV = inUV;
-    outColor = inColor;
+    outColor = mix(inColor, params.override_color, params.is_override_color);
 }
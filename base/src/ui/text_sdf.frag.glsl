@@ -0,0 +1,27 @@
+
+#version 450 core
+#extension GL_ARB_separate_shader_objects : enable
+
+layout (location = 0) in vec2 inUV;
+layout (location = 1) in vec4 inColor;
+
+layout (location = 0) out vec4 outColor;
+
+// the glyph edge lies on 0.5 of the distance field.
+layout (binding = 0) uniform sampler2D font_glyphs;
+
+void main() {
+
+    float distance = texture(font_glyphs, inUV).r;
+    // keep the anti-aliased edge about one pixel wide at any scale.
+    float smooth_width = fwidth(distance) * 0.7;
+    float alpha = smoothstep(0.5 - smooth_width, 0.5 + smooth_width, distance);
+
+    vec4 color = vec4(inColor.xyz, inColor.w * alpha);
+
+    if (color.a <= 0.01) {
+        discard;
+    }
+
+    outColor = color;
+}
@@ -1,5 +1,6 @@
 
 use crate::vkfloat;
+use crate::error::{VkResult, VkError};
 
 #[derive(Debug, Clone, Copy)]
 pub struct VkColor {
@@ -31,6 +32,67 @@ impl VkColor {
             a: (a as f32) / 255.0,
         }
     }
+
+    /// Create a color from 8-bit channels(etc. picked from a color picker). It is the same as `new_u8`.
+    #[inline]
+    pub fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> VkColor {
+        VkColor::new_u8(r, g, b, a)
+    }
+
+    /// Parse a color from hex string in `#RRGGBB` or `#RRGGBBAA` format. The leading `#` is optional, and the alpha is 1.0 if omitted.
+    ///
+    /// For example, `VkColor::from_hex("#FF8000")` is the orange color `(1.0, 0.5, 0.0, 1.0)`.
+    pub fn from_hex(hex: &str) -> VkResult<VkColor> {
+
+        let digits = hex.trim_start_matches('#');
+
+        if (digits.len() != 6 && digits.len() != 8) || !digits.is_ascii() {
+            return Err(VkError::custom(format!("Invalid hex color `{}`, which should be in `#RRGGBB` or `#RRGGBBAA` format.", hex)))
+        }
+
+        let channel = |index: usize| -> VkResult<u8> {
+            u8::from_str_radix(&digits[index * 2..index * 2 + 2], 16)
+                .map_err(|_| VkError::custom(format!("Invalid hex color `{}`, which contains non-hex digits.", hex)))
+        };
+
+        let alpha = if digits.len() == 8 { channel(3)? } else { 255 };
+        Ok(VkColor::from_rgba8(channel(0)?, channel(1)?, channel(2)?, alpha))
+    }
+
+    /// Convert this color from sRGB space to linear space. The alpha channel is unchanged.
+    ///
+    /// The colors picked in sRGB(etc. by `from_hex`) should be converted to linear before written to an `_SRGB` attachment,
+    /// since the hardware will encode them to sRGB again(etc. sRGB 0.5 is linear 0.214).
+    pub fn to_linear(&self) -> VkColor {
+        VkColor::new(srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b), self.a)
+    }
+
+    /// Convert a color from linear space to sRGB space. The alpha channel is unchanged.
+    ///
+    /// It is the inverse of `to_linear`(etc. linear 0.214 is sRGB 0.5).
+    pub fn from_linear(linear: VkColor) -> VkColor {
+        VkColor::new(linear_to_srgb(linear.r), linear_to_srgb(linear.g), linear_to_srgb(linear.b), linear.a)
+    }
+}
+
+/// The sRGB electro-optical transfer function, see the sRGB specification(IEC 61966-2-1).
+fn srgb_to_linear(value: vkfloat) -> vkfloat {
+
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// The inverse of `srgb_to_linear`.
+fn linear_to_srgb(value: vkfloat) -> vkfloat {
+
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
 }
 
 impl From<[vkfloat; 4]> for VkColor {
@@ -75,3 +137,70 @@ impl From<VkColor> for [u8; 4] {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::VkColor;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < EPSILON, "expected {}, but got {}", expected, actual);
+    }
+
+    #[test]
+    fn srgb_half_is_linear_0_214() {
+
+        let linear = VkColor::new(0.5, 0.5, 0.5, 0.5).to_linear();
+        assert_near(linear.r, 0.214);
+        assert_near(linear.g, 0.214);
+        assert_near(linear.b, 0.214);
+        // alpha is not converted.
+        assert_near(linear.a, 0.5);
+
+        let srgb = VkColor::from_linear(VkColor::new(0.214, 0.214, 0.214, 1.0));
+        assert_near(srgb.r, 0.5);
+    }
+
+    #[test]
+    fn black_and_white_round_trip() {
+
+        for &value in [0.0, 1.0].iter() {
+
+            let color = VkColor::new(value, value, value, 1.0);
+            assert_near(color.to_linear().r, value);
+            assert_near(VkColor::from_linear(color).r, value);
+            assert_near(VkColor::from_linear(color.to_linear()).r, value);
+        }
+    }
+
+    #[test]
+    fn parse_hex_colors() {
+
+        let orange = VkColor::from_hex("#FF8000").unwrap();
+        assert_near(orange.r, 1.0);
+        assert_near(orange.g, 128.0 / 255.0);
+        assert_near(orange.b, 0.0);
+        assert_near(orange.a, 1.0);
+
+        // the leading `#` is optional, and the last two digits are alpha.
+        let translucent = VkColor::from_hex("00ff0080").unwrap();
+        assert_near(translucent.g, 1.0);
+        assert_near(translucent.a, 128.0 / 255.0);
+    }
+
+    #[test]
+    fn reject_invalid_hex_colors() {
+
+        // wrong length.
+        assert!(VkColor::from_hex("#FFF").is_err());
+        assert!(VkColor::from_hex("#FF80001").is_err());
+        assert!(VkColor::from_hex("").is_err());
+        // non-hex digits.
+        assert!(VkColor::from_hex("#GG8000").is_err());
+        assert!(VkColor::from_hex("#FF80-0").is_err());
+        // 6 bytes, but not 6 ASCII digits.
+        assert!(VkColor::from_hex("#ÿÿÿ").is_err());
+    }
+}
@@ -4,6 +4,8 @@ use std::time::Instant;
 const FPS_SAMPLE_COUNT: usize = 5;
 const FPS_SAMPLE_COUNT_FLOAT: f32 = FPS_SAMPLE_COUNT as f32;
 const DEFAULT_PREFER_FPS: f32 = 60.0;
+/// the count of recent frames kept in the frame time history.
+const FRAME_HISTORY_COUNT: usize = 240;
 
 
 pub struct FpsCounter {
@@ -16,6 +18,14 @@ pub struct FpsCounter {
     delta_frame: u32,
 
     second_counter: u32,
+
+    /// the frame times in milliseconds, where each frame is written twice(at `i` and `i + FRAME_HISTORY_COUNT`),
+    /// so that the recent frames are always a contiguous slice in chronological order.
+    history: Vec<f32>,
+    /// the position to write the next frame in `history`.
+    history_cursor: usize,
+    /// the count of valid frames in `history`, which is at most `FRAME_HISTORY_COUNT`.
+    history_len: usize,
 }
 
 impl FpsCounter {
@@ -29,6 +39,9 @@ impl FpsCounter {
             current_frame: 0,
             delta_frame: 0,
             second_counter: 0,
+            history: vec![0.0; FRAME_HISTORY_COUNT * 2],
+            history_cursor: 0,
+            history_len: 0,
         }
     }
 
@@ -46,6 +59,11 @@ impl FpsCounter {
         self.samples[self.current_frame] = self.delta_frame;
         self.current_frame = (self.current_frame + 1) % FPS_SAMPLE_COUNT;
 
+        let frame_time_ms = time_elapsed.as_micros() as f32 / 1000.0;
+        self.history[self.history_cursor] = frame_time_ms;
+        self.history[self.history_cursor + FRAME_HISTORY_COUNT] = frame_time_ms;
+        self.history_cursor = (self.history_cursor + 1) % FRAME_HISTORY_COUNT;
+        self.history_len = (self.history_len + 1).min(FRAME_HISTORY_COUNT);
 
         if self.is_tick_second() {
             self.second_counter = 0;
@@ -73,6 +91,33 @@ impl FpsCounter {
         1000_000.0_f32 / (sum as f32 / FPS_SAMPLE_COUNT_FLOAT)
     }
 
+    /// Return the frame times(in milliseconds) of the recent frames in chronological order, up to the last 240 frames.
+    ///
+    /// It is intended to plot a frame time graph, where the spikes are visible.
+    #[inline]
+    pub fn frame_times(&self) -> &[f32] {
+        let end = self.history_cursor + FRAME_HISTORY_COUNT;
+        &self.history[(end - self.history_len)..end]
+    }
+
+    /// Return the frame time(in milliseconds) at percentile `p`(in range `[0.0, 100.0]`) of the recent frames, or 0.0 if no frame has been recorded.
+    ///
+    /// For example, `percentile(99.0)` is the frame time that only 1% of frames exceed, and `1000.0 / percentile(99.0)` is the 1%-low FPS.
+    pub fn percentile(&self, p: f32) -> f32 {
+
+        debug_assert!(p >= 0.0 && p <= 100.0, "The percentile must be in range [0.0, 100.0]!");
+
+        let mut sorted = self.frame_times().to_vec();
+        if sorted.is_empty() {
+            return 0.0
+        }
+
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+        // the nearest-rank method.
+        let rank = ((p / 100.0) * sorted.len() as f32).ceil() as usize;
+        sorted[rank.max(1).min(sorted.len()) - 1]
+    }
+
     #[inline]
     pub fn is_tick_second(&self) -> bool {
         self.second_counter > 1000_000_u32
@@ -33,6 +33,10 @@ pub enum FrameAction {
     Rendering,
     /// tell program the swapchain has to update to adapt current window surface.
     SwapchainRecreate,
+    /// skip the update and rendering of this frame, while the window keeps showing the last presented frame and receiving events.
+    ///
+    /// Return it from `RenderWorkflow::receive_input` every frame while the program is paused.
+    Pause,
     /// Indicate the program to terminal.
     Terminal,
 }
@@ -0,0 +1,28 @@
+//! A hasher whose result can be stored to disk.
+
+/// FNV-1a hasher, whose result is stable across runs and Rust versions, unlike `std::collections::hash_map::DefaultHasher`.
+///
+/// It is used for the keys of cache files(etc. the compiled SPIR-V or the glyph atlas).
+pub(crate) struct FnvHasher(u64);
+
+impl FnvHasher {
+
+    pub fn new() -> FnvHasher {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        // separate the consecutive fields, so that ("ab", "c") and ("a", "bc") are hashed differently.
+        self.0 ^= 0xff;
+        self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
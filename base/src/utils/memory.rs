@@ -4,23 +4,30 @@ use ash::vk;
 use std::ops::{Add, Sub, Not, BitAnd};
 
 use crate::context::VkDevice;
-use crate::vkuint;
+use crate::{vkuint, vkbytes, vkptr};
 
-pub fn get_memory_type_index(device: &VkDevice, mut type_bits: vkuint, properties: vk::MemoryPropertyFlags) -> vkuint {
+pub fn get_memory_type_index(device: &VkDevice, type_bits: vkuint, properties: vk::MemoryPropertyFlags) -> vkuint {
 
-    // Iterate over all memory types available for the device used in this example.
+    find_memory_type_index(device, type_bits, properties)
+        .expect("Could not find a suitable memory type")
+}
+
+/// Return the first memory type in `type_bits` that supports all the `properties`, or `None` if there is no such memory type.
+pub fn find_memory_type_index(device: &VkDevice, mut type_bits: vkuint, properties: vk::MemoryPropertyFlags) -> Option<vkuint> {
+
+    // Iterate over all memory types available for the device.
     let memories = &device.phy.memories;
     for i in 0..memories.memory_type_count {
         if (type_bits & 1) == 1 {
             if memories.memory_types[i as usize].property_flags.contains(properties) {
-                return i
+                return Some(i)
             }
         }
 
         type_bits >>= 1;
     }
 
-    panic!("Could not find a suitable memory type")
+    None
 }
 
 pub fn is_memory_support_flags(device: &VkDevice, memory_type_index: vkuint, request_flags: vk::MemoryPropertyFlags) -> bool {
@@ -39,6 +46,42 @@ pub unsafe fn any_as_u8_slice<T: Sized>(p: &T) -> &[u8] {
     )
 }
 
+/// Copy `data` to the memory at `offset` bytes after `ptr`(etc. a sub-allocated region of a mapped buffer).
+///
+/// The data is copied byte by byte, so the destination does not need to satisfy the alignment of `T`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for writes of `offset + size_of_val(data)` bytes, and the region must not overlap `data`.
+pub unsafe fn copy_to_ptr_at<T: Copy>(ptr: vkptr, offset: vkbytes, data: &[T]) {
+
+    debug_assert!(!ptr.is_null(), "Copy data to a null pointer!");
+
+    let data_size = ::std::mem::size_of_val(data);
+    let dst = (ptr as *mut u8).add(offset as usize);
+    ::std::ptr::copy_nonoverlapping(data.as_ptr() as *const u8, dst, data_size);
+}
+
+/// Round up `size` to the next multiple of `alignment`.
+///
+/// `alignment` must be the power of 2, and 0 is regarded as no alignment requirement.
+#[inline]
+pub fn align_up(size: vkbytes, alignment: vkbytes) -> vkbytes {
+
+    if alignment == 0 {
+        size
+    } else {
+        debug_assert!(alignment.is_power_of_two(), "The alignment must be the power of 2!");
+        size.align_to(alignment)
+    }
+}
+
+/// Return the size of `T` aligned to `minUniformBufferOffsetAlignment` of `device`,
+/// which is the stride between the elements of a dynamic uniform buffer.
+#[inline]
+pub fn dynamic_alignment_of<T>(device: &VkDevice) -> vkbytes {
+    align_up(::std::mem::size_of::<T>() as vkbytes, device.phy.limits.min_uniform_buffer_offset_alignment)
+}
 
 pub trait IntegerAlignable: Copy + Add<Output=Self> + Sub<Output=Self> + Not<Output=Self> + BitAnd<Output=Self> {
     const INTEGER_UNIT: Self;
@@ -6,3 +6,7 @@ pub mod fps;
 pub mod shaderc;
 pub mod memory;
 pub mod color;
+pub mod texture_gen;
+pub mod uniform;
+pub mod query;
+pub(crate) mod hash;
@@ -0,0 +1,242 @@
+//! GPU profiling by Vulkan queries.
+
+use ash::vk;
+use ash::version::DeviceV1_0;
+
+use crate::ci::query::QueryPoolCI;
+use crate::ci::VkObjectBuildableCI;
+use crate::command::VkCmdRecorder;
+use crate::context::VkDevice;
+
+use crate::{VkResult, VkError};
+use crate::vkuint;
+
+
+/// A pool of GPU timestamps, which measures the time spent by the commands on graphics queue.
+///
+/// For each frame, call `reset` before any timestamp is written, bracket the measured commands by `write_timestamp`,
+/// and call `resolve` after the frame has finished on GPU(etc. after its fence is waited).
+pub struct TimestampPool {
+
+    pool: vk::QueryPool,
+    capacity: vkuint,
+    /// the number of timestamps written since last reset.
+    written: vkuint,
+
+    /// the number of nanoseconds for a timestamp to be incremented by 1.
+    timestamp_period: f64,
+    /// the mask of meaningful bits of timestamps.
+    valid_mask: u64,
+}
+
+impl TimestampPool {
+
+    /// Create a pool for at most `capacity` timestamps per frame.
+    pub fn new(device: &VkDevice, capacity: vkuint) -> VkResult<TimestampPool> {
+
+        let valid_bits = device.logic.queues.graphics.timestamp_valid_bits;
+        if valid_bits == 0 {
+            return Err(VkError::unsupported("Timestamp Query"))
+        }
+
+        let valid_mask = if valid_bits >= 64 { !0 } else { (1_u64 << valid_bits) - 1 };
+
+        let pool = QueryPoolCI::new(vk::QueryType::TIMESTAMP, capacity)
+            .build(device)?;
+
+        let timestamp_pool = TimestampPool {
+            pool, capacity,
+            written: 0,
+            timestamp_period: device.phy.limits.timestamp_period as f64,
+            valid_mask,
+        };
+        Ok(timestamp_pool)
+    }
+
+    /// Record the commands to reset all timestamps in this pool.
+    ///
+    /// It must be recorded outside of render pass.
+    pub fn reset<T>(&mut self, recorder: &VkCmdRecorder<T>) {
+
+        recorder.reset_query_pool(self.pool, 0, self.capacity);
+        self.written = 0;
+    }
+
+    /// Record the command to write a timestamp after all previous commands have completed `stage`.
+    ///
+    /// Return the index of the timestamp, or `None` if the pool is full.
+    pub fn write_timestamp<T>(&mut self, recorder: &VkCmdRecorder<T>, stage: vk::PipelineStageFlags) -> Option<usize> {
+
+        if self.written >= self.capacity {
+            return None
+        }
+
+        recorder.write_timestamp(stage, self.pool, self.written);
+        self.written += 1;
+
+        Some((self.written - 1) as usize)
+    }
+
+    /// Read back the written timestamps, and return the time of each one relative to the first one, in milliseconds.
+    ///
+    /// The difference between two adjacent values is the GPU time spent between the two timestamps.
+    /// This method blocks until all the written timestamps are available.
+    pub fn resolve(&self, device: &VkDevice) -> VkResult<Vec<f64>> {
+
+        if self.written == 0 {
+            return Ok(Vec::new())
+        }
+
+        let mut timestamps = vec![0_u64; self.written as usize];
+
+        unsafe {
+            device.logic.handle.get_query_pool_results(self.pool, 0, self.written, &mut timestamps, vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT)
+                .map_err(|e| VkError::vk(e, "Get Query Pool Results"))?;
+        }
+
+        let start = timestamps[0] & self.valid_mask;
+        let elapsed = timestamps.iter().map(|&timestamp| {
+            // the timestamps may wrap around within the valid bits.
+            let ticks = (timestamp & self.valid_mask).wrapping_sub(start) & self.valid_mask;
+            ticks as f64 * self.timestamp_period / 1_000_000.0
+        }).collect();
+
+        Ok(elapsed)
+    }
+
+    /// The number of timestamps written since last reset.
+    #[inline]
+    pub fn written_count(&self) -> usize {
+        self.written as usize
+    }
+
+    pub fn discard_by(self, device: &VkDevice) {
+        device.discard(self.pool);
+    }
+}
+
+
+/// The counters collected by `StatisticsPool`. The counters that are not enabled are `None`.
+#[derive(Debug, Clone, Default)]
+pub struct PipelineStats {
+    pub input_assembly_vertices   : Option<u64>,
+    pub input_assembly_primitives : Option<u64>,
+    pub vertex_shader_invocations : Option<u64>,
+    pub geometry_shader_invocations: Option<u64>,
+    pub geometry_shader_primitives : Option<u64>,
+    pub clipping_invocations: Option<u64>,
+    pub clipping_primitives : Option<u64>,
+    pub fragment_shader_invocations: Option<u64>,
+    pub tessellation_control_shader_patches: Option<u64>,
+    pub tessellation_evaluation_shader_invocations: Option<u64>,
+    pub compute_shader_invocations: Option<u64>,
+}
+
+/// A pipeline statistics query, which counts the work processed by the pipeline stages(etc. the invocations of fragment shader).
+///
+/// It requires `pipeline_statistics_query` to be enabled in `PhysicalDevConfig::request_features`.
+/// For each frame, bracket the measured commands by `begin` and `end` outside of render pass,
+/// and call `fetch` after the frame has finished on GPU.
+pub struct StatisticsPool {
+
+    pool: vk::QueryPool,
+    statistics: vk::QueryPipelineStatisticFlags,
+}
+
+impl StatisticsPool {
+
+    /// The counters in the order that Vulkan writes them.
+    const COUNTER_ORDER: [vk::QueryPipelineStatisticFlags; 11] = [
+        vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES,
+        vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES,
+        vk::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS,
+        vk::QueryPipelineStatisticFlags::GEOMETRY_SHADER_INVOCATIONS,
+        vk::QueryPipelineStatisticFlags::GEOMETRY_SHADER_PRIMITIVES,
+        vk::QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS,
+        vk::QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES,
+        vk::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS,
+        vk::QueryPipelineStatisticFlags::TESSELLATION_CONTROL_SHADER_PATCHES,
+        vk::QueryPipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS,
+        vk::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS,
+    ];
+
+    /// Create the query collecting the counters in `statistics`.
+    pub fn new(device: &VkDevice, statistics: vk::QueryPipelineStatisticFlags) -> VkResult<StatisticsPool> {
+
+        if device.phy.features_enabled().pipeline_statistics_query != vk::TRUE {
+            return Err(VkError::custom("Pipeline statistics query requires `pipeline_statistics_query` feature, please enable it in `PhysicalDevConfig::request_features`."))
+        }
+
+        let pool = QueryPoolCI::new(vk::QueryType::PIPELINE_STATISTICS, 1)
+            .pipeline_statistics(statistics)
+            .build(device)?;
+
+        let statistics_pool = StatisticsPool { pool, statistics };
+        Ok(statistics_pool)
+    }
+
+    /// Record the commands to reset and begin the query. It must be recorded outside of render pass.
+    pub fn begin<T>(&self, recorder: &VkCmdRecorder<T>) {
+
+        recorder
+            .reset_query_pool(self.pool, 0, 1)
+            .begin_query(self.pool, 0, vk::QueryControlFlags::empty());
+    }
+
+    pub fn end<T>(&self, recorder: &VkCmdRecorder<T>) {
+        recorder.end_query(self.pool, 0);
+    }
+
+    /// Read back the counters between `begin` and `end`. This method blocks until the result is available.
+    pub fn fetch(&self, device: &VkDevice) -> VkResult<PipelineStats> {
+
+        let enabled: Vec<vk::QueryPipelineStatisticFlags> = StatisticsPool::COUNTER_ORDER.iter()
+            .filter(|&&counter| self.statistics.contains(counter))
+            .cloned().collect();
+
+        if enabled.is_empty() {
+            return Ok(PipelineStats::default())
+        }
+
+        // all the enabled counters of a query are written as a single result.
+        let mut values = vec![0_u64; enabled.len()];
+        // ash derives the data size from the query count, so the function pointer is called directly to declare the size of all counters.
+        let result_size = ::std::mem::size_of::<u64>() * values.len();
+
+        let result = unsafe {
+            device.logic.handle.fp_v1_0().get_query_pool_results(
+                device.logic.handle.handle(), self.pool, 0, 1,
+                result_size, values.as_mut_ptr() as _, result_size as vk::DeviceSize,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT)
+        };
+
+        if result != vk::Result::SUCCESS {
+            return Err(VkError::vk(result, "Get Query Pool Results"))
+        }
+
+        let mut stats = PipelineStats::default();
+        for (&counter, &value) in enabled.iter().zip(values.iter()) {
+
+            let field = match counter {
+                | vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES     => &mut stats.input_assembly_vertices,
+                | vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES   => &mut stats.input_assembly_primitives,
+                | vk::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS   => &mut stats.vertex_shader_invocations,
+                | vk::QueryPipelineStatisticFlags::GEOMETRY_SHADER_INVOCATIONS => &mut stats.geometry_shader_invocations,
+                | vk::QueryPipelineStatisticFlags::GEOMETRY_SHADER_PRIMITIVES  => &mut stats.geometry_shader_primitives,
+                | vk::QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS        => &mut stats.clipping_invocations,
+                | vk::QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES         => &mut stats.clipping_primitives,
+                | vk::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS => &mut stats.fragment_shader_invocations,
+                | vk::QueryPipelineStatisticFlags::TESSELLATION_CONTROL_SHADER_PATCHES        => &mut stats.tessellation_control_shader_patches,
+                | vk::QueryPipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS => &mut stats.tessellation_evaluation_shader_invocations,
+                | _ => &mut stats.compute_shader_invocations,
+            };
+            *field = Some(value);
+        }
+
+        Ok(stats)
+    }
+
+    pub fn discard_by(self, device: &VkDevice) {
+        device.discard(self.pool);
+    }
+}
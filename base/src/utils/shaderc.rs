@@ -1,39 +1,61 @@
 
+use crate::utils::hash::FnvHasher;
 use crate::error::{VkResult, VkError};
 
 use std::path::{PathBuf, Path};
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Write};
+use std::rc::Rc;
+use std::cell::RefCell;
 
+#[derive(Debug, Clone)]
 pub struct ShadercOptions {
 
+    /// the optimization level of the generated SPIR-V.
     pub optimal_level   : shaderc::OptimizationLevel,
+    /// whether to emit the source-level debug information(etc. for source debugging in RenderDoc).
     pub debug_info      : bool,
+    /// the language of shader source, which is GLSL by default.
+    pub source_language : shaderc::SourceLanguage,
     pub suppress_warning: bool,
     pub error_warning   : bool,
 }
 
 impl Default for ShadercOptions {
 
+    /// In debug builds, the shaders are compiled without optimization and with debug information.
+    /// In release builds, the shaders are optimized for performance and stripped of debug information.
     fn default() -> ShadercOptions {
 
-        ShadercOptions {
-            optimal_level    : shaderc::OptimizationLevel::Performance,
-            debug_info       : true,
-            suppress_warning : false,
-            error_warning    : true,
+        if cfg!(debug_assertions) {
+            ShadercOptions {
+                optimal_level    : shaderc::OptimizationLevel::Zero,
+                debug_info       : true,
+                source_language  : shaderc::SourceLanguage::GLSL,
+                suppress_warning : false,
+                error_warning    : true,
+            }
+        } else {
+            ShadercOptions {
+                optimal_level    : shaderc::OptimizationLevel::Performance,
+                debug_info       : false,
+                source_language  : shaderc::SourceLanguage::GLSL,
+                suppress_warning : false,
+                error_warning    : true,
+            }
         }
     }
 }
 
 impl ShadercOptions {
 
-    fn to_shaderc_options(&self) -> VkResult<shaderc::CompileOptions> {
+    fn to_shaderc_options(&self) -> VkResult<shaderc::CompileOptions<'static>> {
 
-        // Default to compile target is vulkan and GLSL.
+        // Default to compile target is vulkan.
         let mut shaderc_options = shaderc::CompileOptions::new()
             .ok_or(VkError::shaderc("There are conflict in Shader Compile Options."))?;
         shaderc_options.set_optimization_level(self.optimal_level);
+        shaderc_options.set_source_language(self.source_language);
 
         if self.debug_info {
             shaderc_options.set_generate_debug_info();
@@ -47,12 +69,28 @@ impl ShadercOptions {
 
         Ok(shaderc_options)
     }
+
+    /// Feed the options that affect the generated SPIR-V to `hasher`.
+    fn hash_into(&self, hasher: &mut FnvHasher) {
+
+        hasher.write(format!("{:?}", self.optimal_level).as_bytes());
+        hasher.write(&[self.debug_info as u8]);
+        hasher.write(format!("{:?}", self.source_language).as_bytes());
+    }
 }
 
+/// The magic number in the first word of SPIR-V module.
+const SPIRV_MAGIC_NUMBER: u32 = 0x0723_0203;
+
 pub struct VkShaderCompiler {
 
     compiler: shaderc::Compiler,
     options: ShadercOptions,
+
+    /// the directory storing the compiled SPIR-V, or `None` if the cache is disabled.
+    cache_dir: Option<PathBuf>,
+    /// the extra directories to search the files of `#include` directives.
+    include_dirs: Vec<PathBuf>,
 }
 
 impl VkShaderCompiler {
@@ -65,6 +103,8 @@ impl VkShaderCompiler {
         let target = VkShaderCompiler {
             compiler,
             options: ShadercOptions::default(),
+            cache_dir: Some(VkShaderCompiler::default_cache_dir()),
+            include_dirs: Vec::new(),
         };
         Ok(target)
     }
@@ -73,12 +113,115 @@ impl VkShaderCompiler {
         self.options = options;
     }
 
+    /// Set the optimization level of the generated SPIR-V(`Zero`, `Size` or `Performance`).
+    ///
+    /// Default is `Zero` in debug builds and `Performance` in release builds.
+    pub fn set_optimization_level(&mut self, level: shaderc::OptimizationLevel) {
+        self.options.optimal_level = level;
+    }
+
+    /// Set whether to emit debug information, which is required by the source-level shader debugging in RenderDoc.
+    ///
+    /// Default is enabled in debug builds and disabled in release builds.
+    pub fn set_debug_info(&mut self, is_enable: bool) {
+        self.options.debug_info = is_enable;
+    }
+
+    /// Set the language of the compiled source(`GLSL` or `HLSL`). Default is `GLSL`.
+    ///
+    /// For HLSL, the `entry_name` of compile methods is the name of entry function(etc. `VSMain`), which is rarely `main`.
+    pub fn set_source_language(&mut self, language: shaderc::SourceLanguage) {
+        self.options.source_language = language;
+    }
+
+    /// Enable or disable the on-disk cache of compiled SPIR-V. The cache is enabled by default.
+    ///
+    /// Disable it to always recompile the shaders(etc. when debugging the shader compiler itself).
+    /// The cache never returns stale code for a changed source, since it is keyed by the hash of source text.
+    pub fn enable_cache(&mut self, is_enable: bool) {
+        self.cache_dir = if is_enable {
+            Some(self.cache_dir.take().unwrap_or_else(VkShaderCompiler::default_cache_dir))
+        } else {
+            None
+        };
+    }
+
+    /// Set the directory storing the compiled SPIR-V. It also enables the cache.
+    ///
+    /// The default directory is `vulkan-base-spirv-cache` in the temporary directory of system.
+    pub fn set_cache_dir(&mut self, dir: impl AsRef<Path>) {
+        self.cache_dir = Some(PathBuf::from(dir.as_ref()));
+    }
+
+    fn default_cache_dir() -> PathBuf {
+        ::std::env::temp_dir().join("vulkan-base-spirv-cache")
+    }
+
     pub fn compile_from_str(&mut self, source_text: &str, stage: shaderc::ShaderKind, input_name: &str, entry_name: &str) -> VkResult<Vec<u8>> {
+        self.compile_with_root(source_text, stage, input_name, entry_name, None)
+    }
+
+    /// Compile the GLSL file at `path`.
+    ///
+    /// The `#include "..."` directives are resolved relative to the including file(starting from the directory of `path`),
+    /// and then in the directories added by `add_include_dir`.
+    pub fn compile_from_path(&mut self, path: impl AsRef<Path>, stage: shaderc::ShaderKind, input_name: &str, entry_name: &str) -> VkResult<Vec<u8>> {
+
+        let path = PathBuf::from(path.as_ref());
+        let source_text = load_to_string(path.clone())?;
+        self.compile_with_root(&source_text, stage, input_name, entry_name, path.parent())
+    }
+
+    /// Add a directory to search the files of `#include` directives, after the directory of including file.
+    ///
+    /// The `#include <...>` directives are only resolved in these directories.
+    pub fn add_include_dir(&mut self, dir: impl AsRef<Path>) {
+        self.include_dirs.push(PathBuf::from(dir.as_ref()));
+    }
 
-        let compile_options = self.options.to_shaderc_options()?;
+    fn compile_with_root(&mut self, source_text: &str, stage: shaderc::ShaderKind, input_name: &str, entry_name: &str, root_dir: Option<&Path>) -> VkResult<Vec<u8>> {
+
+        let cache_path = if let Some(cache_dir) = self.cache_dir.clone() {
+
+            // hash the preprocessed source, so that the changes of included files also invalidate the cache.
+            let preprocessed = self.preprocess(source_text, input_name, entry_name, root_dir)?;
+
+            let mut hasher = FnvHasher::new();
+            hasher.write(preprocessed.as_bytes());
+            hasher.write(format!("{:?}", stage).as_bytes());
+            hasher.write(entry_name.as_bytes());
+            self.options.hash_into(&mut hasher);
+
+            Some(cache_dir.join(format!("{:016x}.spv", hasher.finish())))
+        } else {
+            None
+        };
+
+        if let Some(ref cache_path) = cache_path {
+            if let Some(spirv) = load_cached_spirv(cache_path) {
+                return Ok(spirv)
+            }
+        }
+
+        let spirv = self.compile_source(source_text, stage, input_name, entry_name, root_dir)?;
+
+        if let Some(ref cache_path) = cache_path {
+            // failing to write cache does not affect the compilation result.
+            if let Err(e) = store_cached_spirv(cache_path, &spirv) {
+                println!("[Warning] Failed to cache the SPIR-V of {} to {}({}).", input_name, cache_path.display(), e);
+            }
+        }
+
+        Ok(spirv)
+    }
+
+    fn compile_source(&mut self, source_text: &str, stage: shaderc::ShaderKind, input_name: &str, entry_name: &str, root_dir: Option<&Path>) -> VkResult<Vec<u8>> {
+
+        let resolver = IncludeResolver::new(root_dir, &self.include_dirs);
+        let compile_options = self.compile_options(&resolver)?;
 
         let result = self.compiler.compile_into_spirv(source_text, stage, input_name, entry_name, Some(&compile_options))
-            .map_err(|e| VkError::shaderc(format!("Failed to compile {}({})", input_name, e)))?;
+            .map_err(|e| resolver.compile_error(input_name, e))?;
 
         if result.get_num_warnings() > 0 {
             println!("{}: {}", input_name, result.get_warning_messages());
@@ -88,11 +231,175 @@ impl VkShaderCompiler {
         Ok(spirv)
     }
 
-    pub fn compile_from_path(&mut self, path: impl AsRef<Path>, stage: shaderc::ShaderKind, input_name: &str, entry_name: &str) -> VkResult<Vec<u8>> {
+    fn preprocess(&mut self, source_text: &str, input_name: &str, entry_name: &str, root_dir: Option<&Path>) -> VkResult<String> {
+
+        let resolver = IncludeResolver::new(root_dir, &self.include_dirs);
+        let compile_options = self.compile_options(&resolver)?;
+
+        let result = self.compiler.preprocess(source_text, input_name, entry_name, Some(&compile_options))
+            .map_err(|e| resolver.compile_error(input_name, e))?;
+
+        Ok(result.as_text())
+    }
+
+    fn compile_options(&self, resolver: &IncludeResolver) -> VkResult<shaderc::CompileOptions<'static>> {
+
+        let mut compile_options = self.options.to_shaderc_options()?;
+
+        let resolver = resolver.clone();
+        compile_options.set_include_callback(move |requested_source, include_type, requesting_source, include_depth| {
+            resolver.resolve(requested_source, include_type, requesting_source, include_depth)
+        });
+
+        Ok(compile_options)
+    }
+}
+
+// ----------------------------------------------------------------------------------------------
+/// The maximum nesting depth of `#include`, to stop the recursive including.
+const MAX_INCLUDE_DEPTH: usize = 32;
+
+/// Resolve the `#include` directives to the files on disk.
+#[derive(Clone)]
+struct IncludeResolver {
+
+    /// the directory of the compiled file, or `None` if the source is not from a file.
+    root_dir: Option<PathBuf>,
+    /// the extra directories to search.
+    include_dirs: Vec<PathBuf>,
+    /// the description of the first include that failed to resolve, which is reported instead of the compile message of shaderc.
+    failure: Rc<RefCell<Option<String>>>,
+}
+
+impl IncludeResolver {
 
-        let source_text = load_to_string(PathBuf::from(path.as_ref()))?;
-        self.compile_from_str(&source_text, stage, input_name, entry_name)
+    fn new(root_dir: Option<&Path>, include_dirs: &[PathBuf]) -> IncludeResolver {
+
+        IncludeResolver {
+            root_dir: root_dir.map(PathBuf::from),
+            include_dirs: include_dirs.to_vec(),
+            failure: Rc::new(RefCell::new(None)),
+        }
     }
+
+    fn resolve(&self, requested_source: &str, include_type: shaderc::IncludeType, requesting_source: &str, include_depth: usize) -> shaderc::IncludeCallbackResult {
+
+        if include_depth > MAX_INCLUDE_DEPTH {
+            return self.fail(format!("#include \"{}\" in {} exceeds the maximum include depth({}), which may be a recursive include.", requested_source, requesting_source, MAX_INCLUDE_DEPTH))
+        }
+
+        let mut search_dirs = Vec::with_capacity(self.include_dirs.len() + 1);
+
+        if let shaderc::IncludeType::Relative = include_type {
+            // the requesting source is the resolved path of included file, or the input name of the compiled file.
+            let requesting_dir = Path::new(requesting_source).parent()
+                .filter(|dir| Path::new(requesting_source).is_file() && dir.is_dir())
+                .map(PathBuf::from)
+                .or_else(|| self.root_dir.clone());
+            search_dirs.extend(requesting_dir);
+        }
+        search_dirs.extend(self.include_dirs.iter().cloned());
+
+        for dir in search_dirs.iter() {
+
+            let candidate = dir.join(requested_source);
+            if candidate.is_file() {
+
+                return match load_to_string(candidate.clone()) {
+                    | Ok(content) => Ok(shaderc::ResolvedInclude {
+                        resolved_name: candidate.to_string_lossy().into_owned(),
+                        content,
+                    }),
+                    | Err(_) => self.fail(format!("Failed to read #include file {} in {}.", candidate.display(), requesting_source)),
+                }
+            }
+        }
+
+        let searched: Vec<String> = search_dirs.iter().map(|dir| dir.display().to_string()).collect();
+        self.fail(format!("Failed to resolve #include \"{}\" in {}(searched directories: [{}]).", requested_source, requesting_source, searched.join(", ")))
+    }
+
+    fn fail(&self, message: String) -> shaderc::IncludeCallbackResult {
+
+        let mut failure = self.failure.borrow_mut();
+        if failure.is_none() {
+            *failure = Some(message.clone());
+        }
+
+        Err(message)
+    }
+
+    /// Convert the compile error of `input_name` to `VkError`, preferring the include failure if there is one.
+    fn compile_error(&self, input_name: &str, error: shaderc::Error) -> VkError {
+
+        match self.failure.borrow().as_ref() {
+            | Some(include_failure) => VkError::shaderc(format!("Failed to compile {}({})", input_name, include_failure)),
+            | None => VkError::shaderc(format!("Failed to compile {}({})", input_name, error)),
+        }
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+// ----------------------------------------------------------------------------------------------
+/// Check that `spirv` declares an entry point for `stage`. The kinds without a fixed stage(etc. `InferFromSource`) always pass.
+pub(crate) fn validate_spirv_stage(spirv: &[u8], stage: shaderc::ShaderKind, input_name: &str) -> VkResult<()> {
+
+    use shaderc::ShaderKind;
+
+    // See `ExecutionModel` in the SPIR-V specification.
+    let expect_model = match stage {
+        | ShaderKind::Vertex         | ShaderKind::DefaultVertex         => 0,
+        | ShaderKind::TessControl    | ShaderKind::DefaultTessControl    => 1,
+        | ShaderKind::TessEvaluation | ShaderKind::DefaultTessEvaluation => 2,
+        | ShaderKind::Geometry       | ShaderKind::DefaultGeometry       => 3,
+        | ShaderKind::Fragment       | ShaderKind::DefaultFragment       => 4,
+        | ShaderKind::Compute        | ShaderKind::DefaultCompute        => 5,
+        | _ => return Ok(()),
+    };
+
+    let words: Vec<u32> = spirv.chunks(4)
+        .filter(|chunk| chunk.len() == 4)
+        .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+    let models = crate::ci::shader::spirv_execution_models(&words, input_name)?;
+
+    if models.contains(&expect_model) {
+        Ok(())
+    } else {
+        Err(VkError::shaderc(format!("The SPIR-V of {} has no entry point for {:?} stage(found execution models: {:?}).", input_name, stage, models)))
+    }
+}
+// ----------------------------------------------------------------------------------------------
+
+/// Read the SPIR-V at `path`, or return `None` if it does not exist or is not a valid SPIR-V module.
+fn load_cached_spirv(path: &Path) -> Option<Vec<u8>> {
+
+    let mut file = File::open(path).ok()?;
+    let mut spirv = Vec::new();
+    file.read_to_end(&mut spirv).ok()?;
+
+    if spirv.len() < 4 || spirv.len() % 4 != 0 {
+        return None
+    }
+
+    let magic_number = u32::from_ne_bytes([spirv[0], spirv[1], spirv[2], spirv[3]]);
+    if magic_number == SPIRV_MAGIC_NUMBER {
+        Some(spirv)
+    } else {
+        None
+    }
+}
+
+fn store_cached_spirv(path: &Path, spirv: &[u8]) -> ::std::io::Result<()> {
+
+    if let Some(cache_dir) = path.parent() {
+        ::std::fs::create_dir_all(cache_dir)?;
+    }
+
+    // write to a temporary file first, so that a crash never leaves a truncated module in cache.
+    let temp_path = path.with_extension("spv.tmp");
+    File::create(&temp_path)?.write_all(spirv)?;
+    ::std::fs::rename(temp_path, path)
 }
 
 fn load_to_string(path: PathBuf) -> VkResult<String> {
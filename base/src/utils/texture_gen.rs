@@ -0,0 +1,172 @@
+//! Procedural texture generators.
+//!
+//! All generators return square images of `size * size` pixels in RGBA8 layout,
+//! which can be uploaded by `Texture2D::from_bytes` with `R8G8B8A8_UNORM` format.
+
+use crate::vkuint;
+
+/// The number of channels of generated pixels.
+const PIXEL_CHANNELS: usize = 4;
+
+/// Generate a checkerboard alternating between `color_a` and `color_b` every `cell_size` pixels.
+pub fn generate_checkerboard(size: vkuint, cell_size: vkuint, color_a: [u8; 4], color_b: [u8; 4]) -> Vec<u8> {
+
+    let cell_size = cell_size.max(1);
+
+    generate_pixels(size, |x, y| {
+        if (x / cell_size + y / cell_size) % 2 == 0 { color_a } else { color_b }
+    })
+}
+
+/// Generate a horizontal gradient from `from`(left) to `to`(right).
+pub fn generate_gradient(size: vkuint, from: [u8; 4], to: [u8; 4]) -> Vec<u8> {
+
+    let max_x = (size.max(2) - 1) as f32;
+
+    generate_pixels(size, |x, _| {
+        let t = x as f32 / max_x;
+        let mut color = [0_u8; PIXEL_CHANNELS];
+        for (i, channel) in color.iter_mut().enumerate() {
+            *channel = (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8;
+        }
+        color
+    })
+}
+
+/// Generate a UV debugging grid.
+///
+/// The red and green channels encode the u and v coordinates, and white grid lines are drawn every `size / 8` pixels.
+pub fn generate_uv_grid(size: vkuint) -> Vec<u8> {
+
+    let cell_size = (size / 8).max(1);
+    let max_coord = (size.max(2) - 1) as f32;
+
+    generate_pixels(size, |x, y| {
+        if x % cell_size == 0 || y % cell_size == 0 || x == size - 1 || y == size - 1 {
+            [255, 255, 255, 255]
+        } else {
+            let u = (x as f32 / max_coord * 255.0) as u8;
+            let v = (y as f32 / max_coord * 255.0) as u8;
+            [u, v, 0, 255]
+        }
+    })
+}
+
+/// Generate grayscale fractal Perlin noise.
+///
+/// `frequency` is the number of noise cells across the image in first octave,
+/// and each of `octaves` doubles the frequency and halves the amplitude.
+/// The same `seed` always generates the same image.
+pub fn generate_noise(size: vkuint, frequency: f32, octaves: vkuint, seed: u32) -> Vec<u8> {
+
+    let noise = PerlinNoise::new(seed);
+    let octaves = octaves.max(1);
+
+    generate_pixels(size, |x, y| {
+
+        let mut value = 0.0;
+        let mut amplitude = 1.0;
+        let mut total_amplitude = 0.0;
+        let mut octave_frequency = frequency / size as f32;
+
+        for _ in 0..octaves {
+            value += noise.sample(x as f32 * octave_frequency, y as f32 * octave_frequency) * amplitude;
+            total_amplitude += amplitude;
+            amplitude *= 0.5;
+            octave_frequency *= 2.0;
+        }
+
+        // remap noise value from [-1, 1] to [0, 255].
+        let gray = ((value / total_amplitude * 0.5 + 0.5).max(0.0).min(1.0) * 255.0) as u8;
+        [gray, gray, gray, 255]
+    })
+}
+
+fn generate_pixels(size: vkuint, mut pixel_at: impl FnMut(vkuint, vkuint) -> [u8; 4]) -> Vec<u8> {
+
+    let mut pixels = Vec::with_capacity((size * size) as usize * PIXEL_CHANNELS);
+
+    for y in 0..size {
+        for x in 0..size {
+            pixels.extend_from_slice(&pixel_at(x, y));
+        }
+    }
+
+    pixels
+}
+
+
+/// Classic 2D Perlin noise with a seeded permutation table.
+struct PerlinNoise {
+    permutation: [u8; 512],
+}
+
+impl PerlinNoise {
+
+    fn new(seed: u32) -> PerlinNoise {
+
+        let mut table: Vec<u8> = (0..=255).collect();
+
+        // shuffle the table with a linear congruential generator, so that no random crate is needed.
+        let mut state = seed;
+        for i in (1..table.len()).rev() {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            let j = (state >> 8) as usize % (i + 1);
+            table.swap(i, j);
+        }
+
+        let mut permutation = [0_u8; 512];
+        for (i, value) in permutation.iter_mut().enumerate() {
+            *value = table[i & 255];
+        }
+
+        PerlinNoise { permutation }
+    }
+
+    /// Sample noise at (x, y), the result is approximately in range [-1, 1].
+    fn sample(&self, x: f32, y: f32) -> f32 {
+
+        let xi = (x.floor() as i32 & 255) as usize;
+        let yi = (y.floor() as i32 & 255) as usize;
+        let xf = x - x.floor();
+        let yf = y - y.floor();
+
+        let u = fade(xf);
+        let v = fade(yf);
+
+        let p = &self.permutation;
+        let aa = p[p[xi] as usize + yi];
+        let ab = p[p[xi] as usize + yi + 1];
+        let ba = p[p[xi + 1] as usize + yi];
+        let bb = p[p[xi + 1] as usize + yi + 1];
+
+        let x1 = lerp(gradient(aa, xf, yf), gradient(ba, xf - 1.0, yf), u);
+        let x2 = lerp(gradient(ab, xf, yf - 1.0), gradient(bb, xf - 1.0, yf - 1.0), u);
+
+        lerp(x1, x2, v)
+    }
+}
+
+#[inline]
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+#[inline]
+fn gradient(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        | 0 =>  x + y,
+        | 1 => -x + y,
+        | 2 =>  x - y,
+        | 3 => -x - y,
+        | 4 =>  x,
+        | 5 => -x,
+        | 6 =>  y,
+        | _ => -y,
+    }
+}
@@ -0,0 +1,107 @@
+//! Uniform buffers which are updated every frame.
+
+use ash::vk;
+
+use crate::ci::buffer::BufferCI;
+use crate::ci::vma::{VmaBuffer, VmaAllocationCI};
+use crate::context::VkDevice;
+use crate::utils::memory::dynamic_alignment_of;
+
+use crate::{VkResult, VkErrorKind};
+use crate::{vkuint, vkbytes, vkptr};
+
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+
+/// A uniform buffer containing one copy of `T` for each frame in flight.
+///
+/// Writing the copy of current frame never touches the copies that may still be read by the frames in flight,
+/// so the data can be updated every frame without waiting for the GPU.
+/// Each copy is aligned to `minUniformBufferOffsetAlignment`, and it is selected by the dynamic offset of `UNIFORM_BUFFER_DYNAMIC` descriptor.
+pub struct RingUniformBuffer<T: Copy> {
+
+    buffer: VmaBuffer,
+    /// the size of each copy in bytes, aligned to `minUniformBufferOffsetAlignment`.
+    aligned_size: vkbytes,
+    frame_count: usize,
+
+    phantom_type: PhantomData<T>,
+}
+
+impl<T: Copy> RingUniformBuffer<T> {
+
+    /// Allocate a persistently mapped buffer for `frame_in_flight` copies of `T`.
+    ///
+    /// If the copies are indexed by the `image_index` of `RenderWorkflow::render_frame`, `frame_in_flight` must be the count of swapchain images.
+    pub fn new(device: &mut VkDevice, frame_in_flight: usize) -> VkResult<RingUniformBuffer<T>> {
+
+        debug_assert!(frame_in_flight > 0, "Frames in flight must be greater than 0!");
+
+        let aligned_size = dynamic_alignment_of::<T>(device);
+
+        let buffer_ci = BufferCI::new(aligned_size * frame_in_flight as vkbytes)
+            .usage(vk::BufferUsageFlags::UNIFORM_BUFFER);
+        let allocation_ci = VmaAllocationCI::new(vma::MemoryUsage::CpuToGpu, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT)
+            .flags(vma::AllocationCreateFlags::MAPPED);
+        let buffer_allocation = device.vma.create_buffer(
+            buffer_ci.as_ref(), allocation_ci.as_ref())
+            .map_err(VkErrorKind::Vma)?;
+
+        let ring = RingUniformBuffer {
+            buffer: VmaBuffer::from(buffer_allocation),
+            aligned_size,
+            frame_count: frame_in_flight,
+            phantom_type: PhantomData,
+        };
+        Ok(ring)
+    }
+
+    /// Copy `data` to the copy of `frame_index`.
+    ///
+    /// Only write the copy that is not read by GPU anymore(etc. the copy of `image_index` in `RenderWorkflow::render_frame`,
+    /// since the previous work on the acquired image has completed).
+    pub fn write(&self, frame_index: usize, data: &T) {
+
+        unsafe {
+            let data_ptr = self.buffer.info.get_mapped_data() as vkptr<u8>;
+            debug_assert_ne!(data_ptr, ptr::null_mut());
+
+            let dst_ptr = data_ptr.offset(self.dynamic_offset(frame_index) as isize) as vkptr<T>;
+            dst_ptr.copy_from_nonoverlapping(data, 1);
+        }
+    }
+
+    /// The dynamic offset to bind the copy of `frame_index`, which is passed to `bind_descriptor_sets`.
+    #[inline]
+    pub fn dynamic_offset(&self, frame_index: usize) -> vkuint {
+        debug_assert!(frame_index < self.frame_count, "Frame index {} is out of the ring of {} frames!", frame_index, self.frame_count);
+        (self.aligned_size * frame_index as vkbytes) as vkuint
+    }
+
+    /// The descriptor for `UNIFORM_BUFFER_DYNAMIC` binding, whose range covers a single copy.
+    #[inline]
+    pub fn descriptor(&self) -> vk::DescriptorBufferInfo {
+        vk::DescriptorBufferInfo {
+            buffer: self.buffer.handle,
+            offset: 0,
+            range : mem::size_of::<T>() as vkbytes,
+        }
+    }
+
+    /// The size of each copy in bytes(the stride between two copies).
+    #[inline]
+    pub fn aligned_size(&self) -> vkbytes {
+        self.aligned_size
+    }
+
+    #[inline]
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    pub fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+        device.vma_discard(self.buffer)
+    }
+}
@@ -1,13 +1,12 @@
 
-pub use self::window::{WindowContext, WindowConfig};
-pub use self::loops::ProcPipeline;
+pub use self::window::{WindowContext, WindowConfig, WindowMode, FullscreenMode};
+pub use self::loops::{ProcPipeline, FrameStats};
 
 mod window;
 mod loops;
 
 
-use ash::vk;
-use crate::context::{VkDevice, VkSwapchain};
+use crate::context::{VkDevice, VkSwapchain, FrameInFlight};
 use crate::utils::frame::FrameAction;
 use crate::input::EventController;
 use crate::error::VkResult;
@@ -18,7 +17,7 @@ use crate::error::VkResult;
 //        ↓             ↓      (swapchain_reload if happen)     ↓
 //        ↓             ↓                                       ↑(game loop)
 //        ↓             ↓                                       ↑
-//      init() -------------> receive_input --> render_frame --------> deinit ----------> destroy Vulkan Context.
+//      init() --> warm_up() --> receive_input --> render_frame ------> deinit ----------> destroy Vulkan Context.
 //                                                                  (terminate program)
 pub trait RenderWorkflow {
 
@@ -26,7 +25,24 @@ pub trait RenderWorkflow {
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, delta_time: f32) -> VkResult<vk::Semaphore>;
+    /// Called once after `init` and before the first presented frame.
+    ///
+    /// Override it to hide the first-frame hitch, etc. create the pipelines here,
+    /// or record and submit a throwaway command buffer to force the driver to finish its lazy compilation.
+    /// The device is waited to be idle after this method returns.
+    fn warm_up(&mut self, _device: &mut VkDevice) -> VkResult<()> {
+        Ok(())
+    }
+
+    /// Record or submit the rendering work of current frame.
+    ///
+    /// `frame.image_index` is the index of the swapchain image acquired for this frame, which is not guaranteed to follow the frame order.
+    /// Use it to select the per-image resources(etc. framebuffers or the command buffers recorded for each framebuffer).
+    /// The framework guarantees that the previous work on the acquired image has completed before this method is called.
+    ///
+    /// The submission of this frame must wait `frame.image_available`, and signal `frame.render_finished` and `frame.fence`.
+    /// `FrameInFlight::submit` does all of these, so that the implementation only needs to provide the command buffers.
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, delta_time: f32) -> VkResult<()>;
 
     fn swapchain_reload(&mut self, _device: &mut VkDevice, _new_chain: &VkSwapchain) -> VkResult<()> {
         Ok(())
@@ -34,5 +50,16 @@ pub trait RenderWorkflow {
 
     fn receive_input(&mut self, inputer: &EventController, delta_time: f32) -> FrameAction;
 
+    /// Advance the simulation by a constant `fixed_delta`(in seconds), for the updates requiring deterministic stepping(etc. physics).
+    ///
+    /// It is only called if a fixed timestep is set by `ProcPipeline::set_fixed_timestep`,
+    /// zero or more times per frame between `receive_input` and `render_frame`, so that the simulated time follows the real time.
+    fn fixed_update(&mut self, _fixed_delta: f32) {}
+
+    /// Called when waiting for a previous frame times out or the device is lost, just before the render loop returns an error.
+    ///
+    /// Override it to save the state or report diagnostics. The device may be unusable at this time.
+    fn on_device_lost(&mut self, _device: &mut VkDevice) {}
+
     fn deinit(self, device: &mut VkDevice) -> VkResult<()>;
 }
@@ -1,47 +1,218 @@
 
-use ash::vk;
-use ash::version::DeviceV1_0;
-
-use crate::context::{VulkanContext, VkDevice, SwapchainSyncError};
+use crate::context::{VulkanContext, FrameSync, SwapchainSyncError};
 use crate::workflow::RenderWorkflow;
 use crate::workflow::window::WindowContext;
-use crate::input::EventController;
+use crate::input::{EventController, KeyBindings};
 use crate::utils::time::VkTimeDuration;
-use crate::utils::frame::{FrameCounter, FrameAction};
+use crate::utils::frame::FrameAction;
 use crate::error::{VkResult, VkError};
 
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_FENCE_TIMEOUT_SECS: u64 = 10;
+/// the maximum count of fixed updates in one frame, to avoid falling behind forever when the updates are slower than real time.
+const MAX_FIXED_STEPS_PER_FRAME: usize = 8;
+/// the maximum count of swapchain recreation when acquiring the image of one frame, before the frame is skipped.
+const MAX_ACQUIRE_RETRIES: usize = 3;
+/// the time to sleep in a paused frame, so that the paused program does not spin the CPU.
+const PAUSED_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
 
 pub struct ProcPipeline {
 
-    window: WindowContext,
+    /// the window to receive events, or `None` in headless mode.
+    window: Option<WindowContext>,
     vulkan: VulkanContext,
 
-    syncs: SyncResource,
+    syncs: FrameSync,
+
+    /// the index of the swapchain image presented by last frame, which is captured by screenshot.
+    last_presented_image: Option<usize>,
+    key_bindings: KeyBindings,
+    fence_timeout: VkTimeDuration,
+
+    /// the interval of `RenderWorkflow::fixed_update`(in seconds), or `None` to use variable timestep only.
+    fixed_timestep: Option<f32>,
+    /// the real time that has not been consumed by fixed updates.
+    fixed_accumulator: f32,
 
-    frame_counter: FrameCounter,
+    /// indicate that the swapchain must be recreated before rendering next frame(etc. the window was resized).
+    is_swapchain_outdated: bool,
 }
 
 impl ProcPipeline {
 
     pub fn new(window: WindowContext, vulkan: VulkanContext) -> VkResult<ProcPipeline> {
+        ProcPipeline::build(Some(window), vulkan)
+    }
 
-        let frame_in_flight = vulkan.swapchain.frame_in_flight();
-        let syncs = SyncResource::new(&vulkan.device, frame_in_flight)?;
-        let frame_counter = FrameCounter::new(frame_in_flight);
+    /// Create the pipeline for the context from `VulkanContext::headless`, which renders without window.
+    ///
+    /// Use `render_offscreen` to run it, since the main loop of `launch` never ends without window events.
+    pub fn headless(vulkan: VulkanContext) -> VkResult<ProcPipeline> {
+
+        if vulkan.swapchain.is_headless() == false {
+            return Err(VkError::custom("The Vulkan context is not created by `VulkanContext::headless`."))
+        }
+        ProcPipeline::build(None, vulkan)
+    }
+
+    fn build(window: Option<WindowContext>, vulkan: VulkanContext) -> VkResult<ProcPipeline> {
 
-        let target = ProcPipeline { window, vulkan, syncs, frame_counter };
+        let frame_in_flight = vulkan.swapchain.frame_in_flight();
+        // the number of frame in flight is equal to the number of swapchain images.
+        let syncs = FrameSync::new(&vulkan.device, frame_in_flight, vulkan.swapchain.images.len())?;
+
+        let target = ProcPipeline {
+            window, vulkan, syncs,
+            last_presented_image: None,
+            key_bindings: KeyBindings::default(),
+            fence_timeout: VkTimeDuration::Time(Duration::from_secs(DEFAULT_FENCE_TIMEOUT_SECS)),
+            fixed_timestep: None,
+            fixed_accumulator: 0.0,
+            is_swapchain_outdated: false,
+        };
         Ok(target)
     }
 
+    /// Remap the keys of logical actions(etc. for AZERTY or Dvorak keyboard layouts).
+    pub fn set_key_bindings(&mut self, bindings: KeyBindings) {
+        self.key_bindings = bindings;
+    }
+
+    /// Set the maximum time to wait for the GPU to finish a previous frame(default is 10 seconds).
+    ///
+    /// If the waiting times out(etc. an infinite loop in shader hangs the GPU), `RenderWorkflow::on_device_lost` is called and an error is returned,
+    /// instead of blocking the program forever. Use `VkTimeDuration::Infinite` to disable the timeout.
+    pub fn set_fence_timeout(&mut self, timeout: VkTimeDuration) {
+        self.fence_timeout = timeout;
+    }
+
+    /// Call `RenderWorkflow::fixed_update` with the constant `timestep`(in seconds, etc. `1.0 / 60.0`) each frame, as many times as the elapsed time requires.
+    ///
+    /// Default is `None`, which only uses the variable `delta_time` passed to `receive_input` and `render_frame`.
+    pub fn set_fixed_timestep(&mut self, timestep: Option<f32>) {
+
+        debug_assert!(timestep.map_or(true, |step| step > 0.0), "The fixed timestep must be greater than 0!");
+        self.fixed_timestep = timestep;
+        self.fixed_accumulator = 0.0;
+    }
+
     pub fn frame_in_flight(&self) -> usize {
         self.vulkan.swapchain.frame_in_flight()
     }
 
     pub fn launch(mut self, mut app: impl RenderWorkflow) -> VkResult<()> {
 
+        self.prepare(&mut app)?;
+        self.main_loop(&mut app)?;
+        self.terminate(app)
+    }
+
+    /// Render `frames` frames and terminate, then print and return the frame time statistics.
+    ///
+    /// The program terminates earlier if the window is closed or `Terminal` is returned.
+    /// Combined with `ProcPipeline::headless`, it runs a benchmark or smoke test without window(etc. in CI).
+    pub fn launch_for(mut self, mut app: impl RenderWorkflow, frames: u64) -> VkResult<FrameStats> {
+
+        self.prepare(&mut app)?;
+
+        let mut stats = FrameStats::default();
+        let mut event_handler = EventController::with_bindings(self.key_bindings.clone());
+
+        let launch_time = Instant::now();
+        for _ in 0..frames {
+
+            let frame_start = Instant::now();
+            let is_continue = self.tick_frame(&mut app, &mut event_handler)?;
+            stats.record(frame_start.elapsed());
+
+            if is_continue == false {
+                break
+            }
+        }
+        self.vulkan.wait_idle()?;
+        stats.total_secs = launch_time.elapsed().as_secs_f32();
+
+        self.terminate(app)?;
+
+        println!("[Info] {}", stats);
+        Ok(stats)
+    }
+
+    /// Render `frames` frames and return the pixels of the last frame(see `VkSwapchain::read_back` for the pixel layout).
+    ///
+    /// This is intended for the pipeline from `ProcPipeline::headless`(etc. to compare with a golden image in tests).
+    /// `RenderWorkflow::render_frame` renders to the offscreen images as if they were swapchain images.
+    /// The program terminates afterwards.
+    pub fn render_offscreen(mut self, mut app: impl RenderWorkflow, frames: usize) -> VkResult<Vec<u8>> {
+
+        self.prepare(&mut app)?;
+
+        let mut event_handler = EventController::with_bindings(self.key_bindings.clone());
+        for _ in 0..frames {
+            if self.tick_frame(&mut app, &mut event_handler)? == false {
+                break
+            }
+        }
+
+        self.vulkan.wait_idle()?;
+        let pixels = self.vulkan.swapchain.read_back(&mut self.vulkan.device);
+
+        self.terminate(app)?;
+        pixels
+    }
+
+    /// Render `frames` frames(including a swapchain recreation in the middle), and check that no allocation of `vma` is leaked.
+    ///
+    /// The allocation stats are recorded after `warm_up` and after the frames, when the device is idle.
+    /// An error is returned if the allocation count does not return to the baseline(etc. `swapchain_reload` forgets to discard a resource).
+    /// The program terminates afterwards, just like the window is closed.
+    pub fn assert_no_growth(mut self, mut app: impl RenderWorkflow, frames: usize) -> VkResult<()> {
+
+        self.prepare(&mut app)?;
+
+        let baseline = self.vulkan.device.allocation_stats()?;
+
+        let mut event_handler = EventController::with_bindings(self.key_bindings.clone());
+        for frame in 0..frames {
+
+            // the same path as resizing the window.
+            if frame == frames / 2 {
+                self.respond_action(&mut app, FrameAction::SwapchainRecreate)?;
+            }
+
+            if self.tick_frame(&mut app, &mut event_handler)? == false {
+                break
+            }
+        }
+
+        self.vulkan.wait_idle()?;
+        // the resources dropped by `Owned` are released lazily, so flush them before comparison.
+        self.vulkan.device.flush_deletion_queue()?;
+        let current = self.vulkan.device.allocation_stats()?;
+
+        self.terminate(app)?;
+
+        if current.allocation_count > baseline.allocation_count {
+            Err(VkError::custom(format!(
+                "Allocations grow from {}({} bytes) to {}({} bytes) after {} frames, some resources may be leaked.",
+                baseline.allocation_count, baseline.used_bytes, current.allocation_count, current.used_bytes, frames)))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn prepare(&mut self, app: &mut impl RenderWorkflow) -> VkResult<()> {
+
         app.init(&self.vulkan.device)?;
 
-        self.main_loop(&mut app)?;
+        app.warm_up(&mut self.vulkan.device)?;
+        // make sure all the warm-up work has finished before the first frame.
+        self.vulkan.wait_idle()
+    }
+
+    fn terminate(mut self, app: impl RenderWorkflow) -> VkResult<()> {
 
         self.vulkan.wait_idle()?;
         // free the program specific resource.
@@ -55,147 +226,279 @@ impl ProcPipeline {
 
     fn main_loop(&mut self, app: &mut impl RenderWorkflow) -> VkResult<()> {
 
-        let mut event_handler = EventController::default();
+        let mut event_handler = EventController::with_bindings(self.key_bindings.clone());
 
-        'loop_marker: loop {
+        while self.tick_frame(app, &mut event_handler)? {}
 
-            macro_rules! response_feedback {
-                ($action:ident) => {
-                    match $action {
-                        | FrameAction::Rendering => {},
-                        | FrameAction::SwapchainRecreate => {
+        Ok(())
+    }
 
-                            self.vulkan.wait_idle()?;
-                            self.vulkan.recreate_swapchain(&self.window)?;
-                            app.swapchain_reload(&mut self.vulkan.device, &self.vulkan.swapchain)?;
-                        },
-                        | FrameAction::Terminal => {
-                            break 'loop_marker
-                        },
-                    }
-                }
-            }
+    /// Process the events and render a frame. Return false if the program should terminate.
+    fn tick_frame(&mut self, app: &mut impl RenderWorkflow, event_handler: &mut EventController) -> VkResult<bool> {
 
-            let delta_time = event_handler.fps_counter.delta_time();
+        let delta_time = event_handler.fps_counter.delta_time();
 
-            self.window.event_loop.poll_events(|event| {
+        if let Some(ref mut window) = self.window {
+            window.event_loop.poll_events(|event| {
                 event_handler.record_event(event);
             });
-            let window_feedback = event_handler.current_action();
-            response_feedback!(window_feedback);
+        }
+        let window_feedback = event_handler.current_action();
+        // the window reports resize on creation too, so only recreate the swapchain when its dimension or HiDPI factor really changes.
+        let is_resize_ignored = window_feedback == FrameAction::SwapchainRecreate && self.is_window_changed()? == false;
+        if is_resize_ignored == false && self.respond_action(app, window_feedback)? == false {
+            return Ok(false)
+        }
+
+        if self.refresh_swapchain(app)? == false {
+            // the window is minimized, so skip rendering until it is restored.
+            event_handler.tick_frame();
+            ::std::thread::sleep(PAUSED_FRAME_INTERVAL);
+            return Ok(true)
+        }
 
-            let input_feedback = app.receive_input(&event_handler, delta_time);
-            response_feedback!(input_feedback);
+        let input_feedback = app.receive_input(event_handler, delta_time);
+        if input_feedback == FrameAction::Pause {
 
-            let render_feedback = self.render_frame(app, delta_time)?;
-            response_feedback!(render_feedback);
+            // keep the window responsive, but skip the update and rendering of this frame.
+            if event_handler.is_capture_requested() {
+                self.save_screenshot()?;
+            }
 
             event_handler.tick_frame();
-            self.frame_counter.tick_frame();
+            ::std::thread::sleep(PAUSED_FRAME_INTERVAL);
+            return Ok(true)
+        }
+        if self.respond_action(app, input_feedback)? == false {
+            return Ok(false)
         }
 
-        Ok(())
+        self.fixed_update(app, delta_time);
+
+        let render_feedback = self.render_frame(app, delta_time)?;
+        self.vulkan.check_validation()?;
+        if self.respond_action(app, render_feedback)? == false {
+            return Ok(false)
+        }
+
+        if event_handler.is_capture_requested() {
+            self.save_screenshot()?;
+        }
+
+        event_handler.tick_frame();
+
+        Ok(true)
+    }
+
+    /// Consume the elapsed `delta_time` by calling `RenderWorkflow::fixed_update` with the fixed timestep, if it is set.
+    fn fixed_update(&mut self, app: &mut impl RenderWorkflow, delta_time: f32) {
+
+        if let Some(timestep) = self.fixed_timestep {
+
+            self.fixed_accumulator += delta_time;
+
+            let mut steps = 0;
+            while self.fixed_accumulator >= timestep && steps < MAX_FIXED_STEPS_PER_FRAME {
+                app.fixed_update(timestep);
+                self.fixed_accumulator -= timestep;
+                steps += 1;
+            }
+
+            if steps == MAX_FIXED_STEPS_PER_FRAME {
+                // drop the time that can not catch up, instead of accumulating it forever.
+                self.fixed_accumulator = self.fixed_accumulator.min(timestep);
+            }
+        }
+    }
+
+    /// Respond to the feedback of a frame. Return false if the program should terminate.
+    fn respond_action(&mut self, app: &mut impl RenderWorkflow, action: FrameAction) -> VkResult<bool> {
+
+        match action {
+            | FrameAction::Rendering
+            | FrameAction::Pause => {},
+            | FrameAction::SwapchainRecreate => {
+                self.is_swapchain_outdated = true;
+                self.refresh_swapchain(app)?;
+            },
+            | FrameAction::Terminal => {
+                return Ok(false)
+            },
+        }
+
+        Ok(true)
+    }
+
+    /// Recreate the swapchain at current window dimension if it is outdated, and notify `RenderWorkflow::swapchain_reload`.
+    ///
+    /// Return false if the swapchain can not be recreated now because the window is minimized(zero-size), and it will be retried next frame.
+    fn refresh_swapchain(&mut self, app: &mut impl RenderWorkflow) -> VkResult<bool> {
+
+        if self.is_swapchain_outdated == false {
+            return Ok(true)
+        }
+
+        if let Some(ref window) = self.window {
+            let dimension = window.dimension()?;
+            if dimension.width == 0 || dimension.height == 0 {
+                return Ok(false)
+            }
+        }
+
+        self.vulkan.wait_idle()?;
+        self.vulkan.recreate_swapchain(self.window.as_ref())?;
+        self.last_presented_image = None;
+        self.syncs.reset(&self.vulkan.device, self.vulkan.swapchain.images.len())?;
+        app.swapchain_reload(&mut self.vulkan.device, &self.vulkan.swapchain)?;
+
+        self.is_swapchain_outdated = false;
+        Ok(true)
+    }
+
+    /// Check if the window dimension or HiDPI factor differs from the swapchain. It is always false in headless mode.
+    fn is_window_changed(&self) -> VkResult<bool> {
+
+        match self.window {
+            | Some(ref window) => {
+                let dimension = window.dimension()?;
+                let current = self.vulkan.swapchain.dimension;
+                let is_resized = dimension.width != current.width || dimension.height != current.height;
+                Ok(is_resized || window.hidpi_factor() != self.vulkan.swapchain.hidpi_factor())
+            },
+            | None => Ok(false),
+        }
     }
 
     fn render_frame(&mut self, app: &mut impl RenderWorkflow, delta_time: f32) -> VkResult<FrameAction> {
 
+        if self.is_swapchain_outdated {
+            // the swapchain is waiting for the window to be restored.
+            return Ok(FrameAction::Rendering)
+        }
+
         // wait and acquire next image. -------------------------------------
-        let fence_ready = self.syncs.sync_fences[self.frame_counter.current_frame()];
-        unsafe {
-            self.vulkan.device.logic.handle.wait_for_fences(&[fence_ready], true, VkTimeDuration::Infinite.into())
-                .map_err(|_| VkError::device("Fence waiting"))?;
-        }
-
-        let acquire_image_index = match self.vulkan.swapchain.next_image(Some(self.syncs.await_present), None) {
-            | Ok(image_index) => image_index,
-            | Err(e) => match e {
-                | SwapchainSyncError::SurfaceOutDate
-                | SwapchainSyncError::SubOptimal => {
-                    return Ok(FrameAction::SwapchainRecreate)
-                },
-                | SwapchainSyncError::TimeOut
-                | SwapchainSyncError::Unknown => {
-                    return Err(VkError::custom(e.to_string()))
+        let mut acquire_retries = 0;
+        let frame = loop {
+            match self.syncs.acquire_next(&self.vulkan.device, &self.vulkan.swapchain, self.fence_timeout) {
+                | Ok(frame) => break frame,
+                | Err(SwapchainSyncError::SurfaceOutDate) if acquire_retries < MAX_ACQUIRE_RETRIES => {
+                    // the surface has changed(etc. the window was resized or moved to another monitor), so recreate the swapchain and try again.
+                    acquire_retries += 1;
+                    self.is_swapchain_outdated = true;
+                    if self.refresh_swapchain(app)? == false {
+                        // the window is minimized, skip this frame.
+                        return Ok(FrameAction::Rendering)
+                    }
                 },
+                | Err(e) => return self.sync_error_action(app, e),
             }
         };
-
-        unsafe {
-            self.vulkan.device.logic.handle.reset_fences(&[fence_ready])
-                .map_err(|_| VkError::device("Fence Resetting"))?;
-        }
+        // the fence of this frame has been waited, so the objects dropped during its last use are no longer used by GPU.
+        self.vulkan.device.flush_frame_deletions(frame.frame_index)?;
         // ------------------------------------------------------------------
 
         // call command buffer(activate pipeline to draw) -------------------
-        let await_render = app.render_frame(&mut self.vulkan.device, fence_ready, self.syncs.await_present, acquire_image_index as _, delta_time)?;
+        app.render_frame(&mut self.vulkan.device, &frame, delta_time)?;
         // ------------------------------------------------------------------
 
         // present image. ---------------------------------------------------
         // TODO: Add ownership transfer if need.
         // see https://github.com/KhronosGroup/Vulkan-Docs/wiki/Synchronization-Examples.
         // or see https://software.intel.com/en-us/articles/api-without-secrets-introduction-to-vulkan-part-3#inpage-nav-6-3
-        match self.vulkan.swapchain.present(&[await_render], acquire_image_index) {
-            | Ok(_) => {},
-            | Err(e) => match e {
-                | SwapchainSyncError::SurfaceOutDate
-                | SwapchainSyncError::SubOptimal => {
-                    return Ok(FrameAction::SwapchainRecreate)
-                },
-                | SwapchainSyncError::TimeOut
-                | SwapchainSyncError::Unknown => {
-                    return Err(VkError::custom(e.to_string()))
-                },
-            },
+        if let Err(e) = self.syncs.present(&self.vulkan.swapchain, &frame) {
+            return self.sync_error_action(app, e)
         }
         // ------------------------------------------------------------------
 
+        self.last_presented_image = Some(frame.image_index);
         Ok(FrameAction::Rendering)
     }
-}
-
 
+    /// Translate the error during image acquiring or presentation to the action of this frame.
+    fn sync_error_action(&mut self, app: &mut impl RenderWorkflow, error: SwapchainSyncError) -> VkResult<FrameAction> {
 
-struct SyncResource {
+        match error {
+            | SwapchainSyncError::SurfaceOutDate
+            | SwapchainSyncError::SubOptimal => {
+                Ok(FrameAction::SwapchainRecreate)
+            },
+            | SwapchainSyncError::DeviceLost
+            | SwapchainSyncError::TimeOut => {
+                // a timeout usually means the GPU hangs, so treat it the same as a lost device.
+                app.on_device_lost(&mut self.vulkan.device);
+                Err(VkError::custom(error.to_string()))
+            },
+            | SwapchainSyncError::Unknown => {
+                Err(VkError::custom(error.to_string()))
+            },
+        }
+    }
 
-    frame_count: usize,
+    /// Save the image presented by last frame to `screenshot_<timestamp>.png` in the working directory.
+    ///
+    /// Failing to capture the image is not fatal, so the error is only printed.
+    fn save_screenshot(&mut self) -> VkResult<()> {
 
-    await_present: vk::Semaphore,
-    sync_fences : Vec<vk::Fence>,
-}
+        let image_index = match self.last_presented_image {
+            | Some(image_index) => image_index,
+            | None => return Ok(()),
+        };
 
-impl SyncResource {
+        // make sure the presented image has been rendered completely.
+        self.vulkan.wait_idle()?;
 
-    pub fn new(device: &VkDevice, frame_count: usize) -> VkResult<SyncResource> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or(0);
+        let path = format!("screenshot_{}.png", timestamp);
 
-        use crate::ci::sync::{SemaphoreCI, FenceCI};
+        let capture_result = self.vulkan.device.capture_swapchain_image(&self.vulkan.swapchain, image_index)
+            .and_then(|image| image.save(&path).map_err(|e| VkError::custom(e.to_string())));
 
-        let await_present = device.build(&SemaphoreCI::new())?;
+        match capture_result {
+            | Ok(_) => println!("[Info] Screenshot is saved to {}.", path),
+            | Err(e) => println!("[Warning] Failed to save screenshot: {}", e),
+        }
+        Ok(())
+    }
+}
 
-        let mut sync_fences = Vec::with_capacity(frame_count);
-        let fence_ci = FenceCI::new(true);
+/// The frame time statistics of `ProcPipeline::launch_for`. The frame times are in milliseconds.
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    /// the count of rendered frames.
+    pub frame_count: u64,
+    /// the total time of all frames, in seconds.
+    pub total_secs: f32,
+    pub average_ms: f32,
+    pub min_ms: f32,
+    pub max_ms: f32,
+}
 
-        for _ in 0..frame_count {
-            sync_fences.push(device.build(&fence_ci)?);
-        }
+impl FrameStats {
 
-        let syncs = SyncResource { frame_count, await_present, sync_fences };
-        Ok(syncs)
-    }
+    fn record(&mut self, frame_time: Duration) {
 
-    #[allow(dead_code)]
-    fn reset(&mut self, device: &VkDevice) -> VkResult<()> {
+        let frame_ms = frame_time.as_secs_f32() * 1000.0;
 
-        self.discard(device);
-        *self = SyncResource::new(device, self.frame_count)?;
+        if self.frame_count == 0 {
+            self.min_ms = frame_ms;
+            self.max_ms = frame_ms;
+        } else {
+            self.min_ms = self.min_ms.min(frame_ms);
+            self.max_ms = self.max_ms.max(frame_ms);
+        }
 
-        Ok(())
+        // the running mean, which does not need to keep all the frame times.
+        self.frame_count += 1;
+        self.average_ms += (frame_ms - self.average_ms) / self.frame_count as f32;
     }
+}
 
-    fn discard(&mut self, device: &VkDevice) {
-
-        device.discard(self.await_present);
-        device.discard(&self.sync_fences);
+impl ::std::fmt::Display for FrameStats {
 
-        self.sync_fences.clear();
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "Rendered {} frames in {:.2}s, frame time: average {:.3}ms, min {:.3}ms, max {:.3}ms.",
+            self.frame_count, self.total_secs, self.average_ms, self.min_ms, self.max_ms)
     }
 }
@@ -19,6 +19,13 @@ pub struct WindowConfig {
 
     pub is_cursor_grap: bool,
     pub is_cursor_hide: bool,
+
+    /// The scale of the resolution that scene renders at, relative to the window(etc. 0.5 renders at half resolution).
+    ///
+    /// The scaled dimension is available as `VkSwapchain::render_dimension`.
+    /// The scene should be rendered into a `RenderTarget` of this dimension and upscaled to swapchain image by `RenderTarget::record_blit`,
+    /// while UI stays at native resolution(see `VkExampleBackend::enable_render_scale` of the examples for the whole workflow).
+    pub render_scale: f32,
 }
 
 impl Default for WindowConfig {
@@ -41,14 +48,48 @@ impl Default for WindowConfig {
 
             is_cursor_grap: false,
             is_cursor_hide: false,
+
+            render_scale: 1.0,
         }
     }
 }
 
+impl WindowConfig {
+
+    /// Set the fullscreen mode of window. It replaces the `mode` of this config.
+    ///
+    /// In the fullscreen modes, the window covers the primary monitor and `dimension` is ignored,
+    /// so that the swapchain is created at the resolution of monitor.
+    pub fn fullscreen(mut self, mode: FullscreenMode) -> WindowConfig {
+
+        self.mode = match mode {
+            | FullscreenMode::Windowed   => WindowMode::Normal,
+            | FullscreenMode::Borderless => WindowMode::Borderless,
+            | FullscreenMode::Exclusive  => WindowMode::Fullscreen,
+        }; self
+    }
+}
+
 pub enum WindowMode {
     Normal,
     Maximized,
+    /// the exclusive fullscreen on the primary monitor.
     Fullscreen,
+    /// a window without decorations that covers the primary monitor(etc. for demoing on a projector).
+    ///
+    /// Unlike `Fullscreen`, switching to other windows does not change the display mode.
+    Borderless,
+}
+
+/// The modes used by `WindowConfig::fullscreen`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FullscreenMode {
+    /// an ordinary window with decorations.
+    Windowed,
+    /// a window without decorations that covers the monitor.
+    Borderless,
+    /// the exclusive fullscreen, which uses the current video mode of the monitor.
+    Exclusive,
 }
 
 
@@ -56,6 +97,8 @@ pub struct WindowContext {
 
     pub(crate) event_loop: winit::EventsLoop,
     pub(crate) handle: winit::Window,
+
+    render_scale: f32,
 }
 
 impl WindowContext {
@@ -63,6 +106,7 @@ impl WindowContext {
     pub fn new(config: WindowConfig) -> VkResult<WindowContext> {
 
         let event_loop = winit::EventsLoop::new();
+        let render_scale = config.render_scale;
 
         let mut builder = winit::WindowBuilder::new()
             .with_title(config.title)
@@ -78,13 +122,25 @@ impl WindowContext {
             builder = builder.with_max_dimensions((max.width, max.height).into());
         }
 
+        let primary_monitor = event_loop.get_primary_monitor();
+        // the size of monitor in logical pixels, which is the unit of window dimension.
+        let monitor_dimension = primary_monitor.get_dimensions().to_logical(primary_monitor.get_hidpi_factor());
+
         builder = match config.mode {
             | WindowMode::Maximized => {
                 builder.with_maximized(true)
             },
             | WindowMode::Fullscreen => {
-                let primary_monitor = event_loop.get_primary_monitor();
-                builder.with_fullscreen(Some(primary_monitor))
+                // winit keeps the current video mode of the monitor for fullscreen window.
+                builder
+                    .with_dimensions(monitor_dimension)
+                    .with_fullscreen(Some(primary_monitor.clone()))
+            },
+            | WindowMode::Borderless => {
+                builder
+                    .with_dimensions(monitor_dimension)
+                    .with_decorations(false)
+                    .with_resizable(false)
             },
             | WindowMode::Normal => {
                 builder
@@ -94,9 +150,15 @@ impl WindowContext {
         let window = WindowContext {
             handle: builder.build(&event_loop)
                 .map_err(|e| VkError::window(e.to_string()))?,
-            event_loop,
+            event_loop, render_scale,
         };
 
+        if let WindowMode::Borderless = config.mode {
+            // move the window to cover the monitor, since it is placed by window manager.
+            let monitor_position = primary_monitor.get_position().to_logical(primary_monitor.get_hidpi_factor());
+            window.handle.set_position(monitor_position);
+        }
+
         window.handle.grab_cursor(config.is_cursor_grap)
             .map_err(|e| VkError::window(e.to_string()))?;
         window.handle.hide_cursor(config.is_cursor_hide);
@@ -104,13 +166,20 @@ impl WindowContext {
         Ok(window)
     }
 
+    /// Return the dimension of window in physical pixels, which is the resolution of swapchain images.
     pub fn dimension(&self) -> VkResult<vk::Extent2D> {
 
+        let hidpi_factor = self.handle.get_hidpi_factor();
         self.handle.get_inner_size()
-            .and_then(|dim| Some(ash::vk::Extent2D { width : dim.width as _, height: dim.height as _, }))
+            .map(|dim| dim.to_physical(hidpi_factor))
+            .and_then(|dim| Some(ash::vk::Extent2D { width : dim.width.round() as _, height: dim.height.round() as _, }))
             .ok_or(VkError::window("Failed to get dimension of current window."))
     }
 
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+
     pub fn hidpi_factor(&self) -> f32 {
         self.handle.get_hidpi_factor() as f32
     }
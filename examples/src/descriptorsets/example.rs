@@ -3,31 +3,31 @@ use ash::vk;
 
 use std::ptr;
 use std::mem;
-use std::path::Path;
 
 use arrayvec::ArrayVec;
 
-use vkbase::context::{VkDevice, VkSwapchain};
+use vkbase::context::{VkDevice, VkSwapchain, FrameInFlight};
 use vkbase::ci::VkObjectBuildableCI;
 use vkbase::ci::buffer::BufferCI;
 use vkbase::ci::vma::{VmaBuffer, VmaAllocationCI};
 use vkbase::gltf::VkglTFModel;
 use vkbase::texture::Texture2D;
 use vkbase::context::VulkanContext;
-use vkbase::{FlightCamera, FrameAction};
+use vkbase::{FlightCamera, FrameAction, KeyAction};
 use vkbase::{vkbytes, vkptr, Vec3F, Mat4F};
 use vkbase::{VkResult, VkErrorKind};
 
 use vkexamples::VkExampleBackend;
+use vkexamples::{asset_path, workspace_path};
 
 const VERTEX_SHADER_SOURCE_PATH  : &'static str = "examples/src/descriptorsets/cube.vert.glsl";
 const FRAGMENT_SHADER_SOURCE_PATH: &'static str = "examples/src/descriptorsets/cube.frag.glsl";
 
-const CUBE_MODEL_PATH: &'static str = "assets/models/cube.gltf";
+const CUBE_MODEL_PATH: &'static str = "models/cube.gltf";
 const CUBE_COUNT: usize = 2;
 const CUBE_TEXTURE_PATHS: [&'static str; CUBE_COUNT] = [
-    "assets/textures/crate01_color_height_rgba.ktx",
-    "assets/textures/crate02_color_height_rgba.ktx",
+    "textures/crate01_color_height_rgba.ktx",
+    "textures/crate02_color_height_rgba.ktx",
 ];
 
 // TODO: Check box to toggle animation is not yet implemented.
@@ -72,7 +72,7 @@ impl VulkanExample {
 
 
         let render_pass = setup_renderpass(device, &context.swapchain)?;
-        let backend = VkExampleBackend::new(device, swapchain, render_pass)?;
+        let backend = VkExampleBackend::new(device, swapchain, render_pass, vk::SampleCountFlags::TYPE_1)?;
 
         let model = prepare_model(device)?;
 
@@ -100,18 +100,11 @@ impl vkbase::RenderWorkflow for VulkanExample {
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, delta_time: f32) -> VkResult<vk::Semaphore> {
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, delta_time: f32) -> VkResult<()> {
 
         self.update_uniforms(delta_time)?;
 
-        let submit_ci = vkbase::ci::device::SubmitCI::new()
-            .add_wait(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, await_present)
-            .add_command(self.backend.commands[image_index])
-            .add_signal(self.backend.await_rendering);
-
-        device.submit(submit_ci, device.logic.queues.graphics.handle, Some(device_available))?;
-
-        Ok(self.backend.await_rendering)
+        frame.submit(device, &[self.backend.commands[frame.image_index]])
     }
 
     fn swapchain_reload(&mut self, device: &mut VkDevice, new_chain: &VkSwapchain) -> VkResult<()> {
@@ -131,7 +124,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
         if inputer.is_key_active() || inputer.is_cursor_active() {
 
-            if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+            if inputer.is_action_pressed(KeyAction::Quit) {
                 return FrameAction::Terminal
             }
 
@@ -158,7 +151,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
             device.vma_discard(cube.uniform_buffer)?;
             cube.texture.discard_by(device)?;
         }
-        device.vma_discard(self.model)?;
+        self.model.discard_by(device)?;
         self.backend.discard_by(device)
     }
 }
@@ -187,7 +180,7 @@ impl VulkanExample {
 
             let render_pass_bi = RenderPassBI::new(self.backend.render_pass, self.backend.framebuffers[i])
                 .render_extent(dimension)
-                .set_clear_values(vkexamples::DEFAULT_CLEAR_VALUES.clone());
+                .clear_values(&vkexamples::DEFAULT_CLEAR_VALUES);
 
             recorder.begin_record()?
                 .begin_render_pass(render_pass_bi)
@@ -249,10 +242,10 @@ impl VulkanExample {
 pub fn prepare_model(device: &mut VkDevice) -> VkResult<VkglTFModel> {
 
     use vkbase::gltf::{GltfModelInfo, load_gltf};
-    use vkbase::gltf::{AttributeFlags, NodeAttachmentFlags};
+    use vkbase::gltf::{AttributeFlags, NodeAttachmentFlags, IndexPrecision};
 
     let model_info = GltfModelInfo {
-        path: Path::new(CUBE_MODEL_PATH),
+        path: &asset_path(CUBE_MODEL_PATH),
         // specify model's vertices layout.
         // in cube.vert.glsl:
         // layout (location = 0) in vec3 inPos;
@@ -264,6 +257,7 @@ pub fn prepare_model(device: &mut VkDevice) -> VkResult<VkglTFModel> {
         //     mat4 transform;
         // } dyn_node;
         node: NodeAttachmentFlags::TRANSFORM_MATRIX,
+        index_type: IndexPrecision::U16,
         transform: None,
     };
 
@@ -322,7 +316,7 @@ fn prepare_uniform(device: &mut VkDevice, camera: &FlightCamera) -> VkResult<Arr
             // the descriptor_set member will be set in setup_descriptor() method.
             descriptor_set: vk::DescriptorSet::null(),
             uniform_buffer: ubo_buffer,
-            texture : Texture2D::load_ktx(device, Path::new(CUBE_TEXTURE_PATHS[i]), vk::Format::R8G8B8A8_UNORM)?,
+            texture : Texture2D::load_ktx(device, &asset_path(CUBE_TEXTURE_PATHS[i]), vk::Format::R8G8B8A8_UNORM)?,
             rotation: 0.0,
         };
         cubes.push(cube);
@@ -357,7 +351,7 @@ struct DescriptorStaff {
 fn setup_descriptor(device: &VkDevice, cubes: &mut ArrayVec<[Cube; 2]>, model: &VkglTFModel) -> VkResult<DescriptorStaff> {
 
     use vkbase::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use vkbase::ci::descriptor::{DescriptorSetAI, DescriptorBufferSetWI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
+    use vkbase::ci::descriptor::{DescriptorBufferSetWI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
 
     /*
         SaschaWillems's comment:
@@ -455,10 +449,7 @@ fn setup_descriptor(device: &VkDevice, cubes: &mut ArrayVec<[Cube; 2]>, model: &
 
     for i in 0..CUBE_COUNT {
 
-        let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-            .add_set_layout(set_layout)
-            .build(device)?;
-        cubes[i].descriptor_set = descriptor_sets.remove(0);
+        cubes[i].descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
         // Update the descriptor set with the actual descriptors matching shader bindings set in the layout.
 
@@ -574,8 +565,8 @@ fn prepare_pipelines(device: &VkDevice, model: &VkglTFModel, render_pass: vk::Re
     use vkbase::ci::shader::{ShaderModuleCI, ShaderStageCI};
 
     let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
-    let vert_codes = shader_compiler.compile_from_path(Path::new(VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-    let frag_codes = shader_compiler.compile_from_path(Path::new(FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
+    let vert_codes = shader_compiler.compile_from_path(&workspace_path(VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
+    let frag_codes = shader_compiler.compile_from_path(&workspace_path(FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
 
     let vert_module = ShaderModuleCI::new(vert_codes)
         .build(device)?;
@@ -161,11 +161,10 @@ impl UboDynamicData {
 
     pub fn prepare_buffer(device: &mut VkDevice) -> VkResult<(VmaBuffer, UboDynamicData, vkuint)> {
 
-        let min_alignment = device.phy.limits.min_uniform_buffer_offset_alignment as usize;
-        println!("minUniformBufferOffsetAlignment in Vulkan: {}", min_alignment);
+        println!("minUniformBufferOffsetAlignment in Vulkan: {}", device.phy.limits.min_uniform_buffer_offset_alignment);
 
         // Calculate required alignment based on minimum device offset alignment.
-        let dynamic_alignment = (::std::mem::size_of::<Mat4F>() + min_alignment - 1) & !(min_alignment - 1);
+        let dynamic_alignment = vkbase::utils::memory::dynamic_alignment_of::<Mat4F>(device) as usize;
         println!("dynamicAlignment: {}", dynamic_alignment);
 
         let buffer_ci = BufferCI::new((dynamic_alignment * OBJECT_INSTANCES) as vkbytes)
@@ -2,16 +2,16 @@
 use ash::vk;
 
 use std::ptr;
-use std::path::Path;
 
-use vkbase::context::{VulkanContext, VkDevice, VkSwapchain};
+use vkbase::context::{VulkanContext, VkDevice, VkSwapchain, FrameInFlight};
 use vkbase::ci::VkObjectBuildableCI;
 use vkbase::ci::vma::VmaBuffer;
-use vkbase::{FlightCamera, FrameAction};
+use vkbase::{FlightCamera, FrameAction, KeyAction};
 use vkbase::{vkbytes, vkuint, vkptr, Vec3F};
 use vkbase::{VkResult, VkErrorKind};
 
 use vkexamples::VkExampleBackend;
+use vkexamples::workspace_path;
 use crate::data::{OBJECT_INSTANCES, INDEX_DATA, Vertex, RotationData, UboView, UboDynamicData};
 
 const SHADER_VERTEX_PATH  : &'static str = "examples/src/dynamicuniformbuffer/base.vert.glsl";
@@ -55,7 +55,7 @@ impl VulkanExample {
         camera.set_move_speed(50.0);
 
         let render_pass = setup_renderpass(device, &context.swapchain)?;
-        let backend = VkExampleBackend::new(device, swapchain, render_pass)?;
+        let backend = VkExampleBackend::new(device, swapchain, render_pass, vk::SampleCountFlags::TYPE_1)?;
 
         let (vertices, indices) = super::data::generate_cube(device)?;
         let (ubo_view, ubo_view_data) = UboView::prepare_buffer(device, &camera)?;
@@ -88,18 +88,11 @@ impl vkbase::RenderWorkflow for VulkanExample {
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, delta_time: f32) -> VkResult<vk::Semaphore> {
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, delta_time: f32) -> VkResult<()> {
 
         self.update_uniforms(device, delta_time)?;
 
-        let submit_ci = vkbase::ci::device::SubmitCI::new()
-            .add_wait(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, await_present)
-            .add_command(self.backend.commands[image_index])
-            .add_signal(self.backend.await_rendering);
-
-        device.submit(submit_ci, device.logic.queues.graphics.handle, Some(device_available))?;
-
-        Ok(self.backend.await_rendering)
+        frame.submit(device, &[self.backend.commands[frame.image_index]])
     }
 
     fn swapchain_reload(&mut self, device: &mut VkDevice, new_chain: &VkSwapchain) -> VkResult<()> {
@@ -120,7 +113,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
         if inputer.is_key_active() || inputer.is_cursor_active() {
 
-            if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+            if inputer.is_action_pressed(KeyAction::Quit) {
                 return FrameAction::Terminal
             }
 
@@ -176,7 +169,7 @@ impl VulkanExample {
 
             let render_pass_bi = RenderPassBI::new(self.backend.render_pass, self.backend.framebuffers[i])
                 .render_extent(dimension)
-                .set_clear_values(vkexamples::DEFAULT_CLEAR_VALUES.clone());
+                .clear_values(&vkexamples::DEFAULT_CLEAR_VALUES);
 
             recorder.begin_record()?
                 .begin_render_pass(render_pass_bi)
@@ -184,7 +177,7 @@ impl VulkanExample {
                 .set_scissor(0, &[scissor])
                 .bind_pipeline(self.pipelines.pipeline)
                 .bind_vertex_buffers(0, &[self.vertices.handle], &[0])
-                .bind_index_buffer(self.indices.handle, vk::IndexType::UINT32, 0);
+                .bind_index_buffer(&self.indices, vk::IndexType::UINT32, 0);
 
             // Render multiple objects using different model matrices by dynamically offsetting into one uniform buffer.
             for i in 0..(OBJECT_INSTANCES as vkuint) {
@@ -252,7 +245,7 @@ struct DescriptorStaff {
 fn setup_descriptor(device: &VkDevice, ubo_view: &VmaBuffer, ubo_dynamics: &VmaBuffer, dynamic_alignment: vkuint) -> VkResult<DescriptorStaff> {
 
     use vkbase::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use vkbase::ci::descriptor::{DescriptorSetAI, DescriptorBufferSetWI, DescriptorSetsUpdateCI};
+    use vkbase::ci::descriptor::{DescriptorBufferSetWI, DescriptorSetsUpdateCI};
 
     // Descriptor Pool.
     let descriptor_pool = DescriptorPoolCI::new(1)
@@ -291,10 +284,7 @@ fn setup_descriptor(device: &VkDevice, ubo_view: &VmaBuffer, ubo_dynamics: &VmaB
         .build(device)?;
 
     // Descriptor set.
-    let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-        .add_set_layout(set_layout)
-        .build(device)?;
-    let descriptor_set = descriptor_sets.remove(0);
+    let descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
     let ubo_view_write = DescriptorBufferSetWI::new(descriptor_set, 0, vk::DescriptorType::UNIFORM_BUFFER)
         .add_buffer(vk::DescriptorBufferInfo {
@@ -401,8 +391,8 @@ fn prepare_pipelines(device: &VkDevice, render_pass: vk::RenderPass, set_layout:
     use vkbase::ci::shader::{ShaderModuleCI, ShaderStageCI};
 
     let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
-    let vert_codes = shader_compiler.compile_from_path(Path::new(SHADER_VERTEX_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-    let frag_codes = shader_compiler.compile_from_path(Path::new(SHADER_FRAGMENT_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
+    let vert_codes = shader_compiler.compile_from_path(&workspace_path(SHADER_VERTEX_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
+    let frag_codes = shader_compiler.compile_from_path(&workspace_path(SHADER_FRAGMENT_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
 
     let vert_module = ShaderModuleCI::new(vert_codes).build(device)?;
     let frag_module = ShaderModuleCI::new(frag_codes).build(device)?;
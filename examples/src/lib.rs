@@ -5,16 +5,20 @@ use ash::version::DeviceV1_0;
 use lazy_static::lazy_static;
 
 use vkbase::ci::VkObjectBuildableCI;
-use vkbase::ci::sync::SemaphoreCI;
 use vkbase::ci::image::{ImageCI, ImageViewCI};
 use vkbase::ci::vma::{VmaImage, VmaAllocationCI};
-use vkbase::ui::{UIRenderer, TextInfo, TextID, TextType, TextHAlign};
+use vkbase::ci::pipeline::RenderPassBI;
+use vkbase::ui::{UIRenderer, TextInfo, TextID, TextType, TextHAlign, TextVAlign, TextLimits};
+use vkbase::command::{VkCmdRecorder, CmdGraphicsApi, IGraphics};
+use vkbase::texture::RenderTarget;
 
 use vkbase::context::{VkDevice, VkSwapchain};
 use vkbase::utils::color::VkColor;
 use vkbase::vkuint;
 use vkbase::{VkResult, VkError, VkErrorKind};
 
+use std::path::{Path, PathBuf};
+
 lazy_static! {
 
     pub static ref DEFAULT_CLEAR_VALUES: Vec<vk::ClearValue> = vec![
@@ -29,42 +33,81 @@ pub struct VkExampleBackend {
     pub render_pass: vk::RenderPass,
     pub framebuffers: Vec<vk::Framebuffer>,
 
-    pub await_rendering: vk::Semaphore,
-
     pub command_pool: vk::CommandPool,
     /// render command buffer for each framebuffer.
     pub commands: Vec<vk::CommandBuffer>,
 
     pub ui_renderer: UIRenderer,
     fps_text_id: Option<TextID>,
+    /// the text of device memory usage, which is `None` unless `set_memory_ui` is called.
+    memory_text_id: Option<TextID>,
+    is_memory_text_outdated: bool,
 
-    depth_image: DepthImage,
+    depth_image: AttachmentImage,
     is_use_depth_attachment: bool,
+
+    /// the sample count of color and depth attachments.
+    sample_count: vk::SampleCountFlags,
+    /// the multisampled color attachment, which is resolved to swapchain image. It is `None` if MSAA is disabled.
+    msaa_color_image: Option<AttachmentImage>,
+
+    /// indicate that `enable_render_scale` has been called.
+    is_render_scale_enabled: bool,
+    /// the resources to render the scene at a scaled resolution. It is `None` if the scene renders at the resolution of swapchain.
+    scaled_scene: Option<ScaledScene>,
+}
+
+/// The resources to render the scene at `VkSwapchain::render_dimension`, and upscale it to swapchain images before drawing UI.
+struct ScaledScene {
+
+    target: RenderTarget,
+    depth_image: AttachmentImage,
+    /// the render pass of scene, which is compatible with the render pass from `setup_sampled_renderpass` without MSAA.
+    scene_pass: vk::RenderPass,
+    scene_framebuffer: vk::Framebuffer,
+
+    /// the render pass drawing UI onto the upscaled swapchain image at native resolution.
+    ui_pass: vk::RenderPass,
+    ui_framebuffers: Vec<vk::Framebuffer>,
+    swapchain_images: Vec<vk::Image>,
 }
 
-struct DepthImage {
+struct AttachmentImage {
     image: VmaImage,
     view : vk::ImageView,
 }
 
 impl VkExampleBackend {
 
-    pub fn new(device: &mut VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass) -> VkResult<VkExampleBackend> {
+    /// `sample_count` is the sample count of the attachments in `renderpass`, use `vk::SampleCountFlags::TYPE_1` to disable MSAA.
+    ///
+    /// If MSAA is enabled, `renderpass` must be compatible with the one from `setup_sampled_renderpass`,
+    /// and `sample_count` should have been clamped by `clamp_sample_count`.
+    pub fn new(device: &mut VkDevice, swapchain: &VkSwapchain, renderpass: vk::RenderPass, sample_count: vk::SampleCountFlags) -> VkResult<VkExampleBackend> {
 
         let dimension = swapchain.dimension;
+        let sample_count = clamp_sample_count(device, sample_count);
         let (command_pool, commands) = setup_commands(device, swapchain.frame_in_flight as _)?;
-        let depth_image = setup_depth_image(device, swapchain.dimension)?;
-        let await_rendering = device.build(&SemaphoreCI::new())?;
+        let depth_image = setup_depth_image(device, swapchain.dimension, sample_count)?;
+        let msaa_color_image = setup_msaa_color_image(device, swapchain, sample_count)?;
 
-        let ui_renderer = UIRenderer::new(device, swapchain, renderpass)?;
+        let mut ui_renderer = UIRenderer::new(device, swapchain, renderpass, TextLimits::default())?;
+        if sample_count != vk::SampleCountFlags::TYPE_1 {
+            ui_renderer.set_sample_count(device, swapchain, renderpass, sample_count)?;
+        }
 
         let mut target = VkExampleBackend {
-            depth_image, await_rendering, ui_renderer,
+            depth_image, ui_renderer,
             commands, command_pool, dimension,
+            sample_count, msaa_color_image,
             fps_text_id: None,
+            memory_text_id: None,
+            is_memory_text_outdated: false,
             render_pass: renderpass,
             framebuffers: Vec::new(),
             is_use_depth_attachment: true,
+            is_render_scale_enabled: false,
+            scaled_scene: None,
         };
         target.setup_framebuffers(device, swapchain)?;
 
@@ -75,6 +118,98 @@ impl VkExampleBackend {
         self.is_use_depth_attachment = is_enable;
     }
 
+    /// The sample count of attachments after clamped by the device limits.
+    pub fn sample_count(&self) -> vk::SampleCountFlags {
+        self.sample_count
+    }
+
+    /// Render the scene at `VkSwapchain::render_dimension`(see `WindowConfig::render_scale`), and upscale it to swapchain images, while UI stays at native resolution.
+    ///
+    /// Record the scene inside the render pass of `scene_pass_bi` with the viewport of `render_dimension`, and then call `finish_scene_pass`.
+    /// It does nothing if the render scale is 1.0, and it is not supported with MSAA.
+    pub fn enable_render_scale(&mut self, device: &mut VkDevice, swapchain: &VkSwapchain) -> VkResult<()> {
+
+        if self.msaa_color_image.is_some() {
+            return Err(VkError::custom("Render scale is not supported with MSAA."))
+        }
+
+        self.is_render_scale_enabled = true;
+        self.reload_scaled_scene(device, swapchain)
+    }
+
+    /// The dimension that scene renders at, which is the swapchain dimension unless `enable_render_scale` is called.
+    pub fn render_dimension(&self) -> vk::Extent2D {
+        self.scaled_scene.as_ref()
+            .map_or(self.dimension, |scaled| scaled.target.dimension)
+    }
+
+    /// Get the begin info of the render pass for scene(without clear values) of the framebuffer at `image_index`.
+    pub fn scene_pass_bi(&self, image_index: usize) -> RenderPassBI {
+
+        match self.scaled_scene {
+            | Some(ref scaled) => {
+                RenderPassBI::new(scaled.scene_pass, scaled.scene_framebuffer)
+                    .render_extent(scaled.target.dimension)
+            },
+            | None => {
+                RenderPassBI::new(self.render_pass, self.framebuffers[image_index])
+                    .render_extent(self.dimension)
+            },
+        }
+    }
+
+    /// Record the UI and end the render pass begun with `scene_pass_bi`.
+    ///
+    /// If the render scale is enabled, the scene is upscaled to the swapchain image at `image_index` first, and then UI is drawn in a separate render pass.
+    pub fn finish_scene_pass(&self, device: &VkDevice, command: vk::CommandBuffer, image_index: usize) {
+
+        let recorder: VkCmdRecorder<IGraphics> = VkCmdRecorder::new(&device.logic, command);
+
+        match self.scaled_scene {
+            | Some(ref scaled) => {
+
+                recorder.end_render_pass();
+
+                scaled.target.record_blit(device, command, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                    scaled.swapchain_images[image_index], self.dimension, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+
+                let ui_pass_bi = RenderPassBI::new(scaled.ui_pass, scaled.ui_framebuffers[image_index])
+                    .render_extent(self.dimension);
+
+                recorder.begin_render_pass(ui_pass_bi);
+                self.ui_renderer.record_command(&recorder);
+                recorder.end_render_pass();
+            },
+            | None => {
+                self.ui_renderer.record_command(&recorder);
+                recorder.end_render_pass();
+            },
+        }
+    }
+
+    /// Recreate the resources of scaled scene for `swapchain` if the render scale is enabled.
+    fn reload_scaled_scene(&mut self, device: &mut VkDevice, swapchain: &VkSwapchain) -> VkResult<()> {
+
+        if let Some(scaled) = self.scaled_scene.take() {
+            scaled.discard_by(device)?;
+        }
+
+        if self.is_render_scale_enabled == false || swapchain.render_dimension == swapchain.dimension {
+            return Ok(())
+        }
+
+        if swapchain.image_usage().contains(vk::ImageUsageFlags::TRANSFER_DST) == false {
+            return Err(VkError::unsupported("Blitting to swapchain images"))
+        }
+
+        let scaled = ScaledScene::new(device, swapchain)?;
+        // the UI is drawn in the render pass of scaled scene, which only has color attachment.
+        self.ui_renderer.swapchain_reload(device, swapchain, scaled.ui_pass)?;
+        self.scaled_scene = Some(scaled);
+
+        Ok(())
+    }
+
     fn setup_framebuffers(&mut self, device: &VkDevice, swapchain: &VkSwapchain) -> VkResult<()> {
 
         use vkbase::ci::pipeline::FramebufferCI;
@@ -84,13 +219,21 @@ impl VkExampleBackend {
 
         for i in 0..swapchain.frame_in_flight() {
 
+            // color attachment is the view of the swapchain image, or the multisampled image that resolves to it.
+            let color_view = self.msaa_color_image.as_ref()
+                .map_or(swapchain.images[i].view, |msaa_image| msaa_image.view);
             let mut framebuffer_ci = FramebufferCI::new_2d(self.render_pass, self.dimension)
-                .add_attachment(swapchain.images[i].view); // color attachment is the view of the swapchain image.
+                .add_attachment(color_view);
 
             if self.is_use_depth_attachment {
                 framebuffer_ci = framebuffer_ci.add_attachment(self.depth_image.view);
             }
 
+            // resolve attachment is the view of the swapchain image.
+            if self.msaa_color_image.is_some() {
+                framebuffer_ci = framebuffer_ci.add_attachment(swapchain.images[i].view);
+            }
+
             let framebuffer = framebuffer_ci.build(device)?;
             self.framebuffers.push(framebuffer);
         }
@@ -103,21 +246,30 @@ impl VkExampleBackend {
         self.dimension = new_chain.dimension;
         self.ui_renderer.swapchain_reload(device, new_chain, render_pass)?;
 
-        let mut new_depth_image = setup_depth_image(device, self.dimension)?;
+        let mut new_depth_image = setup_depth_image(device, self.dimension, self.sample_count)?;
         std::mem::swap(&mut new_depth_image, &mut self.depth_image);
 
         device.discard(new_depth_image.view);
         device.vma_discard(new_depth_image.image)?;
 
+        let mut new_msaa_image = setup_msaa_color_image(device, new_chain, self.sample_count)?;
+        std::mem::swap(&mut new_msaa_image, &mut self.msaa_color_image);
+
+        if let Some(old_msaa_image) = new_msaa_image {
+            device.discard(old_msaa_image.view);
+            device.vma_discard(old_msaa_image.image)?;
+        }
+
         device.discard(&self.framebuffers);
         device.discard(self.render_pass);
 
         self.render_pass = render_pass;
         self.setup_framebuffers(device, new_chain)?;
+        self.reload_scaled_scene(device, new_chain)?;
 
         unsafe {
             device.logic.handle.reset_command_pool(self.command_pool, vk::CommandPoolResetFlags::empty())
-                .map_err(|_| VkError::device("Reset Command Pool"))?;
+                .map_err(|e| VkError::vk(e, "Reset Command Pool"))?;
         }
         Ok(())
     }
@@ -128,8 +280,11 @@ impl VkExampleBackend {
             content: String::from(title),
             scale: 12.0,
             align: TextHAlign::Left,
+            valign: TextVAlign::Top,
             color: VkColor::WHITE,
             location: vk::Offset2D { x: 5, y: 0 },
+            outline: None,
+            shadow: None,
             r#type: TextType::Static,
         };
 
@@ -137,8 +292,11 @@ impl VkExampleBackend {
             content: device.phy.device_name.clone(),
             scale: 12.0,
             align: TextHAlign::Left,
+            valign: TextVAlign::Top,
             color: VkColor::WHITE,
             location: vk::Offset2D { x: 5, y: 40 },
+            outline: None,
+            shadow: None,
             r#type: TextType::Static,
         };
 
@@ -146,8 +304,11 @@ impl VkExampleBackend {
             content: String::from("FPS: 00.00"),
             scale: 12.0,
             align: TextHAlign::Left,
+            valign: TextVAlign::Top,
             color: VkColor::WHITE,
             location: vk::Offset2D { x: 5, y: 80 },
+            outline: None,
+            shadow: None,
             r#type: TextType::Dynamic { capacity: 15 },
         };
 
@@ -158,11 +319,57 @@ impl VkExampleBackend {
         Ok(())
     }
 
+    /// Show the usage of device local memory(and its budget if `VK_EXT_memory_budget` is supported) below the FPS text. Call it after `set_basic_ui`.
+    pub fn set_memory_ui(&mut self) -> VkResult<()> {
+
+        let memory_text = TextInfo {
+            content: String::from("Memory: 0.0 / 0.0 MB"),
+            scale: 12.0,
+            align: TextHAlign::Left,
+            valign: TextVAlign::Top,
+            color: VkColor::WHITE,
+            location: vk::Offset2D { x: 5, y: 100 },
+            outline: None,
+            shadow: None,
+            r#type: TextType::Dynamic { capacity: 40 },
+        };
+
+        self.memory_text_id = Some(self.ui_renderer.add_text(memory_text)?);
+        self.is_memory_text_outdated = true;
+
+        Ok(())
+    }
+
+    /// Refresh the memory text if it is outdated. Call it in `render_frame` since the query requires the device.
+    pub fn update_memory_text(&mut self, device: &VkDevice) -> VkResult<()> {
+
+        if let Some(text_id) = self.memory_text_id {
+
+            if self.is_memory_text_outdated {
+                const MEGA_BYTES: f32 = 1024.0 * 1024.0;
+
+                let (used, budget) = device.memory_stats()?.device_local();
+                // only show the budget if it is reported by `VK_EXT_memory_budget`.
+                let memory = match budget {
+                    | Some(budget) => format!("Memory: {:.1} / {:.1} MB", used as f32 / MEGA_BYTES, budget as f32 / MEGA_BYTES),
+                    | None => format!("Memory: {:.1} MB", used as f32 / MEGA_BYTES),
+                };
+                self.ui_renderer.change_text(memory, text_id);
+                self.is_memory_text_outdated = false;
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn update_fps_text(&mut self, inputer: &vkbase::EventController) {
 
         // update text on fps per second.
         if inputer.fps_counter.is_tick_second() {
 
+            // the memory text is refreshed at the same frequency as fps text.
+            self.is_memory_text_outdated = true;
+
             if let Some(text_id) = self.fps_text_id {
                 let fps = format!("FPS: {}", inputer.fps_counter.fps());
                 self.ui_renderer.change_text(fps, text_id);
@@ -182,16 +389,254 @@ impl VkExampleBackend {
         device.discard(self.depth_image.view);
         device.vma_discard(self.depth_image.image)?;
 
-        device.discard(self.await_rendering);
+        if let Some(msaa_image) = self.msaa_color_image {
+            device.discard(msaa_image.view);
+            device.vma_discard(msaa_image.image)?;
+        }
+
+        if let Some(scaled) = self.scaled_scene {
+            scaled.discard_by(device)?;
+        }
 
         Ok(())
     }
 }
 
-fn setup_depth_image(device: &mut VkDevice, dimension: vk::Extent2D) -> VkResult<DepthImage> {
+impl ScaledScene {
+
+    fn new(device: &mut VkDevice, swapchain: &VkSwapchain) -> VkResult<ScaledScene> {
+
+        use vkbase::ci::pipeline::FramebufferCI;
+
+        let dimension = swapchain.render_dimension;
+
+        let target = RenderTarget::new(device, swapchain.backend_format, dimension)?;
+        let depth_image = setup_depth_image(device, dimension, vk::SampleCountFlags::TYPE_1)?;
+
+        let scene_pass = setup_scaled_scene_renderpass(device, swapchain)?;
+        let scene_framebuffer = FramebufferCI::new_2d(scene_pass, dimension)
+            .add_attachment(target.view)
+            .add_attachment(depth_image.view)
+            .build(device)?;
+
+        let ui_pass = setup_ui_renderpass(device, swapchain)?;
+        let mut ui_framebuffers = Vec::with_capacity(swapchain.images.len());
+        for image in swapchain.images.iter() {
+            let framebuffer = FramebufferCI::new_2d(ui_pass, swapchain.dimension)
+                .add_attachment(image.view)
+                .build(device)?;
+            ui_framebuffers.push(framebuffer);
+        }
+
+        let swapchain_images = swapchain.images.iter().map(|image| image.image).collect();
+
+        let scaled = ScaledScene { target, depth_image, scene_pass, scene_framebuffer, ui_pass, ui_framebuffers, swapchain_images };
+        Ok(scaled)
+    }
+
+    fn discard_by(self, device: &mut VkDevice) -> VkResult<()> {
+
+        device.discard(&self.ui_framebuffers);
+        device.discard(self.ui_pass);
+        device.discard(self.scene_framebuffer);
+        device.discard(self.scene_pass);
+
+        device.discard(self.depth_image.view);
+        device.vma_discard(self.depth_image.image)?;
+        self.target.discard_by(device)
+    }
+}
+
+/// Get the highest sample count that is supported by both color and depth attachments of the device, but not greater than `requested`.
+pub fn clamp_sample_count(device: &VkDevice, requested: vk::SampleCountFlags) -> vk::SampleCountFlags {
+
+    let supported = device.phy.limits.framebuffer_color_sample_counts & device.phy.limits.framebuffer_depth_sample_counts;
+
+    let candidates = [
+        vk::SampleCountFlags::TYPE_64, vk::SampleCountFlags::TYPE_32, vk::SampleCountFlags::TYPE_16,
+        vk::SampleCountFlags::TYPE_8, vk::SampleCountFlags::TYPE_4, vk::SampleCountFlags::TYPE_2,
+    ];
+
+    let clamped = candidates.iter()
+        .find(|&&count| count.as_raw() <= requested.as_raw() && supported.contains(count))
+        .cloned()
+        .unwrap_or(vk::SampleCountFlags::TYPE_1);
+
+    if clamped != requested {
+        println!("[Warning] Sample count {:?} is not supported by the device, use {:?} instead.", requested, clamped);
+    }
+    clamped
+}
+
+/// Create the render pass with a color attachment and a depth attachment of `sample_count` samples.
+///
+/// If `sample_count` is greater than 1, the color attachment is resolved to the swapchain image at the end of subpass,
+/// and the framebuffer attachments are in the order of (multisampled color, depth, swapchain image).
+pub fn setup_sampled_renderpass(device: &VkDevice, swapchain: &VkSwapchain, sample_count: vk::SampleCountFlags) -> VkResult<vk::RenderPass> {
+
+    use vkbase::ci::pipeline::RenderPassCI;
+    use vkbase::ci::pipeline::{AttachmentDescCI, SubpassDescCI, SubpassDependencyCI};
+
+    let is_msaa = sample_count != vk::SampleCountFlags::TYPE_1;
+
+    let color_attachment = if is_msaa {
+        // the multisampled image is only used in this render pass, so it does not need to be stored.
+        AttachmentDescCI::new(swapchain.backend_format)
+            .sample_count(sample_count)
+            .op(vk::AttachmentLoadOp::CLEAR, vk::AttachmentStoreOp::DONT_CARE)
+            .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+    } else {
+        AttachmentDescCI::new(swapchain.backend_format)
+            .op(vk::AttachmentLoadOp::CLEAR, vk::AttachmentStoreOp::STORE)
+            .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::PRESENT_SRC_KHR)
+    };
+
+    let depth_attachment = AttachmentDescCI::new(device.supported_depth_format())
+        .sample_count(sample_count)
+        .op(vk::AttachmentLoadOp::CLEAR, vk::AttachmentStoreOp::DONT_CARE)
+        .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+
+    let mut subpass_description = SubpassDescCI::new(vk::PipelineBindPoint::GRAPHICS)
+        .add_color_attachment(0, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL) // Attachment 0 is color.
+        .set_depth_stencil_attachment(1, vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL); // Attachment 1 is depth-stencil.
+
+    let dependency0 = SubpassDependencyCI::new(vk::SUBPASS_EXTERNAL, 0)
+        .stage_mask(vk::PipelineStageFlags::BOTTOM_OF_PIPE, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+        .access_mask(vk::AccessFlags::MEMORY_READ, vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+        .flags(vk::DependencyFlags::BY_REGION);
+
+    let dependency1 = SubpassDependencyCI::new(0, vk::SUBPASS_EXTERNAL)
+        .stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, vk::PipelineStageFlags::BOTTOM_OF_PIPE)
+        .access_mask(vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE, vk::AccessFlags::MEMORY_READ)
+        .flags(vk::DependencyFlags::BY_REGION);
+
+    let mut render_pass_ci = RenderPassCI::new()
+        .add_attachment(color_attachment)
+        .add_attachment(depth_attachment);
+
+    if is_msaa {
+        let resolve_attachment = AttachmentDescCI::new(swapchain.backend_format)
+            .op(vk::AttachmentLoadOp::DONT_CARE, vk::AttachmentStoreOp::STORE)
+            .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::PRESENT_SRC_KHR);
+
+        render_pass_ci = render_pass_ci.add_attachment(resolve_attachment);
+        subpass_description = subpass_description
+            .add_resolve_attachment(2, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL); // Attachment 2 is the resolve target.
+    }
+
+    let render_pass = render_pass_ci
+        .add_subpass(subpass_description)
+        .add_dependency(dependency0)
+        .add_dependency(dependency1)
+        .name("example render pass")
+        .build(device)?;
+
+    Ok(render_pass)
+}
+
+/// Create the render pass of scene for render scale, which keeps the color attachment in `COLOR_ATTACHMENT_OPTIMAL` for blitting.
+///
+/// Its attachments are compatible with the render pass from `setup_sampled_renderpass` without MSAA, so that the pipelines can be shared.
+fn setup_scaled_scene_renderpass(device: &VkDevice, swapchain: &VkSwapchain) -> VkResult<vk::RenderPass> {
+
+    use vkbase::ci::pipeline::RenderPassCI;
+    use vkbase::ci::pipeline::{AttachmentDescCI, SubpassDescCI, SubpassDependencyCI};
+
+    let color_attachment = AttachmentDescCI::new(swapchain.backend_format)
+        .op(vk::AttachmentLoadOp::CLEAR, vk::AttachmentStoreOp::STORE)
+        .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+
+    let depth_attachment = AttachmentDescCI::new(device.supported_depth_format())
+        .op(vk::AttachmentLoadOp::CLEAR, vk::AttachmentStoreOp::DONT_CARE)
+        .layout(vk::ImageLayout::UNDEFINED, vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+
+    let subpass_description = SubpassDescCI::new(vk::PipelineBindPoint::GRAPHICS)
+        .add_color_attachment(0, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+        .set_depth_stencil_attachment(1, vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+
+    // the previous blit must finish reading the color attachment before it is cleared.
+    let dependency0 = SubpassDependencyCI::new(vk::SUBPASS_EXTERNAL, 0)
+        .stage_mask(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+        .access_mask(vk::AccessFlags::TRANSFER_READ, vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE);
+
+    let render_pass = RenderPassCI::new()
+        .add_attachment(color_attachment)
+        .add_attachment(depth_attachment)
+        .add_subpass(subpass_description)
+        .add_dependency(dependency0)
+        .name("scaled scene render pass")
+        .build(device)?;
+
+    Ok(render_pass)
+}
+
+/// Create the render pass drawing UI onto the swapchain image that has been written by blitting.
+fn setup_ui_renderpass(device: &VkDevice, swapchain: &VkSwapchain) -> VkResult<vk::RenderPass> {
+
+    use vkbase::ci::pipeline::RenderPassCI;
+    use vkbase::ci::pipeline::{AttachmentDescCI, SubpassDescCI, SubpassDependencyCI};
+
+    // keep the upscaled scene.
+    let color_attachment = AttachmentDescCI::new(swapchain.backend_format)
+        .op(vk::AttachmentLoadOp::LOAD, vk::AttachmentStoreOp::STORE)
+        .layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL, vk::ImageLayout::PRESENT_SRC_KHR);
+
+    let subpass_description = SubpassDescCI::new(vk::PipelineBindPoint::GRAPHICS)
+        .add_color_attachment(0, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+
+    let dependency0 = SubpassDependencyCI::new(0, vk::SUBPASS_EXTERNAL)
+        .stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, vk::PipelineStageFlags::BOTTOM_OF_PIPE)
+        .access_mask(vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE, vk::AccessFlags::MEMORY_READ)
+        .flags(vk::DependencyFlags::BY_REGION);
+
+    let render_pass = RenderPassCI::new()
+        .add_attachment(color_attachment)
+        .add_subpass(subpass_description)
+        .add_dependency(dependency0)
+        .name("ui render pass")
+        .build(device)?;
+
+    Ok(render_pass)
+}
+
+/// Create the multisampled color image if `sample_count` is greater than 1.
+fn setup_msaa_color_image(device: &mut VkDevice, swapchain: &VkSwapchain, sample_count: vk::SampleCountFlags) -> VkResult<Option<AttachmentImage>> {
+
+    if sample_count == vk::SampleCountFlags::TYPE_1 {
+        return Ok(None)
+    }
+
+    let image = {
+        let color_ci = ImageCI::new_2d(swapchain.backend_format, swapchain.dimension)
+            .samples(sample_count)
+            .usages(vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSIENT_ATTACHMENT);
+        let allocation_ci = VmaAllocationCI::new(vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
+        let color_allocation = device.vma.create_image(
+            color_ci.as_ref(), allocation_ci.as_ref())
+            .map_err(VkErrorKind::Vma)?;
+        VmaImage::from(color_allocation)
+    };
+
+    let view = ImageViewCI::new(image.handle, vk::ImageViewType::TYPE_2D, swapchain.backend_format)
+        .sub_range(vk::ImageSubresourceRange {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            base_mip_level: 0,
+            level_count   : 1,
+            base_array_layer: 0,
+            layer_count     : 1,
+        }).build(device)?;
+
+    let result = AttachmentImage { image, view };
+    Ok(Some(result))
+}
+
+fn setup_depth_image(device: &mut VkDevice, dimension: vk::Extent2D, sample_count: vk::SampleCountFlags) -> VkResult<AttachmentImage> {
+
+    let depth_format = device.supported_depth_format();
 
     let image = {
-        let depth_ci = ImageCI::new_2d(device.phy.depth_format, dimension)
+        let depth_ci = ImageCI::new_2d(depth_format, dimension)
+            .samples(sample_count)
             .usages(vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT);
         let allocation_ci = VmaAllocationCI::new(vma::MemoryUsage::GpuOnly, vk::MemoryPropertyFlags::DEVICE_LOCAL);
         let depth_allocation = device.vma.create_image(
@@ -200,16 +645,23 @@ fn setup_depth_image(device: &mut VkDevice, dimension: vk::Extent2D) -> VkResult
         VmaImage::from(depth_allocation)
     };
 
-    let view = ImageViewCI::new(image.handle, vk::ImageViewType::TYPE_2D, device.phy.depth_format)
+    // the stencil aspect is only valid for the formats with stencil component.
+    let aspect_mask = match depth_format {
+        | vk::Format::D32_SFLOAT
+        | vk::Format::D16_UNORM => vk::ImageAspectFlags::DEPTH,
+        | _ => vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL,
+    };
+
+    let view = ImageViewCI::new(image.handle, vk::ImageViewType::TYPE_2D, depth_format)
         .sub_range(vk::ImageSubresourceRange {
-            aspect_mask: vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL,
+            aspect_mask,
             base_mip_level: 0,
             level_count   : 1,
             base_array_layer: 0,
             layer_count     : 1,
         }).build(device)?;
 
-    let result = DepthImage { image, view };
+    let result = AttachmentImage { image, view };
     Ok(result)
 }
 
@@ -226,3 +678,55 @@ fn setup_commands(device: &VkDevice, buffer_count: vkuint) -> VkResult<(vk::Comm
 
     Ok((command_pool, command_buffers))
 }
+
+/// The environment variable to override the location of assets directory.
+const ASSET_DIR_ENV: &'static str = "VKEXAMPLES_ASSET_DIR";
+
+/// The environment variable to set the render scale of examples(etc. `VKEXAMPLES_RENDER_SCALE=0.5` for half resolution).
+const RENDER_SCALE_ENV: &'static str = "VKEXAMPLES_RENDER_SCALE";
+
+/// Read the render scale from `$VKEXAMPLES_RENDER_SCALE`, or return 1.0 if it is not set or invalid.
+///
+/// Assign it to `WindowConfig::render_scale`, and call `VkExampleBackend::enable_render_scale` to render the scene at this scale.
+pub fn render_scale() -> f32 {
+
+    ::std::env::var(RENDER_SCALE_ENV).ok()
+        .and_then(|scale| scale.parse::<f32>().ok())
+        .filter(|&scale| scale > 0.0)
+        .unwrap_or(1.0)
+}
+
+/// The environment variable to run an example for a fixed number of frames and check the leak of allocations(etc. `VKEXAMPLES_LEAK_CHECK_FRAMES=120`).
+const LEAK_CHECK_ENV: &'static str = "VKEXAMPLES_LEAK_CHECK_FRAMES";
+
+/// Read the frame count of leak check from `$VKEXAMPLES_LEAK_CHECK_FRAMES`, or return `None` if it is not set or invalid.
+///
+/// Pass it to `ProcPipeline::assert_no_growth` instead of calling `ProcPipeline::launch`.
+pub fn leak_check_frames() -> Option<usize> {
+
+    ::std::env::var(LEAK_CHECK_ENV).ok()
+        .and_then(|frames| frames.parse::<usize>().ok())
+        .filter(|&frames| frames > 0)
+}
+
+/// Resolve `relative`(etc. "models/cube.gltf") against the assets directory, so that examples can run from any working directory.
+///
+/// The assets directory is `$VKEXAMPLES_ASSET_DIR` if this environment variable is set, or `assets` in workspace root otherwise.
+pub fn asset_path(relative: impl AsRef<Path>) -> PathBuf {
+
+    let asset_dir = ::std::env::var_os(ASSET_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_path("assets"));
+
+    asset_dir.join(relative)
+}
+
+/// Resolve `relative`(etc. "examples/src/triangle_v1/triangle.vert.glsl") against the workspace root.
+pub fn workspace_path(relative: impl AsRef<Path>) -> PathBuf {
+
+    // the manifest directory of this crate is `examples` in workspace root.
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir.parent()
+        .unwrap_or(manifest_dir)
+        .join(relative)
+}
@@ -3,22 +3,22 @@ use ash::vk;
 
 use std::ptr;
 use std::mem;
-use std::path::Path;
 
-use vkbase::context::{VkDevice, VkSwapchain};
+use vkbase::context::{VkDevice, VkSwapchain, FrameInFlight};
 use vkbase::ci::VkObjectBuildableCI;
 use vkbase::ci::buffer::BufferCI;
 use vkbase::ci::vma::{VmaBuffer, VmaAllocationCI};
 use vkbase::ci::shader::{ShaderModuleCI, ShaderStageCI};
 use vkbase::gltf::VkglTFModel;
-use vkbase::ui::{TextInfo, TextType, TextHAlign};
+use vkbase::ui::{TextInfo, TextType, TextHAlign, TextVAlign};
 use vkbase::context::VulkanContext;
 use vkbase::utils::color::VkColor;
-use vkbase::{FlightCamera, FrameAction};
+use vkbase::{FlightCamera, FrameAction, KeyAction};
 use vkbase::{vkbytes, vkptr, Vec3F, Mat4F, Vec4F};
 use vkbase::{VkResult, VkErrorKind};
 
 use vkexamples::VkExampleBackend;
+use vkexamples::{asset_path, workspace_path};
 
 const PHONG_VERTEX_SHADER_SOURCE_PATH      : &'static str = "examples/src/pipelines/phong.vert.glsl";
 const PHONG_FRAGMENT_SHADER_SOURCE_PATH    : &'static str = "examples/src/pipelines/phong.frag.glsl";
@@ -26,7 +26,7 @@ const TOON_VERTEX_SHADER_SOURCE_PATH       : &'static str = "examples/src/pipeli
 const TOON_FRAGMENT_SHADER_SOURCE_PATH     : &'static str = "examples/src/pipelines/toon.frag.glsl";
 const WIREFRAME_VERTEX_SHADER_SOURCE_PATH  : &'static str = "examples/src/pipelines/wireframe.vert.glsl";
 const WIREFRAME_FRAGMENT_SHADER_SOURCE_PATH: &'static str = "examples/src/pipelines/wireframe.frag.glsl";
-const MODEL_PATH: &'static str = "assets/models/treasure_smooth.gltf";
+const MODEL_PATH: &'static str = "models/treasure_smooth.gltf";
 
 
 pub struct VulkanExample {
@@ -75,7 +75,7 @@ impl VulkanExample {
         };
 
         let render_pass = setup_renderpass(device, &context.swapchain)?;
-        let backend = VkExampleBackend::new(device, swapchain, render_pass)?;
+        let backend = VkExampleBackend::new(device, swapchain, render_pass, vk::SampleCountFlags::TYPE_1)?;
 
         let model = prepare_model(device)?;
         let uniform_buffer = prepare_uniform(device, &ubo_data)?;
@@ -96,6 +96,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
     fn init(&mut self, device: &VkDevice) -> VkResult<()> {
 
         self.backend.set_basic_ui(device, super::WINDOW_TITLE)?;
+        self.backend.set_memory_ui()?;
 
         let screen_width  = self.backend.dimension.width  as i32;
         let screen_height = self.backend.dimension.height as i32;
@@ -104,8 +105,11 @@ impl vkbase::RenderWorkflow for VulkanExample {
             content: String::from("Phong Shading Pipeline"),
             scale: 16.0,
             align: TextHAlign::Center,
+            valign: TextVAlign::Top,
             color: VkColor::WHITE,
             location: vk::Offset2D { x: screen_width / 6, y: screen_height / 8 * 7 },
+            outline: None,
+            shadow: None,
             r#type: TextType::Static,
         };
         self.backend.ui_renderer.add_text(phong_text)?;
@@ -114,8 +118,11 @@ impl vkbase::RenderWorkflow for VulkanExample {
             content: String::from("Toon Shading Pipeline"),
             scale: 16.0,
             align: TextHAlign::Center,
+            valign: TextVAlign::Top,
             color: VkColor::WHITE,
             location: vk::Offset2D { x: screen_width / 6 * 3, y: screen_height / 8 * 7 },
+            outline: None,
+            shadow: None,
             r#type: TextType::Static,
         };
         self.backend.ui_renderer.add_text(toon_text)?;
@@ -124,8 +131,11 @@ impl vkbase::RenderWorkflow for VulkanExample {
             content: String::from("Wireframe Pipeline"),
             scale: 16.0,
             align: TextHAlign::Center,
+            valign: TextVAlign::Top,
             color: VkColor::WHITE,
             location: vk::Offset2D { x: screen_width / 6 * 5 , y: screen_height / 8 * 7 },
+            outline: None,
+            shadow: None,
             r#type: TextType::Static,
         };
         self.backend.ui_renderer.add_text(wireframe_text)?;
@@ -135,29 +145,22 @@ impl vkbase::RenderWorkflow for VulkanExample {
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, _delta_time: f32) -> VkResult<vk::Semaphore> {
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, _delta_time: f32) -> VkResult<()> {
 
         if self.is_toggle_event {
             self.update_uniforms()?;
         }
 
-        let submit_ci = vkbase::ci::device::SubmitCI::new()
-            .add_wait(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, await_present)
-            .add_command(self.backend.commands[image_index])
-            .add_signal(self.backend.await_rendering);
+        self.backend.update_memory_text(device)?;
 
-        // Submit to the graphics queue passing a wait fence.
-        device.submit(submit_ci, device.logic.queues.graphics.handle, Some(device_available))?;
-
-        Ok(self.backend.await_rendering)
+        // Submit to the graphics queue with the semaphores and fence of current frame.
+        frame.submit(device, &[self.backend.commands[frame.image_index]])
     }
 
     fn swapchain_reload(&mut self, device: &mut VkDevice, new_chain: &VkSwapchain) -> VkResult<()> {
 
         // recreate the resources.
-        device.discard(self.pipelines.phong);
-        device.discard(self.pipelines.toon);
-        device.discard(self.pipelines.wireframe);
+        device.discard(&[self.pipelines.phong, self.pipelines.toon, self.pipelines.wireframe]);
 
         let render_pass = setup_renderpass(device, new_chain)?;
         self.backend.swapchain_reload(device, new_chain, render_pass)?;
@@ -172,7 +175,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
         if inputer.is_key_active() || inputer.is_cursor_active() {
 
-            if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+            if inputer.is_action_pressed(KeyAction::Quit) {
                 return FrameAction::Terminal
             }
 
@@ -192,13 +195,11 @@ impl vkbase::RenderWorkflow for VulkanExample {
         device.discard(self.descriptors.layout);
         device.discard(self.descriptors.pool);
 
-        device.discard(self.pipelines.phong);
-        device.discard(self.pipelines.toon);
-        device.discard(self.pipelines.wireframe);
+        device.discard(&[self.pipelines.phong, self.pipelines.toon, self.pipelines.wireframe]);
         device.discard(self.pipelines.layout);
 
         device.vma_discard(self.uniform_buffer)?;
-        device.vma_discard(self.model)?;
+        self.model.discard_by(device)?;
         self.backend.discard_by(device)
     }
 }
@@ -233,11 +234,12 @@ impl VulkanExample {
 
             let render_pass_bi = RenderPassBI::new(self.backend.render_pass, self.backend.framebuffers[i])
                 .render_extent(dimension)
-                .set_clear_values(vkexamples::DEFAULT_CLEAR_VALUES.clone());
+                .clear_values(&vkexamples::DEFAULT_CLEAR_VALUES);
 
             recorder.begin_record()?
                 .begin_render_pass(render_pass_bi)
-                .set_scissor(0, &[scissor]);
+                .set_scissor(0, &[scissor])
+                .begin_label("glTF model", VkColor::YELLOW);
 
             { // Left: Solid colored
                 viewport.width = dimension.width as f32 / 3.0;
@@ -270,6 +272,8 @@ impl VulkanExample {
                 }
             }
 
+            recorder.end_label();
+
             self.backend.ui_renderer.record_command(&recorder);
 
             recorder
@@ -297,12 +301,13 @@ impl VulkanExample {
 pub fn prepare_model(device: &mut VkDevice) -> VkResult<VkglTFModel> {
 
     use vkbase::gltf::{GltfModelInfo, load_gltf};
-    use vkbase::gltf::{AttributeFlags, NodeAttachmentFlags};
+    use vkbase::gltf::{AttributeFlags, NodeAttachmentFlags, IndexPrecision};
 
     let model_info = GltfModelInfo {
-        path: Path::new(MODEL_PATH),
+        path: &asset_path(MODEL_PATH),
         attribute: AttributeFlags::POSITION | AttributeFlags::NORMAL, // specify model's vertices layout.
         node: NodeAttachmentFlags::TRANSFORM_MATRIX, // specify model's node attachment layout.
+        index_type: IndexPrecision::U16,
         transform: None,
     };
 
@@ -359,7 +364,7 @@ struct DescriptorStaff {
 fn setup_descriptor(device: &VkDevice, uniform_buffer: &VmaBuffer, model: &VkglTFModel) -> VkResult<DescriptorStaff> {
 
     use vkbase::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use vkbase::ci::descriptor::{DescriptorSetAI, DescriptorBufferSetWI, DescriptorSetsUpdateCI};
+    use vkbase::ci::descriptor::{DescriptorBufferSetWI, DescriptorSetsUpdateCI};
 
     // Descriptor Pool.
     let descriptor_pool = DescriptorPoolCI::new(1)
@@ -401,10 +406,7 @@ fn setup_descriptor(device: &VkDevice, uniform_buffer: &VmaBuffer, model: &VkglT
         .build(device)?;
 
     // Descriptor set.
-    let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-        .add_set_layout(set_layout)
-        .build(device)?;
-    let descriptor_set = descriptor_sets.remove(0);
+    let descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
     let ubo_write_info = DescriptorBufferSetWI::new(descriptor_set, 0, vk::DescriptorType::UNIFORM_BUFFER)
         .add_buffer(vk::DescriptorBufferInfo {
@@ -505,108 +507,87 @@ fn prepare_pipelines(device: &VkDevice, model: &VkglTFModel, render_pass: vk::Re
         .add_push_constants(material_range)
         .build(device)?;
 
-    // base pipeline.
-    let mut pipeline_ci = GraphicsPipelineCI::new(render_pass, pipeline_layout);
-
-    pipeline_ci.set_vertex_input(model.meshes.vertex_input.clone());
-    pipeline_ci.set_viewport(viewport_state);
-    pipeline_ci.set_rasterization(rasterization_state.clone());
-    pipeline_ci.set_depth_stencil(depth_stencil_state);
-    pipeline_ci.set_color_blend(blend_state);
-    pipeline_ci.set_dynamic(dynamic_state);
-
-
-    let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
-
-    let phong_pipeline = {
-
-        let vert_codes = shader_compiler.compile_from_path(Path::new(PHONG_VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-        let frag_codes = shader_compiler.compile_from_path(Path::new(PHONG_FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
-
-        let vert_module = ShaderModuleCI::new(vert_codes)
-            .build(device)?;
-        let frag_module = ShaderModuleCI::new(frag_codes).build(device)?;
-
-        let shaders = [
-            ShaderStageCI::new(vk::ShaderStageFlags::VERTEX, vert_module),
-            ShaderStageCI::new(vk::ShaderStageFlags::FRAGMENT, frag_module),
-        ];
-        pipeline_ci.set_shaders(&shaders);
-
-        // Using this pipeline as the base for the other pipelines (derivatives).
-        // Pipeline derivatives can be used for pipelines that share most of their state
-        // depending on the implementation this may result in better performance for pipeline switching and faster creation time.
-        pipeline_ci.set_flags(vk::PipelineCreateFlags::ALLOW_DERIVATIVES);
-
-        let pipeline = device.build(&pipeline_ci)?;
+    // all pipelines share the same states except shaders and rasterization.
+    let new_pipeline_ci = |rasterization: RasterizationSCI| {
 
-        device.discard(vert_module);
-        device.discard(frag_module);
+        let mut pipeline_ci = GraphicsPipelineCI::new(render_pass, pipeline_layout);
 
-        pipeline
+        pipeline_ci.set_vertex_input(model.meshes.vertex_input.clone());
+        pipeline_ci.set_viewport(viewport_state.clone());
+        pipeline_ci.set_rasterization(rasterization);
+        pipeline_ci.set_depth_stencil(depth_stencil_state.clone());
+        pipeline_ci.set_color_blend(blend_state.clone());
+        pipeline_ci.set_dynamic(dynamic_state.clone());
+        pipeline_ci
     };
 
-    let toon_pipeline = {
 
-        let vert_codes = shader_compiler.compile_from_path(Path::new(TOON_VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-        let frag_codes = shader_compiler.compile_from_path(Path::new(TOON_FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
-
-        let vert_module = ShaderModuleCI::new(vert_codes)
-            .build(device)?;
-        let frag_module = ShaderModuleCI::new(frag_codes).build(device)?;
-
-        let shaders = [
-            ShaderStageCI::new(vk::ShaderStageFlags::VERTEX, vert_module),
-            ShaderStageCI::new(vk::ShaderStageFlags::FRAGMENT, frag_module),
-        ];
-        pipeline_ci.set_shaders(&shaders);
-        // Base pipeline will be our first created pipeline.
-        pipeline_ci.set_base_pipeline(phong_pipeline);
-        // All pipelines created after the base pipeline will be derivatives.
-        pipeline_ci.set_flags(vk::PipelineCreateFlags::DERIVATIVE);
-
-        let pipeline = device.build(&pipeline_ci)?;
-
-        device.discard(vert_module);
-        device.discard(frag_module);
-
-        pipeline
-    };
+    let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
 
-    let wireframe_pipeline = {
+    let mut load_shaders = |vert_path: &str, frag_path: &str| -> VkResult<(vk::ShaderModule, vk::ShaderModule)> {
 
-        let vert_codes = shader_compiler.compile_from_path(Path::new(WIREFRAME_VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-        let frag_codes = shader_compiler.compile_from_path(Path::new(WIREFRAME_FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
+        let vert_codes = shader_compiler.compile_from_path(&workspace_path(vert_path), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
+        let frag_codes = shader_compiler.compile_from_path(&workspace_path(frag_path), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
 
         let vert_module = ShaderModuleCI::new(vert_codes)
             .build(device)?;
         let frag_module = ShaderModuleCI::new(frag_codes).build(device)?;
 
-        let shaders = [
-            ShaderStageCI::new(vk::ShaderStageFlags::VERTEX, vert_module),
-            ShaderStageCI::new(vk::ShaderStageFlags::FRAGMENT, frag_module),
-        ];
-        pipeline_ci.set_shaders(&shaders);
-
-        // Non solid rendering is not a mandatory Vulkan feature.
-        if device.phy.features_enabled().fill_mode_non_solid == vk::TRUE {
-            rasterization_state = rasterization_state.polygon(vk::PolygonMode::LINE);
-            pipeline_ci.set_rasterization(rasterization_state);
-        }
-
-        let pipeline = device.build(&pipeline_ci)?;
+        Ok((vert_module, frag_module))
+    };
 
-        device.discard(vert_module);
-        device.discard(frag_module);
+    let phong_modules = load_shaders(PHONG_VERTEX_SHADER_SOURCE_PATH, PHONG_FRAGMENT_SHADER_SOURCE_PATH)?;
+    let toon_modules = load_shaders(TOON_VERTEX_SHADER_SOURCE_PATH, TOON_FRAGMENT_SHADER_SOURCE_PATH)?;
+    let wireframe_modules = load_shaders(WIREFRAME_VERTEX_SHADER_SOURCE_PATH, WIREFRAME_FRAGMENT_SHADER_SOURCE_PATH)?;
+
+    let phong_shaders = [
+        ShaderStageCI::new(vk::ShaderStageFlags::VERTEX, phong_modules.0),
+        ShaderStageCI::new(vk::ShaderStageFlags::FRAGMENT, phong_modules.1),
+    ];
+    let toon_shaders = [
+        ShaderStageCI::new(vk::ShaderStageFlags::VERTEX, toon_modules.0),
+        ShaderStageCI::new(vk::ShaderStageFlags::FRAGMENT, toon_modules.1),
+    ];
+    let wireframe_shaders = [
+        ShaderStageCI::new(vk::ShaderStageFlags::VERTEX, wireframe_modules.0),
+        ShaderStageCI::new(vk::ShaderStageFlags::FRAGMENT, wireframe_modules.1),
+    ];
+
+    let mut phong_pipeline_ci = new_pipeline_ci(rasterization_state.clone());
+    phong_pipeline_ci.set_shaders(&phong_shaders);
+    // Using this pipeline as the base for the other pipelines (derivatives).
+    // Pipeline derivatives can be used for pipelines that share most of their state
+    // depending on the implementation this may result in better performance for pipeline switching and faster creation time.
+    phong_pipeline_ci.set_flags(vk::PipelineCreateFlags::ALLOW_DERIVATIVES);
+
+    let mut toon_pipeline_ci = new_pipeline_ci(rasterization_state.clone());
+    toon_pipeline_ci.set_shaders(&toon_shaders);
+    // Base pipeline will be our first created pipeline(the first element in the batch).
+    toon_pipeline_ci.set_base_pipeline_index(0);
+    // All pipelines created after the base pipeline will be derivatives.
+    toon_pipeline_ci.set_flags(vk::PipelineCreateFlags::DERIVATIVE);
+
+    // Non solid rendering is not a mandatory Vulkan feature.
+    if device.phy.features_enabled().fill_mode_non_solid == vk::TRUE {
+        rasterization_state = rasterization_state.polygon(vk::PolygonMode::LINE);
+    }
+    let mut wireframe_pipeline_ci = new_pipeline_ci(rasterization_state);
+    wireframe_pipeline_ci.set_shaders(&wireframe_shaders);
+    wireframe_pipeline_ci.set_base_pipeline_index(0);
+    wireframe_pipeline_ci.set_flags(vk::PipelineCreateFlags::DERIVATIVE);
 
-        pipeline
-    };
+    // create the three pipelines in one driver call.
+    let pipelines = device.build_pipelines(&[&phong_pipeline_ci, &toon_pipeline_ci, &wireframe_pipeline_ci])?;
 
+    for (vert_module, frag_module) in [phong_modules, toon_modules, wireframe_modules].iter() {
+        device.discard(*vert_module);
+        device.discard(*frag_module);
+    }
 
     let result = PipelineStaff {
-        phong: phong_pipeline,
-        toon : toon_pipeline,
-        wireframe: wireframe_pipeline,
+        phong: pipelines[0],
+        toon : pipelines[1],
+        wireframe: pipelines[2],
 
         layout: pipeline_layout,
     };
@@ -4,23 +4,23 @@ use ash::version::DeviceV1_0;
 
 use std::ptr;
 use std::mem;
-use std::path::Path;
 
-use vkbase::context::{VkDevice, VkSwapchain};
+use vkbase::context::{VkDevice, VkSwapchain, FrameInFlight};
 use vkbase::ci::VkObjectBuildableCI;
 use vkbase::ci::buffer::BufferCI;
 use vkbase::ci::vma::{VmaBuffer, VmaAllocationCI};
 use vkbase::gltf::VkglTFModel;
 use vkbase::context::VulkanContext;
-use vkbase::{FlightCamera, FrameAction};
+use vkbase::{FlightCamera, FrameAction, KeyAction};
 use vkbase::{vkbytes, vkuint, vkptr, Vec3F, Mat4F};
 use vkbase::{VkResult, VkError, VkErrorKind};
 
 use vkexamples::VkExampleBackend;
+use vkexamples::{asset_path, workspace_path};
 
 const VERTEX_SHADER_SOURCE_PATH  : &'static str = "examples/src/pushconstants/lights.vert.glsl";
 const FRAGMENT_SHADER_SOURCE_PATH: &'static str = "examples/src/pushconstants/lights.frag.glsl";
-const MODEL_PATH: &'static str = "assets/models/samplescene.gltf";
+const MODEL_PATH: &'static str = "models/samplescene.gltf";
 
 
 pub struct VulkanExample {
@@ -76,7 +76,7 @@ impl VulkanExample {
         };
 
         let render_pass = setup_renderpass(device, &context.swapchain)?;
-        let backend = VkExampleBackend::new(device, swapchain, render_pass)?;
+        let backend = VkExampleBackend::new(device, swapchain, render_pass, vk::SampleCountFlags::TYPE_1)?;
 
         let model = prepare_model(device)?;
 
@@ -105,21 +105,14 @@ impl vkbase::RenderWorkflow for VulkanExample {
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, delta_time: f32) -> VkResult<vk::Semaphore> {
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, delta_time: f32) -> VkResult<()> {
 
         self.update(delta_time);
 
         // Refresh the push constant data for current command buffer.
-        self.rebuild_command(device, image_index)?;
+        self.rebuild_command(device, frame.image_index)?;
 
-        let submit_ci = vkbase::ci::device::SubmitCI::new()
-            .add_wait(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, await_present)
-            .add_command(self.backend.commands[image_index])
-            .add_signal(self.backend.await_rendering);
-
-        device.submit(submit_ci, device.logic.queues.graphics.handle, Some(device_available))?;
-
-        Ok(self.backend.await_rendering)
+        frame.submit(device, &[self.backend.commands[frame.image_index]])
     }
 
     fn swapchain_reload(&mut self, device: &mut VkDevice, new_chain: &VkSwapchain) -> VkResult<()> {
@@ -141,7 +134,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
         if inputer.is_key_active() || inputer.is_cursor_active() {
 
-            if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+            if inputer.is_action_pressed(KeyAction::Quit) {
                 return FrameAction::Terminal
             }
 
@@ -165,7 +158,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
         device.discard(self.pipelines.layout);
 
         device.vma_discard(self.ubo_buffer)?;
-        device.vma_discard(self.model)?;
+        self.model.discard_by(device)?;
         self.backend.discard_by(device)
     }
 }
@@ -200,7 +193,7 @@ impl VulkanExample {
         unsafe {
             let command = self.backend.commands[command_index];
             device.logic.handle.reset_command_buffer(command, vk::CommandBufferResetFlags::empty())
-                .map_err(|_| VkError::device("Reset Command Buffer"))?;
+                .map_err(|e| VkError::vk(e, "Reset Command Buffer"))?;
         }
 
         self.record_command(device, command_index, self.backend.dimension)
@@ -234,7 +227,7 @@ impl VulkanExample {
 
         let render_pass_bi = RenderPassBI::new(self.backend.render_pass, self.backend.framebuffers[command_index])
             .render_extent(dimension)
-            .set_clear_values(vkexamples::DEFAULT_CLEAR_VALUES.clone());
+            .clear_values(&vkexamples::DEFAULT_CLEAR_VALUES);
 
         recorder.begin_record()?
             .begin_render_pass(render_pass_bi)
@@ -282,10 +275,10 @@ impl VulkanExample {
 pub fn prepare_model(device: &mut VkDevice) -> VkResult<VkglTFModel> {
 
     use vkbase::gltf::{GltfModelInfo, load_gltf};
-    use vkbase::gltf::{AttributeFlags, NodeAttachmentFlags};
+    use vkbase::gltf::{AttributeFlags, NodeAttachmentFlags, IndexPrecision};
 
     let model_info = GltfModelInfo {
-        path: Path::new(MODEL_PATH),
+        path: &asset_path(MODEL_PATH),
         // specify model's vertices layout.
         // in light.vert.glsl:
         // layout (location = 0) in vec3 inPos;
@@ -297,6 +290,7 @@ pub fn prepare_model(device: &mut VkDevice) -> VkResult<VkglTFModel> {
         //     mat4 transform;
         // } dyn_node;
         node: NodeAttachmentFlags::TRANSFORM_MATRIX,
+        index_type: IndexPrecision::U16,
         transform: None,
     };
 
@@ -346,7 +340,7 @@ struct DescriptorStaff {
 fn setup_descriptor(device: &VkDevice, uniform_buffer: &VmaBuffer, model: &VkglTFModel) -> VkResult<DescriptorStaff> {
 
     use vkbase::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use vkbase::ci::descriptor::{DescriptorSetAI, DescriptorBufferSetWI, DescriptorSetsUpdateCI};
+    use vkbase::ci::descriptor::{DescriptorBufferSetWI, DescriptorSetsUpdateCI};
 
     // Descriptor Pool.
     let descriptor_pool = DescriptorPoolCI::new(1)
@@ -388,10 +382,7 @@ fn setup_descriptor(device: &VkDevice, uniform_buffer: &VmaBuffer, model: &VkglT
         .build(device)?;
 
     // Descriptor set.
-    let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-        .add_set_layout(set_layout)
-        .build(device)?;
-    let descriptor_set = descriptor_sets.remove(0);
+    let descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
     let ubo_write_info = DescriptorBufferSetWI::new(descriptor_set, 0, vk::DescriptorType::UNIFORM_BUFFER)
         .add_buffer(vk::DescriptorBufferInfo {
@@ -512,8 +503,8 @@ fn prepare_pipelines(device: &VkDevice, model: &VkglTFModel, render_pass: vk::Re
     use vkbase::ci::shader::{ShaderModuleCI, ShaderStageCI};
 
     let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
-    let vert_codes = shader_compiler.compile_from_path(Path::new(VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-    let frag_codes = shader_compiler.compile_from_path(Path::new(FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
+    let vert_codes = shader_compiler.compile_from_path(&workspace_path(VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
+    let frag_codes = shader_compiler.compile_from_path(&workspace_path(FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
 
     let vert_module = ShaderModuleCI::new(vert_codes)
         .build(device)?;
@@ -3,9 +3,8 @@ use ash::vk;
 
 use std::ptr;
 use std::mem;
-use std::path::Path;
 
-use vkbase::context::{VkDevice, VkSwapchain};
+use vkbase::context::{VkDevice, VkSwapchain, FrameInFlight};
 use vkbase::ci::VkObjectBuildableCI;
 use vkbase::ci::buffer::BufferCI;
 use vkbase::ci::vma::{VmaBuffer, VmaAllocationCI};
@@ -13,16 +12,17 @@ use vkbase::ci::shader::{ShaderModuleCI, ShaderStageCI};
 use vkbase::gltf::VkglTFModel;
 use vkbase::texture::Texture2D;
 use vkbase::context::VulkanContext;
-use vkbase::{FlightCamera, FrameAction};
+use vkbase::{FlightCamera, FrameAction, KeyAction};
 use vkbase::{vkbytes, vkuint, vkfloat, vkptr, Vec3F, Vec4F, Mat4F};
 use vkbase::{VkResult, VkErrorKind};
 
 use vkexamples::VkExampleBackend;
+use vkexamples::{asset_path, workspace_path};
 
 const VERTEX_SHADER_SOURCE_PATH  : &'static str = "examples/src/specializationconstants/uber.vert.glsl";
 const FRAGMENT_SHADER_SOURCE_PATH: &'static str = "examples/src/specializationconstants/uber.frag.glsl";
-const MODEL_PATH  : &'static str = "assets/models/color_teapot_spheres.gltf";
-const TEXTURE_PATH: &'static str = "assets/textures/metalplate_nomips_rgba.ktx";
+const MODEL_PATH  : &'static str = "models/color_teapot_spheres.gltf";
+const TEXTURE_PATH: &'static str = "textures/metalplate_nomips_rgba.ktx";
 
 
 pub struct VulkanExample {
@@ -72,10 +72,10 @@ impl VulkanExample {
         };
 
         let render_pass = setup_renderpass(device, &context.swapchain)?;
-        let backend = VkExampleBackend::new(device, swapchain, render_pass)?;
+        let backend = VkExampleBackend::new(device, swapchain, render_pass, vk::SampleCountFlags::TYPE_1)?;
 
         let model = prepare_model(device)?;
-        let color_map = Texture2D::load_ktx(device, Path::new(TEXTURE_PATH), vk::Format::R8G8B8A8_UNORM)?;
+        let color_map = Texture2D::load_ktx(device, &asset_path(TEXTURE_PATH), vk::Format::R8G8B8A8_UNORM)?;
         let ubo_buffer = prepare_uniform(device)?;
         let descriptors = setup_descriptor(device, &ubo_buffer, &model, &color_map)?;
 
@@ -101,27 +101,18 @@ impl vkbase::RenderWorkflow for VulkanExample {
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, _delta_time: f32) -> VkResult<vk::Semaphore> {
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, _delta_time: f32) -> VkResult<()> {
 
         self.update_uniforms()?;
 
-        let submit_ci = vkbase::ci::device::SubmitCI::new()
-            .add_wait(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, await_present)
-            .add_command(self.backend.commands[image_index])
-            .add_signal(self.backend.await_rendering);
-
-        // Submit to the graphics queue passing a wait fence.
-        device.submit(submit_ci, device.logic.queues.graphics.handle, Some(device_available))?;
-
-        Ok(self.backend.await_rendering)
+        // Submit to the graphics queue with the semaphores and fence of current frame.
+        frame.submit(device, &[self.backend.commands[frame.image_index]])
     }
 
     fn swapchain_reload(&mut self, device: &mut VkDevice, new_chain: &VkSwapchain) -> VkResult<()> {
 
         // recreate the resources.
-        device.discard(self.pipelines.phong);
-        device.discard(self.pipelines.toon);
-        device.discard(self.pipelines.textured);
+        device.discard(&[self.pipelines.phong, self.pipelines.toon, self.pipelines.textured]);
 
         let render_pass = setup_renderpass(device, new_chain)?;
         self.backend.swapchain_reload(device, new_chain, render_pass)?;
@@ -136,7 +127,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
         if inputer.is_key_active() || inputer.is_cursor_active() {
 
-            if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+            if inputer.is_action_pressed(KeyAction::Quit) {
                 return FrameAction::Terminal
             }
 
@@ -156,13 +147,11 @@ impl vkbase::RenderWorkflow for VulkanExample {
         device.discard(self.descriptors.layout);
         device.discard(self.descriptors.pool);
 
-        device.discard(self.pipelines.phong);
-        device.discard(self.pipelines.toon);
-        device.discard(self.pipelines.textured);
+        device.discard(&[self.pipelines.phong, self.pipelines.toon, self.pipelines.textured]);
         device.discard(self.pipelines.layout);
 
         device.vma_discard(self.ubo_buffer)?;
-        device.vma_discard(self.model)?;
+        self.model.discard_by(device)?;
 
         self.color_map.discard_by(device)?;
         self.backend.discard_by(device)
@@ -199,7 +188,7 @@ impl VulkanExample {
 
             let render_pass_bi = RenderPassBI::new(self.backend.render_pass, self.backend.framebuffers[i])
                 .render_extent(dimension)
-                .set_clear_values(vkexamples::DEFAULT_CLEAR_VALUES.clone());
+                .clear_values(&vkexamples::DEFAULT_CLEAR_VALUES);
 
             recorder.begin_record()?
                 .begin_render_pass(render_pass_bi)
@@ -260,14 +249,15 @@ impl VulkanExample {
 pub fn prepare_model(device: &mut VkDevice) -> VkResult<VkglTFModel> {
 
     use vkbase::gltf::{GltfModelInfo, load_gltf};
-    use vkbase::gltf::{AttributeFlags, NodeAttachmentFlags};
+    use vkbase::gltf::{AttributeFlags, NodeAttachmentFlags, IndexPrecision};
 
     let model_info = GltfModelInfo {
-        path: Path::new(MODEL_PATH),
+        path: &asset_path(MODEL_PATH),
         // specify model's vertices layout.
         attribute: AttributeFlags::POSITION | AttributeFlags::NORMAL | AttributeFlags::TEXCOORD_0,
         // specify model's node attachment layout.
         node: NodeAttachmentFlags::TRANSFORM_MATRIX,
+        index_type: IndexPrecision::U16,
         transform: None,
     };
 
@@ -315,7 +305,7 @@ struct DescriptorStaff {
 fn setup_descriptor(device: &VkDevice, ubo_buffer: &VmaBuffer, model: &VkglTFModel, color_map: &Texture2D) -> VkResult<DescriptorStaff> {
 
     use vkbase::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use vkbase::ci::descriptor::{DescriptorSetAI, DescriptorBufferSetWI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
+    use vkbase::ci::descriptor::{DescriptorBufferSetWI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
 
     // Descriptor Pool.
     let descriptor_pool = DescriptorPoolCI::new(1)
@@ -371,10 +361,7 @@ fn setup_descriptor(device: &VkDevice, ubo_buffer: &VmaBuffer, model: &VkglTFMod
         .build(device)?;
 
     // Descriptor set.
-    let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-        .add_set_layout(set_layout)
-        .build(device)?;
-    let descriptor_set = descriptor_sets.remove(0);
+    let descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
     let ubo_write_info = DescriptorBufferSetWI::new(descriptor_set, 0, vk::DescriptorType::UNIFORM_BUFFER)
         .add_buffer(vk::DescriptorBufferInfo {
@@ -529,8 +516,8 @@ fn prepare_pipelines(device: &VkDevice, model: &VkglTFModel, render_pass: vk::Re
     // All pipelines will use the same "uber" shader and specialization constants to change branching and parameters of that shader
     let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
 
-    let vert_codes = shader_compiler.compile_from_path(Path::new(VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-    let frag_codes = shader_compiler.compile_from_path(Path::new(FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
+    let vert_codes = shader_compiler.compile_from_path(&workspace_path(VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
+    let frag_codes = shader_compiler.compile_from_path(&workspace_path(FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
 
     let vert_module = ShaderModuleCI::new(vert_codes)
         .build(device)?;
@@ -1,17 +1,17 @@
 
 use ash::vk;
 
-use std::path::Path;
 
 use vkbase::context::{VkDevice, VkSwapchain};
 use vkbase::ci::VkObjectBuildableCI;
 use vkbase::ci::shader::{ShaderModuleCI, ShaderStageCI};
 use vkbase::context::VulkanContext;
 use vkbase::utils::color::VkColor;
-use vkbase::FrameAction;
+use vkbase::{FrameAction, KeyAction};
 use vkbase::VkResult;
 
 use vkexamples::VkExampleBackend;
+use vkexamples::workspace_path;
 use crate::text::{TextPool, TextInfo, GlyphImages};
 
 const TEXT_VERTEX_SHADER_SOURCE_PATH  : &'static str = "examples/src/text-overlay/text.vert.glsl";
@@ -36,18 +36,18 @@ struct PipelineStaff {
 
 impl VulkanExample {
 
-    pub fn new(context: &mut VulkanContext, hidpi_factor: f32) -> VkResult<VulkanExample> {
+    pub fn new(context: &mut VulkanContext) -> VkResult<VulkanExample> {
 
         let device = &mut context.device;
         let swapchain = &context.swapchain;
 
         let render_pass = setup_renderpass(device, &context.swapchain)?;
 
-        let mut backend_res = VkExampleBackend::new(device, swapchain, render_pass)?;
+        let mut backend_res = VkExampleBackend::new(device, swapchain, render_pass, vk::SampleCountFlags::TYPE_1)?;
         backend_res.enable_depth_attachment(false);
 
         let text_glyphs = GlyphImages::from_font(device, include_bytes!("../../../assets/fonts/Roboto-Regular.ttf"))?;
-        let text_pool = TextPool::new(device, swapchain.dimension, hidpi_factor)?;
+        let text_pool = TextPool::new(device, swapchain.dimension, swapchain.hidpi_factor())?;
         let descriptors = setup_descriptor(device, &text_glyphs)?;
 
         let pipelines = prepare_pipelines(device, swapchain.dimension, backend_res.render_pass, descriptors.layout)?;
@@ -110,7 +110,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
         if inputer.is_key_active() {
 
-            if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+            if inputer.is_action_pressed(KeyAction::Quit) {
                 return FrameAction::Terminal
             }
         }
@@ -173,7 +173,7 @@ struct DescriptorStaff {
 fn setup_descriptor(device: &VkDevice, glyphs: &GlyphImages) -> VkResult<DescriptorStaff> {
 
     use vkbase::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use vkbase::ci::descriptor::{DescriptorSetAI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
+    use vkbase::ci::descriptor::{DescriptorImageSetWI, DescriptorSetsUpdateCI};
 
     // Descriptor Pool.
     let descriptor_pool = DescriptorPoolCI::new(1)
@@ -196,10 +196,7 @@ fn setup_descriptor(device: &VkDevice, glyphs: &GlyphImages) -> VkResult<Descrip
         .build(device)?;
 
     // Descriptor set.
-    let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-        .add_set_layout(set_layout)
-        .build(device)?;
-    let descriptor_set = descriptor_sets.remove(0);
+    let descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
     // update descriptorsets.
     let sampled_image_write_info = DescriptorImageSetWI::new(descriptor_set, 0, vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
@@ -295,8 +292,8 @@ fn prepare_pipelines(device: &VkDevice, dimension: vk::Extent2D, render_pass: vk
 
 
     let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
-    let vert_codes = shader_compiler.compile_from_path(Path::new(TEXT_VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-    let frag_codes = shader_compiler.compile_from_path(Path::new(TEXT_FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
+    let vert_codes = shader_compiler.compile_from_path(&workspace_path(TEXT_VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
+    let frag_codes = shader_compiler.compile_from_path(&workspace_path(TEXT_FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
 
     let vert_module = ShaderModuleCI::new(vert_codes)
         .build(device)?;
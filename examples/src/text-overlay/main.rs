@@ -30,7 +30,7 @@ fn main() {
     let mut vk_context = VulkanContext::new(&window)
         .build().expect("Error when creating Vulkan Context");
 
-    let app = example::VulkanExample::new(&mut vk_context, window.hidpi_factor())
+    let app = example::VulkanExample::new(&mut vk_context)
         .expect("Error when initializing application");
 
     let entry = ProcPipeline::new(window, vk_context).unwrap();
@@ -2,23 +2,23 @@
 use ash::vk;
 
 use std::ptr;
-use std::path::Path;
 
-use vkbase::context::{VulkanContext, VkDevice, VkSwapchain};
+use vkbase::context::{VulkanContext, VkDevice, VkSwapchain, FrameInFlight};
 use vkbase::ci::VkObjectBuildableCI;
 use vkbase::ci::vma::VmaBuffer;
 use vkbase::utils::color::VkColor;
-use vkbase::ui::{TextInfo, TextType, TextHAlign};
-use vkbase::{FlightCamera, FrameAction};
+use vkbase::ui::{TextInfo, TextType, TextHAlign, TextVAlign};
+use vkbase::{FlightCamera, FrameAction, KeyAction};
 use vkbase::{vkuint, vkptr, Vec3F, Vec4F};
 use vkbase::VkResult;
 
 use vkexamples::VkExampleBackend;
+use vkexamples::{asset_path, workspace_path};
 use crate::data::{INDEX_DATA, Vertex, UboVS, Texture};
 
 const SHADER_VERTEX_PATH  : &'static str = "examples/src/texture/texture.vert.glsl";
 const SHADER_FRAGMENT_PATH: &'static str = "examples/src/texture/texture.frag.glsl";
-const TEXTURE_PATH: &'static str = "assets/textures/metalplate01_rgba.ktx";
+const TEXTURE_PATH: &'static str = "textures/metalplate01_rgba.ktx";
 
 pub struct VulkanExample {
 
@@ -55,11 +55,12 @@ impl VulkanExample {
         camera.set_move_speed(5.0);
 
         let render_pass = setup_renderpass(device, &context.swapchain)?;
-        let backend = VkExampleBackend::new(device, swapchain, render_pass)?;
+        let mut backend = VkExampleBackend::new(device, swapchain, render_pass, vk::SampleCountFlags::TYPE_1)?;
+        backend.enable_render_scale(device, swapchain)?;
 
         let (vertices, indices) = super::data::generate_quad(device)?;
         let (ubo_buffer, ubo_data) = UboVS::prepare_buffer(device, &camera)?;
-        let texture = Texture::load_ktx(device, Path::new(TEXTURE_PATH))?;
+        let texture = Texture::load_ktx(device, &asset_path(TEXTURE_PATH))?;
 
         let descriptors = setup_descriptor(device, &ubo_buffer, &texture)?;
 
@@ -86,29 +87,25 @@ impl vkbase::RenderWorkflow for VulkanExample {
             content: format!("Lod bias: {:1.2} (numpad +/- to change)", self.ubo_data.lod_bias),
             scale: 14.0,
             align: TextHAlign::Left,
+            valign: TextVAlign::Top,
             color: VkColor::WHITE,
             location: vk::Offset2D { x: 5, y: 140 },
+            outline: None,
+            shadow: None,
             r#type: TextType::Dynamic { capacity: 40 },
         };
         self.lod_text_id = self.backend.ui_renderer.add_text(lod_text)?;
 
         self.update_uniforms()?;
-        self.record_commands(device, self.backend.dimension)?;
+        self.record_commands(device, self.backend.render_dimension())?;
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, _delta_time: f32) -> VkResult<vk::Semaphore> {
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, _delta_time: f32) -> VkResult<()> {
 
         self.update_uniforms()?;
 
-        let submit_ci = vkbase::ci::device::SubmitCI::new()
-            .add_wait(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, await_present)
-            .add_command(self.backend.commands[image_index])
-            .add_signal(self.backend.await_rendering);
-
-        device.submit(submit_ci, device.logic.queues.graphics.handle, Some(device_available))?;
-
-        Ok(self.backend.await_rendering)
+        frame.submit(device, &[self.backend.commands[frame.image_index]])
     }
 
     fn swapchain_reload(&mut self, device: &mut VkDevice, new_chain: &VkSwapchain) -> VkResult<()> {
@@ -120,7 +117,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
         self.backend.swapchain_reload(device, new_chain, render_pass)?;
         self.pipelines = prepare_pipelines(device, self.backend.render_pass, self.descriptors.layout)?;
 
-        self.record_commands(device, self.backend.dimension)?;
+        self.record_commands(device, self.backend.render_dimension())?;
 
         Ok(())
     }
@@ -129,7 +126,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
         if inputer.is_key_active() || inputer.is_cursor_active() {
 
-            if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+            if inputer.is_action_pressed(KeyAction::Quit) {
                 return FrameAction::Terminal
             }
 
@@ -193,13 +190,12 @@ impl VulkanExample {
         for (i, &command) in self.backend.commands.iter().enumerate() {
 
             use vkbase::command::{VkCmdRecorder, CmdGraphicsApi, IGraphics};
-            use vkbase::ci::pipeline::RenderPassBI;
 
             let recorder: VkCmdRecorder<IGraphics> = VkCmdRecorder::new(&device.logic, command);
 
-            let render_pass_bi = RenderPassBI::new(self.backend.render_pass, self.backend.framebuffers[i])
-                .render_extent(dimension)
-                .set_clear_values(vkexamples::DEFAULT_CLEAR_VALUES.clone());
+            // the scene is rendered at the scaled resolution if `WindowConfig::render_scale` is set.
+            let render_pass_bi = self.backend.scene_pass_bi(i)
+                .clear_values(&vkexamples::DEFAULT_CLEAR_VALUES);
 
             recorder.begin_record()?
                 .begin_render_pass(render_pass_bi)
@@ -211,10 +207,10 @@ impl VulkanExample {
                 .bind_index_buffer(self.indices.handle, vk::IndexType::UINT32, 0)
                 .draw_indexed(INDEX_DATA.len() as vkuint, 1, 0, 0, 0);
 
-            self.backend.ui_renderer.record_command(&recorder);
+            // the UI is drawn at native resolution.
+            self.backend.finish_scene_pass(device, command, i);
 
-            recorder.end_render_pass()
-                .end_record()?;
+            recorder.end_record()?;
         }
 
         Ok(())
@@ -249,7 +245,7 @@ struct DescriptorStaff {
 fn setup_descriptor(device: &VkDevice, ubo_buffer: &VmaBuffer, texture: &Texture) -> VkResult<DescriptorStaff> {
 
     use vkbase::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use vkbase::ci::descriptor::{DescriptorSetAI, DescriptorBufferSetWI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
+    use vkbase::ci::descriptor::{DescriptorBufferSetWI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
 
     // Descriptor Pool.
     let descriptor_pool = DescriptorPoolCI::new(1)
@@ -288,10 +284,7 @@ fn setup_descriptor(device: &VkDevice, ubo_buffer: &VmaBuffer, texture: &Texture
         .build(device)?;
 
     // Descriptor set.
-    let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-        .add_set_layout(set_layout)
-        .build(device)?;
-    let descriptor_set = descriptor_sets.remove(0);
+    let descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
     let ubo_write = DescriptorBufferSetWI::new(descriptor_set, 0, vk::DescriptorType::UNIFORM_BUFFER)
         .add_buffer(vk::DescriptorBufferInfo {
@@ -395,8 +388,8 @@ fn prepare_pipelines(device: &VkDevice, render_pass: vk::RenderPass, set_layout:
     use vkbase::ci::shader::{ShaderModuleCI, ShaderStageCI};
 
     let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
-    let vert_codes = shader_compiler.compile_from_path(Path::new(SHADER_VERTEX_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-    let frag_codes = shader_compiler.compile_from_path(Path::new(SHADER_FRAGMENT_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
+    let vert_codes = shader_compiler.compile_from_path(&workspace_path(SHADER_VERTEX_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
+    let frag_codes = shader_compiler.compile_from_path(&workspace_path(SHADER_FRAGMENT_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
 
     let vert_module = ShaderModuleCI::new(vert_codes)
         .build(device)?;
@@ -23,6 +23,7 @@ fn main() {
     win_config.title = WINDOW_TITLE.to_string();
     win_config.is_cursor_hide = true; // hide cursor.
     win_config.is_cursor_grap = true; // grap cursor operations.
+    win_config.render_scale = vkexamples::render_scale(); // set `VKEXAMPLES_RENDER_SCALE` to render the scene at a scaled resolution.
 
     let window = WindowContext::new(win_config)
         .expect("Error when creating Window Context");
@@ -39,7 +40,13 @@ fn main() {
 
     let entry = ProcPipeline::new(window, vk_context).unwrap();
 
-    match entry.launch(app) {
+    // set `VKEXAMPLES_LEAK_CHECK_FRAMES` to check that no allocation is leaked after the frames and a swapchain recreation.
+    let result = match vkexamples::leak_check_frames() {
+        | Some(frames) => entry.assert_no_growth(app, frames),
+        | None => entry.launch(app),
+    };
+
+    match result {
         | Ok(_) => {},
         | Err(e) => {
             eprintln!("{}", e)
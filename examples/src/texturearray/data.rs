@@ -5,7 +5,7 @@ use ash::vk;
 
 use std::mem;
 use std::ptr;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use vkbase::ci::buffer::BufferCI;
 use vkbase::ci::image::{ImageCI, ImageViewCI, ImageBarrierCI, SamplerCI};
@@ -20,9 +20,11 @@ use vkbase::FlightCamera;
 use vkbase::{vkuint, vkbytes, vkfloat, vkptr, Vec3F, Vec2F, Vec4F, Mat4F};
 use vkbase::{VkResult, VkError, VkErrorKind};
 
-const TEXTURE_ARRAY_BC3_PATH      : &'static str = "assets/textures/texturearray_bc3_unorm.ktx";
-const TEXTURE_ARRAY_ASTC_LDR_PATH : &'static str = "assets/textures/texturearray_astc_8x8_unorm.ktx";
-const TEXTURE_ARRAY_ETC2_PATH     : &'static str = "assets/textures/texturearray_etc2_unorm.ktx";
+use vkexamples::asset_path;
+
+const TEXTURE_ARRAY_BC3_PATH      : &'static str = "textures/texturearray_bc3_unorm.ktx";
+const TEXTURE_ARRAY_ASTC_LDR_PATH : &'static str = "textures/texturearray_astc_8x8_unorm.ktx";
+const TEXTURE_ARRAY_ETC2_PATH     : &'static str = "textures/texturearray_etc2_unorm.ktx";
 
 lazy_static! {
 
@@ -223,11 +225,11 @@ impl TextureArray {
         // As the support differs between implementations, we need to check device features and select a proper format and file.
 
         let (texture_path, texture_format) = if device.phy.features_enabled().texture_compression_bc == vk::TRUE {
-            (PathBuf::from(TEXTURE_ARRAY_BC3_PATH), vk::Format::BC3_UNORM_BLOCK)
+            (asset_path(TEXTURE_ARRAY_BC3_PATH), vk::Format::BC3_UNORM_BLOCK)
         } else if device.phy.features_enabled().texture_compression_astc_ldr == vk::TRUE {
-            (PathBuf::from(TEXTURE_ARRAY_ASTC_LDR_PATH), vk::Format::ASTC_8X8_UNORM_BLOCK)
+            (asset_path(TEXTURE_ARRAY_ASTC_LDR_PATH), vk::Format::ASTC_8X8_UNORM_BLOCK)
         } else if device.phy.features_enabled().texture_compression_etc2 == vk::TRUE {
-            (PathBuf::from(TEXTURE_ARRAY_ETC2_PATH), vk::Format::ETC2_R8G8B8_UNORM_BLOCK)
+            (asset_path(TEXTURE_ARRAY_ETC2_PATH), vk::Format::ETC2_R8G8B8_UNORM_BLOCK)
         } else {
             return Err(VkError::unsupported("Compressed texture format"))
         };
@@ -3,16 +3,16 @@ use ash::vk;
 
 use std::ptr;
 use std::mem;
-use std::path::Path;
 
-use vkbase::context::{VkDevice, VkSwapchain};
+use vkbase::context::{VkDevice, VkSwapchain, FrameInFlight};
 use vkbase::ci::VkObjectBuildableCI;
 use vkbase::context::VulkanContext;
-use vkbase::{FlightCamera, FrameAction};
+use vkbase::{FlightCamera, FrameAction, KeyAction};
 use vkbase::{vkbytes, vkptr, Vec3F, Mat4F};
 use vkbase::VkResult;
 
 use vkexamples::VkExampleBackend;
+use vkexamples::workspace_path;
 use super::data::{Skybox, UBOVS};
 
 const SKY_BOX_VERTEX_SHADER_SOURCE_PATH  : &'static str = "examples/src/texturecubemap/skybox.vert.glsl";
@@ -52,7 +52,7 @@ impl VulkanExample {
 
 
         let render_pass = setup_renderpass(device, &context.swapchain)?;
-        let backend = VkExampleBackend::new(device, swapchain, render_pass)?;
+        let backend = VkExampleBackend::new(device, swapchain, render_pass, vk::SampleCountFlags::TYPE_1)?;
 
         let mut skybox = Skybox::load_meshes(device, &camera)?;
 
@@ -80,18 +80,11 @@ impl vkbase::RenderWorkflow for VulkanExample {
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, delta_time: f32) -> VkResult<vk::Semaphore> {
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, delta_time: f32) -> VkResult<()> {
 
         self.update_uniforms(delta_time)?;
 
-        let submit_ci = vkbase::ci::device::SubmitCI::new()
-            .add_wait(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, await_present)
-            .add_command(self.backend.commands[image_index])
-            .add_signal(self.backend.await_rendering);
-
-        device.submit(submit_ci, device.logic.queues.graphics.handle, Some(device_available))?;
-
-        Ok(self.backend.await_rendering)
+        frame.submit(device, &[self.backend.commands[frame.image_index]])
     }
 
     fn swapchain_reload(&mut self, device: &mut VkDevice, new_chain: &VkSwapchain) -> VkResult<()> {
@@ -111,7 +104,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
         if inputer.is_key_active() || inputer.is_cursor_active() {
 
-            if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+            if inputer.is_action_pressed(KeyAction::Quit) {
                 return FrameAction::Terminal
             }
 
@@ -163,7 +156,7 @@ impl VulkanExample {
 
             let render_pass_bi = RenderPassBI::new(self.backend.render_pass, self.backend.framebuffers[i])
                 .render_extent(dimension)
-                .set_clear_values(vkexamples::DEFAULT_CLEAR_VALUES.clone());
+                .clear_values(&vkexamples::DEFAULT_CLEAR_VALUES);
 
             recorder.begin_record()?
                 .begin_render_pass(render_pass_bi)
@@ -225,7 +218,7 @@ struct DescriptorStaff {
 fn setup_descriptor(device: &VkDevice, skybox: &mut Skybox) -> VkResult<DescriptorStaff> {
 
     use vkbase::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use vkbase::ci::descriptor::{DescriptorSetAI, DescriptorBufferSetWI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
+    use vkbase::ci::descriptor::{DescriptorBufferSetWI, DescriptorImageSetWI, DescriptorSetsUpdateCI};
 
     let descriptor_pool = DescriptorPoolCI::new(2)
         .add_descriptor(vk::DescriptorType::UNIFORM_BUFFER, 2)
@@ -288,10 +281,7 @@ fn setup_descriptor(device: &VkDevice, skybox: &mut Skybox) -> VkResult<Descript
         .build(device)?;
 
 
-    let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-        .add_set_layout(set_layout)
-        .build(device)?;
-    skybox.descriptor_set = descriptor_sets.remove(0);
+    skybox.descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
 
     // Binding 0: Object matrices Uniform buffer.
@@ -306,7 +296,7 @@ fn setup_descriptor(device: &VkDevice, skybox: &mut Skybox) -> VkResult<Descript
         .add_buffer(skybox.model.nodes.node_descriptor());
     // Binding 2: Object texture.
     let sampler_write_info = DescriptorImageSetWI::new(skybox.descriptor_set, 2, vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
-        .add_image(skybox.texture.descriptor());
+        .add_image(skybox.texture.descriptor);
 
     DescriptorSetsUpdateCI::new()
         .add_write(&ubo_write_info)
@@ -399,8 +389,8 @@ fn prepare_pipelines(device: &VkDevice, skybox: &Skybox, render_pass: vk::Render
     use vkbase::ci::shader::{ShaderModuleCI, ShaderStageCI};
 
     let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
-    let vert_codes = shader_compiler.compile_from_path(Path::new(SKY_BOX_VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-    let frag_codes = shader_compiler.compile_from_path(Path::new(SKY_BOX_FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
+    let vert_codes = shader_compiler.compile_from_path(&workspace_path(SKY_BOX_VERTEX_SHADER_SOURCE_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
+    let frag_codes = shader_compiler.compile_from_path(&workspace_path(SKY_BOX_FRAGMENT_SHADER_SOURCE_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
 
     let vert_module = ShaderModuleCI::new(vert_codes)
         .build(device)?;
@@ -228,7 +228,7 @@ fn allocate_buffer<D: Copy>(device: &VkDevice, data: &[D], buffer_usage: vk::Buf
     // Create a host-visible buffer to copy the vertices data to (staging buffer).
     let staging_buffer = unsafe {
         device.logic.handle.create_buffer(&staging_buffer_ci, None)
-            .map_err(|_| VkError::create("Staging Buffer"))?
+            .map_err(|e| VkError::vk(e, "Create Staging Buffer"))?
     };
     let staging_memory_requirement = unsafe {
         device.logic.handle.get_buffer_memory_requirements(staging_buffer)
@@ -245,14 +245,14 @@ fn allocate_buffer<D: Copy>(device: &VkDevice, data: &[D], buffer_usage: vk::Buf
 
     let staging_memory = unsafe {
         device.logic.handle.allocate_memory(&staging_mem_alloc, None)
-            .map_err(|_| VkError::create("Memory Allocate"))?
+            .map_err(|e| VkError::vk(e, "Allocate Memory"))?
     };
 
     unsafe {
 
         // map and copy.
         let data_ptr = device.logic.handle.map_memory(staging_memory, 0, staging_mem_alloc.allocation_size, vk::MemoryMapFlags::empty())
-            .map_err(|_| VkError::device("Map Memory"))?;
+            .map_err(|e| VkError::vk(e, "Map Memory"))?;
 
         let mapped_copy_target = ::std::slice::from_raw_parts_mut(data_ptr as *mut D, data.len());
         mapped_copy_target.copy_from_slice(data);
@@ -260,7 +260,7 @@ fn allocate_buffer<D: Copy>(device: &VkDevice, data: &[D], buffer_usage: vk::Buf
         device.logic.handle.unmap_memory(staging_memory);
 
         device.logic.handle.bind_buffer_memory(staging_buffer, staging_memory, 0)
-            .map_err(|_| VkError::device("Binding Buffer Memory"))?;
+            .map_err(|e| VkError::vk(e, "Binding Buffer Memory"))?;
     }
 
 
@@ -272,7 +272,7 @@ fn allocate_buffer<D: Copy>(device: &VkDevice, data: &[D], buffer_usage: vk::Buf
 
     let target_buffer = unsafe {
         device.logic.handle.create_buffer(&target_buffer_ci, None)
-            .map_err(|_| VkError::create("Vertex Buffer"))?
+            .map_err(|e| VkError::vk(e, "Create Vertex Buffer"))?
     };
     let target_memory_requirement = unsafe {
         device.logic.handle.get_buffer_memory_requirements(target_buffer)
@@ -286,12 +286,12 @@ fn allocate_buffer<D: Copy>(device: &VkDevice, data: &[D], buffer_usage: vk::Buf
 
     let target_memory = unsafe {
         device.logic.handle.allocate_memory(&target_mem_alloc, None)
-            .map_err(|_| VkError::create("Memory Allocate"))?
+            .map_err(|e| VkError::vk(e, "Allocate Memory"))?
     };
 
     unsafe {
         device.logic.handle.bind_buffer_memory(target_buffer, target_memory, 0)
-            .map_err(|_| VkError::device("Binding Buffer Memory"))?;
+            .map_err(|e| VkError::vk(e, "Binding Buffer Memory"))?;
     }
 
     let result = BufferResourceTmp { buffer_size, staging_buffer, staging_memory, target_buffer, target_memory };
@@ -319,7 +319,7 @@ pub fn prepare_uniform(device: &VkDevice, dimension: vk::Extent2D) -> VkResult<U
 
     let uniform_buffer = unsafe {
         device.logic.handle.create_buffer(&uniform_buffer_ci, None)
-            .map_err(|_| VkError::create("Buffer"))?
+            .map_err(|e| VkError::vk(e, "Create Buffer"))?
     };
     // Get memory requirements including size, alignment and memory type.
     let memory_requirement = unsafe {
@@ -340,12 +340,12 @@ pub fn prepare_uniform(device: &VkDevice, dimension: vk::Extent2D) -> VkResult<U
     };
     let uniform_memory = unsafe {
         device.logic.handle.allocate_memory(&mem_alloc, None)
-            .map_err(|_| VkError::create("Memory Allocate"))?
+            .map_err(|e| VkError::vk(e, "Allocate Memory"))?
     };
 
     unsafe {
         device.logic.handle.bind_buffer_memory(uniform_buffer, uniform_memory, 0)
-            .map_err(|_| VkError::device("Binding Buffer Memory"))?
+            .map_err(|e| VkError::vk(e, "Binding Buffer Memory"))?
     };
 
     let descriptor_info = vk::DescriptorBufferInfo {
@@ -380,7 +380,7 @@ fn update_uniform_buffers(device: &VkDevice, dimension: vk::Extent2D, uniforms:
     // Map uniform buffer and update it.
     unsafe {
         let data_ptr = device.logic.handle.map_memory(uniforms.memory, 0, mem::size_of::<UboVS>() as _, vk::MemoryMapFlags::empty())
-            .map_err(|_| VkError::device("Map Memory"))?;
+            .map_err(|e| VkError::vk(e, "Map Memory"))?;
 
         let mapped_copy_target = ::std::slice::from_raw_parts_mut(data_ptr as *mut UboVS, ubo_data.len());
         mapped_copy_target.copy_from_slice(&ubo_data);
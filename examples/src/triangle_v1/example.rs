@@ -2,10 +2,10 @@
 use ash::vk;
 use ash::version::DeviceV1_0;
 
-use vkbase::context::{VkDevice, VkSwapchain};
+use vkbase::context::{VkDevice, VkSwapchain, FrameInFlight};
 use vkbase::{VkResult, VkError};
 use vkbase::ci::VkObjectBuildableCI;
-use vkbase::FrameAction;
+use vkbase::{FrameAction, KeyAction};
 use vkbase::vkuint;
 
 use std::ptr;
@@ -63,9 +63,6 @@ pub struct VulkanExample {
     command_pool: vk::CommandPool,
     // Command buffers used for rendering.
     commands: Vec<vk::CommandBuffer>,
-
-    // this semaphore is used to ensure that all rendering commands submitted have been finished before presenting the image.
-    await_rendering: vk::Semaphore,
 }
 
 impl VulkanExample {
@@ -91,14 +88,11 @@ impl VulkanExample {
         let framebuffers = setup_framebuffers(device, &context.swapchain, render_pass, &depth_image)?;
         let pipeline = prepare_pipelines(device, render_pass, pipeline_layout)?;
 
-        let await_rendering = setup_sync_primitive(device)?;
-
         let target = VulkanExample {
             command_pool, commands,
             descriptor_pool, descriptor_set, descriptor_set_layout,
             pipeline, pipeline_layout, render_pass, framebuffers,
             vertex_buffer, index_buffer, uniform_buffer, depth_image, dimension,
-            await_rendering,
         };
         Ok(target)
     }
@@ -112,30 +106,31 @@ impl vkbase::RenderWorkflow for VulkanExample {
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, _delta_time: f32) -> VkResult<vk::Semaphore> {
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, _delta_time: f32) -> VkResult<()> {
 
         let submit_infos = [
             vk::SubmitInfo {
                 s_type: vk::StructureType::SUBMIT_INFO,
                 p_next: ptr::null(),
                 wait_semaphore_count   : 1,
-                p_wait_semaphores      : &await_present,
+                p_wait_semaphores      : &frame.image_available,
                 // Pipeline stage at which the queue submission will wait (via p_wait_semaphores).
                 p_wait_dst_stage_mask  : &vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
                 command_buffer_count   : 1,
-                p_command_buffers      : &self.commands[image_index],
+                p_command_buffers      : &self.commands[frame.image_index],
                 signal_semaphore_count : 1,
-                p_signal_semaphores    : &self.await_rendering,
+                // this semaphore is used to ensure that all rendering commands submitted have been finished before presenting the image.
+                p_signal_semaphores    : &frame.render_finished,
             },
         ];
 
         // Submit to the graphics queue passing a wait fence.
         unsafe {
-            device.logic.handle.queue_submit(device.logic.queues.graphics.handle, &submit_infos, device_available)
-                .map_err(|_| VkError::device("Queue Submit"))?;
+            device.logic.handle.queue_submit(device.logic.queues.graphics.handle, &submit_infos, frame.fence)
+                .map_err(|e| VkError::vk(e, "Queue Submit"))?;
         }
 
-        Ok(self.await_rendering)
+        Ok(())
     }
 
     fn swapchain_reload(&mut self, device: &mut VkDevice, new_chain: &VkSwapchain) -> VkResult<()> {
@@ -172,7 +167,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
             self.pipeline = prepare_pipelines(device, self.render_pass, self.pipeline_layout)?;
 
             device.logic.handle.reset_command_pool(self.command_pool, vk::CommandPoolResetFlags::RELEASE_RESOURCES)
-                .map_err(|_| VkError::device("Reset Command Poll"))?;
+                .map_err(|e| VkError::vk(e, "Reset Command Pool"))?;
             self.record_commands(device, self.dimension)?;
         }
 
@@ -181,7 +176,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
     fn receive_input(&mut self, inputer: &vkbase::EventController, _delta_time: f32) -> FrameAction {
 
-        if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+        if inputer.is_action_pressed(KeyAction::Quit) {
             return FrameAction::Terminal
         }
 
@@ -216,8 +211,6 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
             destructor.destroy_buffer(self.uniform_buffer.buffer, None);
             destructor.free_memory(self.uniform_buffer.memory, None);
-
-            destructor.destroy_semaphore(self.await_rendering, None);
         }
 
         Ok(())
@@ -277,7 +270,7 @@ impl VulkanExample {
             unsafe {
 
                 device.logic.handle.begin_command_buffer(command.clone(), &cmd_begin_ci)
-                    .map_err(|_| VkError::device("Begin command buffer"))?;
+                    .map_err(|e| VkError::vk(e, "Begin command buffer"))?;
 
                 device.logic.handle.cmd_begin_render_pass(command.clone(), &renderpass_begin_ci, vk::SubpassContents::INLINE);
                 // update viewport state.
@@ -305,7 +298,7 @@ impl VulkanExample {
                 // ending the render pass will add an implicit barrier transitioning the frame buffer color attachment to
                 // VK_IMAGE_LAYOUT_PRESENT_SRC_KHR for presenting it to the windowing system.
                 device.logic.handle.end_command_buffer(command.clone())
-                    .map_err(|_| VkError::device("End command buffer"))?;
+                    .map_err(|e| VkError::vk(e, "End command buffer"))?;
             }
 
         }
@@ -327,7 +320,7 @@ pub fn create_command_buffer(device: &VkDevice, pool: vk::CommandPool, count: vk
 
     let buffers = unsafe {
         device.logic.handle.allocate_command_buffers(&command_buffer_ci)
-            .map_err(|_| VkError::create("Command Buffers"))?
+            .map_err(|e| VkError::vk(e, "Allocate Command Buffers"))?
     };
 
     Ok(buffers)
@@ -359,7 +352,7 @@ fn setup_descriptor_pool(device: &VkDevice) -> VkResult<vk::DescriptorPool> {
 
     let descriptor_pool = unsafe {
         device.logic.handle.create_descriptor_pool(&descriptor_pool_ci, None)
-            .map_err(|_| VkError::create("Descriptor Pool"))?
+            .map_err(|e| VkError::vk(e, "Create Descriptor Pool"))?
     };
 
     Ok(descriptor_pool)
@@ -392,7 +385,7 @@ fn setup_descriptor_layout(device: &VkDevice) -> VkResult<(vk::DescriptorSetLayo
 
     let descriptor_set_layout = unsafe {
         device.logic.handle.create_descriptor_set_layout(&descriptor_layout_ci, None)
-            .map_err(|_| VkError::create("Descriptor Set Layout"))?
+            .map_err(|e| VkError::vk(e, "Create Descriptor Set Layout"))?
     };
     let pipeline_layout_ci = vk::PipelineLayoutCreateInfo {
         s_type: vk::StructureType::PIPELINE_LAYOUT_CREATE_INFO,
@@ -406,7 +399,7 @@ fn setup_descriptor_layout(device: &VkDevice) -> VkResult<(vk::DescriptorSetLayo
 
     let pipeline_layout = unsafe {
         device.logic.handle.create_pipeline_layout(&pipeline_layout_ci, None)
-            .map_err(|_| VkError::create("Pipeline Layout"))?
+            .map_err(|e| VkError::vk(e, "Create Pipeline Layout"))?
     };
 
     Ok((descriptor_set_layout, pipeline_layout))
@@ -427,7 +420,7 @@ fn setup_descriptor_set(device: &VkDevice, pool: vk::DescriptorPool, layout: vk:
 
     let descriptor_set = unsafe {
         device.logic.handle.allocate_descriptor_sets(&descriptor_set_allot_ci)
-            .map_err(|_| VkError::create("Allocate Descriptor Set"))?
+            .map_err(|e| VkError::vk(e, "Allocate Descriptor Set"))?
     }.remove(0);
 
 
@@ -486,7 +479,7 @@ fn setup_depth_stencil(device: &VkDevice, dimension: vk::Extent2D) -> VkResult<D
 
     let image = unsafe {
         device.logic.handle.create_image(&image_ci, None)
-            .map_err(|_| VkError::create("Image"))?
+            .map_err(|e| VkError::vk(e, "Create Image"))?
     };
     let image_requirement  = unsafe {
         device.logic.handle.get_image_memory_requirements(image)
@@ -502,9 +495,9 @@ fn setup_depth_stencil(device: &VkDevice, dimension: vk::Extent2D) -> VkResult<D
 
     let memory = unsafe {
         let memory = device.logic.handle.allocate_memory(&mem_alloc, None)
-            .map_err(|_| VkError::create("Allocate Image Memory"))?;
+            .map_err(|e| VkError::vk(e, "Allocate Image Memory"))?;
         device.logic.handle.bind_image_memory(image, memory, 0)
-            .map_err(|_| VkError::device("Bind Image Memory."))?;
+            .map_err(|e| VkError::vk(e, "Bind Image Memory"))?;
         memory
     };
 
@@ -535,7 +528,7 @@ fn setup_depth_stencil(device: &VkDevice, dimension: vk::Extent2D) -> VkResult<D
 
     let view = unsafe {
         device.logic.handle.create_image_view(&depth_view_ci, None)
-            .map_err(|_| VkError::create("Image View"))?
+            .map_err(|e| VkError::vk(e, "Create Image View"))?
     };
 
     let result = DepthImage { image, view, memory };
@@ -667,7 +660,7 @@ fn setup_renderpass(device: &VkDevice, swapchain: &VkSwapchain) -> VkResult<vk::
 
     let render_pass = unsafe {
         device.logic.handle.create_render_pass(&renderpass_ci, None)
-            .map_err(|_| VkError::create("Render Pass"))?
+            .map_err(|e| VkError::vk(e, "Create Render Pass"))?
     };
     Ok(render_pass)
 }
@@ -701,7 +694,7 @@ fn setup_framebuffers(device: &VkDevice, swapchain: &VkSwapchain, render_pass: v
 
         let framebuffer = unsafe {
             device.logic.handle.create_framebuffer(&framebuffer_ci, None)
-                .map_err(|_| VkError::create("Framebuffers"))?
+                .map_err(|e| VkError::vk(e, "Create Framebuffers"))?
         };
         framebuffers.push(framebuffer);
     }
@@ -909,7 +902,7 @@ fn prepare_pipelines(device: &VkDevice, render_pass: vk::RenderPass, layout: vk:
     // Create rendering pipeline using the specified states
     let pipeline = unsafe {
         device.logic.handle.create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_ci], None)
-            .map_err(|_| VkError::create("Graphics Pipeline"))?
+            .map_err(|(_, e)| VkError::vk(e, "Create Graphics Pipeline"))?
     }.remove(0);
 
     // Shader modules are no longer needed once the graphics pipeline has been created.
@@ -919,18 +912,3 @@ fn prepare_pipelines(device: &VkDevice, render_pass: vk::RenderPass, layout: vk:
     }
     Ok(pipeline)
 }
-
-fn setup_sync_primitive(device: &VkDevice) -> VkResult<vk::Semaphore> {
-
-    let semaphore_ci = vk::SemaphoreCreateInfo {
-        s_type: vk::StructureType::SEMAPHORE_CREATE_INFO,
-        p_next: ptr::null(),
-        flags: vk::SemaphoreCreateFlags::empty(),
-    };
-
-    let semaphore = unsafe {
-        device.logic.handle.create_semaphore(&semaphore_ci, None)
-            .map_err(|_| VkError::create("Semaphore"))?
-    };
-    Ok(semaphore)
-}
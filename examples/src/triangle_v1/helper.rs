@@ -45,7 +45,7 @@ pub fn create_command_pool(device: &VkDevice) -> VkResult<vk::CommandPool> {
 
     let pool = unsafe {
         device.logic.handle.create_command_pool(&command_pool_ci, None)
-            .map_err(|_| VkError::create("Command Pool"))?
+            .map_err(|e| VkError::vk(e, "Create Command Pool"))?
     };
     Ok(pool)
 }
@@ -65,7 +65,7 @@ pub fn create_command_buffer(device: &VkDevice, pool: vk::CommandPool, is_begin:
 
     let mut buffers = unsafe {
         device.logic.handle.allocate_command_buffers(&command_buffer_ci)
-            .map_err(|_| VkError::create("Command Buffers"))?
+            .map_err(|e| VkError::vk(e, "Allocate Command Buffers"))?
     };
     let cmd_buffer = buffers.pop().unwrap();
 
@@ -80,7 +80,7 @@ pub fn create_command_buffer(device: &VkDevice, pool: vk::CommandPool, is_begin:
 
         unsafe {
             device.logic.handle.begin_command_buffer(cmd_buffer, &begin_info)
-                .map_err(|_| VkError::device("Begin Command Buffer"))?
+                .map_err(|e| VkError::vk(e, "Begin Command Buffer"))?
         }
     }
 
@@ -93,7 +93,7 @@ pub fn flush_command_buffer(device: &VkDevice, pool: vk::CommandPool, command: v
 
     unsafe {
         device.logic.handle.end_command_buffer(command)
-            .map_err(|_| VkError::create("End Command Buffer"))?;
+            .map_err(|e| VkError::vk(e, "End Command Buffer"))?;
     }
 
     let submit_info = vk::SubmitInfo {
@@ -118,15 +118,15 @@ pub fn flush_command_buffer(device: &VkDevice, pool: vk::CommandPool, command: v
     unsafe {
 
         let fence = device.logic.handle.create_fence(&fence_ci, None)
-            .map_err(|_| VkError::create("Fence"))?;
+            .map_err(|e| VkError::vk(e, "Create Fence"))?;
 
         // Submit to the queue.
         device.logic.handle.queue_submit(device.logic.queues.graphics.handle, &[submit_info], fence)
-            .map_err(|_| VkError::device("Queue Submit"))?;
+            .map_err(|e| VkError::vk(e, "Queue Submit"))?;
 
         // Wait for the fence to signal that command buffer has finished executing.
         device.logic.handle.wait_for_fences(&[fence], true, VkTimeDuration::Infinite.into())
-            .map_err(|_| VkError::device("Wait for fences"))?;
+            .map_err(|e| VkError::vk(e, "Wait for fences"))?;
 
         device.logic.handle.destroy_fence(fence, None);
         device.logic.handle.free_command_buffers(pool, &[command]);
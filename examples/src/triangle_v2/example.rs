@@ -2,14 +2,14 @@
 use ash::vk;
 
 use std::ptr;
-use std::path::Path;
 
-use vkbase::context::{VkDevice, VkSwapchain};
+use vkbase::context::{VkDevice, VkSwapchain, FrameInFlight};
 use vkbase::ci::VkObjectBuildableCI;
 use vkbase::VkResult;
-use vkbase::FrameAction;
+use vkbase::{FrameAction, KeyAction};
 
 use vkexamples::VkExampleBackend;
+use vkexamples::workspace_path;
 use crate::data::{Vertex, VertexBuffer, IndexBuffer, UniformBuffer, DescriptorStaff};
 
 const SHADER_VERTEX_PATH  : &'static str = "examples/src/triangle_v1/triangle.vert.glsl";
@@ -37,7 +37,7 @@ impl VulkanExample {
         let dimension = swapchain.dimension;
 
         let render_pass = setup_renderpass(device, &context.swapchain)?;
-        let backend_res = VkExampleBackend::new(device, swapchain, render_pass)?;
+        let backend_res = VkExampleBackend::new(device, swapchain, render_pass, vk::SampleCountFlags::TYPE_1)?;
 
         let (vertex_buffer, index_buffer) = super::data::prepare_vertices(device)?;
         let uniform_buffer = super::data::prepare_uniform(device, dimension)?;
@@ -62,17 +62,10 @@ impl vkbase::RenderWorkflow for VulkanExample {
         Ok(())
     }
 
-    fn render_frame(&mut self, device: &mut VkDevice, device_available: vk::Fence, await_present: vk::Semaphore, image_index: usize, _delta_time: f32) -> VkResult<vk::Semaphore> {
+    fn render_frame(&mut self, device: &mut VkDevice, frame: &FrameInFlight, _delta_time: f32) -> VkResult<()> {
 
-        let submit_ci = vkbase::ci::device::SubmitCI::new()
-            .add_wait(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, await_present)
-            .add_command(self.backend.commands[image_index])
-            .add_signal(self.backend.await_rendering);
-
-        // Submit to the graphics queue passing a wait fence.
-        device.submit(submit_ci, device.logic.queues.graphics.handle, Some(device_available))?;
-
-        Ok(self.backend.await_rendering)
+        // Submit to the graphics queue with the semaphores and fence of current frame.
+        frame.submit(device, &[self.backend.commands[frame.image_index]])
     }
 
     fn swapchain_reload(&mut self, device: &mut VkDevice, new_chain: &VkSwapchain) -> VkResult<()> {
@@ -91,7 +84,7 @@ impl vkbase::RenderWorkflow for VulkanExample {
 
     fn receive_input(&mut self, inputer: &vkbase::EventController, _delta_time: f32) -> FrameAction {
 
-        if inputer.key.is_key_pressed(winit::VirtualKeyCode::Escape) {
+        if inputer.is_action_pressed(KeyAction::Quit) {
             return FrameAction::Terminal
         }
 
@@ -148,7 +141,7 @@ impl VulkanExample {
 
             let render_pass_bi = RenderPassBI::new(self.backend.render_pass, self.backend.framebuffers[i])
                 .render_extent(dimension)
-                .set_clear_values(clear_values.clone());
+                .clear_values(&clear_values);
 
             recorder.begin_record()?
                 .begin_render_pass(render_pass_bi)
@@ -170,7 +163,7 @@ impl VulkanExample {
 fn setup_descriptor(device: &VkDevice, uniforms: &UniformBuffer) -> VkResult<DescriptorStaff> {
 
     use vkbase::ci::descriptor::{DescriptorPoolCI, DescriptorSetLayoutCI};
-    use vkbase::ci::descriptor::{DescriptorSetAI, DescriptorBufferSetWI, DescriptorSetsUpdateCI};
+    use vkbase::ci::descriptor::{DescriptorBufferSetWI, DescriptorSetsUpdateCI};
     use vkbase::ci::pipeline::PipelineLayoutCI;
 
     // Descriptor Pool.
@@ -197,10 +190,7 @@ fn setup_descriptor(device: &VkDevice, uniforms: &UniformBuffer) -> VkResult<Des
         .build(device)?;
 
     // Descriptor set.
-    let mut descriptor_sets = DescriptorSetAI::new(descriptor_pool)
-        .add_set_layout(set_layout)
-        .build(device)?;
-    let descriptor_set = descriptor_sets.remove(0);
+    let descriptor_set = device.allocate_descriptor_set(descriptor_pool, set_layout)?;
 
     let write_info = DescriptorBufferSetWI::new(descriptor_set, 0, vk::DescriptorType::UNIFORM_BUFFER)
         .add_buffer(uniforms.descriptor.clone());
@@ -280,8 +270,8 @@ fn prepare_pipelines(device: &VkDevice, render_pass: vk::RenderPass, layout: vk:
     use vkbase::ci::shader::{ShaderModuleCI, ShaderStageCI};
 
     let mut shader_compiler = vkbase::utils::shaderc::VkShaderCompiler::new()?;
-    let vert_codes = shader_compiler.compile_from_path(Path::new(SHADER_VERTEX_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
-    let frag_codes = shader_compiler.compile_from_path(Path::new(SHADER_FRAGMENT_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
+    let vert_codes = shader_compiler.compile_from_path(&workspace_path(SHADER_VERTEX_PATH), shaderc::ShaderKind::Vertex, "[Vertex Shader]", "main")?;
+    let frag_codes = shader_compiler.compile_from_path(&workspace_path(SHADER_FRAGMENT_PATH), shaderc::ShaderKind::Fragment, "[Fragment Shader]", "main")?;
 
     let vert_module = ShaderModuleCI::new(vert_codes)
         .build(device)?;